@@ -0,0 +1,101 @@
+//! Writes the static asset manifest: every file under `public/assets` mapped
+//! to a name carrying a hash of its contents, see `src/assets.rs`. Also sets
+//! `GIT_COMMIT_HASH` for `/admin/system` and the Sentry release.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use sha2::{Digest, Sha256};
+
+const ASSETS_DIR: &str = "public/assets";
+const HASH_LEN: usize = 8;
+
+fn main() {
+    println!("cargo:rerun-if-changed={ASSETS_DIR}");
+
+    let root = Path::new(ASSETS_DIR);
+    let mut files = Vec::new();
+    collect(root, &mut files);
+    files.sort();
+
+    let mut manifest = String::from("pub(crate) static MANIFEST: &[(&str, &str)] = &[\n");
+    for path in files {
+        let logical = path
+            .strip_prefix(root)
+            .expect("collected under the assets dir")
+            .to_string_lossy()
+            .replace('\\', "/");
+        let contents = fs::read(&path).expect("asset is readable");
+        let hash = format!("{:x}", Sha256::digest(&contents));
+        manifest.push_str(&format!(
+            "    ({logical:?}, {:?}),\n",
+            fingerprint(&logical, &hash[..HASH_LEN])
+        ));
+    }
+    manifest.push_str("];\n");
+
+    let out = PathBuf::from(env::var("OUT_DIR").expect("set by cargo"));
+    fs::write(out.join("asset_manifest.rs"), manifest).expect("OUT_DIR is writable");
+
+    commit_hash();
+}
+
+/// Builds without a checkout (such as container images) can pass
+/// `GIT_COMMIT_HASH` in the environment instead.
+fn commit_hash() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT_HASH");
+    if env::var_os("GIT_COMMIT_HASH").is_some() {
+        return;
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    // HEAD names the branch; the commit moves in the branch's ref
+    if let Ok(head) = fs::read_to_string(".git/HEAD")
+        && let Some(branch) = head.trim().strip_prefix("ref: ")
+    {
+        println!("cargo:rerun-if-changed=.git/{branch}");
+        println!("cargo:rerun-if-changed=.git/packed-refs");
+    }
+    let hash = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+    if let Some(hash) = hash.map(|h| h.trim().to_string()).filter(|h| !h.is_empty()) {
+        println!("cargo:rustc-env=GIT_COMMIT_HASH={hash}");
+    }
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, files);
+        } else if !path.to_string_lossy().contains(':') {
+            // Skips editor and OS metadata such as `logo.svg:Zone.Identifier`
+            files.push(path);
+        }
+    }
+}
+
+/// `css/main.css` becomes `css/main.<hash>.css`.
+fn fingerprint(logical: &str, hash: &str) -> String {
+    let (dir, file) = logical
+        .rsplit_once('/')
+        .map_or(("", logical), |(d, f)| (d, f));
+    let file = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem}.{hash}.{ext}"),
+        _ => format!("{file}.{hash}"),
+    };
+    if dir.is_empty() {
+        file
+    } else {
+        format!("{dir}/{file}")
+    }
+}
@@ -0,0 +1,324 @@
+## Layout
+
+nav-home = Home
+nav-authors = Authors
+nav-reading = Reading
+nav-pending = To read
+nav-done = Finished
+nav-clubs = Clubs
+nav-events = Events
+nav-venues = Venues
+profile = Profile
+profile-member-since = Member since { $date }
+sign-in = Sign in
+sign-up = Sign up
+sign-out = Sign out
+guest-mode = Guest mode
+impersonation-banner = Signed in as { $username } for support
+impersonation-stop = Back to your account
+language = Language
+theme-toggle = Switch theme
+tagline = Book tracker
+welcome = Welcome
+search-placeholder = Search
+search-users = People
+search-empty = Nothing found
+
+## Pages
+
+not-found-title = Page not found
+not-found-text = The requested page ({ $uri }) could not be found.
+email-change-title = Email change
+email-changed = Your account email is now { $email }.
+preferences-title = Settings
+export-title = Export my data
+export-description = A ZIP archive of your profile and everything else stored about you, as JSON and CSV. We will email you a download link once it is ready.
+export-request = Request an export
+export-pending = Your export is being prepared, the link will arrive by email.
+moderation-title = Moderation
+moderation-empty = No open reports
+moderation-target = Reported
+moderation-reports = Reports
+moderation-reasons = Reasons
+moderation-last-report = Last report
+moderation-hidden = Hidden
+moderation-dismiss = Dismiss
+moderation-hide = Hide
+moderation-ban = Ban
+moderation-resolved = Reports resolved
+clubs-title = Clubs
+clubs-description = Culture clubs to read, watch and discuss together
+clubs-empty = No clubs yet, start the first one
+club-create = Start a club
+club-member-count = Members: { $count }
+club-you-are-member = You are a member
+club-your-role = You are { $role } of this club
+club-join = Join
+club-leave = Leave
+club-sign-in-to-join = Sign in to join
+club-promote = Promote
+club-demote = Demote
+club-remove = Remove
+club-joined = Welcome to the club
+club-left = You have left the club
+club-member-updated = Member updated
+club-role-owner = owner
+club-role-admin = admin
+club-role-member = member
+club-events = Upcoming events
+events-title = Events
+events-description = Readings, screenings and meetups coming up
+events-empty = No upcoming events
+event-create = Announce an event
+event-organized-by = Organized by
+event-going-count = Going: { $count }
+event-interested-count = Interested: { $count }
+event-your-rsvp = Your answer: { $status }
+event-rsvp-going = Going
+event-rsvp-interested = Interested
+event-rsvp-withdraw = Withdraw answer
+event-rsvp-saved = Answer saved
+event-sign-in-to-rsvp = Sign in to answer
+club-polls = Polls
+club-polls-empty = No polls yet
+poll-create = Start a poll
+poll-single = One answer
+poll-multiple = Several answers
+poll-closes-at = Open until { $date }
+poll-closed-at = Closed on { $date }
+poll-option-votes = { $count } ({ $percent }%)
+poll-voters = Voted: { $count }
+poll-you-voted = You voted; click an answer again to take it back
+poll-results-always = Results are always shown
+poll-results-after_vote = Results are shown after voting
+poll-results-after_close = Results are shown when the poll closes
+venues-title = Venues
+venues-description = Theaters, museums, cinemas and other places to go
+venues-near = Near you
+venues-near-me = Find venues near me
+venues-near-search = Search here
+venues-near-empty = No venues within 10 km
+venues-all = All venues
+venues-empty = No venues yet
+venues-admin-add = Add a venue
+venue-added = Venue { $name } added
+traffic-title = Traffic
+traffic-window = Over the last { $minutes } min on this app instance
+traffic-total = Total
+traffic-requests = Requests
+traffic-errors-5xx = 5xx errors
+traffic-latency = p50 / p95 / p99, ms
+traffic-slowest-routes = Slowest routes
+traffic-route = Route
+traffic-p50 = p50, ms
+traffic-p95 = p95, ms
+traffic-p99 = p99, ms
+traffic-top-users = Most active users
+traffic-user = User
+traffic-queries = Database queries
+traffic-queries-description = Since the instance started, by total time
+traffic-query = Query
+traffic-calls = Calls
+traffic-errors = Errors
+traffic-slow = Slow
+traffic-total-ms = Total, ms
+traffic-mean-ms = Mean, ms
+traffic-max-ms = Max, ms
+venue-on-map = On the map
+venue-events = Upcoming events here
+venue-distance-m = { $distance } m away
+venue-distance-km = { $distance } km away
+venue-kind-theater = Theater
+venue-kind-museum = Museum
+venue-kind-cinema = Cinema
+venue-kind-library = Library
+venue-kind-concert-hall = Concert hall
+venue-kind-other = Other
+blocks-title = Blocked users
+blocks-description = Blocked users do not find you or your profile. Muted users stay out of your search results.
+blocks-empty = You have not blocked or muted anyone
+block-kind-block = Blocked
+block-kind-mute = Muted
+block-remove = Remove
+block-add = Add
+block-block-done = { $username } is blocked
+block-mute-done = { $username } is muted
+block-removed = Removed from the list
+interests-title = Interests
+interests-description = Pick the genres you follow.
+interests-empty = No genres yet
+genres-admin-title = Genres
+genres-admin-edit = Add or edit a genre
+genres-admin-new = New genre
+genres-admin-top-level = Top level
+genre-added = Genre { $name } added
+genre-saved = Genre { $name } saved
+genre-deleted = Genre deleted
+palette-api-keys = API keys
+palette-curator = Become a curator
+palette-export = Export my data
+palette-admin-badges = Badges
+palette-admin-venues = Manage venues
+palette-admin-genres = Manage genres
+palette-admin-system = System
+palette-admin-traffic = Traffic
+
+## Forms
+
+field-username = Username
+field-email = Email
+field-password = Password
+field-confirm-password = Confirm password
+field-first-name = First name
+field-last-name = Last name
+field-bio = Bio
+markdown-hint = Markdown: **bold**, *italic*, [links](https://example.com), lists, quotes and @username mentions
+field-city = City
+password-strength = Password strength
+remember-me = Remember me
+guest-login = Guest mode (shared device, signs out after 15 minutes)
+reset = Reset
+save = Save
+add = Add
+delete = Delete
+field-language = Interface language
+field-timezone = Timezone
+language-auto = Same as browser
+report = Report
+report-reason = What is wrong?
+report-sent = Thank you, moderators will take a look
+club-name = Name
+club-slug = Address (latin letters, digits and hyphens)
+club-description = Description
+event-title = Title
+event-starts-at = Starts at ({ $timezone })
+event-venue = Venue
+event-description = Description
+poll-question = Question
+poll-options = Answers, one per line
+poll-allow-multiple = Allow several answers
+poll-results = Results
+poll-closes-at-label = Closes at ({ $timezone }), optional
+venue-latitude = Latitude
+venue-longitude = Longitude
+venue-name = Name
+venue-slug = Page address (latin letters, digits and hyphens)
+venue-kind = Kind
+venue-address = Address
+venue-coordinates = Coordinates
+block-kind = Block or mute
+genre-field = Genre
+genre-name = Name
+genre-slug = Address (latin letters, digits and hyphens)
+genre-address = Address
+genre-parent = Part of
+
+## Validation
+
+invalid-csrf = Invalid CSRF token
+invalid-email = Enter a valid email
+validation-length-range = Length must be { $min } to { $max } characters
+validation-length-min = Length must be at least { $min } characters
+validation-length-max = Length must be at most { $max } characters
+validation-length = Invalid length
+validation-invalid = Invalid value
+validation-search-length = Narrow the search: { $min } to { $max } characters
+registration-failed = Could not sign up with these details
+email-taken = This email is already registered
+email-domain-not-allowed = Sign up with this email domain is not available
+email-disposable = Disposable email addresses cannot be used, enter a permanent one
+passwords-mismatch = Passwords do not match
+password-requirements = Password requirements: { $rules }
+password-rule-uppercase = uppercase letter
+password-rule-lowercase = lowercase letter
+password-rule-digit = digit
+password-rule-special = special character
+password-rule-length = { $min } to { $max } characters
+strength-0 = Very weak
+strength-1 = Weak
+strength-2 = Fair
+strength-3 = Good
+strength-4 = Strong
+username-length = Usernames are { $min } to { $max } characters long
+username-charset = Use latin letters, digits, dots, hyphens and underscores
+username-reserved = This name is reserved
+username-taken = This username is taken
+username-check-failed = Could not check the username
+captcha-retry = Please complete the check again
+captcha-unavailable = The check is unavailable, try again later
+email-change-invalid = The link is invalid or has already been used
+email-change-expired = The link has expired, request the email change again
+email-change-current = This is already your email
+email-change-failed = Could not change the email
+email-change-confirm-subject = Confirm your new email
+email-change-confirm-body = To change the email of account { $username } to this address, open this link within a day: { $link }
+email-change-notice-subject = Email change requested
+email-change-notice-body = An email change to { $email } was requested for account { $username }. If it wasn't you, change your password.
+preferences-invalid = Pick a language and timezone from the list
+report-not-found = Nothing to report here
+report-duplicate = You have already reported this
+report-own = You cannot report your own content
+report-reason-required = Tell us what is wrong
+report-reason-too-long = Keep the reason under { $max } characters
+moderation-not-bannable = Only profile owners can be banned
+moderation-failed = Could not process the report
+club-not-found = Club not found
+club-slug-length = Addresses are { $min } to { $max } characters long
+club-slug-charset = Use lowercase latin letters, digits and hyphens inside
+club-slug-reserved = This address is reserved
+club-slug-taken = This address is taken
+club-name-required = Name the club
+club-name-too-long = Keep the name under { $max } characters
+club-description-too-long = Keep the description under { $max } characters
+club-forbidden = Your role in the club does not allow this
+club-last-owner = Make someone else an owner before leaving
+club-failed = Could not update the club
+event-not-found = Event not found
+event-title-required = Give the event a title
+event-title-too-long = Keep the title under { $max } characters
+event-venue-too-long = Keep the venue under { $max } characters
+event-description-too-long = Keep the description under { $max } characters
+event-start-invalid = Enter the start date and time
+event-start-past = The event must start in the future
+event-forbidden = Only club admins and owners announce club events
+event-started = The event has already started
+event-failed = Could not save the event
+event-reminder-subject = Reminder: { $title }
+event-reminder-going = { $username }, you are going to "{ $title }". It starts { $starts_at }{ $place }. Details: { $link }
+event-reminder-interested = { $username }, you were interested in "{ $title }". It starts { $starts_at }{ $place }. Details: { $link }
+poll-not-found = Poll not found
+poll-question-required = Ask a question
+poll-question-too-long = The question must be at most { $max } characters
+poll-too-few-options = Give at least { $min } answers
+poll-too-many-options = Give at most { $max } answers
+poll-option-too-long = Answers must be at most { $max } characters
+poll-deadline-invalid = Enter the closing date and time
+poll-deadline-past = The poll must close in the future
+poll-forbidden = Only club admins and owners start polls
+poll-members-only = Only club members vote
+poll-closed = The poll is closed
+poll-failed = Could not save the poll
+venue-not-found = Venue not found
+venue-slug-taken = This address is taken
+venue-name-required = Name the venue
+venue-name-too-long = Keep the name under { $max } characters
+venue-address-too-long = Keep the address under { $max } characters
+venue-kind-unknown = Choose the kind of venue
+venue-coordinates-invalid = Enter latitude from -90 to 90 and longitude from -180 to 180
+venue-forbidden = Only admins add venues
+venue-failed = Could not save the venue
+block-user-not-found = No user with that username
+block-self = You cannot block yourself
+block-failed = Could not update blocks
+genre-not-found = Genre not found
+genre-slug-taken = This address is taken
+genre-name-required = Name the genre
+genre-name-too-long = Keep the name under { $max } characters
+genre-parent-invalid = Choose an existing genre outside this one
+genre-has-subgenres = Move or delete the subgenres first
+genre-forbidden = Only admins manage genres
+genre-failed = Could not save the genre
+export-link-invalid = The link is invalid or has expired, request a new export
+export-failed = Could not prepare the export
+export-ready-subject = Your data export is ready
+export-ready-body = The data of account { $username } is ready. Download it within { $hours } hours while signed in: { $link }
@@ -0,0 +1,324 @@
+## Layout
+
+nav-home = Главная
+nav-authors = Авторы
+nav-reading = Читаю
+nav-pending = Прочитать
+nav-done = Прочитанные
+nav-clubs = Клубы
+nav-events = События
+nav-venues = Площадки
+profile = Профиль
+profile-member-since = На сайте с { $date }
+sign-in = Войти
+sign-up = Зарегистрироваться
+sign-out = Выйти
+guest-mode = Гостевой режим
+impersonation-banner = Вы вошли как { $username } в режиме поддержки
+impersonation-stop = Вернуться в свой аккаунт
+language = Язык
+theme-toggle = Сменить тему
+tagline = Трекер книг
+welcome = Добро пожаловать
+search-placeholder = Поиск
+search-users = Люди
+search-empty = Ничего не найдено
+
+## Pages
+
+not-found-title = Страница не найдена
+not-found-text = Запрошенная страница ({ $uri }) не найдена.
+email-change-title = Смена почты
+email-changed = Почта аккаунта изменена на { $email }.
+preferences-title = Настройки
+export-title = Выгрузка моих данных
+export-description = ZIP-архив с профилем и всем остальным, что о вас хранится, в JSON и CSV. Когда он будет готов, мы пришлём ссылку на почту.
+export-request = Запросить выгрузку
+export-pending = Выгрузка готовится, ссылка придёт на почту.
+moderation-title = Модерация
+moderation-empty = Открытых жалоб нет
+moderation-target = Объект жалобы
+moderation-reports = Жалобы
+moderation-reasons = Причины
+moderation-last-report = Последняя жалоба
+moderation-hidden = Скрыто
+moderation-dismiss = Отклонить
+moderation-hide = Скрыть
+moderation-ban = Заблокировать
+moderation-resolved = Жалобы рассмотрены
+clubs-title = Клубы
+clubs-description = Культурные клубы, чтобы читать, смотреть и обсуждать вместе
+clubs-empty = Клубов пока нет, создайте первый
+club-create = Создать клуб
+club-member-count = Участники: { $count }
+club-you-are-member = Вы участник
+club-your-role = Ваша роль в клубе: { $role }
+club-join = Вступить
+club-leave = Выйти
+club-sign-in-to-join = Войдите, чтобы вступить
+club-promote = Повысить
+club-demote = Понизить
+club-remove = Исключить
+club-joined = Добро пожаловать в клуб
+club-left = Вы вышли из клуба
+club-member-updated = Участник обновлён
+club-role-owner = владелец
+club-role-admin = администратор
+club-role-member = участник
+club-events = Ближайшие события
+events-title = События
+events-description = Чтения, показы и встречи, которые скоро пройдут
+events-empty = Ближайших событий нет
+event-create = Объявить событие
+event-organized-by = Организатор:
+event-going-count = Пойдут: { $count }
+event-interested-count = Интересуются: { $count }
+event-your-rsvp = Ваш ответ: { $status }
+event-rsvp-going = Пойду
+event-rsvp-interested = Интересно
+event-rsvp-withdraw = Отменить ответ
+event-rsvp-saved = Ответ сохранён
+event-sign-in-to-rsvp = Войдите, чтобы ответить
+club-polls = Опросы
+club-polls-empty = Опросов пока нет
+poll-create = Начать опрос
+poll-single = Один ответ
+poll-multiple = Несколько ответов
+poll-closes-at = Открыт до { $date }
+poll-closed-at = Закрыт { $date }
+poll-option-votes = { $count } ({ $percent }%)
+poll-voters = Проголосовали: { $count }
+poll-you-voted = Вы проголосовали; нажмите на ответ ещё раз, чтобы отменить его
+poll-results-always = Результаты видны всегда
+poll-results-after_vote = Результаты видны после голосования
+poll-results-after_close = Результаты видны после закрытия опроса
+venues-title = Площадки
+venues-description = Театры, музеи, кинотеатры и другие места, куда можно сходить
+venues-near = Рядом с вами
+venues-near-me = Найти площадки рядом
+venues-near-search = Искать здесь
+venues-near-empty = В радиусе 10 км площадок нет
+venues-all = Все площадки
+venues-empty = Площадок пока нет
+venues-admin-add = Добавить площадку
+venue-added = Площадка { $name } добавлена
+traffic-title = Трафик
+traffic-window = За последние { $minutes } мин. на этом экземпляре приложения
+traffic-total = Всего
+traffic-requests = Запросов
+traffic-errors-5xx = Ошибок 5xx
+traffic-latency = p50 / p95 / p99, мс
+traffic-slowest-routes = Самые медленные маршруты
+traffic-route = Маршрут
+traffic-p50 = p50, мс
+traffic-p95 = p95, мс
+traffic-p99 = p99, мс
+traffic-top-users = Самые активные пользователи
+traffic-user = Пользователь
+traffic-queries = Запросы к базе данных
+traffic-queries-description = С запуска экземпляра, по суммарному времени
+traffic-query = Запрос
+traffic-calls = Вызовов
+traffic-errors = Ошибок
+traffic-slow = Медленных
+traffic-total-ms = Всего, мс
+traffic-mean-ms = Среднее, мс
+traffic-max-ms = Максимум, мс
+venue-on-map = На карте
+venue-events = Ближайшие события здесь
+venue-distance-m = { $distance } м
+venue-distance-km = { $distance } км
+venue-kind-theater = Театр
+venue-kind-museum = Музей
+venue-kind-cinema = Кинотеатр
+venue-kind-library = Библиотека
+venue-kind-concert-hall = Концертный зал
+venue-kind-other = Другое
+blocks-title = Блокировки
+blocks-description = Заблокированные пользователи не найдут вас и ваш профиль. Скрытые пользователи пропадут из вашего поиска.
+blocks-empty = Вы никого не блокировали и не скрывали
+block-kind-block = Заблокирован
+block-kind-mute = Скрыт
+block-remove = Убрать
+block-add = Добавить
+block-block-done = { $username } заблокирован
+block-mute-done = { $username } скрыт
+block-removed = Убран из списка
+interests-title = Интересы
+interests-description = Отметьте жанры, которые вам интересны.
+interests-empty = Жанров пока нет
+genres-admin-title = Жанры
+genres-admin-edit = Добавить или изменить жанр
+genres-admin-new = Новый жанр
+genres-admin-top-level = Верхний уровень
+genre-added = Жанр { $name } добавлен
+genre-saved = Жанр { $name } сохранён
+genre-deleted = Жанр удалён
+palette-api-keys = API-ключи
+palette-curator = Стать куратором
+palette-export = Выгрузить мои данные
+palette-admin-badges = Значки
+palette-admin-venues = Управление площадками
+palette-admin-genres = Управление жанрами
+palette-admin-system = Система
+palette-admin-traffic = Трафик
+
+## Forms
+
+field-username = Имя пользователя
+field-email = Email
+field-password = Пароль
+field-confirm-password = Повторите пароль
+field-first-name = Имя
+field-last-name = Фамилия
+field-bio = О себе
+markdown-hint = Markdown: **жирный**, *курсив*, [ссылки](https://example.com), списки, цитаты и упоминания @username
+field-city = Город
+password-strength = Надёжность пароля
+remember-me = Запомнить меня
+guest-login = Гостевой режим (чужое устройство, выход через 15 минут)
+reset = Сбросить
+save = Сохранить
+add = Добавить
+delete = Удалить
+field-language = Язык интерфейса
+field-timezone = Часовой пояс
+language-auto = Как в браузере
+report = Пожаловаться
+report-reason = Что не так?
+report-sent = Спасибо, модераторы посмотрят
+club-name = Название
+club-slug = Адрес (латинские буквы, цифры и дефисы)
+club-description = Описание
+event-title = Название
+event-starts-at = Начало ({ $timezone })
+event-venue = Место
+event-description = Описание
+poll-question = Вопрос
+poll-options = Варианты ответа, по одному в строке
+poll-allow-multiple = Можно выбрать несколько
+poll-results = Результаты
+poll-closes-at-label = Закрывается ({ $timezone }), необязательно
+venue-latitude = Широта
+venue-longitude = Долгота
+venue-name = Название
+venue-slug = Адрес страницы (латиница, цифры и дефисы)
+venue-kind = Тип
+venue-address = Почтовый адрес
+venue-coordinates = Координаты
+block-kind = Заблокировать или скрыть
+genre-field = Жанр
+genre-name = Название
+genre-slug = Адрес (латиница, цифры и дефисы)
+genre-address = Адрес
+genre-parent = Входит в
+
+## Validation
+
+invalid-csrf = Недействительный CSRF-токен
+invalid-email = Введите корректный email
+validation-length-range = Длина должна быть от { $min } до { $max } символов
+validation-length-min = Длина должна быть не меньше { $min } символов
+validation-length-max = Длина должна быть не больше { $max } символов
+validation-length = Некорректная длина
+validation-invalid = Некорректное значение
+validation-search-length = Уточните запрос: от { $min } до { $max } символов
+registration-failed = Не удалось зарегистрироваться с этими данными
+email-taken = Почта уже зарегистрирована
+email-domain-not-allowed = Регистрация с почтой на этом домене недоступна
+email-disposable = Одноразовые почтовые ящики не подходят, укажите постоянный адрес
+passwords-mismatch = Пароли не совпадают
+password-requirements = Требования к паролю: { $rules }
+password-rule-uppercase = Заглавная буква
+password-rule-lowercase = строчная буква
+password-rule-digit = цифра
+password-rule-special = спецсимвол
+password-rule-length = длина от { $min } до { $max } символов
+strength-0 = Очень слабый
+strength-1 = Слабый
+strength-2 = Средний
+strength-3 = Хороший
+strength-4 = Надёжный
+username-length = Длина имени пользователя от { $min } до { $max } символов
+username-charset = Допустимы латинские буквы, цифры, точка, дефис и подчёркивание
+username-reserved = Это имя зарезервировано
+username-taken = Имя пользователя уже занято
+username-check-failed = Не удалось проверить имя пользователя
+captcha-retry = Пройдите проверку ещё раз
+captcha-unavailable = Проверка недоступна, попробуйте позже
+email-change-invalid = Ссылка недействительна или уже использована
+email-change-expired = Срок действия ссылки истёк, запросите смену почты ещё раз
+email-change-current = Это ваш текущий адрес
+email-change-failed = Не удалось сменить почту
+email-change-confirm-subject = Подтвердите новый адрес
+email-change-confirm-body = Чтобы сменить почту аккаунта { $username } на этот адрес, откройте ссылку в течение суток: { $link }
+email-change-notice-subject = Запрошена смена почты
+email-change-notice-body = Для аккаунта { $username } запрошена смена почты на { $email }. Если это были не вы, смените пароль.
+preferences-invalid = Выберите язык и часовой пояс из списка
+report-not-found = Жаловаться не на что
+report-duplicate = Вы уже пожаловались на это
+report-own = Нельзя пожаловаться на себя
+report-reason-required = Расскажите, что не так
+report-reason-too-long = Причина должна быть короче { $max } символов
+moderation-not-bannable = Заблокировать можно только владельца профиля
+moderation-failed = Не удалось обработать жалобу
+club-not-found = Клуб не найден
+club-slug-length = Адрес должен быть от { $min } до { $max } символов
+club-slug-charset = Используйте строчные латинские буквы, цифры и дефисы внутри
+club-slug-reserved = Этот адрес зарезервирован
+club-slug-taken = Этот адрес занят
+club-name-required = Назовите клуб
+club-name-too-long = Название должно быть короче { $max } символов
+club-description-too-long = Описание должно быть короче { $max } символов
+club-forbidden = Ваша роль в клубе этого не позволяет
+club-last-owner = Прежде чем выйти, сделайте владельцем кого-то ещё
+club-failed = Не удалось обновить клуб
+event-not-found = Событие не найдено
+event-title-required = Назовите событие
+event-title-too-long = Название не длиннее { $max } символов
+event-venue-too-long = Место не длиннее { $max } символов
+event-description-too-long = Описание не длиннее { $max } символов
+event-start-invalid = Укажите дату и время начала
+event-start-past = Событие должно начаться в будущем
+event-forbidden = События клуба объявляют только его администраторы и владельцы
+event-started = Событие уже началось
+event-failed = Не удалось сохранить событие
+event-reminder-subject = Напоминание: { $title }
+event-reminder-going = { $username }, вы собирались пойти на событие «{ $title }». Оно начнётся { $starts_at }{ $place }. Подробности: { $link }
+event-reminder-interested = { $username }, вас заинтересовало событие «{ $title }». Оно начнётся { $starts_at }{ $place }. Подробности: { $link }
+poll-not-found = Опрос не найден
+poll-question-required = Задайте вопрос
+poll-question-too-long = Вопрос не длиннее { $max } символов
+poll-too-few-options = Укажите хотя бы { $min } варианта ответа
+poll-too-many-options = Не больше { $max } вариантов ответа
+poll-option-too-long = Вариант ответа не длиннее { $max } символов
+poll-deadline-invalid = Укажите дату и время закрытия
+poll-deadline-past = Опрос должен закрыться в будущем
+poll-forbidden = Опросы в клубе начинают только его администраторы и владельцы
+poll-members-only = Голосуют только участники клуба
+poll-closed = Опрос закрыт
+poll-failed = Не удалось сохранить опрос
+venue-not-found = Площадка не найдена
+venue-slug-taken = Этот адрес занят
+venue-name-required = Назовите площадку
+venue-name-too-long = Название не длиннее { $max } символов
+venue-address-too-long = Адрес не длиннее { $max } символов
+venue-kind-unknown = Выберите тип площадки
+venue-coordinates-invalid = Укажите широту от -90 до 90 и долготу от -180 до 180
+venue-forbidden = Площадки добавляют только администраторы
+venue-failed = Не удалось сохранить площадку
+block-user-not-found = Пользователь с таким именем не найден
+block-self = Нельзя заблокировать себя
+block-failed = Не удалось обновить блокировки
+genre-not-found = Жанр не найден
+genre-slug-taken = Этот адрес занят
+genre-name-required = Укажите название жанра
+genre-name-too-long = Название не длиннее { $max } символов
+genre-parent-invalid = Выберите существующий жанр вне этого
+genre-has-subgenres = Сначала перенесите или удалите поджанры
+genre-forbidden = Жанрами управляют только администраторы
+genre-failed = Не удалось сохранить жанр
+export-link-invalid = Ссылка недействительна или устарела, запросите выгрузку заново
+export-failed = Не удалось подготовить выгрузку
+export-ready-subject = Выгрузка данных готова
+export-ready-body = Данные аккаунта { $username } готовы. Скачайте их в течение { $hours } ч., войдя в аккаунт: { $link }
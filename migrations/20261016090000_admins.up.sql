@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS curator_applications;
+
+ALTER TABLE users DROP COLUMN IF EXISTS badge;
+
@@ -0,0 +1,16 @@
+-- Add up migration script here
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS badge VARCHAR;
+
+CREATE TABLE IF NOT EXISTS curator_applications (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  motivation TEXT NOT NULL,
+  status VARCHAR NOT NULL DEFAULT 'pending',
+  reviewed_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  reviewed_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS curator_applications_status_idx ON curator_applications (status);
+
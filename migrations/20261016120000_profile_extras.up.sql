@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS profile_extras JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS username_history;
+
+DROP INDEX IF EXISTS users_username_lower_idx;
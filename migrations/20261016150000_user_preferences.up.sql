@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS locale VARCHAR,
+ADD COLUMN IF NOT EXISTS timezone VARCHAR NOT NULL DEFAULT 'UTC';
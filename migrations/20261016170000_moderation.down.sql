@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS hidden_content;
+
+DROP TABLE IF EXISTS reports;
+
+ALTER TABLE users DROP COLUMN IF EXISTS banned_at;
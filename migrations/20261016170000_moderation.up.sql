@@ -0,0 +1,29 @@
+-- Add up migration script here
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS banned_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS reports (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  target_kind VARCHAR NOT NULL,
+  target_id UUID NOT NULL,
+  reporter_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  reason TEXT NOT NULL,
+  status VARCHAR NOT NULL DEFAULT 'open',
+  resolved_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  resolved_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One open report per reporter and target
+CREATE UNIQUE INDEX IF NOT EXISTS reports_open_reporter_idx ON reports (target_kind, target_id, reporter_id)
+WHERE
+  status = 'open';
+
+CREATE TABLE IF NOT EXISTS hidden_content (
+  target_kind VARCHAR NOT NULL,
+  target_id UUID NOT NULL,
+  -- NULL when hidden automatically after reports
+  hidden_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  hidden_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (target_kind, target_id)
+);
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Bios saved earlier are rendered on read until they are edited
+ALTER TABLE users ADD COLUMN IF NOT EXISTS bio_html TEXT;
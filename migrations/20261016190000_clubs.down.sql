@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS club_members;
+
+DROP TABLE IF EXISTS clubs;
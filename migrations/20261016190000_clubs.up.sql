@@ -0,0 +1,22 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS clubs (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  slug VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS clubs_slug_idx ON clubs (LOWER(slug));
+
+CREATE TABLE IF NOT EXISTS club_members (
+  club_id UUID NOT NULL REFERENCES clubs (id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  -- owner, admin or member
+  role VARCHAR NOT NULL DEFAULT 'member',
+  joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (club_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS club_members_user_idx ON club_members (user_id);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS event_rsvps;
+
+DROP TABLE IF EXISTS events;
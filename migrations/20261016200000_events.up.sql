@@ -0,0 +1,26 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS events (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  title VARCHAR NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  venue VARCHAR NOT NULL DEFAULT '',
+  starts_at TIMESTAMPTZ NOT NULL,
+  created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  -- Set for events organized by a club
+  club_id UUID REFERENCES clubs (id) ON DELETE CASCADE,
+  reminder_sent_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS events_starts_at_idx ON events (starts_at);
+
+CREATE INDEX IF NOT EXISTS events_club_idx ON events (club_id, starts_at);
+
+CREATE TABLE IF NOT EXISTS event_rsvps (
+  event_id UUID NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  -- going or interested
+  status VARCHAR NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (event_id, user_id)
+);
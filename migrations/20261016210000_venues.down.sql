@@ -0,0 +1,9 @@
+-- Add down migration script here
+ALTER TABLE events
+DROP COLUMN IF EXISTS venue_id;
+
+DROP TABLE IF EXISTS venues;
+
+DROP EXTENSION IF EXISTS earthdistance;
+
+DROP EXTENSION IF EXISTS cube;
@@ -0,0 +1,27 @@
+-- Add up migration script here
+-- Great-circle distances for "near me" search; both are trusted extensions
+CREATE EXTENSION IF NOT EXISTS cube;
+
+CREATE EXTENSION IF NOT EXISTS earthdistance;
+
+CREATE TABLE IF NOT EXISTS venues (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  slug VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  -- theater, museum, cinema, library, concert_hall or other
+  kind VARCHAR NOT NULL,
+  address VARCHAR NOT NULL DEFAULT '',
+  latitude DOUBLE PRECISION NOT NULL CHECK (latitude BETWEEN -90 AND 90),
+  longitude DOUBLE PRECISION NOT NULL CHECK (longitude BETWEEN -180 AND 180),
+  created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS venues_slug_idx ON venues (LOWER(slug));
+
+CREATE INDEX IF NOT EXISTS venues_location_idx ON venues USING gist (ll_to_earth (latitude, longitude));
+
+ALTER TABLE events
+ADD COLUMN IF NOT EXISTS venue_id UUID REFERENCES venues (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS events_venue_idx ON events (venue_id, starts_at);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- One relation per pair: a block replaces a mute and vice versa
+CREATE TABLE IF NOT EXISTS user_blocks (
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  target_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  -- block or mute
+  kind VARCHAR NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, target_id),
+  CHECK (user_id <> target_id)
+);
+
+CREATE INDEX IF NOT EXISTS user_blocks_target_idx ON user_blocks (target_id);
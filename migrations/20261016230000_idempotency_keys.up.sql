@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- Responses to POST API calls, replayed when a client retries with the same
+-- Idempotency-Key
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+  -- SHA-256 of the client's credentials and its key
+  key VARCHAR PRIMARY KEY,
+  -- SHA-256 of the method, path and body of the first request
+  request_hash VARCHAR NOT NULL,
+  -- NULL while the first request is still running
+  status SMALLINT,
+  content_type VARCHAR,
+  body BYTEA,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idempotency_keys_created_at_idx ON idempotency_keys (created_at);
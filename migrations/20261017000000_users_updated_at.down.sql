@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS users_touch_updated_at ON users;
+
+DROP INDEX IF EXISTS users_updated_at_idx;
+
+ALTER TABLE users
+DROP COLUMN IF EXISTS updated_at;
+
+DROP FUNCTION IF EXISTS touch_updated_at ();
@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- Sets updated_at on every update that changes the row
+CREATE OR REPLACE FUNCTION touch_updated_at () RETURNS TRIGGER AS $$
+BEGIN
+  NEW.updated_at = NOW();
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+UPDATE users
+SET
+  updated_at = created_at;
+
+CREATE INDEX IF NOT EXISTS users_updated_at_idx ON users (updated_at);
+
+CREATE OR REPLACE TRIGGER users_touch_updated_at BEFORE
+UPDATE ON users FOR EACH ROW WHEN (OLD IS DISTINCT FROM NEW)
+EXECUTE FUNCTION touch_updated_at ();
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Trigram similarity for search suggestions with typos; a trusted extension
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS users_username_trgm_idx ON users USING gin (username gin_trgm_ops);
+
+-- Same expression as queries/users/suggest_similar.sql, which the index serves
+CREATE INDEX IF NOT EXISTS users_full_name_trgm_idx ON users USING gin (
+  (COALESCE(first_name, '') || ' ' || COALESCE(last_name, '')) gin_trgm_ops
+);
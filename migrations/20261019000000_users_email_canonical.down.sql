@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS users_email_canonical_key;
+
+ALTER TABLE users
+DROP COLUMN IF EXISTS email_canonical;
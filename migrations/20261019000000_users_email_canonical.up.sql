@@ -0,0 +1,61 @@
+-- Add up migration script here
+-- Normalized email used to find duplicate accounts; email keeps the address
+-- as the user typed it
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS email_canonical VARCHAR;
+
+-- Backfill with the default folding: gmail.com and googlemail.com lose dots
+-- and +tags. When existing accounts fold to the same address, the one already
+-- written that way, else the oldest, gets it; the others keep their email
+WITH lowered AS (
+  SELECT
+    id,
+    lower(email) AS email,
+    created_at
+  FROM
+    users
+),
+folded AS (
+  SELECT
+    id,
+    CASE
+      WHEN split_part(email, '@', 2) IN ('gmail.com', 'googlemail.com') THEN replace(split_part(split_part(email, '@', 1), '+', 1), '.', '') || '@gmail.com'
+      ELSE email
+    END AS canonical,
+    email,
+    created_at
+  FROM
+    lowered
+),
+ranked AS (
+  SELECT
+    id,
+    email,
+    canonical,
+    ROW_NUMBER() OVER (
+      PARTITION BY
+        canonical
+      ORDER BY
+        email = canonical DESC,
+        created_at,
+        id
+    ) AS rank
+  FROM
+    folded
+)
+UPDATE users
+SET
+  email_canonical = CASE
+    WHEN ranked.rank = 1 THEN ranked.canonical
+    ELSE ranked.email
+  END
+FROM
+  ranked
+WHERE
+  users.id = ranked.id;
+
+ALTER TABLE users
+ALTER COLUMN email_canonical
+SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_email_canonical_key ON users (email_canonical);
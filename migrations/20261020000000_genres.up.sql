@@ -0,0 +1,25 @@
+-- Add up migration script here
+-- Curated genres and interests; a genre may sit under a broader one
+CREATE TABLE IF NOT EXISTS genres (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  slug VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  -- Deleting a genre with subgenres fails; they are moved or deleted first
+  parent_id UUID REFERENCES genres (id),
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  CHECK (parent_id <> id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS genres_slug_idx ON genres (LOWER(slug));
+
+CREATE INDEX IF NOT EXISTS genres_parent_idx ON genres (parent_id);
+
+-- Genres a user picked as their interests
+CREATE TABLE IF NOT EXISTS user_interests (
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  genre_id UUID NOT NULL REFERENCES genres (id) ON DELETE CASCADE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, genre_id)
+);
+
+CREATE INDEX IF NOT EXISTS user_interests_genre_idx ON user_interests (genre_id);
@@ -0,0 +1,34 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS polls (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  club_id UUID NOT NULL REFERENCES clubs (id) ON DELETE CASCADE,
+  question VARCHAR NOT NULL,
+  -- Whether voters may pick several options
+  multiple BOOLEAN NOT NULL DEFAULT FALSE,
+  -- always, after_vote or after_close
+  results VARCHAR NOT NULL DEFAULT 'always',
+  -- NULL keeps the poll open
+  closes_at TIMESTAMPTZ,
+  created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS polls_club_idx ON polls (club_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS poll_options (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  poll_id UUID NOT NULL REFERENCES polls (id) ON DELETE CASCADE,
+  position INT NOT NULL,
+  text VARCHAR NOT NULL,
+  UNIQUE (poll_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS poll_votes (
+  poll_id UUID NOT NULL REFERENCES polls (id) ON DELETE CASCADE,
+  option_id UUID NOT NULL REFERENCES poll_options (id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (option_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS poll_votes_poll_user_idx ON poll_votes (poll_id, user_id);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS curator_applications_pending_user_idx;
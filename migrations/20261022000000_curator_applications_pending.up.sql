@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- Keep the oldest of any pending duplicates left by concurrent applications
+UPDATE
+  curator_applications a
+SET
+  status = 'rejected',
+  reviewed_at = NOW()
+WHERE
+  a.status = 'pending'
+  AND EXISTS (
+    SELECT
+      1
+    FROM
+      curator_applications b
+    WHERE
+      b.user_id = a.user_id
+      AND b.status = 'pending'
+      AND (b.created_at, b.id) < (a.created_at, a.id));
+
+CREATE UNIQUE INDEX IF NOT EXISTS curator_applications_pending_user_idx ON curator_applications (user_id)
+WHERE
+  status = 'pending';
@@ -0,0 +1,17 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS data_exports (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+  user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  -- The rest is set once the background job built the archive
+  token_hash VARCHAR UNIQUE,
+  -- ZIP archive, kept in the database until there is file storage
+  archive BYTEA,
+  ready_at TIMESTAMPTZ,
+  expires_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One export per user waits for the job at a time
+CREATE UNIQUE INDEX IF NOT EXISTS data_exports_pending_user_idx ON data_exports (user_id)
+WHERE
+  ready_at IS NULL;
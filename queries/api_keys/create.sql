@@ -0,0 +1,7 @@
+-- Create a new API key
+-- Returns the created key record
+INSERT INTO api_keys (user_id, name, prefix, key_hash, scopes, rate_limit_per_minute)
+  VALUES ($1, $2, $3, $4, $5, $6)
+RETURNING
+  id, user_id, name, prefix, key_hash, scopes, rate_limit_per_minute, last_used_at, created_at;
+
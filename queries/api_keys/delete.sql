@@ -0,0 +1,8 @@
+-- Delete API key of a user
+-- Returns deleted key id or null if not found
+DELETE FROM api_keys
+WHERE id = $1
+  AND user_id = $2
+RETURNING
+  id;
+
@@ -0,0 +1,17 @@
+-- Get API keys by their public prefix, trying each given prefix length
+-- Returns the matching key records
+SELECT
+  id,
+  user_id,
+  name,
+  prefix,
+  key_hash,
+  scopes,
+  rate_limit_per_minute,
+  last_used_at,
+  created_at
+FROM
+  api_keys
+WHERE
+  prefix = ANY ($1::VARCHAR[]);
+
@@ -0,0 +1,19 @@
+-- List API keys of a user
+-- Returns key records ordered by created_at DESC
+SELECT
+  id,
+  user_id,
+  name,
+  prefix,
+  key_hash,
+  scopes,
+  rate_limit_per_minute,
+  last_used_at,
+  created_at
+FROM
+  api_keys
+WHERE
+  user_id = $1
+ORDER BY
+  created_at DESC;
+
@@ -0,0 +1,8 @@
+-- Record API key usage
+UPDATE
+  api_keys
+SET
+  last_used_at = NOW()
+WHERE
+  id = $1;
+
@@ -0,0 +1,6 @@
+-- Add a member, keeping the role of an existing one
+INSERT INTO
+  club_members (club_id, user_id, role)
+VALUES
+  ($1, $2, $3)
+ON CONFLICT (club_id, user_id) DO NOTHING;
@@ -0,0 +1,8 @@
+-- Number of owners of a club
+SELECT
+  COUNT(*) AS "count!"
+FROM
+  club_members
+WHERE
+  club_id = $1
+  AND role = 'owner';
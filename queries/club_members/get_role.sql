@@ -0,0 +1,8 @@
+-- Role of a user in a club, no rows when not a member
+SELECT
+  role
+FROM
+  club_members
+WHERE
+  club_id = $1
+  AND user_id = $2;
@@ -0,0 +1,20 @@
+-- List the members of a club, owners and admins first
+-- Banned users are left out
+SELECT
+  m.user_id,
+  u.username,
+  m.role,
+  m.joined_at
+FROM
+  club_members m
+  JOIN users u ON u.id = m.user_id
+WHERE
+  m.club_id = $1
+  AND u.banned_at IS NULL
+ORDER BY
+  CASE m.role
+    WHEN 'owner' THEN 0
+    WHEN 'admin' THEN 1
+    ELSE 2
+  END,
+  m.joined_at;
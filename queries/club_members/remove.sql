@@ -0,0 +1,5 @@
+-- Remove a member from a club
+DELETE FROM club_members
+WHERE
+  club_id = $1
+  AND user_id = $2;
@@ -0,0 +1,7 @@
+-- Change the role of a member
+UPDATE club_members
+SET
+  role = $3
+WHERE
+  club_id = $1
+  AND user_id = $2;
@@ -0,0 +1,36 @@
+-- Create a club with its creator as the owner
+-- Returns the club record or null if the slug is taken
+WITH
+  club AS (
+    INSERT INTO
+      clubs (slug, name, description, created_by)
+    VALUES
+      ($1, $2, $3, $4)
+    ON CONFLICT DO NOTHING
+    RETURNING
+      id,
+      slug,
+      name,
+      description,
+      created_by,
+      created_at
+  ),
+  owner AS (
+    INSERT INTO
+      club_members (club_id, user_id, role)
+    SELECT
+      id,
+      $4,
+      'owner'
+    FROM
+      club
+  )
+SELECT
+  id AS "id!",
+  slug AS "slug!",
+  name AS "name!",
+  description AS "description!",
+  created_by,
+  created_at AS "created_at!"
+FROM
+  club;
@@ -0,0 +1,12 @@
+-- Get a club by slug, case-insensitively
+SELECT
+  id,
+  slug,
+  name,
+  description,
+  created_by,
+  created_at
+FROM
+  clubs
+WHERE
+  LOWER(slug) = LOWER($1);
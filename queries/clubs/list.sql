@@ -0,0 +1,18 @@
+-- List clubs with member counts, biggest first
+-- is_member tells whether user $1 belongs to the club
+SELECT
+  c.slug,
+  c.name,
+  c.description,
+  COUNT(m.user_id) AS "member_count!",
+  COALESCE(BOOL_OR(m.user_id = $1), FALSE) AS "is_member!"
+FROM
+  clubs c
+  LEFT JOIN club_members m ON m.club_id = c.id
+GROUP BY
+  c.id
+ORDER BY
+  COUNT(m.user_id) DESC,
+  c.created_at DESC
+LIMIT
+  $2;
@@ -0,0 +1,7 @@
+-- Create a curator application
+-- Returns the created application record
+INSERT INTO curator_applications (user_id, motivation)
+  VALUES ($1, $2)
+RETURNING
+  id, user_id, motivation, status, reviewed_by, reviewed_at, created_at;
+
@@ -0,0 +1,16 @@
+-- Get pending curator application of a user
+-- Returns application record or null if not found
+SELECT
+  id,
+  user_id,
+  motivation,
+  status,
+  reviewed_by,
+  reviewed_at,
+  created_at
+FROM
+  curator_applications
+WHERE
+  user_id = $1
+  AND status = 'pending';
+
@@ -0,0 +1,16 @@
+-- List pending curator applications with applicant usernames
+-- Returns application records ordered by created_at
+SELECT
+  a.id,
+  a.user_id,
+  u.username,
+  a.motivation,
+  a.created_at
+FROM
+  curator_applications a
+  JOIN users u ON u.id = a.user_id
+WHERE
+  a.status = 'pending'
+ORDER BY
+  a.created_at;
+
@@ -0,0 +1,14 @@
+-- Resolve pending curator application
+-- Returns resolved application record or null if not found
+UPDATE
+  curator_applications
+SET
+  status = $2,
+  reviewed_by = $3,
+  reviewed_at = NOW()
+WHERE
+  id = $1
+  AND status = 'pending'
+RETURNING
+  id, user_id, motivation, status, reviewed_by, reviewed_at, created_at;
+
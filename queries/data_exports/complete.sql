@@ -0,0 +1,18 @@
+-- Store the built archive of a waiting export with its download token
+-- Returns the export, no row when another run completed it first
+UPDATE
+  data_exports
+SET
+  archive = $2,
+  token_hash = $3,
+  expires_at = $4,
+  ready_at = NOW()
+WHERE
+  id = $1
+  AND ready_at IS NULL
+RETURNING
+  id,
+  user_id,
+  ready_at,
+  expires_at,
+  created_at;
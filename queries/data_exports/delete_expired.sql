@@ -0,0 +1,13 @@
+-- Delete up to $1 exports whose download link expired
+DELETE FROM data_exports
+WHERE
+  id IN (
+    SELECT
+      id
+    FROM
+      data_exports
+    WHERE
+      expires_at < NOW()
+    LIMIT
+      $1
+  );
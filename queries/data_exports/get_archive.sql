@@ -0,0 +1,9 @@
+-- The archive of the user's export with the token, while it has not expired
+SELECT
+  archive AS "archive!"
+FROM
+  data_exports
+WHERE
+  token_hash = $1
+  AND user_id = $2
+  AND expires_at > NOW();
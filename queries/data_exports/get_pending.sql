@@ -0,0 +1,12 @@
+-- The user's export waiting for the background job, if any
+SELECT
+  id,
+  user_id,
+  ready_at,
+  expires_at,
+  created_at
+FROM
+  data_exports
+WHERE
+  user_id = $1
+  AND ready_at IS NULL;
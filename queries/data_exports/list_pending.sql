@@ -0,0 +1,15 @@
+-- Up to $1 exports waiting for the background job, oldest first
+SELECT
+  id,
+  user_id,
+  ready_at,
+  expires_at,
+  created_at
+FROM
+  data_exports
+WHERE
+  ready_at IS NULL
+ORDER BY
+  created_at
+LIMIT
+  $1;
@@ -0,0 +1,15 @@
+-- Queue a data export for the user, unless one is already waiting
+-- Returns the queued export, no row when one was waiting
+INSERT INTO
+  data_exports (user_id)
+VALUES
+  ($1)
+ON CONFLICT (user_id)
+WHERE
+  ready_at IS NULL DO NOTHING
+RETURNING
+  id,
+  user_id,
+  ready_at,
+  expires_at,
+  created_at;
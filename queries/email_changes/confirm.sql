@@ -0,0 +1,16 @@
+-- Mark a pending email change as confirmed
+-- Returns the change, or nothing when it was already confirmed
+UPDATE
+  email_changes
+SET
+  confirmed_at = NOW()
+WHERE
+  id = $1
+  AND confirmed_at IS NULL
+RETURNING
+  id,
+  user_id,
+  new_email,
+  expires_at,
+  confirmed_at,
+  created_at;
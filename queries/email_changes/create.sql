@@ -0,0 +1,20 @@
+-- Start an email change, replacing the user's unconfirmed ones
+-- Returns the pending change
+WITH
+  cleared AS (
+    DELETE FROM email_changes
+    WHERE
+      user_id = $1
+      AND confirmed_at IS NULL
+  )
+INSERT INTO
+  email_changes (user_id, new_email, token_hash, expires_at)
+VALUES
+  ($1, $2, $3, $4)
+RETURNING
+  id,
+  user_id,
+  new_email,
+  expires_at,
+  confirmed_at,
+  created_at;
@@ -0,0 +1,15 @@
+-- Delete up to $1 expired unconfirmed email changes
+-- Confirmed changes are kept as account history
+DELETE FROM email_changes
+WHERE
+  id IN (
+    SELECT
+      id
+    FROM
+      email_changes
+    WHERE
+      confirmed_at IS NULL
+      AND expires_at < NOW()
+    LIMIT
+      $1
+  );
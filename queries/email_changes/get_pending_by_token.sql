@@ -0,0 +1,13 @@
+-- Get an unconfirmed email change by token hash, expired ones included
+SELECT
+  id,
+  user_id,
+  new_email,
+  expires_at,
+  confirmed_at,
+  created_at
+FROM
+  email_changes
+WHERE
+  token_hash = $1
+  AND confirmed_at IS NULL;
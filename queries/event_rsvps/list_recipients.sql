@@ -0,0 +1,13 @@
+-- People to remind of an event: everyone going or interested, except banned users
+SELECT
+  u.username,
+  u.email,
+  u.timezone,
+  u.locale,
+  r.status
+FROM
+  event_rsvps r
+  JOIN users u ON u.id = r.user_id
+WHERE
+  r.event_id = $1
+  AND u.banned_at IS NULL;
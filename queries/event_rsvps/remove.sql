@@ -0,0 +1,5 @@
+-- Withdraw an RSVP
+DELETE FROM event_rsvps
+WHERE
+  event_id = $1
+  AND user_id = $2;
@@ -0,0 +1,9 @@
+-- RSVP to an event, replacing an earlier answer
+INSERT INTO
+  event_rsvps (event_id, user_id, status)
+VALUES
+  ($1, $2, $3)
+ON CONFLICT (event_id, user_id) DO UPDATE
+SET
+  status = EXCLUDED.status,
+  created_at = NOW();
@@ -0,0 +1,23 @@
+-- Create an event, optionally organized by a club and held at a venue
+INSERT INTO
+  events (
+    title,
+    description,
+    venue,
+    starts_at,
+    created_by,
+    club_id,
+    venue_id
+  )
+VALUES
+  ($1, $2, $3, $4, $5, $6, $7)
+RETURNING
+  id,
+  title,
+  description,
+  venue,
+  starts_at,
+  created_by,
+  club_id,
+  venue_id,
+  created_at;
@@ -0,0 +1,21 @@
+-- Events starting within the next $1 seconds that have not been reminded of
+SELECT
+  id,
+  title,
+  description,
+  venue,
+  starts_at,
+  created_by,
+  club_id,
+  venue_id,
+  created_at
+FROM
+  events
+WHERE
+  reminder_sent_at IS NULL
+  AND starts_at > NOW()
+  AND starts_at <= NOW() + MAKE_INTERVAL(secs => $1)
+ORDER BY
+  starts_at
+LIMIT
+  $2;
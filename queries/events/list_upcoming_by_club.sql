@@ -0,0 +1,40 @@
+-- List the upcoming events of club $2, soonest first
+-- Includes the RSVP counts and the RSVP of viewer $1
+SELECT
+  e.id,
+  e.title,
+  e.description,
+  e.venue,
+  e.starts_at,
+  c.slug AS "club_slug?",
+  c.name AS "club_name?",
+  v.slug AS "venue_slug?",
+  v.name AS "venue_name?",
+  COUNT(r.user_id) FILTER (
+    WHERE
+      r.status = 'going'
+  ) AS "going_count!",
+  COUNT(r.user_id) FILTER (
+    WHERE
+      r.status = 'interested'
+  ) AS "interested_count!",
+  MAX(r.status) FILTER (
+    WHERE
+      r.user_id = $1
+  ) AS viewer_status
+FROM
+  events e
+  LEFT JOIN clubs c ON c.id = e.club_id
+  LEFT JOIN venues v ON v.id = e.venue_id
+  LEFT JOIN event_rsvps r ON r.event_id = e.id
+WHERE
+  e.club_id = $2
+  AND e.starts_at > NOW()
+GROUP BY
+  e.id,
+  c.id,
+  v.id
+ORDER BY
+  e.starts_at
+LIMIT
+  $3;
@@ -0,0 +1,6 @@
+-- Record that the reminder for an event was sent
+UPDATE events
+SET
+  reminder_sent_at = NOW()
+WHERE
+  id = $1;
@@ -0,0 +1,13 @@
+-- Add a genre, under parent $3 when given
+-- Returns the genre record or null if the slug is taken
+INSERT INTO
+  genres (slug, name, parent_id)
+VALUES
+  ($1, $2, $3)
+ON CONFLICT DO NOTHING
+RETURNING
+  id,
+  slug,
+  name,
+  parent_id,
+  created_at;
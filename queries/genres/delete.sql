@@ -0,0 +1,4 @@
+-- Delete a genre; fails while it has subgenres
+DELETE FROM genres
+WHERE
+  id = $1;
@@ -0,0 +1,11 @@
+-- Get a genre by ID
+SELECT
+  id,
+  slug,
+  name,
+  parent_id,
+  created_at
+FROM
+  genres
+WHERE
+  id = $1;
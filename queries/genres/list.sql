@@ -0,0 +1,11 @@
+-- List all genres by name; the tree is built from parent_id
+SELECT
+  id,
+  slug,
+  name,
+  parent_id,
+  created_at
+FROM
+  genres
+ORDER BY
+  name;
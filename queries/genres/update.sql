@@ -0,0 +1,40 @@
+-- Rename genre $1 and move it under parent $4, or to the top when null
+-- Returns the genre record, or null when it does not exist or the parent is
+-- the genre itself or one of its subgenres
+WITH RECURSIVE
+  subtree AS (
+    SELECT
+      id
+    FROM
+      genres
+    WHERE
+      id = $1
+    UNION
+    SELECT
+      g.id
+    FROM
+      genres g
+      JOIN subtree s ON g.parent_id = s.id
+  )
+UPDATE genres
+SET
+  slug = $2,
+  name = $3,
+  parent_id = $4
+WHERE
+  id = $1
+  AND (
+    $4::UUID IS NULL
+    OR $4 NOT IN (
+      SELECT
+        id
+      FROM
+        subtree
+    )
+  )
+RETURNING
+  id,
+  slug,
+  name,
+  parent_id,
+  created_at;
@@ -0,0 +1,6 @@
+-- Hide content from public pages; hiding twice keeps the first record
+INSERT INTO
+  hidden_content (target_kind, target_id, hidden_by)
+VALUES
+  ($1, $2, $3)
+ON CONFLICT (target_kind, target_id) DO NOTHING;
@@ -0,0 +1,11 @@
+-- Check whether content is hidden from public pages
+SELECT
+  EXISTS (
+    SELECT
+      1
+    FROM
+      hidden_content
+    WHERE
+      target_kind = $1
+      AND target_id = $2
+  ) AS "hidden!";
@@ -0,0 +1,5 @@
+-- Show previously hidden content again
+DELETE FROM hidden_content
+WHERE
+  target_kind = $1
+  AND target_id = $2;
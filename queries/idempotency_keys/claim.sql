@@ -0,0 +1,17 @@
+-- Claim key $1 for a request hashed $2; a key older than $3 hours is
+-- claimed again. Returns nothing when someone else holds the key.
+INSERT INTO
+  idempotency_keys (key, request_hash)
+VALUES
+  ($1, $2)
+ON CONFLICT (key) DO UPDATE
+SET
+  request_hash = EXCLUDED.request_hash,
+  status = NULL,
+  content_type = NULL,
+  body = NULL,
+  created_at = NOW()
+WHERE
+  idempotency_keys.created_at < NOW() - MAKE_INTERVAL(hours => $3)
+RETURNING
+  key;
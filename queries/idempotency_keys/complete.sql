@@ -0,0 +1,8 @@
+-- Store the response to the request holding key $1
+UPDATE idempotency_keys
+SET
+  status = $2,
+  content_type = $3,
+  body = $4
+WHERE
+  key = $1;
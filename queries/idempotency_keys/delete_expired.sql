@@ -0,0 +1,13 @@
+-- Delete up to $2 keys older than $1 hours
+DELETE FROM idempotency_keys
+WHERE
+  key IN (
+    SELECT
+      key
+    FROM
+      idempotency_keys
+    WHERE
+      created_at < NOW() - MAKE_INTERVAL(hours => $1)
+    LIMIT
+      $2
+  );
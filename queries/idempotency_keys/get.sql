@@ -0,0 +1,10 @@
+-- The request and stored response for key $1
+SELECT
+  request_hash,
+  status,
+  content_type,
+  body
+FROM
+  idempotency_keys
+WHERE
+  key = $1;
@@ -0,0 +1,4 @@
+-- Free key $1 so a retry runs the request again
+DELETE FROM idempotency_keys
+WHERE
+  key = $1;
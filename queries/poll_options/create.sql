@@ -0,0 +1,9 @@
+-- Add options $2 to poll $1, numbered in the given order
+INSERT INTO
+  poll_options (poll_id, position, text)
+SELECT
+  $1,
+  o.position::INT,
+  o.text
+FROM
+  UNNEST($2::TEXT[]) WITH ORDINALITY AS o (text, position);
@@ -0,0 +1,16 @@
+-- Options of poll $1 with their vote counts
+SELECT
+  o.id,
+  o.poll_id,
+  o.position,
+  o.text,
+  COUNT(v.user_id) AS "votes!"
+FROM
+  poll_options o
+  LEFT JOIN poll_votes v ON v.option_id = o.id
+WHERE
+  o.poll_id = $1
+GROUP BY
+  o.id
+ORDER BY
+  o.position;
@@ -0,0 +1,13 @@
+-- Vote for options $2 of poll $1 as user $3, ignoring options of other polls
+INSERT INTO
+  poll_votes (poll_id, option_id, user_id)
+SELECT
+  poll_id,
+  id,
+  $3
+FROM
+  poll_options
+WHERE
+  poll_id = $1
+  AND id = ANY ($2::UUID[])
+ON CONFLICT (option_id, user_id) DO NOTHING;
@@ -0,0 +1,5 @@
+-- Withdraw user $2's votes in poll $1
+DELETE FROM poll_votes
+WHERE
+  poll_id = $1
+  AND user_id = $2;
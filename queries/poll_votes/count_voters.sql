@@ -0,0 +1,7 @@
+-- People who voted in poll $1
+SELECT
+  COUNT(DISTINCT user_id) AS "count!"
+FROM
+  poll_votes
+WHERE
+  poll_id = $1;
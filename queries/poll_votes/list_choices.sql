@@ -0,0 +1,8 @@
+-- Options of poll $1 user $2 voted for
+SELECT
+  option_id
+FROM
+  poll_votes
+WHERE
+  poll_id = $1
+  AND user_id = $2;
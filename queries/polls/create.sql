@@ -0,0 +1,14 @@
+-- Create a poll in club $1; its options are added separately
+INSERT INTO
+  polls (club_id, question, multiple, results, closes_at, created_by)
+VALUES
+  ($1, $2, $3, $4, $5, $6)
+RETURNING
+  id,
+  club_id,
+  question,
+  multiple,
+  results,
+  closes_at,
+  created_by,
+  created_at;
@@ -0,0 +1,13 @@
+SELECT
+  id,
+  club_id,
+  question,
+  multiple,
+  results,
+  closes_at,
+  created_by,
+  created_at
+FROM
+  polls
+WHERE
+  id = $1;
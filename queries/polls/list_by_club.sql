@@ -0,0 +1,18 @@
+-- The newest polls of club $1
+SELECT
+  id,
+  club_id,
+  question,
+  multiple,
+  results,
+  closes_at,
+  created_by,
+  created_at
+FROM
+  polls
+WHERE
+  club_id = $1
+ORDER BY
+  created_at DESC
+LIMIT
+  $2;
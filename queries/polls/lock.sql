@@ -0,0 +1,9 @@
+-- Lock poll $1 until the transaction ends, so changes to its votes apply
+-- one at a time
+SELECT
+  id
+FROM
+  polls
+WHERE
+  id = $1
+FOR UPDATE;
@@ -0,0 +1,9 @@
+-- Count distinct users with an open report on the target
+SELECT
+  COUNT(DISTINCT reporter_id) AS "count!"
+FROM
+  reports
+WHERE
+  target_kind = $1
+  AND target_id = $2
+  AND status = 'open';
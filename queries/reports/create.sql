@@ -0,0 +1,19 @@
+-- Report content, once per reporter while the report is open
+-- Returns the report record or null if already reported
+INSERT INTO
+  reports (target_kind, target_id, reporter_id, reason)
+VALUES
+  ($1, $2, $3, $4)
+ON CONFLICT (target_kind, target_id, reporter_id)
+WHERE
+  status = 'open' DO NOTHING
+RETURNING
+  id,
+  target_kind,
+  target_id,
+  reporter_id,
+  reason,
+  status,
+  resolved_by,
+  resolved_at,
+  created_at;
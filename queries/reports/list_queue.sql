@@ -0,0 +1,34 @@
+-- List reported targets with open reports, most reported first
+-- Profiles are labelled with the username of the reported user
+SELECT
+  r.target_kind,
+  r.target_id,
+  MAX(u.username) AS target_label,
+  COUNT(*) AS "report_count!",
+  ARRAY_AGG(
+    r.reason
+    ORDER BY
+      r.created_at
+  ) AS "reasons!",
+  MAX(r.created_at) AS "last_reported_at!",
+  EXISTS (
+    SELECT
+      1
+    FROM
+      hidden_content h
+    WHERE
+      h.target_kind = r.target_kind
+      AND h.target_id = r.target_id
+  ) AS "hidden!"
+FROM
+  reports r
+  LEFT JOIN users u ON r.target_kind = 'profile'
+  AND u.id = r.target_id
+WHERE
+  r.status = 'open'
+GROUP BY
+  r.target_kind,
+  r.target_id
+ORDER BY
+  COUNT(*) DESC,
+  MAX(r.created_at) DESC;
@@ -0,0 +1,12 @@
+-- Close all open reports on the target
+-- Returns the number of closed reports
+UPDATE
+  reports
+SET
+  status = $3,
+  resolved_by = $4,
+  resolved_at = NOW()
+WHERE
+  target_kind = $1
+  AND target_id = $2
+  AND status = 'open';
@@ -0,0 +1,12 @@
+-- List applied migrations
+-- Returns migration records ordered by version
+SELECT
+  version,
+  description,
+  installed_on,
+  success
+FROM
+  _sqlx_migrations
+ORDER BY
+  version;
+
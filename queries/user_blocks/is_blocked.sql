@@ -0,0 +1,12 @@
+-- Whether user $1 has blocked user $2
+SELECT
+  EXISTS (
+    SELECT
+      1
+    FROM
+      user_blocks
+    WHERE
+      user_id = $1
+      AND target_id = $2
+      AND kind = 'block'
+  ) AS "blocked!";
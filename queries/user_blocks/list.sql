@@ -0,0 +1,13 @@
+-- Users blocked or muted by user $1, latest first
+SELECT
+  b.target_id,
+  u.username,
+  b.kind,
+  b.created_at
+FROM
+  user_blocks b
+  JOIN users u ON u.id = b.target_id
+WHERE
+  b.user_id = $1
+ORDER BY
+  b.created_at DESC;
@@ -0,0 +1,5 @@
+-- Unblock or unmute a user
+DELETE FROM user_blocks
+WHERE
+  user_id = $1
+  AND target_id = $2;
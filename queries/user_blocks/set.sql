@@ -0,0 +1,9 @@
+-- Block or mute a user, replacing an earlier relation
+INSERT INTO
+  user_blocks (user_id, target_id, kind)
+VALUES
+  ($1, $2, $3)
+ON CONFLICT (user_id, target_id) DO UPDATE
+SET
+  kind = EXCLUDED.kind,
+  created_at = NOW();
@@ -0,0 +1,6 @@
+-- Add genre $2 to the interests of user $1
+INSERT INTO
+  user_interests (user_id, genre_id)
+VALUES
+  ($1, $2)
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,7 @@
+-- Genres user $1 is interested in
+SELECT
+  genre_id
+FROM
+  user_interests
+WHERE
+  user_id = $1;
@@ -0,0 +1,5 @@
+-- Remove genre $2 from the interests of user $1
+DELETE FROM user_interests
+WHERE
+  user_id = $1
+  AND genre_id = $2;
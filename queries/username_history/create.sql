@@ -0,0 +1,3 @@
+-- Record a previous username of a user
+INSERT INTO username_history (user_id, old_username)
+  VALUES ($1, $2);
@@ -0,0 +1,11 @@
+-- Find the user who most recently gave up a username
+-- Returns user id or null if the name was never used
+SELECT
+  user_id
+FROM
+  username_history
+WHERE
+  LOWER(old_username) = LOWER($1)
+ORDER BY
+  changed_at DESC
+LIMIT 1;
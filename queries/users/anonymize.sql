@@ -0,0 +1,180 @@
+-- Anonymize a user on a data erasure request: content they created moves to
+-- the ghost user ($2), personal records go and the profile is scrubbed
+-- Clubs never lose their last owner
+-- Returns the scrubbed user record
+WITH clubs AS (
+  UPDATE
+    clubs
+  SET
+    created_by = $2
+  WHERE
+    created_by = $1
+),
+events AS (
+  UPDATE
+    events
+  SET
+    created_by = $2
+  WHERE
+    created_by = $1
+),
+venues AS (
+  UPDATE
+    venues
+  SET
+    created_by = $2
+  WHERE
+    created_by = $1
+),
+polls AS (
+  UPDATE
+    polls
+  SET
+    created_by = $2
+  WHERE
+    created_by = $1
+),
+-- Open reports go: the ghost may already have one open on the same target
+open_reports AS (
+  DELETE FROM reports
+  WHERE reporter_id = $1
+    AND status = 'open'
+),
+reports AS (
+  UPDATE
+    reports
+  SET
+    reporter_id = $2
+  WHERE
+    reporter_id = $1
+    AND status <> 'open'
+),
+api_keys AS (
+  DELETE FROM api_keys
+  WHERE user_id = $1
+),
+email_changes AS (
+  DELETE FROM email_changes
+  WHERE user_id = $1
+),
+username_history AS (
+  DELETE FROM username_history
+  WHERE user_id = $1
+),
+user_blocks AS (
+  DELETE FROM user_blocks
+  WHERE user_id = $1
+    OR target_id = $1
+),
+-- Clubs the user solely owns pass to their longest-standing admin, or member
+successors AS (
+  UPDATE
+    club_members m
+  SET
+    role = 'owner'
+  FROM (
+    SELECT DISTINCT ON (o.club_id)
+      o.club_id,
+      o.user_id
+    FROM
+      club_members o
+      JOIN club_members s ON s.club_id = o.club_id
+        AND s.user_id = $1
+        AND s.role = 'owner'
+    WHERE
+      o.user_id <> $1
+      AND NOT EXISTS (
+        SELECT
+          1
+        FROM
+          club_members x
+        WHERE
+          x.club_id = o.club_id
+          AND x.role = 'owner'
+          AND x.user_id <> $1)
+    ORDER BY
+      o.club_id,
+      o.role = 'admin' DESC,
+      o.joined_at,
+      o.user_id) n
+  WHERE
+    m.club_id = n.club_id
+    AND m.user_id = n.user_id
+),
+-- and clubs with nobody else in them to the ghost user
+orphaned_clubs AS (
+  INSERT INTO club_members (club_id, user_id, role)
+  SELECT
+    s.club_id,
+    $2,
+    'owner'
+  FROM
+    club_members s
+  WHERE
+    s.user_id = $1
+    AND s.role = 'owner'
+    AND NOT EXISTS (
+      SELECT
+        1
+      FROM
+        club_members o
+      WHERE
+        o.club_id = s.club_id
+        AND o.user_id <> $1)
+),
+club_members AS (
+  DELETE FROM club_members
+  WHERE user_id = $1
+),
+event_rsvps AS (
+  DELETE FROM event_rsvps
+  WHERE user_id = $1
+),
+curator_applications AS (
+  DELETE FROM curator_applications
+  WHERE user_id = $1
+),
+poll_votes AS (
+  DELETE FROM poll_votes
+  WHERE user_id = $1
+),
+user_interests AS (
+  DELETE FROM user_interests
+  WHERE user_id = $1
+)
+UPDATE
+  users
+SET
+  username = $3,
+  email = $4,
+  email_canonical = $4,
+  password = '!',
+  first_name = NULL,
+  last_name = NULL,
+  bio = NULL,
+  bio_html = NULL,
+  profile_extras = '{}',
+  is_admin = FALSE,
+  badge = NULL,
+  locale = NULL,
+  theme = NULL,
+  banned_at = COALESCE(banned_at, NOW())
+WHERE
+  id = $1
+RETURNING
+  id,
+  username,
+  email,
+  first_name,
+  last_name,
+  bio,
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at;
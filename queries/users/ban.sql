@@ -0,0 +1,25 @@
+-- Ban a user; an existing ban keeps its date
+-- Returns updated user record
+UPDATE
+  users
+SET
+  banned_at = COALESCE(banned_at, NOW())
+WHERE
+  id = $1
+RETURNING
+  id,
+  username,
+  email,
+  first_name,
+  last_name,
+  bio,
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at;
@@ -1,7 +1,7 @@
 -- Create a new user
 -- Returns the created user record
-INSERT INTO users (username, email, password, first_name, last_name, bio)
-  VALUES ($1, $2, $3, $4, $5, $6)
+INSERT INTO users (username, email, password, first_name, last_name, bio, profile_extras, bio_html, email_canonical)
+  VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
 RETURNING
-  id, username, email, first_name, last_name, bio, created_at;
+  id, username, email, first_name, last_name, bio, bio_html, profile_extras, is_admin, badge, locale, timezone, theme, banned_at, created_at, updated_at;
 
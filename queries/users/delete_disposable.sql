@@ -0,0 +1,17 @@
+-- Delete up to $3 test accounts created over a day ago
+-- Test accounts are named with prefix $1 and use an address at domain $2
+DELETE FROM users
+WHERE
+  id IN (
+    SELECT
+      id
+    FROM
+      users
+    WHERE
+      starts_with(username, $1)
+      AND split_part(email, '@', 2) = $2
+      AND NOT is_admin
+      AND created_at < NOW() - INTERVAL '1 day'
+    LIMIT
+      $3
+  );
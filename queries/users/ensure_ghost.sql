@@ -0,0 +1,6 @@
+-- Create the ghost user that owns content of anonymized accounts; the
+-- leading underscore is outside the username rules, so no one can sign up as it
+INSERT INTO users (id, username, email, email_canonical, password, banned_at)
+  VALUES ($1, '_ghost', 'ghost@anonymized.invalid', 'ghost@anonymized.invalid', '!', NOW())
+ON CONFLICT (id)
+  DO NOTHING;
@@ -0,0 +1,226 @@
+-- Everything stored about a user besides the profile, for a data export
+-- Each column is a JSON array of records with camelCase keys; secrets such as
+-- API key hashes are left out
+SELECT
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'clubId',
+            c.id,
+            'club',
+            c.name,
+            'role',
+            m.role,
+            'joinedAt',
+            m.joined_at
+          )
+          ORDER BY
+            m.joined_at
+        )
+      FROM
+        club_members m
+        JOIN clubs c ON c.id = m.club_id
+      WHERE
+        m.user_id = u.id
+    ),
+    '[]'
+  ) AS "club_memberships!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'eventId',
+            e.id,
+            'event',
+            e.title,
+            'status',
+            r.status,
+            'createdAt',
+            r.created_at
+          )
+          ORDER BY
+            r.created_at
+        )
+      FROM
+        event_rsvps r
+        JOIN events e ON e.id = r.event_id
+      WHERE
+        r.user_id = u.id
+    ),
+    '[]'
+  ) AS "event_rsvps!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'pollId',
+            p.id,
+            'question',
+            p.question,
+            'option',
+            o.text,
+            'createdAt',
+            v.created_at
+          )
+          ORDER BY
+            v.created_at
+        )
+      FROM
+        poll_votes v
+        JOIN polls p ON p.id = v.poll_id
+        JOIN poll_options o ON o.id = v.option_id
+      WHERE
+        v.user_id = u.id
+    ),
+    '[]'
+  ) AS "poll_votes!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'username',
+            t.username,
+            'kind',
+            b.kind,
+            'createdAt',
+            b.created_at
+          )
+          ORDER BY
+            b.created_at
+        )
+      FROM
+        user_blocks b
+        JOIN users t ON t.id = b.target_id
+      WHERE
+        b.user_id = u.id
+    ),
+    '[]'
+  ) AS "blocks!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'genreId',
+            g.id,
+            'genre',
+            g.name,
+            'createdAt',
+            i.created_at
+          )
+          ORDER BY
+            i.created_at
+        )
+      FROM
+        user_interests i
+        JOIN genres g ON g.id = i.genre_id
+      WHERE
+        i.user_id = u.id
+    ),
+    '[]'
+  ) AS "genre_interests!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'id',
+            k.id,
+            'name',
+            k.name,
+            'prefix',
+            k.prefix,
+            'scopes',
+            k.scopes,
+            'lastUsedAt',
+            k.last_used_at,
+            'createdAt',
+            k.created_at
+          )
+          ORDER BY
+            k.created_at
+        )
+      FROM
+        api_keys k
+      WHERE
+        k.user_id = u.id
+    ),
+    '[]'
+  ) AS "api_keys!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'username',
+            h.old_username,
+            'changedAt',
+            h.changed_at
+          )
+          ORDER BY
+            h.changed_at
+        )
+      FROM
+        username_history h
+      WHERE
+        h.user_id = u.id
+    ),
+    '[]'
+  ) AS "username_history!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'targetKind',
+            r.target_kind,
+            'targetId',
+            r.target_id,
+            'reason',
+            r.reason,
+            'status',
+            r.status,
+            'createdAt',
+            r.created_at
+          )
+          ORDER BY
+            r.created_at
+        )
+      FROM
+        reports r
+      WHERE
+        r.reporter_id = u.id
+    ),
+    '[]'
+  ) AS "reports!",
+  COALESCE(
+    (
+      SELECT
+        JSONB_AGG(
+          JSONB_BUILD_OBJECT(
+            'motivation',
+            a.motivation,
+            'status',
+            a.status,
+            'createdAt',
+            a.created_at
+          )
+          ORDER BY
+            a.created_at
+        )
+      FROM
+        curator_applications a
+      WHERE
+        a.user_id = u.id
+    ),
+    '[]'
+  ) AS "curator_applications!"
+FROM
+  users u
+WHERE
+  u.id = $1;
@@ -0,0 +1,5 @@
+-- Get the user whose email folds to the same canonical address
+-- Returns user record or null if not found
+SELECT id, username, email, first_name, last_name, bio, bio_html, profile_extras, is_admin, badge, locale, timezone, theme, banned_at, created_at, updated_at
+FROM users
+WHERE email_canonical = $1;
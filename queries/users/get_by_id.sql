@@ -1,5 +1,5 @@
 -- Get user by ID
 -- Returns user record or null if not found
-SELECT id, username, email, first_name, last_name, bio, created_at
+SELECT id, username, email, first_name, last_name, bio, bio_html, profile_extras, is_admin, badge, locale, timezone, theme, banned_at, created_at, updated_at
 FROM users
 WHERE id = $1;
\ No newline at end of file
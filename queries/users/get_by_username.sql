@@ -1,4 +1,4 @@
--- Get user by username
+-- Get user by username, case-insensitively
 -- Returns user record or null if not found
 SELECT
   id,
@@ -7,9 +7,18 @@ SELECT
   first_name,
   last_name,
   bio,
-  created_at
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at
 FROM
   users
 WHERE
-  username = $1;
+  LOWER(username) = LOWER($1);
 
@@ -0,0 +1,26 @@
+-- Set user badge unless the user already has one
+-- Returns updated user record
+UPDATE
+  users
+SET
+  badge = COALESCE(badge, $2)
+WHERE
+  id = $1
+RETURNING
+  id,
+  username,
+  email,
+  first_name,
+  last_name,
+  bio,
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at;
+
@@ -0,0 +1,26 @@
+-- Grant admin rights
+-- Returns updated user record
+UPDATE
+  users
+SET
+  is_admin = TRUE
+WHERE
+  id = $1
+RETURNING
+  id,
+  username,
+  email,
+  first_name,
+  last_name,
+  bio,
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at;
+
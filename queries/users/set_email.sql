@@ -0,0 +1,27 @@
+-- Set user email
+-- Returns updated user record
+UPDATE
+  users
+SET
+  email = $2,
+  email_canonical = $3
+WHERE
+  id = $1
+RETURNING
+  id,
+  username,
+  email,
+  first_name,
+  last_name,
+  bio,
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at;
+
@@ -0,0 +1,7 @@
+-- Replace the stored password hash
+UPDATE
+  users
+SET
+  password = $2
+WHERE
+  id = $1;
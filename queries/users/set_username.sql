@@ -0,0 +1,25 @@
+-- Change username of a user
+-- Returns updated user record
+UPDATE
+  users
+SET
+  username = $2
+WHERE
+  id = $1
+RETURNING
+  id,
+  username,
+  email,
+  first_name,
+  last_name,
+  bio,
+  bio_html,
+  profile_extras,
+  is_admin,
+  badge,
+  locale,
+  timezone,
+  theme,
+  banned_at,
+  created_at,
+  updated_at;
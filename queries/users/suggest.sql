@@ -0,0 +1,56 @@
+-- Suggest users for the global search box
+-- Scores username prefixes, names and bios containing the pattern by the
+-- weights $4, $5 and $6; banned users and profiles hidden by moderation are
+-- left out, as are users viewer $3 blocked or muted and users who blocked
+-- the viewer
+SELECT
+  u.id,
+  u.username,
+  u.first_name,
+  u.last_name,
+  u.badge,
+  (
+    $4::FLOAT8 * (u.username ILIKE $1 || '%')::INT + $5::FLOAT8 * (
+      CONCAT_WS(' ', u.first_name, u.last_name) ILIKE '%' || $1 || '%'
+    )::INT + $6::FLOAT8 * (COALESCE(u.bio, '') ILIKE '%' || $1 || '%')::INT
+  ) AS "score!"
+FROM
+  users u
+WHERE
+  u.banned_at IS NULL
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      hidden_content h
+    WHERE
+      h.target_kind = 'profile'
+      AND h.target_id = u.id
+  )
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      user_blocks b
+    WHERE
+      (
+        b.user_id = $3
+        AND b.target_id = u.id
+      )
+      OR (
+        b.user_id = u.id
+        AND b.target_id = $3
+        AND b.kind = 'block'
+      )
+  )
+  AND (
+    u.username ILIKE $1 || '%'
+    OR CONCAT_WS(' ', u.first_name, u.last_name) ILIKE '%' || $1 || '%'
+    OR u.bio ILIKE '%' || $1 || '%'
+  )
+ORDER BY
+  "score!" DESC,
+  LENGTH(u.username),
+  u.username
+LIMIT
+  $2;
@@ -0,0 +1,54 @@
+-- Typo tolerant fallback for queries/users/suggest.sql: usernames and names
+-- trigram-similar to the raw query $1, scored by similarity times the
+-- weights $4 and $5; bios are too long to compare usefully. The same users
+-- are left out as there
+SELECT
+  u.id,
+  u.username,
+  u.first_name,
+  u.last_name,
+  u.badge,
+  (
+    $4::FLOAT8 * SIMILARITY(u.username, $1) + $5::FLOAT8 * SIMILARITY(
+      COALESCE(u.first_name, '') || ' ' || COALESCE(u.last_name, ''),
+      $1
+    )
+  ) AS "score!"
+FROM
+  users u
+WHERE
+  u.banned_at IS NULL
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      hidden_content h
+    WHERE
+      h.target_kind = 'profile'
+      AND h.target_id = u.id
+  )
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      user_blocks b
+    WHERE
+      (
+        b.user_id = $3
+        AND b.target_id = u.id
+      )
+      OR (
+        b.user_id = u.id
+        AND b.target_id = $3
+        AND b.kind = 'block'
+      )
+  )
+  AND (
+    u.username % $1
+    OR (COALESCE(u.first_name, '') || ' ' || COALESCE(u.last_name, '')) % $1
+  )
+ORDER BY
+  "score!" DESC,
+  u.username
+LIMIT
+  $2;
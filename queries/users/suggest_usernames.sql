@@ -0,0 +1,46 @@
+-- Usernames starting with the pattern $1 for mention and share
+-- autocompletion, served by the trigram index; shortest first so an exact
+-- match leads. Leaves out the same users as queries/users/suggest.sql
+-- for viewer $3
+SELECT
+  u.id,
+  u.username,
+  u.first_name,
+  u.last_name,
+  u.badge,
+  SIMILARITY(u.username, $1)::FLOAT8 AS "score!"
+FROM
+  users u
+WHERE
+  u.banned_at IS NULL
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      hidden_content h
+    WHERE
+      h.target_kind = 'profile'
+      AND h.target_id = u.id
+  )
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      user_blocks b
+    WHERE
+      (
+        b.user_id = $3
+        AND b.target_id = u.id
+      )
+      OR (
+        b.user_id = u.id
+        AND b.target_id = $3
+        AND b.kind = 'block'
+      )
+  )
+  AND u.username ILIKE $1 || '%'
+ORDER BY
+  LENGTH(u.username),
+  u.username
+LIMIT
+  $2;
@@ -7,6 +7,8 @@ SET
     password = COALESCE($4, password),
     first_name = COALESCE($5, first_name),
     last_name = COALESCE($6, last_name),
-    bio = COALESCE($7, bio)
+    bio = COALESCE($7, bio),
+    bio_html = COALESCE($8, bio_html),
+    email_canonical = COALESCE($9, email_canonical)
 WHERE id = $1
-RETURNING id, username, email, first_name, last_name, bio, created_at;
\ No newline at end of file
+RETURNING id, username, email, first_name, last_name, bio, bio_html, profile_extras, is_admin, badge, locale, timezone, theme, banned_at, created_at, updated_at;
\ No newline at end of file
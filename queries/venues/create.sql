@@ -0,0 +1,25 @@
+-- Add a venue
+-- Returns the venue record or null if the slug is taken
+INSERT INTO
+  venues (
+    slug,
+    name,
+    kind,
+    address,
+    latitude,
+    longitude,
+    created_by
+  )
+VALUES
+  ($1, $2, $3, $4, $5, $6, $7)
+ON CONFLICT DO NOTHING
+RETURNING
+  id,
+  slug,
+  name,
+  kind,
+  address,
+  latitude,
+  longitude,
+  created_by,
+  created_at;
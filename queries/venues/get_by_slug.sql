@@ -0,0 +1,15 @@
+-- Get a venue by slug, case-insensitively
+SELECT
+  id,
+  slug,
+  name,
+  kind,
+  address,
+  latitude,
+  longitude,
+  created_by,
+  created_at
+FROM
+  venues
+WHERE
+  LOWER(slug) = LOWER($1);
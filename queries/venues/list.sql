@@ -0,0 +1,18 @@
+-- List venues by kind and name
+SELECT
+  id,
+  slug,
+  name,
+  kind,
+  address,
+  latitude,
+  longitude,
+  created_by,
+  created_at
+FROM
+  venues
+ORDER BY
+  kind,
+  name
+LIMIT
+  $1;
@@ -0,0 +1,21 @@
+-- Venues within $3 meters of ($1, $2), nearest first
+-- earth_box narrows the search through the location index; it is a square
+-- around the circle, so the exact distance is checked as well
+SELECT
+  id,
+  slug,
+  name,
+  kind,
+  address,
+  latitude,
+  longitude,
+  earth_distance (ll_to_earth ($1, $2), ll_to_earth (latitude, longitude)) AS "distance_m!"
+FROM
+  venues
+WHERE
+  earth_box (ll_to_earth ($1, $2), $3) @> ll_to_earth (latitude, longitude)
+  AND earth_distance (ll_to_earth ($1, $2), ll_to_earth (latitude, longitude)) <= $3
+ORDER BY
+  "distance_m!"
+LIMIT
+  $4;
@@ -0,0 +1,47 @@
+//! Content-hashed names of the files in `public/assets`, generated by
+//! `build.rs`. Hashed URLs change with the file, so they can be cached forever.
+
+include!(concat!(env!("OUT_DIR"), "/asset_manifest.rs"));
+
+/// Where static assets are mounted.
+pub const ASSETS_PREFIX: &str = "/public/assets";
+
+/// URL of the asset at `name` (relative to `public/assets`), fingerprinted
+/// when it is in the manifest. Templates call it as
+/// `crate::assets::asset("css/main.css")`.
+pub fn asset(name: &str) -> String {
+    let name = name.trim_start_matches('/');
+    let file = MANIFEST
+        .iter()
+        .find(|(logical, _)| *logical == name)
+        .map_or(name, |(_, hashed)| *hashed);
+    format!("{ASSETS_PREFIX}/{file}")
+}
+
+/// Logical name behind a fingerprinted one, if `hashed` is in the manifest.
+pub fn resolve(hashed: &str) -> Option<&'static str> {
+    MANIFEST
+        .iter()
+        .find(|(_, h)| *h == hashed)
+        .map(|(logical, _)| *logical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_roundtrip() {
+        let url = asset("css/main.css");
+        let hashed = url.strip_prefix("/public/assets/").unwrap();
+        assert_ne!(hashed, "css/main.css");
+        assert!(hashed.starts_with("css/main.") && hashed.ends_with(".css"));
+        assert_eq!(resolve(hashed), Some("css/main.css"));
+        assert_eq!(resolve("css/main.css"), None);
+    }
+
+    #[test]
+    fn test_unknown_asset_is_left_alone() {
+        assert_eq!(asset("/img/missing.png"), "/public/assets/img/missing.png");
+    }
+}
@@ -0,0 +1,88 @@
+//! Command line of the `app` binary. Operational commands reuse [`crate::build`]
+//! and the services, so routine tasks never need direct database access.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::smoke;
+
+#[derive(Debug, Parser)]
+#[command(name = "app", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// The command to run; serving is the default.
+    pub fn command(self) -> Command {
+        self.command.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default, Subcommand)]
+pub enum Command {
+    /// Run the web server
+    #[default]
+    Serve,
+    /// Apply pending migrations and list their status
+    Migrate,
+    /// Make the user with this email an admin, creating the account if needed
+    CreateAdmin {
+        #[arg(long)]
+        email: String,
+        /// For a new account; taken from the email when omitted
+        #[arg(long)]
+        username: Option<String>,
+    },
+    /// Delete expired sessions now instead of waiting for the cleanup task
+    CleanupSessions,
+    /// Erase a user's personal data on a GDPR request, keeping their content
+    /// under the ghost user
+    Anonymize {
+        /// Id, email or username
+        user: String,
+    },
+    /// Exercise the main user flows against a running instance and print a
+    /// JSON report
+    Smoke(smoke::Options),
+    /// Write the OpenAPI description of the JSON API
+    ExportOpenapi {
+        /// File to write; printed when omitted
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_parse() {
+        let cli = Cli::try_parse_from(["app"]).unwrap();
+        assert!(matches!(cli.command(), Command::Serve));
+
+        let cli = Cli::try_parse_from(["app", "create-admin", "--email", "a@example.com"]).unwrap();
+        assert!(matches!(
+            cli.command(),
+            Command::CreateAdmin { email, username: None } if email == "a@example.com"
+        ));
+
+        let cli = Cli::try_parse_from(["app", "smoke", "--base-url", "http://localhost/"]).unwrap();
+        assert!(matches!(
+            cli.command(),
+            Command::Smoke(options) if options.base_url == "http://localhost"
+        ));
+
+        let cli = Cli::try_parse_from(["app", "export-openapi", "--output", "api.json"]).unwrap();
+        assert!(matches!(
+            cli.command(),
+            Command::ExportOpenapi { output: Some(path) } if path == PathBuf::from("api.json")
+        ));
+
+        assert!(Cli::try_parse_from(["app", "create-admin"]).is_err());
+        assert!(Cli::try_parse_from(["app", "export"]).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! Resolves the real client address behind reverse proxies. Forwarding
+//! headers are only believed when the connection comes from a trusted proxy
+//! (`server.trusted_proxies`), and each hop is only believed while the hop
+//! after it is trusted too, so clients cannot spoof their address.
+
+use std::{fmt::Display, net::IpAddr, str::FromStr};
+
+use axum::http::{HeaderMap, header};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// An IP network in CIDR notation; a bare address is a single host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = canonical(ip);
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(net).into(), u32::from(ip).into(), 32, self.prefix)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    if prefix == 0 {
+        return true;
+    }
+    let shift = bits - prefix;
+    net >> shift == ip >> shift
+}
+
+/// IPv4 clients of a dual-stack socket show up as `::ffff:a.b.c.d`.
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+impl FromStr for IpNet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr = canonical(
+            addr.parse::<IpAddr>()
+                .map_err(|_| format!("{s} is not an IP address or network"))?,
+        );
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("{s} has an invalid prefix length"))?,
+            None => max,
+        };
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl Display for IpNet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    nets: Vec<IpNet>,
+}
+
+impl TrustedProxies {
+    pub fn new(nets: Vec<IpNet>) -> Self {
+        Self { nets }
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.nets.iter().any(|net| net.contains(ip))
+    }
+
+    /// The client address for a request from `peer`, or `None` when it is
+    /// unknown. A missing `peer` means a unix socket, whose other end can
+    /// only be a local proxy, so its headers are believed.
+    pub fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer.map(canonical);
+        if peer.is_some_and(|peer| !self.is_trusted(peer)) {
+            return peer;
+        }
+        let hops = forwarded_for(headers);
+        // Walk back from the hop nearest to us; the first address not one of
+        // our proxies is the client. Anything further left is client-supplied.
+        let mut client = peer;
+        for hop in hops.iter().rev() {
+            let Some(hop) = hop else {
+                // Unknown or obfuscated: nothing beyond it can be trusted
+                break;
+            };
+            client = Some(*hop);
+            if !self.is_trusted(*hop) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// Hops from `Forwarded` (RFC 7239) or, failing that, `X-Forwarded-For`,
+/// client first. `None` marks a hop without a usable address.
+fn forwarded_for(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let forwarded: Vec<Option<IpAddr>> = headers
+        .get_all(header::FORWARDED)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("for").then(|| parse_node(value))
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(parse_node)
+        .collect()
+}
+
+/// Accepts `1.2.3.4`, `1.2.3.4:80`, `"[2001:db8::1]:80"` and bare IPv6.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(canonical(ip));
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        let (ip, _) = rest.split_once(']')?;
+        return ip.parse::<IpAddr>().ok().map(canonical);
+    }
+    let (ip, _port) = node.rsplit_once(':')?;
+    ip.parse::<std::net::Ipv4Addr>().ok().map(IpAddr::V4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn proxies(nets: &[&str]) -> TrustedProxies {
+        TrustedProxies::new(nets.iter().map(|n| n.parse().unwrap()).collect())
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_parse_networks() {
+        let net: IpNet = "10.0.0.0/8".parse().unwrap();
+        assert!(net.contains(ip("10.1.2.3")));
+        assert!(!net.contains(ip("11.0.0.1")));
+        assert!(net.contains(ip("::ffff:10.0.0.1")));
+
+        let host: IpNet = "127.0.0.1".parse().unwrap();
+        assert_eq!(host.to_string(), "127.0.0.1/32");
+        assert!(!host.contains(ip("127.0.0.2")));
+
+        let v6: IpNet = "fd00::/8".parse().unwrap();
+        assert!(v6.contains(ip("fd12::1")));
+        assert!(!v6.contains(ip("10.0.0.1")));
+
+        assert!(
+            "0.0.0.0/0"
+                .parse::<IpNet>()
+                .unwrap()
+                .contains(ip("8.8.8.8"))
+        );
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+        assert!("example.com".parse::<IpNet>().is_err());
+    }
+
+    #[test]
+    fn test_untrusted_peer_is_the_client() {
+        let proxies = proxies(&["10.0.0.0/8"]);
+        let headers = headers(&[(X_FORWARDED_FOR, "1.1.1.1")]);
+        assert_eq!(
+            proxies.resolve(Some(ip("203.0.113.9")), &headers),
+            Some(ip("203.0.113.9"))
+        );
+    }
+
+    #[test]
+    fn test_x_forwarded_for_skips_trusted_hops() {
+        let proxies = proxies(&["10.0.0.0/8"]);
+        // The client prepended a fake address; 10.0.0.2 is our second proxy
+        let headers = headers(&[(X_FORWARDED_FOR, "6.6.6.6, 198.51.100.7, 10.0.0.2")]);
+        assert_eq!(
+            proxies.resolve(Some(ip("10.0.0.1")), &headers),
+            Some(ip("198.51.100.7"))
+        );
+        assert_eq!(
+            proxies.resolve(Some(ip("10.0.0.1")), &HeaderMap::new()),
+            Some(ip("10.0.0.1"))
+        );
+    }
+
+    #[test]
+    fn test_forwarded_header() {
+        let proxies = proxies(&["127.0.0.1"]);
+        let headers = headers(&[
+            ("forwarded", r#"for="[2001:db8:cafe::17]:4711";proto=https"#),
+            (X_FORWARDED_FOR, "9.9.9.9"),
+        ]);
+        assert_eq!(
+            proxies.resolve(Some(ip("127.0.0.1")), &headers),
+            Some(ip("2001:db8:cafe::17"))
+        );
+        let headers = headers(&[("forwarded", "for=192.0.2.43, for=unknown")]);
+        assert_eq!(
+            proxies.resolve(Some(ip("127.0.0.1")), &headers),
+            Some(ip("127.0.0.1"))
+        );
+    }
+
+    #[test]
+    fn test_unix_socket_peer_trusts_headers() {
+        let proxies = TrustedProxies::default();
+        let headers = headers(&[(X_FORWARDED_FOR, "192.0.2.1:5555")]);
+        assert_eq!(proxies.resolve(None, &headers), Some(ip("192.0.2.1")));
+        assert_eq!(proxies.resolve(None, &HeaderMap::new()), None);
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+};
+use tower::ServiceExt;
+
+use crate::{
+    AppState,
+    controllers::{API_PREFIX, ApiError, ApiErrorEnvelope, api_routes},
+    extractors::{ApiKeyAuth, ValidatedJson},
+    models::api::{BatchOperation, BatchRequest, BatchResponse, BatchResult},
+    router::REQUEST_ID_HEADER,
+};
+
+/// Headers of the batch request every operation is sent with.
+const SHARED_HEADERS: [&str; 5] = [
+    "authorization",
+    "x-api-key",
+    "cookie",
+    "accept-language",
+    REQUEST_ID_HEADER,
+];
+/// Larger bodies of a single operation are dropped from the results.
+const MAX_RESULT_BODY_BYTES: usize = 1024 * 1024;
+
+/// `POST /api/v1/batch`: runs the operations in order through the API
+/// routes with the batch's credentials and answers with each one's status
+/// and body. A failing operation does not stop the ones after it. Needs an
+/// API key with the `read` scope, or `write` when any operation is not a
+/// GET; each operation still checks the key itself.
+#[utoipa::path(
+    post,
+    path = "/batch",
+    context_path = API_PREFIX,
+    tag = "batch",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, body = BatchResponse),
+        (status = 401, description = "No or unknown API key", body = ApiErrorEnvelope),
+        (status = 422, description = "Validation failed", body = ApiErrorEnvelope),
+    ),
+    security(("api_key" = ["read"]), ("api_key" = ["write"])),
+)]
+pub async fn batch(
+    auth: ApiKeyAuth,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidatedJson(batch): ValidatedJson<BatchRequest>,
+) -> Result<Json<BatchResponse>, ApiError> {
+    let writes = batch
+        .operations
+        .iter()
+        .any(|o| !o.method.eq_ignore_ascii_case("GET"));
+    auth.require_scope(if writes { "write" } else { "read" })?;
+    let routes = api_routes(state.clone()).with_state(state);
+    let mut results = Vec::with_capacity(batch.operations.len());
+    for operation in batch.operations {
+        let result = match sub_request(&operation, &headers) {
+            Ok(req) => run(routes.clone(), req).await,
+            Err(e) => BatchResult {
+                status: e.status.as_u16(),
+                body: serde_json::json!({ "error": e }),
+            },
+        };
+        results.push(result);
+    }
+    Ok(Json(BatchResponse { results }))
+}
+
+fn sub_request(operation: &BatchOperation, headers: &HeaderMap) -> Result<Request, ApiError> {
+    let wrong_input =
+        |message: &str| ApiError::new(StatusCode::BAD_REQUEST, "wrong_input", message);
+    let method = Method::from_bytes(operation.method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| wrong_input("Unknown method"))?;
+    if !operation.path.starts_with(&format!("{API_PREFIX}/")) {
+        return Err(wrong_input("Operations must call the API"));
+    }
+    if operation.path.starts_with(&format!("{API_PREFIX}/batch")) {
+        return Err(wrong_input("Batches do not nest"));
+    }
+    // Sign-in and sign-up would run with the batch's forwarded key and
+    // session cookie, and any cookie they set is lost as results keep only
+    // status and body
+    if operation.path.starts_with(&format!("{API_PREFIX}/auth/")) {
+        return Err(wrong_input("Operations may not sign in or up"));
+    }
+    let mut builder = Request::builder().method(method).uri(&operation.path);
+    for name in SHARED_HEADERS {
+        for value in headers.get_all(name) {
+            builder = builder.header(name, value);
+        }
+    }
+    let body = match &operation.body {
+        Some(body) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(serde_json::to_vec(body).unwrap_or_default())
+        }
+        None => Body::empty(),
+    };
+    builder
+        .body(body)
+        .map_err(|_| wrong_input("Invalid operation path"))
+}
+
+async fn run(routes: Router, req: Request) -> BatchResult {
+    let Ok(res) = routes.oneshot(req).await;
+    let status = res.status().as_u16();
+    let body = to_bytes(res.into_body(), MAX_RESULT_BODY_BYTES)
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+    BatchResult { status, body }
+}
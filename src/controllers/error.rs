@@ -0,0 +1,307 @@
+use axum::{
+    Json,
+    extract::Request,
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use validator::ValidationErrors;
+
+use crate::{
+    extractors::field_messages,
+    i18n::Locale,
+    models::domain::IDEMPOTENCY_KEY_MAX_LEN,
+    router::REQUEST_ID_HEADER,
+    services::{
+        ApiKeysServiceError, BotProtectionError, EmailChangeServiceError, IdempotencyServiceError,
+        UsernameServiceError, UsersServiceError,
+    },
+};
+
+/// Error body shared by all JSON API routes:
+/// `{ "error": { "code", "message", "details", "request_id" } }`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    pub status: StatusCode,
+    pub code: String,
+    pub message: String,
+    pub details: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ApiErrorEnvelope {
+    error: ApiError,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: code.into(),
+            message: message.into(),
+            details: None,
+            request_id: None,
+        }
+    }
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+    /// Failed validation with per-field messages in `locale` as details.
+    pub fn validation(errors: &ValidationErrors, locale: Locale) -> Self {
+        let details = serde_json::to_value(field_messages(errors, locale)).unwrap_or_default();
+        ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "validation_failed",
+            "Request validation failed",
+        )
+        .with_details(details)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let envelope = ApiErrorEnvelope {
+            error: self.clone(),
+        };
+        let mut response = (self.status, Json(envelope)).into_response();
+        // picked up by `attach_request_id` to fill in the request id
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+impl From<UsersServiceError> for ApiError {
+    fn from(value: UsersServiceError) -> Self {
+        match value {
+            UsersServiceError::NotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "not_found", "Resource not found")
+            }
+            UsersServiceError::WrongCredentials(err) => {
+                ApiError::new(StatusCode::BAD_REQUEST, "wrong_credentials", err)
+            }
+            UsersServiceError::EmailDomain(err) => ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                err.code(),
+                err.message(Locale::default()),
+            ),
+            UsersServiceError::DatabaseError(_) | UsersServiceError::VerificationError(_) => {
+                ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    "Internal server error",
+                )
+            }
+        }
+    }
+}
+
+impl From<ApiKeysServiceError> for ApiError {
+    fn from(value: ApiKeysServiceError) -> Self {
+        match value {
+            ApiKeysServiceError::NotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "not_found", "Resource not found")
+            }
+            ApiKeysServiceError::InvalidKey => ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "invalid_api_key",
+                "Invalid API key",
+            ),
+            ApiKeysServiceError::RateLimited => ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                "API key rate limit exceeded",
+            ),
+            ApiKeysServiceError::WrongInput(err) => {
+                ApiError::new(StatusCode::BAD_REQUEST, "wrong_input", err)
+            }
+            ApiKeysServiceError::DatabaseError(_) => ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                "Internal server error",
+            ),
+        }
+    }
+}
+
+impl IntoResponse for ApiKeysServiceError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl From<IdempotencyServiceError> for ApiError {
+    fn from(value: IdempotencyServiceError) -> Self {
+        match value {
+            IdempotencyServiceError::InvalidKey => ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "wrong_input",
+                format!(
+                    "Idempotency-Key must be 1 to {IDEMPOTENCY_KEY_MAX_LEN} visible characters"
+                ),
+            ),
+            IdempotencyServiceError::KeyReused => ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "idempotency_key_reused",
+                "Idempotency-Key was already used for a different request",
+            ),
+            IdempotencyServiceError::InProgress => ApiError::new(
+                StatusCode::CONFLICT,
+                "idempotency_key_in_progress",
+                "A request with this Idempotency-Key is still running",
+            ),
+            IdempotencyServiceError::DatabaseError(_) => ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                "Internal server error",
+            ),
+        }
+    }
+}
+
+impl IntoResponse for IdempotencyServiceError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl From<BotProtectionError> for ApiError {
+    fn from(value: BotProtectionError) -> Self {
+        match value {
+            BotProtectionError::Honeypot | BotProtectionError::CaptchaFailed => ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "captcha_failed",
+                "Bot check failed",
+            ),
+            BotProtectionError::ProviderError(_) => ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "captcha_unavailable",
+                "Bot check is unavailable",
+            ),
+        }
+    }
+}
+
+impl From<UsernameServiceError> for ApiError {
+    fn from(value: UsernameServiceError) -> Self {
+        match value {
+            UsernameServiceError::NotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "not_found", "Resource not found")
+            }
+            UsernameServiceError::Taken => ApiError::new(
+                StatusCode::CONFLICT,
+                "username_taken",
+                "Username is already taken",
+            ),
+            UsernameServiceError::Rule(err) => ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "wrong_input",
+                err.message(Locale::default()),
+            ),
+            UsernameServiceError::DatabaseError(_) => ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                "Internal server error",
+            ),
+        }
+    }
+}
+
+impl IntoResponse for UsersServiceError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl From<EmailChangeServiceError> for ApiError {
+    fn from(value: EmailChangeServiceError) -> Self {
+        match value {
+            EmailChangeServiceError::NotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "not_found", "Resource not found")
+            }
+            EmailChangeServiceError::Expired => ApiError::new(
+                StatusCode::GONE,
+                "email_change_expired",
+                "Confirmation link has expired",
+            ),
+            EmailChangeServiceError::Taken => ApiError::new(
+                StatusCode::CONFLICT,
+                "email_taken",
+                "Email is already registered",
+            ),
+            EmailChangeServiceError::SameEmail => ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "wrong_input",
+                "This is already the account email",
+            ),
+            EmailChangeServiceError::DatabaseError(_) => ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                "Internal server error",
+            ),
+        }
+    }
+}
+
+/// Middleware for API routes re-rendering `ApiError` bodies with the request id,
+/// keeping the status and headers of the original response.
+pub async fn attach_request_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+    let response = next.run(request).await;
+    let (Some(error), Some(request_id)) = (response.extensions().get::<ApiError>(), request_id)
+    else {
+        return response;
+    };
+    let error = error.clone().with_request_id(request_id);
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = Json(ApiErrorEnvelope { error }).into_response().into_body();
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_envelope_shape() {
+        let error = ApiError::new(StatusCode::BAD_REQUEST, "bad", "Bad request")
+            .with_request_id("request-1");
+        let json = serde_json::to_value(ApiErrorEnvelope { error }).unwrap();
+
+        assert_eq!(json["error"]["code"], "bad");
+        assert_eq!(json["error"]["message"], "Bad request");
+        assert_eq!(json["error"]["details"], serde_json::Value::Null);
+        assert_eq!(json["error"]["request_id"], "request-1");
+        assert!(json["error"].get("status").is_none());
+    }
+
+    #[test]
+    fn test_api_error_hides_database_errors() {
+        let error = ApiError::from(UsersServiceError::DatabaseError(
+            "relation users does not exist".into(),
+        ));
+
+        assert_eq!(error.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!error.message.contains("relation"));
+    }
+
+    #[test]
+    fn test_api_error_not_found() {
+        let error = ApiError::from(UsersServiceError::NotFound);
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+        assert_eq!(error.code, "not_found");
+    }
+}
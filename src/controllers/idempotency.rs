@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::error;
+
+use crate::{
+    AppState,
+    controllers::ApiError,
+    models::domain::IdempotentResponse,
+    services::{IdempotencyDecision, IdempotentRequest},
+};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses replayed for a retried key.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+/// Requests and responses above this are not buffered for replay.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Headers that tell clients apart; a key is scoped to them.
+const CREDENTIAL_HEADERS: [&str; 3] = ["authorization", "x-api-key", "cookie"];
+
+/// Replays the stored response when a POST is retried with the same
+/// `Idempotency-Key`, so flaky networks do not create things twice.
+/// Requests without the header pass through.
+pub async fn idempotency(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER).cloned() else {
+        return next.run(req).await;
+    };
+    let (parts, body) = req.into_parts();
+    let Ok(body) = to_bytes(body, MAX_BODY_BYTES).await else {
+        return ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+            "Request body is too large",
+        )
+        .into_response();
+    };
+    let credentials: Vec<&[u8]> = CREDENTIAL_HEADERS
+        .iter()
+        .map(|name| parts.headers.get(*name).map_or(&b""[..], |v| v.as_bytes()))
+        .collect();
+    let request = match IdempotentRequest::new(
+        key.to_str().unwrap_or_default(),
+        &credentials,
+        parts.method.as_str(),
+        parts.uri.path(),
+        &body,
+    ) {
+        Ok(request) => request,
+        Err(e) => return e.into_response(),
+    };
+    let service = &state.idempotency_service;
+    match service.begin(&request).await {
+        Ok(IdempotencyDecision::Run) => {}
+        Ok(IdempotencyDecision::Replay(stored)) => return replay(stored),
+        Err(e) => return e.into_response(),
+    }
+
+    let res = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = res.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!("{e:?}");
+            if let Err(e) = service.abandon(&request).await {
+                error!("{e:?}");
+            }
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    if let Err(e) = service
+        .complete(&request, parts.status, content_type, &body)
+        .await
+    {
+        // A retry runs the request again, which is no worse than having no key
+        error!("{e:?}");
+        if let Err(e) = service.abandon(&request).await {
+            error!("{e:?}");
+        }
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn replay(stored: IdempotentResponse) -> Response {
+    let status = stored
+        .status
+        .and_then(|s| StatusCode::from_u16(s as u16).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut res = (status, stored.body.unwrap_or_default()).into_response();
+    let headers = res.headers_mut();
+    headers.remove(header::CONTENT_TYPE);
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|v| HeaderValue::from_str(&v).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    res
+}
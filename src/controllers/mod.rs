@@ -1 +1,51 @@
+mod batch;
+mod error;
+mod idempotency;
+mod openapi;
 pub mod users;
+use std::sync::Arc;
+
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+pub use batch::batch;
+pub(crate) use error::ApiErrorEnvelope;
+pub use error::{ApiError, attach_request_id};
+pub use idempotency::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER, idempotency};
+pub use openapi::{ApiDoc, openapi_json};
+
+use crate::AppState;
+
+/// Where the JSON API lives.
+pub const API_PREFIX: &str = "/api/v1";
+
+/// JSON API routes under [`API_PREFIX`]. Not mounted on the site router yet
+/// apart from [`batch_routes`], which dispatches its operations into them.
+/// POSTs honour `Idempotency-Key`; error bodies carry the request id.
+pub fn api_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let routes = Router::new()
+        .route("/auth/sign-in", post(users::sign_in))
+        .route("/auth/sign-up", post(users::sign_up))
+        .route("/users", get(users::list_users).post(users::create_user))
+        .route("/users/suggest", get(users::suggest_users))
+        .route(
+            "/users/{id}",
+            get(users::get_user_by_id)
+                .patch(users::update_user)
+                .delete(users::delete_user),
+        )
+        .route("/batch", post(batch))
+        .route_layer(middleware::from_fn_with_state(state, idempotency))
+        .layer(middleware::from_fn(attach_request_id));
+    Router::new().nest(API_PREFIX, routes)
+}
+
+/// The part of the API the site router mounts: `POST /api/v1/batch`.
+pub fn batch_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let routes = Router::new()
+        .route("/batch", post(batch))
+        .route_layer(middleware::from_fn_with_state(state, idempotency))
+        .layer(middleware::from_fn(attach_request_id));
+    Router::new().nest(API_PREFIX, routes)
+}
@@ -0,0 +1,74 @@
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+};
+
+use super::{batch, users};
+use crate::extractors::API_KEY_HEADER;
+
+/// OpenAPI description of the JSON API routes, written out by
+/// `app export-openapi`. Schemas are collected from the handlers' bodies.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        users::sign_in,
+        users::sign_up,
+        users::list_users,
+        users::create_user,
+        users::suggest_users,
+        users::get_user_by_id,
+        users::update_user,
+        users::delete_user,
+        batch::batch,
+    ),
+    modifiers(&ApiKeySecurity),
+    tags(
+        (name = "auth", description = "Signing in and up"),
+        (name = "users", description = "User accounts and profiles"),
+        (name = "batch", description = "Several API calls in one request"),
+    ),
+)]
+pub struct ApiDoc;
+
+/// Keys go in the `X-Api-Key` header, see [`crate::extractors::ApiKeyAuth`].
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+            );
+    }
+}
+
+/// The API description as pretty-printed JSON.
+pub fn openapi_json() -> serde_json::Result<String> {
+    ApiDoc::openapi().to_pretty_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document() {
+        let doc: serde_json::Value = serde_json::from_str(&openapi_json().unwrap()).unwrap();
+
+        let user = &doc["paths"]["/api/v1/users/{id}"];
+        for method in ["get", "patch", "delete"] {
+            assert!(user[method].is_object(), "{method} /users/{{id}}");
+        }
+        assert!(doc["paths"]["/api/v1/batch"]["post"].is_object());
+        assert!(doc["paths"]["/api/v1/auth/sign-in"]["post"].is_object());
+        assert!(doc["components"]["schemas"]["UserResponse"].is_object());
+        assert!(doc["components"]["schemas"]["ApiErrorEnvelope"].is_object());
+        assert_eq!(
+            doc["components"]["securitySchemes"]["api_key"]["name"],
+            API_KEY_HEADER
+        );
+    }
+}
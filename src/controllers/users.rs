@@ -1,112 +1,302 @@
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use axum::{
     Json, debug_handler,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
 };
+use uuid::Uuid;
 
 use crate::{
     AppState,
-    models::{
-        CreateUser, SignInRequest, SignInResponse, SignUpRequest, SignUpResponse, UpdateUser, User,
-        UserListResponse,
+    controllers::{API_PREFIX, ApiError, ApiErrorEnvelope},
+    extractors::{ApiKeyAuth, ValidatedJson},
+    i18n::Locale,
+    models::api::{
+        CreateUserRequest, DeleteUserResponse, ListUsersRequest, PublicUser, SignInRequest,
+        SignInResponse, SignUpRequest, SignUpResponse, SuggestUsersQuery, UpdateUserRequest,
+        UserFields, UserFieldsQuery, UserListView, UserResponse, UserSuggestionResponse,
     },
+    router::{etag_matches, http_date, version_etag},
     services::UsersServiceError,
 };
 
+#[utoipa::path(
+    post,
+    path = "/auth/sign-in",
+    context_path = API_PREFIX,
+    tag = "auth",
+    request_body = SignInRequest,
+    responses(
+        (status = 200, body = SignInResponse),
+        (status = 400, description = "Wrong email or password", body = ApiErrorEnvelope),
+        (status = 422, description = "Validation failed", body = ApiErrorEnvelope),
+    ),
+)]
 #[debug_handler]
 pub async fn sign_in(
     State(state): State<Arc<AppState>>,
-    Json(credentials): Json<SignInRequest>,
+    ValidatedJson(credentials): ValidatedJson<SignInRequest>,
 ) -> Result<Json<SignInResponse>, UsersServiceError> {
     let response = state.users_service.sign_in(credentials).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/sign-up",
+    context_path = API_PREFIX,
+    tag = "auth",
+    request_body = SignUpRequest,
+    responses(
+        (status = 200, body = SignUpResponse),
+        (status = 400, description = "The details were rejected", body = ApiErrorEnvelope),
+        (status = 422, description = "Validation failed", body = ApiErrorEnvelope),
+    ),
+)]
 #[debug_handler]
 pub async fn sign_up(
     State(state): State<Arc<AppState>>,
-    Json(user_data): Json<SignUpRequest>,
+    locale: Locale,
+    ValidatedJson(user_data): ValidatedJson<SignUpRequest>,
 ) -> Result<Json<SignUpResponse>, UsersServiceError> {
-    let response = state.users_service.sign_up(user_data).await?;
+    let response = state.users_service.sign_up(user_data, locale).await?;
     Ok(Json(response))
 }
 
+/// Needs a `write` key of an admin.
+#[utoipa::path(
+    post,
+    path = "/users",
+    context_path = API_PREFIX,
+    tag = "users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 403, description = "The key may not create users", body = ApiErrorEnvelope),
+        (status = 422, description = "Validation failed", body = ApiErrorEnvelope),
+    ),
+    security(("api_key" = ["write"])),
+)]
 #[debug_handler]
 pub async fn create_user(
+    auth: ApiKeyAuth,
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<CreateUser>,
-) -> Result<Json<User>, UsersServiceError> {
-    let created = state.users_service.create(payload).await?;
-    Ok(Json(created))
+    ValidatedJson(payload): ValidatedJson<CreateUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    auth.require_scope("write")?;
+    require_account_access(&state, &auth, None).await?;
+    let created = state.users_service.create(payload.into()).await?;
+    Ok(Json(created.into()))
 }
+/// Tagged with the user's `updated_at`, so clients revalidate with
+/// `If-None-Match` and get 304 until the profile changes. Needs a `read` key.
+#[utoipa::path(
+    get,
+    path = "/users/{id}",
+    context_path = API_PREFIX,
+    tag = "users",
+    params(("id" = Uuid, Path)),
+    responses(
+        (status = 200, body = PublicUser),
+        (status = 304, description = "The profile has not changed"),
+        (status = 404, description = "No such user", body = ApiErrorEnvelope),
+    ),
+    security(("api_key" = ["read"])),
+)]
 pub async fn get_user_by_id(
+    auth: ApiKeyAuth,
     Path(id): Path<String>,
     State(state): State<Arc<AppState>>,
-) -> Result<Json<User>, UsersServiceError> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    auth.require_scope("read")?;
     let user = state.users_service.get_by_id(&id).await?;
-    Ok(Json(user))
+    let tag = version_etag(user.id, user.updated_at);
+    let last_modified = http_date(user.updated_at);
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| etag_matches(v, &tag));
+    let mut res = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        Json(PublicUser::from(user)).into_response()
+    };
+    for (name, value) in [(header::ETAG, tag), (header::LAST_MODIFIED, last_modified)] {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            res.headers_mut().insert(name, value);
+        }
+    }
+    Ok(res)
 }
 
-#[derive(Deserialize)]
-pub struct ListUsersRequest {
-    pub page: u32,
-    pub per_page: u32,
-    pub search_query: Option<String>,
+/// Results differ per key owner, who never sees users they blocked or who
+/// blocked them, so only their client may cache them. A newly blocked user
+/// can linger in suggestions for this long.
+const SUGGEST_CACHE_CONTROL: &str = "private, max-age=300";
+
+/// `?q=` autocompletes usernames for mentions and share invites, at most 10.
+/// Needs an API key with the `read` scope.
+#[utoipa::path(
+    get,
+    path = "/users/suggest",
+    context_path = API_PREFIX,
+    tag = "users",
+    params(SuggestUsersQuery),
+    responses((status = 200, body = Vec<UserSuggestionResponse>)),
+    security(("api_key" = ["read"])),
+)]
+pub async fn suggest_users(
+    auth: ApiKeyAuth,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SuggestUsersQuery>,
+) -> Result<Response, ApiError> {
+    auth.require_scope("read")?;
+    let users = state
+        .search_service
+        .mention_suggestions(&query.q, auth.0.user_id)
+        .await
+        .map_err(UsersServiceError::from)?;
+    let users: Vec<UserSuggestionResponse> = users.into_iter().map(Into::into).collect();
+    let mut res = Json(users).into_response();
+    res.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(SUGGEST_CACHE_CONTROL),
+    );
+    Ok(res)
 }
 
+/// `?fields=id,username` leaves the other user fields out of the response.
+/// Needs a `read` key.
+#[utoipa::path(
+    get,
+    path = "/users",
+    context_path = API_PREFIX,
+    tag = "users",
+    params(UserFieldsQuery),
+    request_body = ListUsersRequest,
+    responses(
+        (status = 200, body = UserListView),
+        (status = 400, description = "Unknown field", body = ApiErrorEnvelope),
+        (status = 422, description = "Validation failed", body = ApiErrorEnvelope),
+    ),
+    security(("api_key" = ["read"])),
+)]
 pub async fn list_users(
+    auth: ApiKeyAuth,
     State(state): State<Arc<AppState>>,
-    Json(data): Json<ListUsersRequest>,
-) -> Result<Json<UserListResponse>, UsersServiceError> {
+    Query(query): Query<UserFieldsQuery>,
+    ValidatedJson(data): ValidatedJson<ListUsersRequest>,
+) -> Result<Json<UserListView>, ApiError> {
+    auth.require_scope("read")?;
+    let fields = UserFields::parse(query.fields.as_deref()).map_err(|field| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "wrong_input",
+            format!("Unknown field: {field}"),
+        )
+    })?;
     let result = state
         .users_service
-        .list(data.page, data.per_page, data.search_query)
+        .list(
+            data.page,
+            data.per_page,
+            data.search_query,
+            data.updated_since,
+        )
         .await?;
-    Ok(Json(result))
-}
-
-#[derive(Debug, Deserialize)]
-pub struct UpdateUserRequest {
-    pub username: Option<String>,
-    pub email: Option<String>,
-    pub password: Option<String>,
-    pub old_password: Option<String>,
-    pub first_name: Option<String>,
-    pub last_name: Option<String>,
-    pub bio: Option<String>,
+    Ok(Json(UserListView::new(result.into(), fields)))
 }
 
+/// Needs a `write` key of the user or of an admin.
+#[utoipa::path(
+    patch,
+    path = "/users/{id}",
+    context_path = API_PREFIX,
+    tag = "users",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 403, description = "Not the key owner's account", body = ApiErrorEnvelope),
+        (status = 422, description = "Validation failed", body = ApiErrorEnvelope),
+    ),
+    security(("api_key" = ["write"])),
+)]
 pub async fn update_user(
+    auth: ApiKeyAuth,
     Path(id): Path<String>,
     State(state): State<Arc<AppState>>,
-    Json(data): Json<UpdateUserRequest>,
-) -> Result<Json<User>, UsersServiceError> {
-    let upd = UpdateUser {
-        username: data.username,
-        email: data.email,
-        password: data.password,
-        first_name: data.first_name,
-        last_name: data.last_name,
-        bio: data.bio,
-    };
-    let updated = state
+    ValidatedJson(mut data): ValidatedJson<UpdateUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    auth.require_scope("write")?;
+    require_account_access(&state, &auth, Some(&id)).await?;
+    let old_password = data.old_password.take();
+    // Renames go through UsernameService so old names keep resolving
+    let username = data.username.take();
+    // New emails only apply once confirmed from the new address
+    let email = data.email.take();
+    let mut updated = state
         .users_service
-        .update(&id, upd, data.old_password)
+        .update(&id, data.into(), old_password)
         .await?;
-    Ok(Json(updated))
-}
-
-#[derive(Debug, Serialize)]
-pub struct DeleteUserResponse {
-    pub deleted_id: uuid::Uuid,
+    if let Some(username) = username {
+        updated = state.username_service.rename(updated.id, &username).await?;
+    }
+    if let Some(email) = email {
+        state
+            .email_change_service
+            .request(updated.id, &email)
+            .await?;
+    }
+    Ok(Json(updated.into()))
 }
 
+/// Needs a `write` key of the user or of an admin.
+#[utoipa::path(
+    delete,
+    path = "/users/{id}",
+    context_path = API_PREFIX,
+    tag = "users",
+    params(("id" = Uuid, Path)),
+    responses(
+        (status = 200, body = DeleteUserResponse),
+        (status = 403, description = "Not the key owner's account", body = ApiErrorEnvelope),
+    ),
+    security(("api_key" = ["write"])),
+)]
 pub async fn delete_user(
+    auth: ApiKeyAuth,
     Path(id): Path<String>,
     State(state): State<Arc<AppState>>,
-) -> Result<Json<DeleteUserResponse>, UsersServiceError> {
+) -> Result<Json<DeleteUserResponse>, ApiError> {
+    auth.require_scope("write")?;
+    require_account_access(&state, &auth, Some(&id)).await?;
     let deleted_id = state.users_service.delete(&id).await?;
     Ok(Json(DeleteUserResponse { deleted_id }))
 }
+
+/// Keys act on their owner's account; only admins' keys act on others, or
+/// on no account in particular when `id` is `None`.
+async fn require_account_access(
+    state: &AppState,
+    auth: &ApiKeyAuth,
+    id: Option<&str>,
+) -> Result<(), ApiError> {
+    let owner = state
+        .users_service
+        .get_by_id(&auth.0.user_id.to_string())
+        .await?;
+    let own = id.is_some_and(|id| id.parse::<Uuid>().is_ok_and(|id| id == owner.id));
+    if own || owner.is_admin {
+        Ok(())
+    } else {
+        Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            "API key may not act on this account",
+        ))
+    }
+}
@@ -0,0 +1,39 @@
+//! Optional error reporting to Sentry (or a Sentry-compatible service such
+//! as GlitchTip), enabled by setting `sentry.dsn`. Panics, `error!` events
+//! and database errors are reported with the request they happened in.
+
+use sentry::ClientInitGuard;
+
+use crate::configuration::Settings;
+
+/// Starts the Sentry client when `sentry.dsn` is set. The guard flushes
+/// pending events when dropped, so keep it alive until shutdown.
+pub fn init(settings: &Settings) -> Option<ClientInitGuard> {
+    let dsn = settings.sentry.dsn.clone();
+    if dsn.is_empty() {
+        return None;
+    }
+    let environment = settings.app.environment.clone();
+    let release = match option_env!("GIT_COMMIT_HASH") {
+        Some(commit) => format!("culturelist@{}+{commit}", env!("CARGO_PKG_VERSION")),
+        None => format!("culturelist@{}", env!("CARGO_PKG_VERSION")),
+    };
+    let sample_rate = settings.sentry.sample_rate;
+    let guard = sentry::init((
+        dsn,
+        sentry::ClientOptions {
+            release: Some(release.into()),
+            environment: Some(environment.into()),
+            sample_rate,
+            // Request bodies and headers may carry passwords and tokens
+            send_default_pii: false,
+            ..Default::default()
+        },
+    ));
+    Some(guard)
+}
+
+/// Reports `error` to the current request's hub; a no-op when reporting is off.
+pub fn capture_error<E: std::error::Error + ?Sized>(error: &E) {
+    sentry::capture_error(error);
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, FromRequest, FromRequestParts, Request},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response, Sse, sse::Event},
+};
+use datastar::{axum::ReadSignals, prelude::PatchSignals};
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::{
+    AppState, controllers::ApiError, i18n::Locale, models::domain::ApiKey,
+    validation::password_policy,
+};
+
+const DATASTAR_REQUEST_HEADER: &str = "datastar-request";
+pub(crate) const API_KEY_HEADER: &str = "x-api-key";
+
+/// Deserializes a JSON body (or datastar signals) and runs `validator::Validate` on it.
+pub struct Validated<T>(pub T);
+
+/// JSON-only counterpart of [`Validated`] for API routes, rejecting with an [`ApiError`].
+pub struct ValidatedJson<T>(pub T);
+
+pub enum ValidationRejection {
+    Malformed(Response),
+    Json(ApiError),
+    Signals(ValidationErrors, Locale),
+}
+
+impl<S, T> FromRequest<S> for Validated<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = ValidationRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let from_datastar = req.headers().contains_key(DATASTAR_REQUEST_HEADER);
+        if from_datastar {
+            let (req, locale) = with_locale(req, state).await;
+            let ReadSignals(value) = ReadSignals::<T>::from_request(req, state)
+                .await
+                .map_err(|e| ValidationRejection::Malformed(e.into_response()))?;
+            value
+                .validate()
+                .map_err(|e| ValidationRejection::Signals(e, locale))?;
+            Ok(Self(value))
+        } else {
+            let ValidatedJson(value) = ValidatedJson::<T>::from_request(req, state)
+                .await
+                .map_err(ValidationRejection::Json)?;
+            Ok(Self(value))
+        }
+    }
+}
+
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let (req, locale) = with_locale(req, state).await;
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::new(e.status(), "malformed_request", e.body_text()))?;
+        value
+            .validate()
+            .map_err(|e| ApiError::validation(&e, locale))?;
+        Ok(Self(value))
+    }
+}
+
+/// Resolves the request's [`Locale`] before the body is consumed.
+async fn with_locale<S: Send + Sync>(req: Request, state: &S) -> (Request, Locale) {
+    let (mut parts, body) = req.into_parts();
+    let locale = Locale::from_request_parts(&mut parts, state)
+        .await
+        .unwrap_or_default();
+    (Request::from_parts(parts, body), locale)
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        match self {
+            ValidationRejection::Malformed(response) => response,
+            ValidationRejection::Json(error) => error.into_response(),
+            ValidationRejection::Signals(errors, locale) => {
+                use asynk_strim::{Yielder, stream_fn};
+                // Forms render `<field>_error` signals next to their inputs
+                let signals: BTreeMap<String, String> = field_messages(&errors, locale)
+                    .into_iter()
+                    .map(|(field, messages)| (format!("{field}_error"), messages.join(", ")))
+                    .collect();
+                Sse::new(stream_fn(
+                    move |mut yielder: Yielder<Result<Event, Infallible>>| async move {
+                        let patch =
+                            PatchSignals::new(serde_json::to_string(&signals).unwrap_or_default());
+                        let sse_event = patch.write_as_axum_sse_event();
+                        yielder.yield_item(Ok(sse_event)).await;
+                    },
+                ))
+                .into_response()
+            }
+        }
+    }
+}
+
+/// Authenticates JSON API requests by the `X-Api-Key` header.
+pub struct ApiKeyAuth(pub ApiKey);
+
+impl ApiKeyAuth {
+    pub fn require_scope(&self, scope: &str) -> Result<(), ApiError> {
+        if self.0.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                "insufficient_scope",
+                format!("API key lacks the `{scope}` scope"),
+            ))
+        }
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for ApiKeyAuth {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let key = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| {
+                ApiError::new(
+                    StatusCode::UNAUTHORIZED,
+                    "missing_api_key",
+                    "X-Api-Key header is required",
+                )
+            })?;
+        let api_key = state.api_keys_service.authenticate(key).await?;
+        Ok(Self(api_key))
+    }
+}
+
+/// The client's address, resolved through trusted proxies; `None` when it
+/// cannot be known.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl FromRequestParts<Arc<AppState>> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        // Only TCP listeners provide connect info
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        Ok(Self(state.trusted_proxies.resolve(peer, &parts.headers)))
+    }
+}
+
+pub fn field_messages(errors: &ValidationErrors, locale: Locale) -> BTreeMap<String, Vec<String>> {
+    let mut res = BTreeMap::new();
+    for (field, kind) in errors.errors() {
+        if let ValidationErrorsKind::Field(field_errors) = kind {
+            let messages = field_errors.iter().map(|e| message(e, locale)).collect();
+            res.insert(field.to_string(), messages);
+        }
+    }
+    res
+}
+
+fn message(error: &ValidationError, locale: Locale) -> String {
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+    match error.code.as_ref() {
+        "email" => locale.t("invalid-email"),
+        "password_requirements" => password_policy().describe(locale),
+        "search_length" => locale.t_args(
+            "validation-search-length",
+            &[
+                ("min", param("min").unwrap_or_default()),
+                ("max", param("max").unwrap_or_default()),
+            ],
+        ),
+        "length" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => {
+                locale.t_args("validation-length-range", &[("min", min), ("max", max)])
+            }
+            (Some(min), None) => locale.t_args("validation-length-min", &[("min", min)]),
+            (None, Some(max)) => locale.t_args("validation-length-max", &[("max", max)]),
+            (None, None) => locale.t("validation-length"),
+        },
+        _ => locale.t("validation-invalid"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Payload {
+        #[validate(email)]
+        email: String,
+        #[validate(length(min = 2, max = 4))]
+        name: String,
+    }
+
+    #[test]
+    fn test_field_messages_valid_payload() {
+        let payload = Payload {
+            email: "test@example.com".to_string(),
+            name: "abc".to_string(),
+        };
+        assert!(payload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_field_messages_per_field() {
+        let payload = Payload {
+            email: "invalid-email".to_string(),
+            name: "a".to_string(),
+        };
+        let errors = payload.validate().unwrap_err();
+        let messages = field_messages(&errors, Locale::Ru);
+
+        assert_eq!(
+            messages["email"],
+            vec!["Введите корректный email".to_string()]
+        );
+        assert_eq!(
+            messages["name"],
+            vec!["Длина должна быть от 2 до 4 символов".to_string()]
+        );
+
+        let messages = field_messages(&errors, Locale::En);
+        assert_eq!(
+            messages["name"],
+            vec!["Length must be 2 to 4 characters".to_string()]
+        );
+    }
+}
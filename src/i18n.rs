@@ -0,0 +1,188 @@
+//! Message catalogs in `locales/<lang>/*.ftl`, looked up through [`Locale`].
+
+use std::{borrow::Cow, collections::HashMap};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use fluent_templates::{LanguageIdentifier, Loader, fluent_bundle::FluentValue, static_loader};
+use serde::{Deserialize, Serialize};
+
+static_loader! {
+    static LOCALES = {
+        locales: "./locales",
+        fallback_language: "ru",
+        // Messages end up in HTML and signals, not bidirectional text
+        customise: |bundle| bundle.set_use_isolating(false),
+    };
+}
+
+/// Session key holding the visitor's chosen locale code.
+pub const LOCALE_KEY: &str = "locale";
+
+/// Timezone for visitors without a stored preference.
+pub const DEFAULT_TIMEZONE: Tz = Tz::UTC;
+
+/// IANA timezone name such as `Europe/Moscow`.
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Ru,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Ru, Locale::En];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Ru => "ru",
+            Locale::En => "en",
+        }
+    }
+
+    /// Name of the language in itself, for the language switcher
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::Ru => "Русский",
+            Locale::En => "English",
+        }
+    }
+
+    /// Matches on the primary language subtag, so `en-GB` is English.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|l| l.code() == primary)
+    }
+
+    /// Picks the stored preference if any, then the best `Accept-Language`
+    /// match, then the default.
+    pub fn negotiate(preference: Option<&str>, accept_language: Option<&str>) -> Self {
+        if let Some(locale) = preference.and_then(Self::from_code) {
+            return locale;
+        }
+        let Some(accept_language) = accept_language else {
+            return Self::default();
+        };
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse().ok())?;
+                Some((tag, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+        // Stable, so equal weights keep the header's order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| Self::from_code(tag))
+            .unwrap_or_default()
+    }
+
+    /// `at` in `tz`, written the way this locale writes dates.
+    pub fn format_datetime(&self, at: &DateTime<Utc>, tz: Tz) -> String {
+        let format = match self {
+            Locale::Ru => "%d.%m.%Y %H:%M",
+            Locale::En => "%b %-d, %Y %H:%M",
+        };
+        at.with_timezone(&tz).format(format).to_string()
+    }
+
+    fn langid(&self) -> LanguageIdentifier {
+        self.code()
+            .parse()
+            .expect("locale codes are valid language identifiers")
+    }
+
+    /// Message `key` in this locale.
+    pub fn t(&self, key: &str) -> String {
+        LOCALES.lookup(&self.langid(), key)
+    }
+
+    /// Message `key` with `{ $name }` placeholders filled from `args`.
+    pub fn t_args(&self, key: &str, args: &[(&'static str, String)]) -> String {
+        let args: HashMap<Cow<'static, str>, FluentValue> = args
+            .iter()
+            .map(|(name, value)| (Cow::Borrowed(*name), FluentValue::from(value.clone())))
+            .collect();
+        LOCALES.lookup_with_args(&self.langid(), key, &args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Locale::from_code("ru"), Some(Locale::Ru));
+        assert_eq!(Locale::from_code("en-GB"), Some(Locale::En));
+        assert_eq!(Locale::from_code("EN_us"), Some(Locale::En));
+        assert_eq!(Locale::from_code("de"), None);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Locale::negotiate(None, None), Locale::Ru);
+        assert_eq!(
+            Locale::negotiate(None, Some("de-DE,en;q=0.8,ru;q=0.5")),
+            Locale::En
+        );
+        assert_eq!(
+            Locale::negotiate(None, Some("ru;q=0.4, en-US;q=0.9")),
+            Locale::En
+        );
+        assert_eq!(Locale::negotiate(None, Some("en;q=0, fr")), Locale::Ru);
+        assert_eq!(Locale::negotiate(Some("ru"), Some("en")), Locale::Ru);
+        assert_eq!(Locale::negotiate(Some("xx"), Some("en")), Locale::En);
+    }
+
+    #[test]
+    fn test_catalogs() {
+        assert_eq!(Locale::Ru.t("sign-in"), "Войти");
+        assert_eq!(Locale::En.t("sign-in"), "Sign in");
+        assert_eq!(
+            Locale::En.t_args("impersonation-banner", &[("username", "reader".into())]),
+            "Signed in as reader for support"
+        );
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        let at = DateTime::parse_from_rfc3339("2026-03-01T21:30:00Z")
+            .unwrap()
+            .to_utc();
+        let moscow = parse_timezone("Europe/Moscow").unwrap();
+        assert_eq!(Locale::Ru.format_datetime(&at, moscow), "02.03.2026 00:30");
+        assert_eq!(
+            Locale::En.format_datetime(&at, Tz::UTC),
+            "Mar 1, 2026 21:30"
+        );
+        assert!(parse_timezone("Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_catalogs_have_same_keys() {
+        let keys = |source: &str| {
+            let mut keys: Vec<String> = source
+                .lines()
+                .filter_map(|line| line.split_once(" = ").map(|(key, _)| key.to_string()))
+                .collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(
+            keys(include_str!("../locales/ru/main.ftl")),
+            keys(include_str!("../locales/en/main.ftl"))
+        );
+    }
+}
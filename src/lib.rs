@@ -1,61 +1,389 @@
 use anyhow::Result;
-use axum_session::SessionConfig;
-use axum_session_sqlx::SessionPgSessionStore;
+use axum_session::{SessionConfig, SessionStore};
+use axum_session_sqlx::{SessionPgPool, SessionPgSessionStore};
 use config::Config;
 use sqlx::{Pool, Postgres};
 
-use crate::{services::UsersService, storage::UsersStorage};
+use crate::{
+    client_ip::TrustedProxies,
+    configuration::Settings,
+    listener::{ListenAddr, Listener},
+    models::domain::{
+        BotProtectionSettings, EmailCanonicalSettings, EmailDomainSettings, ModerationSettings,
+        SearchSettings, SignupSettings,
+    },
+    services::{
+        ApiKeysService, BadgesService, BlocksService, BotProtectionService, CleanupTask,
+        ClubsService, DataExportsService, DataExportsTask, DisposableDomainsTask,
+        EmailChangeService, EmailDomainPolicy, EventRemindersTask, EventsService, GenresService,
+        IdempotencyService, Mailer, ModerationService, PageCache, PageCacheSettings, PollsService,
+        Scheduler, SchedulerSettings, SearchService, SystemService, TrafficStats, UsernameService,
+        UsersService, VenuesService,
+    },
+    storage::{
+        ApiKeysStorage, ClubsStorage, CuratorApplicationsStorage, DataExportsStorage,
+        EmailChangesStorage, EventsStorage, GenresStorage, IdempotencyKeysStorage, PollsStorage,
+        ReportsStorage, SystemStorage, UserBlocksStorage, UsersStorage, VenuesStorage,
+    },
+};
 
+pub mod assets;
+pub mod cli;
+pub mod client_ip;
 pub mod configuration;
 pub mod controllers;
+pub mod error_reporting;
+pub mod extractors;
+pub mod i18n;
+pub mod listener;
 pub mod logger;
+pub mod markdown;
+pub mod meta;
 pub mod models;
 mod router;
 mod services;
+pub mod smoke;
 mod storage;
+#[cfg(feature = "test_support")]
+pub mod test_support;
+pub mod validation;
 
-pub async fn build(config: &Config) -> Result<App> {
+/// `settings` must come from [`configuration::Settings::load`] on `config`.
+pub async fn build(config: &Config, settings: &Settings) -> Result<App> {
     tracing::info!("Building application");
-    let pool = storage::get_pool(config).await?;
-    let port = config.get_int("server.port").unwrap_or(3000) as u16;
-    Ok(App { pool, port })
+    let pool = storage::get_pool(&settings.database).await?;
+    let read_pool = storage::get_read_pool(&settings.database).await?;
+    App::new(pool, read_pool, config, settings)
 }
 
 pub struct App {
     pool: Pool<Postgres>,
-    port: u16,
+    read_pool: Option<Pool<Postgres>>,
+    argon2_params: argon2::Params,
+    listen: ListenAddr,
+    trusted_proxies: TrustedProxies,
+    environment: String,
+    /// Public address used in links sent by email
+    base_url: String,
+    settings: Vec<(String, String)>,
+    signup: SignupSettings,
+    email_domains: EmailDomainSettings,
+    email_canonical: EmailCanonicalSettings,
+    bot_protection: BotProtectionSettings,
+    privacy_mode: bool,
+    /// Idle lifetime of regular sessions
+    session_lifetime: chrono::Duration,
+    /// Lifetime of sessions signed in with "remember me"
+    remember_lifetime: chrono::Duration,
+    jwt_secret: String,
+    /// Empty generates a CSRF cookie key per start
+    cookie_key: String,
+    security_headers: router::SecurityHeaders,
+    page_cache: PageCacheSettings,
+    moderation: ModerationSettings,
+    search: SearchSettings,
+    scheduler: SchedulerSettings,
 }
 
 #[derive(Clone)]
 pub struct AppState {
     pub users_service: UsersService,
+    pub system_service: SystemService,
+    pub api_keys_service: ApiKeysService,
+    pub badges_service: BadgesService,
+    pub username_service: UsernameService,
+    pub bot_protection_service: BotProtectionService,
+    pub email_change_service: EmailChangeService,
+    pub moderation_service: ModerationService,
+    pub search_service: SearchService,
+    pub clubs_service: ClubsService,
+    pub data_exports_service: DataExportsService,
+    pub events_service: EventsService,
+    pub polls_service: PollsService,
+    pub venues_service: VenuesService,
+    pub genres_service: GenresService,
+    pub blocks_service: BlocksService,
+    pub idempotency_service: IdempotencyService,
+    /// Rendered public pages for anonymous visitors
+    pub page_cache: PageCache,
+    /// Recent requests for the admin traffic page
+    pub traffic_stats: TrafficStats,
+    pub trusted_proxies: TrustedProxies,
 }
 
 impl App {
+    /// [`build`] on already connected pools, with migrations applied.
+    pub fn new(
+        pool: Pool<Postgres>,
+        read_pool: Option<Pool<Postgres>>,
+        config: &Config,
+        settings: &Settings,
+    ) -> Result<Self> {
+        let argon2_params = storage::argon2_params(&settings.argon2)?;
+        let listen = settings
+            .listen_addr()
+            .map_err(|e| anyhow::anyhow!("server.listen: {e}"))?;
+        let environment = settings.app.environment.clone();
+        let base_url = settings.base_url();
+        let trusted_proxies = settings
+            .trusted_proxies()
+            .map_err(|e| anyhow::anyhow!("server.trusted_proxies: {e}"))?;
+        let redacted_config = configuration::redacted(config);
+        validation::init(settings.password.clone());
+        storage::query_stats::init(settings.database.slow_query_ms);
+        let signup = settings.signup.clone();
+        let email_domains = settings.email_domains.clone();
+        let email_canonical = settings.email_canonical.clone();
+        let bot_protection = settings.bot_protection.clone();
+        let privacy_mode = settings.auth.privacy_mode;
+        let session_lifetime = chrono::Duration::hours(settings.session.lifetime_hours);
+        let remember_lifetime = chrono::Duration::days(settings.session.remember_days);
+        let jwt_secret = settings.jwt.secret.clone();
+        let cookie_key = settings.cookies.key.clone();
+        let security_headers = settings.security.clone();
+        let page_cache = settings.cache.clone();
+        let moderation = settings.moderation.clone();
+        let search = settings.search.clone();
+        let scheduler = settings.scheduler.clone();
+        Ok(App {
+            pool,
+            read_pool,
+            argon2_params,
+            listen,
+            trusted_proxies,
+            environment,
+            base_url,
+            settings: redacted_config,
+            signup,
+            email_domains,
+            email_canonical,
+            bot_protection,
+            privacy_mode,
+            session_lifetime,
+            remember_lifetime,
+            jwt_secret,
+            cookie_key,
+            security_headers,
+            page_cache,
+            moderation,
+            search,
+            scheduler,
+        })
+    }
+
     pub async fn run(&self) -> Result<()> {
-        // sessions
-        let session_config = SessionConfig::default().with_table_name("sessions_table");
-        let session_store =
-            SessionPgSessionStore::new(Some(self.pool.clone().into()), session_config)
-                .await
-                .unwrap();
+        let listener = Listener::bind(&self.listen).await?;
+        self.serve(listener, shutdown_signal()).await
+    }
 
+    /// Serves on an already bound `listener` until `shutdown` completes.
+    pub async fn serve<F>(&self, listener: Listener, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let session_store = self.session_store(true).await?;
+        let (service, scheduler) = self.router(session_store).await?;
+        scheduler.start();
+        let served = listener.serve(service, shutdown).await;
+        scheduler.shutdown();
+        served?;
+
+        Ok(())
+    }
+
+    /// Sessions kept in the database, or only in memory when `persistent`
+    /// is false, as in tests.
+    pub async fn session_store(&self, persistent: bool) -> Result<SessionStore<SessionPgPool>> {
+        let session_config = SessionConfig::default()
+            .with_table_name(storage::SESSIONS_TABLE)
+            .with_lifetime(self.session_lifetime)
+            .with_max_lifetime(self.remember_lifetime);
+        let pool = persistent.then(|| self.pool.clone().into());
+        let session_store = SessionPgSessionStore::new(pool, session_config).await?;
+        Ok(session_store)
+    }
+
+    /// The whole site on `session_store`, ready to serve or to call with
+    /// `tower::ServiceExt::oneshot`. The scheduler is returned unstarted.
+    pub async fn router(
+        &self,
+        session_store: SessionStore<SessionPgPool>,
+    ) -> Result<(axum::Router, Scheduler)> {
         // services
-        let users_storage = UsersStorage::new(self.pool.clone()).await?;
-        let users_service = UsersService::new(users_storage);
+        let page_cache = PageCache::new(self.page_cache.clone());
+        let users_storage = UsersStorage::new(self.pool.clone())
+            .await?
+            .with_read_pool(self.read_pool.clone())
+            .with_argon2_params(self.argon2_params.clone())
+            .with_search_settings(self.search.clone())
+            .with_email_canonical_settings(self.email_canonical.clone());
+        let email_domain_policy = EmailDomainPolicy::new(self.email_domains.clone());
+        let users_service = UsersService::new(users_storage.clone(), self.signup.clone())
+            .with_privacy_mode(self.privacy_mode)
+            .with_jwt_secret(self.jwt_secret.clone())
+            .with_email_domains(email_domain_policy.clone());
+        let system_storage = SystemStorage::new(self.pool.clone()).await?;
+        let email_changes_storage = EmailChangesStorage::new(self.pool.clone()).await?;
+        let events_storage = EventsStorage::new(self.pool.clone()).await?;
+        let idempotency_keys_storage = IdempotencyKeysStorage::new(self.pool.clone()).await?;
+        let data_exports_storage = DataExportsStorage::new(self.pool.clone()).await?;
+        let mut scheduler = Scheduler::new(self.scheduler.clone())
+            .with_task(CleanupTask::new(
+                system_storage.clone(),
+                email_changes_storage.clone(),
+                idempotency_keys_storage.clone(),
+                data_exports_storage.clone(),
+                users_storage.clone(),
+            ))
+            .with_task(DataExportsTask::new(
+                data_exports_storage.clone(),
+                users_service.clone(),
+                Mailer::new(&self.environment),
+                self.base_url.clone(),
+            ))
+            .with_task(EventRemindersTask::new(
+                events_storage.clone(),
+                Mailer::new(&self.environment),
+                self.base_url.clone(),
+            ));
+        if !self.email_domains.disposable_list_url.is_empty() {
+            scheduler = scheduler.with_task(DisposableDomainsTask::new(
+                email_domain_policy,
+                self.email_domains.disposable_list_url.clone(),
+            ));
+        }
+        let system_service = SystemService::new(
+            system_storage,
+            self.environment.clone(),
+            self.settings.clone(),
+        )
+        .with_scheduler(scheduler.clone());
+        let api_keys_storage = ApiKeysStorage::new(self.pool.clone()).await?;
+        let api_keys_service = ApiKeysService::new(api_keys_storage);
+        let curator_applications_storage =
+            CuratorApplicationsStorage::new(self.pool.clone()).await?;
+        let badges_service =
+            BadgesService::new(curator_applications_storage, users_storage.clone())
+                .with_page_cache(page_cache.clone());
+        let email_change_service = EmailChangeService::new(
+            email_changes_storage,
+            users_storage.clone(),
+            Mailer::new(&self.environment),
+            self.base_url.clone(),
+        )
+        .with_privacy_mode(self.privacy_mode);
+        let reports_storage = ReportsStorage::new(self.pool.clone()).await?;
+        let moderation_service = ModerationService::new(
+            reports_storage,
+            users_storage.clone(),
+            self.moderation.clone(),
+        )
+        .with_page_cache(page_cache.clone());
+        let search_service = SearchService::new(users_storage.clone());
+        let clubs_storage = ClubsStorage::new(self.pool.clone()).await?;
+        let clubs_service = ClubsService::new(clubs_storage.clone());
+        let polls_service = PollsService::new(
+            PollsStorage::new(self.pool.clone()).await?,
+            clubs_storage.clone(),
+        );
+        let venues_storage = VenuesStorage::new(self.pool.clone()).await?;
+        let events_service = EventsService::new(
+            events_storage.clone(),
+            clubs_storage,
+            venues_storage.clone(),
+        );
+        let venues_service = VenuesService::new(venues_storage, events_storage);
+        let genres_service = GenresService::new(GenresStorage::new(self.pool.clone()).await?);
+        let user_blocks_storage = UserBlocksStorage::new(self.pool.clone()).await?;
+        let blocks_service = BlocksService::new(user_blocks_storage, users_storage.clone());
+        let username_service =
+            UsernameService::new(users_storage).with_page_cache(page_cache.clone());
+        let bot_protection_service = BotProtectionService::new(self.bot_protection.clone());
+        let idempotency_service = IdempotencyService::new(idempotency_keys_storage);
+        let data_exports_service = DataExportsService::new(data_exports_storage);
 
         // app state
-        let app_state = AppState { users_service };
+        let app_state = AppState {
+            users_service,
+            system_service,
+            api_keys_service,
+            badges_service,
+            username_service,
+            bot_protection_service,
+            email_change_service,
+            moderation_service,
+            search_service,
+            clubs_service,
+            data_exports_service,
+            events_service,
+            polls_service,
+            venues_service,
+            genres_service,
+            blocks_service,
+            idempotency_service,
+            page_cache,
+            traffic_stats: TrafficStats::default(),
+            trusted_proxies: self.trusted_proxies.clone(),
+        };
 
-        // server
-        let addr = format!("0.0.0.0:{p}", p = self.port);
-        let listener = tokio::net::TcpListener::bind(&addr).await?;
-        let service = router::init(&format!("http://{addr}"), session_store, app_state);
-        axum::serve(listener, service)
-            .with_graceful_shutdown(shutdown_signal())
-            .await?;
+        // router
+        // Behind a unix socket the browser only ever sees the proxy's origin
+        let allowed_origin = match &self.listen {
+            ListenAddr::Tcp(addr) => format!("http://{addr}"),
+            ListenAddr::Unix(_) => self.base_url.clone(),
+        };
+        let service = router::init(
+            &allowed_origin,
+            session_store,
+            &self.cookie_key,
+            self.security_headers.clone(),
+            app_state,
+        );
+        Ok((service, scheduler))
+    }
 
-        Ok(())
+    /// `app migrate`: migrations are applied while building the app, this
+    /// reports where the schema stands.
+    pub async fn migrations(&self) -> Result<Vec<models::domain::MigrationStatus>> {
+        let system_storage = SystemStorage::new(self.pool.clone()).await?;
+        Ok(system_storage.migrations().await?)
+    }
+
+    /// `app create-admin --email <email>`, see [`UsersService::create_admin`].
+    pub async fn create_admin(
+        &self,
+        email: &str,
+        username: Option<&str>,
+    ) -> Result<(models::domain::User, Option<String>)> {
+        let users_storage = UsersStorage::new(self.pool.clone())
+            .await?
+            .with_argon2_params(self.argon2_params.clone())
+            .with_email_canonical_settings(self.email_canonical.clone());
+        let users_service = UsersService::new(users_storage, self.signup.clone());
+        let created = users_service
+            .create_admin(email, username)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to create admin {email}: {e}"))?;
+        Ok(created)
+    }
+
+    /// `app cleanup-sessions`: returns how many expired sessions were deleted.
+    pub async fn cleanup_sessions(&self) -> Result<u64> {
+        let system_storage = SystemStorage::new(self.pool.clone()).await?;
+        Ok(services::purge_sessions(&system_storage).await?)
+    }
+
+    /// `app anonymize <user>`: erases a user's personal data on a GDPR
+    /// request. Runs outside the web server, so nobody signed in, including
+    /// an admin impersonating someone, can trigger it.
+    pub async fn anonymize_user(&self, user: &str) -> Result<models::domain::User> {
+        let users_storage = UsersStorage::new(self.pool.clone()).await?;
+        let users_service = UsersService::new(users_storage, self.signup.clone());
+        let anonymized = users_service
+            .anonymize(user)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to anonymize {user}: {e}"))?;
+        Ok(anonymized)
     }
 }
 
@@ -0,0 +1,211 @@
+//! Where the server accepts connections: `server.listen` as `tcp://host:port`
+//! or `unix:///path/to.sock` (e.g. behind nginx), or the socket passed by
+//! systemd socket activation, which takes precedence when present.
+
+use std::{fmt::Display, future::Future, io, net::SocketAddr, path::PathBuf, str::FromStr};
+
+use axum::Router;
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+/// First file descriptor passed by systemd (`SD_LISTEN_FDS_START`).
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(addr) = s.strip_prefix("tcp://") {
+            if addr
+                .rsplit_once(':')
+                .is_none_or(|(_, port)| port.parse::<u16>().is_err())
+            {
+                return Err(format!("{s} has no port"));
+            }
+            Ok(Self::Tcp(addr.to_string()))
+        } else if let Some(path) = s.strip_prefix("unix://") {
+            if path.is_empty() {
+                return Err(format!("{s} has no socket path"));
+            }
+            Ok(Self::Unix(PathBuf::from(path)))
+        } else {
+            Err(format!("{s} is neither tcp://host:port nor unix:///path"))
+        }
+    }
+}
+
+impl Display for ListenAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListenAddr::Tcp(addr) => write!(f, "tcp://{addr}"),
+            ListenAddr::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix {
+        listener: UnixListener,
+        /// Socket file to remove on shutdown; `None` when systemd owns it
+        path: Option<PathBuf>,
+    },
+}
+
+impl Listener {
+    /// The systemd socket if this process was socket activated, otherwise
+    /// a new socket bound to `addr`.
+    pub async fn bind(addr: &ListenAddr) -> io::Result<Self> {
+        #[cfg(unix)]
+        if let Some(listener) = Self::from_systemd()? {
+            tracing::info!("listening on the socket passed by systemd");
+            return Ok(listener);
+        }
+        tracing::info!("listening on {addr}");
+        match addr {
+            ListenAddr::Tcp(addr) => Ok(Self::Tcp(TcpListener::bind(addr).await?)),
+            #[cfg(unix)]
+            ListenAddr::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+                if std::fs::metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+                    // Only a socket nobody listens on is left behind by an
+                    // unclean shutdown; a live one belongs to another server
+                    match std::os::unix::net::UnixStream::connect(path) {
+                        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                            std::fs::remove_file(path)?;
+                        }
+                        Ok(_) => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::AddrInUse,
+                                format!("{} is in use by another process", path.display()),
+                            ));
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(Self::Unix {
+                    listener: UnixListener::bind(path)?,
+                    path: Some(path.clone()),
+                })
+            }
+            #[cfg(not(unix))]
+            ListenAddr::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unix sockets are not supported on this platform",
+            )),
+        }
+    }
+
+    /// Takes the first socket from `LISTEN_FDS` when `LISTEN_PID` is us.
+    #[cfg(unix)]
+    fn from_systemd() -> io::Result<Option<Self>> {
+        use std::os::fd::{FromRawFd, IntoRawFd};
+
+        let for_us = std::env::var("LISTEN_PID")
+            .ok()
+            .and_then(|pid| pid.parse::<u32>().ok())
+            .is_some_and(|pid| pid == std::process::id());
+        let fds = std::env::var("LISTEN_FDS")
+            .ok()
+            .and_then(|fds| fds.parse::<i32>().ok())
+            .unwrap_or(0);
+        if !for_us || fds < 1 {
+            return Ok(None);
+        }
+        if fds > 1 {
+            tracing::warn!("systemd passed {fds} sockets, using the first");
+        }
+        // SAFETY: systemd hands over ownership of the descriptors starting at
+        // SD_LISTEN_FDS_START, and nothing else in the process uses them
+        let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(SD_LISTEN_FDS_START) };
+        // getsockname only decodes AF_UNIX addresses, anything else is TCP
+        if unix.local_addr().is_ok() {
+            unix.set_nonblocking(true)?;
+            return Ok(Some(Self::Unix {
+                listener: UnixListener::from_std(unix)?,
+                path: None,
+            }));
+        }
+        // SAFETY: the descriptor was just released by `into_raw_fd`
+        let tcp = unsafe { std::net::TcpListener::from_raw_fd(unix.into_raw_fd()) };
+        tcp.set_nonblocking(true)?;
+        Ok(Some(Self::Tcp(TcpListener::from_std(tcp)?)))
+    }
+
+    pub async fn serve<F>(self, router: Router, shutdown: F) -> io::Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        match self {
+            Listener::Tcp(listener) => {
+                // The peer address for `ClientIp`
+                axum::serve(
+                    listener,
+                    router.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .await
+            }
+            #[cfg(unix)]
+            Listener::Unix { listener, path } => {
+                let served = axum::serve(listener, router)
+                    .with_graceful_shutdown(shutdown)
+                    .await;
+                if let Some(path) = path {
+                    let _ = std::fs::remove_file(path);
+                }
+                served
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_addr() {
+        assert_eq!(
+            "tcp://0.0.0.0:3000".parse(),
+            Ok(ListenAddr::Tcp("0.0.0.0:3000".to_string()))
+        );
+        assert_eq!(
+            "tcp://[::1]:8080".parse(),
+            Ok(ListenAddr::Tcp("[::1]:8080".to_string()))
+        );
+        assert_eq!(
+            "unix:///run/culturelist/app.sock".parse(),
+            Ok(ListenAddr::Unix(PathBuf::from("/run/culturelist/app.sock")))
+        );
+        assert!("tcp://localhost".parse::<ListenAddr>().is_err());
+        assert!("unix://".parse::<ListenAddr>().is_err());
+        assert!("0.0.0.0:3000".parse::<ListenAddr>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bind_unix_replaces_stale_socket() {
+        let path = std::env::temp_dir().join(format!("culturelist-{}.sock", uuid::Uuid::new_v4()));
+        let addr = ListenAddr::Unix(path.clone());
+        // A socket file left behind by a previous run
+        drop(Listener::bind(&addr).await.unwrap());
+        assert!(path.exists());
+        let listener = Listener::bind(&addr).await.unwrap();
+        assert!(matches!(listener, Listener::Unix { path: Some(_), .. }));
+        // The socket is live now, so a second server must not take it over
+        let err = Listener::bind(&addr).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        drop(listener);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
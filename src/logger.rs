@@ -1,29 +1,48 @@
 use anyhow::Result;
-use config::Config;
+use tracing::Level;
+use tracing_subscriber::layer::SubscriberExt;
 
-pub fn init(config: &Config) -> Result<()> {
-    let env = config
-        .get_string("app.environment")
-        .unwrap_or("development".into());
-    match env.as_str() {
-        "production" => {
-            let subscriber = tracing_subscriber::FmtSubscriber::builder()
-                .with_max_level(tracing::Level::WARN)
-                .with_file(true)
-                .with_line_number(true)
-                .with_target(false)
-                .pretty()
-                .finish();
+use crate::configuration::{LogFormat, Settings};
+
+/// `log.format` selects `pretty` output (the default) or one JSON object per
+/// line for Loki/ELK; `log.level` overrides the per-environment level.
+/// `error!` events also go to error reporting when it is enabled.
+pub fn init(settings: &Settings) -> Result<()> {
+    let default_level = if settings.is_production() {
+        Level::WARN
+    } else {
+        Level::INFO
+    };
+    let level = settings
+        .log
+        .level
+        .as_deref()
+        .and_then(|level| level.parse::<Level>().ok())
+        .unwrap_or(default_level);
+    let builder = tracing_subscriber::FmtSubscriber::builder()
+        .with_max_level(level)
+        .with_file(true)
+        .with_line_number(true);
+    match settings.log.format {
+        LogFormat::Json => {
+            // Targets (access, audit, metrics) and the request span fields
+            // are what log collectors filter on
+            let subscriber = builder
+                .with_target(true)
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(true)
+                .finish()
+                .with(sentry::integrations::tracing::layer());
             tracing::subscriber::set_global_default(subscriber)?;
         }
-        _ => {
-            let subscriber = tracing_subscriber::FmtSubscriber::builder()
-                .with_max_level(tracing::Level::INFO)
-                .with_file(true)
-                .with_line_number(true)
+        LogFormat::Pretty => {
+            let subscriber = builder
                 .with_target(false)
                 .pretty()
-                .finish();
+                .finish()
+                .with(sentry::integrations::tracing::layer());
             tracing::subscriber::set_global_default(subscriber)?;
         }
     }
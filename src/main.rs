@@ -1,8 +1,65 @@
+use clap::Parser;
+
+use app::cli::{Cli, Command};
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let command = Cli::parse().command();
+    if let Command::Smoke(options) = &command {
+        let report = app::smoke::run(options).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.passed {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Command::ExportOpenapi { output } = &command {
+        let json = app::controllers::openapi_json()?;
+        match output {
+            Some(path) => std::fs::write(path, json)?,
+            None => println!("{json}"),
+        }
+        return Ok(());
+    }
+
     let config = app::configuration::init()?;
-    app::logger::init(&config)?;
-    let application = app::build(&config).await?;
-    application.run().await?;
+    // Before the logger, so a bad config is reported even if logging is what is broken
+    let settings = app::configuration::Settings::load(&config)?;
+    let _error_reporting = app::error_reporting::init(&settings);
+    app::logger::init(&settings)?;
+    // Applies pending migrations
+    let application = app::build(&config, &settings).await?;
+    match command {
+        Command::Serve => application.run().await?,
+        Command::Migrate => {
+            for migration in application.migrations().await? {
+                let status = if migration.applied {
+                    "applied"
+                } else {
+                    "pending"
+                };
+                println!("{} {} {status}", migration.version, migration.description);
+            }
+        }
+        Command::CreateAdmin { email, username } => {
+            let (admin, password) = application
+                .create_admin(&email, username.as_deref())
+                .await?;
+            println!("{} ({}) is an admin", admin.username, admin.email);
+            if let Some(password) = password {
+                println!("generated password: {password}");
+            }
+        }
+        Command::CleanupSessions => {
+            let deleted = application.cleanup_sessions().await?;
+            println!("deleted {deleted} expired sessions");
+        }
+        Command::Anonymize { user } => {
+            let anonymized = application.anonymize_user(&user).await?;
+            println!("anonymized {user} as {}", anonymized.username);
+        }
+        // Need no database, handled before connecting
+        Command::Smoke(_) | Command::ExportOpenapi { .. } => {}
+    }
     Ok(())
 }
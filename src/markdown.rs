@@ -0,0 +1,181 @@
+//! Markdown in user content (bios, reviews). Only a small subset survives:
+//! emphasis, links, lists, quotes and code. Raw HTML in the source is shown
+//! as text and the output is sanitized, so it is safe to render unescaped.
+//! `@username` mentions link to the profile.
+
+use std::sync::LazyLock;
+
+use ammonia::Builder;
+use pulldown_cmark::{
+    CowStr, Event, LinkType, Options, Parser, Tag, TagEnd, TextMergeStream, html,
+};
+
+use crate::models::domain::check_username_rules;
+
+/// Sources longer than this are not rendered.
+pub const MAX_SOURCE_LENGTH: usize = 10_000;
+
+const ALLOWED_TAGS: [&str; 14] = [
+    "p",
+    "br",
+    "em",
+    "strong",
+    "del",
+    "a",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "code",
+    "pre",
+    "hr",
+    "span",
+];
+
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::empty();
+    builder
+        .add_tags(ALLOWED_TAGS)
+        .add_tag_attributes("a", ["href", "title"])
+        .add_url_schemes(["http", "https", "mailto"])
+        .link_rel(Some("nofollow noopener noreferrer ugc"));
+    builder
+});
+
+/// Sanitized HTML for the markdown `source`.
+pub fn render(source: &str) -> String {
+    if source.len() > MAX_SOURCE_LENGTH {
+        return String::new();
+    }
+    let parser = Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        other => other,
+    });
+    let events = link_mentions(TextMergeStream::new(parser));
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.into_iter());
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+/// Turns `@username` in text into links to `/u/{username}`. Text inside
+/// links and code blocks is left alone.
+fn link_mentions<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut linked = Vec::new();
+    // Nesting of links and code blocks around the current event
+    let mut verbatim = 0usize;
+    for event in events {
+        match &event {
+            Event::Start(Tag::Link { .. } | Tag::CodeBlock(_)) => verbatim += 1,
+            Event::End(TagEnd::Link | TagEnd::CodeBlock) => verbatim = verbatim.saturating_sub(1),
+            Event::Text(text) if verbatim == 0 && text.contains('@') => {
+                push_mentions(text, &mut linked);
+                continue;
+            }
+            _ => {}
+        }
+        linked.push(event);
+    }
+    linked
+}
+
+fn push_mentions(text: &str, events: &mut Vec<Event<'_>>) {
+    let mut plain_from = 0;
+    let mut search_from = 0;
+    while let Some(offset) = text[search_from..].find('@') {
+        let at = search_from + offset;
+        let name = mention_at(&text[at + 1..]);
+        search_from = at + 1 + name.len();
+        // Skips emails and the like
+        let attached = text[..at].chars().next_back().is_some_and(is_username_char);
+        if attached || check_username_rules(name).is_err() {
+            continue;
+        }
+        if plain_from < at {
+            events.push(Event::Text(CowStr::from(text[plain_from..at].to_string())));
+        }
+        events.push(Event::Start(Tag::Link {
+            link_type: LinkType::Inline,
+            dest_url: CowStr::from(format!("/u/{name}")),
+            title: CowStr::Borrowed(""),
+            id: CowStr::Borrowed(""),
+        }));
+        events.push(Event::Text(CowStr::from(format!("@{name}"))));
+        events.push(Event::End(TagEnd::Link));
+        plain_from = search_from;
+    }
+    if plain_from < text.len() {
+        events.push(Event::Text(CowStr::from(text[plain_from..].to_string())));
+    }
+}
+
+/// The username right after an `@`; a trailing dot ends the sentence.
+fn mention_at(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !is_username_char(c))
+        .unwrap_or(text.len());
+    text[..end].trim_end_matches('.')
+}
+
+fn is_username_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_subset() {
+        let html = render("**bold** *it* ~~gone~~\n\n- one\n- two");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<em>it</em>"));
+        assert!(html.contains("<del>gone</del>"));
+        assert!(html.contains("<li>one</li>"));
+    }
+
+    #[test]
+    fn test_raw_html_is_text() {
+        let html = render("<script>alert(1)</script> <b>hi</b>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains("&lt;b&gt;hi"));
+    }
+
+    #[test]
+    fn test_links_are_sanitized() {
+        let html = render("[ok](https://example.com) [bad](javascript:alert(1))");
+        assert!(html.contains(r#"href="https://example.com""#));
+        assert!(html.contains("nofollow"));
+        assert!(!html.contains("javascript:"));
+    }
+
+    #[test]
+    fn test_headings_and_images_are_dropped() {
+        let html = render("# Title\n\n![cat](https://example.com/cat.png)");
+        assert!(!html.contains("<h1"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("Title"));
+    }
+
+    #[test]
+    fn test_mentions_link_to_profiles() {
+        let html = render("Thanks @anna_b and @Bob.\n\nMail me at me@example.com");
+        assert!(html.contains(r#"href="/u/anna_b""#));
+        assert!(html.contains(">@anna_b</a>"));
+        assert!(html.contains(">@Bob</a>."));
+        assert!(html.contains("me@example.com"));
+        assert!(!html.contains("/u/example.com"));
+    }
+
+    #[test]
+    fn test_mentions_skip_code_and_links() {
+        let html = render("`@anna` [@anna](https://example.com) @ab @admin");
+        assert!(!html.contains("/u/"));
+        assert!(html.contains("<code>@anna</code>"));
+    }
+
+    #[test]
+    fn test_too_long_source() {
+        assert!(render(&"a".repeat(MAX_SOURCE_LENGTH + 1)).is_empty());
+    }
+}
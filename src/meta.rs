@@ -0,0 +1,111 @@
+use serde_json::{Value, json};
+
+/// Open Graph and schema.org metadata a handler sets on
+/// `PageContext::meta` for the layout to render.
+#[derive(Debug, Clone, Default)]
+pub struct PageMeta {
+    pub title: String,
+    pub description: String,
+    /// `og:type`, `website` unless the page is about a single item
+    pub og_type: String,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub json_ld: Option<Value>,
+}
+
+impl PageMeta {
+    pub fn website(title: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            description: description.into(),
+            og_type: "website".to_string(),
+            ..Default::default()
+        }
+    }
+    pub fn with_image(mut self, image: impl Into<String>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+    pub fn with_json_ld(mut self, json_ld: Value) -> Self {
+        self.json_ld = Some(json_ld);
+        self
+    }
+    /// JSON-LD for embedding in `<script type="application/ld+json">`; `<`
+    /// is escaped so the data can never close the tag.
+    pub fn json_ld_script(&self) -> Option<String> {
+        self.json_ld
+            .as_ref()
+            .map(|value| value.to_string().replace('<', "\\u003c"))
+    }
+}
+
+/// schema.org `CreativeWork`, such as a book.
+pub fn creative_work(name: &str, author: Option<&str>, url: Option<&str>) -> Value {
+    let mut work = json!({
+        "@context": "https://schema.org",
+        "@type": "CreativeWork",
+        "name": name,
+    });
+    if let Some(author) = author {
+        work["author"] = json!({ "@type": "Person", "name": author });
+    }
+    if let Some(url) = url {
+        work["url"] = json!(url);
+    }
+    work
+}
+
+/// schema.org `Review` of `item` (see [`creative_work`]) with a 1-5 rating.
+pub fn review(item: Value, author: &str, body: &str, rating: Option<u8>) -> Value {
+    let mut item = item;
+    if let Some(object) = item.as_object_mut() {
+        object.remove("@context");
+    }
+    let mut review = json!({
+        "@context": "https://schema.org",
+        "@type": "Review",
+        "itemReviewed": item,
+        "author": { "@type": "Person", "name": author },
+        "reviewBody": body,
+    });
+    if let Some(rating) = rating {
+        review["reviewRating"] = json!({
+            "@type": "Rating",
+            "ratingValue": rating,
+            "bestRating": 5,
+            "worstRating": 1,
+        });
+    }
+    review
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_ld_cannot_close_script() {
+        let meta = PageMeta::website("t", "d").with_json_ld(creative_work(
+            "</script><script>alert(1)",
+            None,
+            None,
+        ));
+        let script = meta.json_ld_script().unwrap();
+        assert!(!script.contains("</script>"));
+        assert!(script.contains("\\u003c/script>"));
+    }
+
+    #[test]
+    fn test_review() {
+        let book = creative_work("Мастер и Маргарита", Some("М. Булгаков"), None);
+        let review = review(book, "reader", "Великолепно", Some(5));
+        assert_eq!(review["@type"], "Review");
+        assert_eq!(review["itemReviewed"]["@type"], "CreativeWork");
+        assert!(review["itemReviewed"].get("@context").is_none());
+        assert_eq!(review["reviewRating"]["ratingValue"], 5);
+    }
+}
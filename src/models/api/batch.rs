@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Most operations one batch may carry.
+pub const MAX_BATCH_OPERATIONS: u64 = 20;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchRequest {
+    #[validate(length(min = 1, max = MAX_BATCH_OPERATIONS))]
+    pub operations: Vec<BatchOperation>,
+}
+
+/// One API call inside a batch, e.g. `{"method": "GET", "path": "/api/v1/users/{id}"}`.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOperation {
+    pub method: String,
+    pub path: String,
+    /// Sent as the JSON body of the call
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResponse {
+    /// In the order of the operations
+    pub results: Vec<BatchResult>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    pub status: u16,
+    /// The JSON body of the call, `null` when it had none
+    pub body: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation() -> BatchOperation {
+        BatchOperation {
+            method: "GET".to_string(),
+            path: "/api/v1/users".to_string(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_batch_request_size() {
+        let empty = BatchRequest { operations: vec![] };
+        assert!(empty.validate().is_err());
+
+        let batch = BatchRequest {
+            operations: vec![operation(); MAX_BATCH_OPERATIONS as usize],
+        };
+        assert!(batch.validate().is_ok());
+
+        let too_big = BatchRequest {
+            operations: vec![operation(); MAX_BATCH_OPERATIONS as usize + 1],
+        };
+        assert!(too_big.validate().is_err());
+    }
+}
@@ -0,0 +1,6 @@
+mod batch;
+mod palette;
+mod user;
+pub use batch::*;
+pub use palette::*;
+pub use user::*;
@@ -0,0 +1,58 @@
+use serde::Serialize;
+
+use crate::models::domain::{Palette, PaletteEntry, UserSuggestion};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteResponse {
+    pub actions: Vec<PaletteActionResponse>,
+    pub users: Vec<PaletteUserResponse>,
+}
+
+impl From<Palette> for PaletteResponse {
+    fn from(value: Palette) -> Self {
+        Self {
+            actions: value.actions.into_iter().map(Into::into).collect(),
+            users: value.users.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteActionResponse {
+    pub label: String,
+    pub url: String,
+}
+
+impl From<PaletteEntry> for PaletteActionResponse {
+    fn from(value: PaletteEntry) -> Self {
+        Self {
+            label: value.label,
+            url: value.url.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteUserResponse {
+    pub username: String,
+    pub full_name: Option<String>,
+    pub badge: Option<String>,
+    pub url: String,
+    /// Relevance; users come sorted by it, highest first
+    pub score: f64,
+}
+
+impl From<UserSuggestion> for PaletteUserResponse {
+    fn from(value: UserSuggestion) -> Self {
+        Self {
+            full_name: value.full_name(),
+            url: format!("/u/{}", value.username),
+            username: value.username,
+            badge: value.badge,
+            score: value.score,
+        }
+    }
+}
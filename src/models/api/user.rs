@@ -0,0 +1,789 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    models::domain::{CreateUser, UpdateUser, User, UserData, UserList, UserSuggestion},
+    validation::validate_password,
+};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+    /// Sanitized HTML rendered from the markdown in `bio`
+    pub bio_html: Option<String>,
+    pub profile_extras: serde_json::Value,
+    pub is_admin: bool,
+    pub badge: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: String,
+    pub theme: Option<String>,
+    /// In the user's own timezone
+    pub created_at: DateTime<FixedOffset>,
+    /// In the user's own timezone
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<User> for UserResponse {
+    fn from(value: User) -> Self {
+        let created_at = value.created_at.with_timezone(&value.tz()).fixed_offset();
+        let updated_at = value.updated_at.with_timezone(&value.tz()).fixed_offset();
+        let bio_html = value.rendered_bio();
+        Self {
+            id: value.id,
+            username: value.username,
+            email: value.email,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            bio: value.bio,
+            bio_html,
+            profile_extras: value.profile_extras,
+            is_admin: value.is_admin,
+            badge: value.badge,
+            locale: value.locale,
+            timezone: value.timezone,
+            theme: value.theme,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+/// Publicly visible projection of a user, without contact details.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicUser {
+    pub id: Uuid,
+    pub username: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+    pub bio_html: Option<String>,
+    pub profile_extras: serde_json::Value,
+    pub badge: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<User> for PublicUser {
+    fn from(value: User) -> Self {
+        let bio_html = value.rendered_bio();
+        Self {
+            id: value.id,
+            username: value.username,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            bio: value.bio,
+            bio_html,
+            profile_extras: value.profile_extras,
+            badge: value.badge,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserRequest {
+    pub username: String,
+    #[validate(email)]
+    pub email: String,
+    #[validate(custom(function = "validate_password"))]
+    pub password: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+}
+
+impl From<CreateUserRequest> for CreateUser {
+    fn from(value: CreateUserRequest) -> Self {
+        Self {
+            username: value.username,
+            email: value.email,
+            password: value.password,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            bio: value.bio,
+            profile_extras: serde_json::json!({}),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserRequest {
+    pub username: Option<String>,
+    #[validate(email)]
+    pub email: Option<String>,
+    #[validate(custom(function = "validate_password"))]
+    pub password: Option<String>,
+    pub old_password: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+}
+
+impl From<UpdateUserRequest> for UpdateUser {
+    fn from(value: UpdateUserRequest) -> Self {
+        Self {
+            username: value.username,
+            email: value.email,
+            password: value.password,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            bio: value.bio,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUsersRequest {
+    #[validate(range(min = 1))]
+    pub page: u32,
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: u32,
+    /// Shorter terms match most of the table, ask for a narrower search instead
+    #[validate(length(min = 3, max = 100, code = "search_length"))]
+    #[serde(default, deserialize_with = "blank_as_none")]
+    pub search_query: Option<String>,
+    /// Only users changed since then, for clients syncing a local copy
+    pub updated_since: Option<DateTime<Utc>>,
+}
+
+/// Trims the string; an empty or blank one means no value.
+fn blank_as_none<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty()))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListResponse {
+    pub users: Vec<PublicUser>,
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl From<UserList> for UserListResponse {
+    fn from(value: UserList) -> Self {
+        Self {
+            users: value.users.into_iter().map(PublicUser::from).collect(),
+            total_count: value.total_count,
+            limit: value.limit,
+            offset: value.offset,
+        }
+    }
+}
+
+/// `?q=` on the username autocompletion.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[serde(default)]
+#[into_params(parameter_in = Query)]
+pub struct SuggestUsersQuery {
+    pub q: String,
+}
+
+/// A user offered for a mention or a share invite.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSuggestionResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub full_name: Option<String>,
+    pub badge: Option<String>,
+}
+
+impl From<UserSuggestion> for UserSuggestionResponse {
+    fn from(value: UserSuggestion) -> Self {
+        Self {
+            full_name: value.full_name(),
+            id: value.id,
+            username: value.username,
+            badge: value.badge,
+        }
+    }
+}
+
+/// `?fields=` on user listings: comma-separated camelCase names of
+/// [`PublicUser`] fields. Absent keeps every field.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[serde(default)]
+#[into_params(parameter_in = Query)]
+pub struct UserFieldsQuery {
+    pub fields: Option<String>,
+}
+
+/// Fields of [`PublicUser`] a client asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserFields {
+    pub id: bool,
+    pub username: bool,
+    pub first_name: bool,
+    pub last_name: bool,
+    pub bio: bool,
+    pub bio_html: bool,
+    pub profile_extras: bool,
+    pub badge: bool,
+    pub created_at: bool,
+    pub updated_at: bool,
+}
+
+impl Default for UserFields {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl UserFields {
+    pub const ALL: UserFields = UserFields {
+        id: true,
+        username: true,
+        first_name: true,
+        last_name: true,
+        bio: true,
+        bio_html: true,
+        profile_extras: true,
+        badge: true,
+        created_at: true,
+        updated_at: true,
+    };
+    const NONE: UserFields = UserFields {
+        id: false,
+        username: false,
+        first_name: false,
+        last_name: false,
+        bio: false,
+        bio_html: false,
+        profile_extras: false,
+        badge: false,
+        created_at: false,
+        updated_at: false,
+    };
+
+    /// Fails with the first unknown field name.
+    pub fn parse(fields: Option<&str>) -> Result<Self, String> {
+        let Some(fields) = fields else {
+            return Ok(Self::ALL);
+        };
+        let mut selected = Self::NONE;
+        for name in fields.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let flag = match name {
+                "id" => &mut selected.id,
+                "username" => &mut selected.username,
+                "firstName" => &mut selected.first_name,
+                "lastName" => &mut selected.last_name,
+                "bio" => &mut selected.bio,
+                "bioHtml" => &mut selected.bio_html,
+                "profileExtras" => &mut selected.profile_extras,
+                "badge" => &mut selected.badge,
+                "createdAt" => &mut selected.created_at,
+                "updatedAt" => &mut selected.updated_at,
+                unknown => return Err(unknown.to_string()),
+            };
+            *flag = true;
+        }
+        Ok(selected)
+    }
+}
+
+/// [`PublicUser`] cut down to [`UserFields`]; fields left out are not
+/// serialized at all, selected ones keep their `null`s.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicUserView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bio: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bio_html: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile_extras: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub badge: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PublicUserView {
+    pub fn new(user: PublicUser, fields: UserFields) -> Self {
+        Self {
+            id: fields.id.then_some(user.id),
+            username: fields.username.then_some(user.username),
+            first_name: fields.first_name.then_some(user.first_name),
+            last_name: fields.last_name.then_some(user.last_name),
+            bio: fields.bio.then_some(user.bio),
+            bio_html: fields.bio_html.then_some(user.bio_html),
+            profile_extras: fields.profile_extras.then_some(user.profile_extras),
+            badge: fields.badge.then_some(user.badge),
+            created_at: fields.created_at.then_some(user.created_at),
+            updated_at: fields.updated_at.then_some(user.updated_at),
+        }
+    }
+}
+
+/// [`UserListResponse`] with users cut down to the requested fields.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListView {
+    pub users: Vec<PublicUserView>,
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl UserListView {
+    pub fn new(list: UserListResponse, fields: UserFields) -> Self {
+        Self {
+            users: list
+                .users
+                .into_iter()
+                .map(|user| PublicUserView::new(user, fields))
+                .collect(),
+            total_count: list.total_count,
+            limit: list.limit,
+            offset: list.offset,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteUserResponse {
+    pub deleted_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInRequest {
+    #[validate(email)]
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInResponse {
+    pub user: UserResponse,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignUpRequest {
+    pub username: String,
+    #[validate(email)]
+    pub email: String,
+    #[validate(custom(function = "validate_password"))]
+    pub password: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+    /// Raw values of `city` and custom profile fields, keyed by field key
+    #[serde(default)]
+    pub profile_extras: BTreeMap<String, String>,
+}
+
+impl From<SignUpRequest> for CreateUser {
+    fn from(value: SignUpRequest) -> Self {
+        Self {
+            username: value.username,
+            email: value.email,
+            password: value.password,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            bio: value.bio,
+            profile_extras: serde_json::json!({}),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignUpResponse {
+    pub user: UserResponse,
+    pub token: String,
+}
+
+/// A user's data export: the profile and the other records kept about them.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserExport {
+    pub exported_at: DateTime<Utc>,
+    pub profile: UserResponse,
+    pub club_memberships: serde_json::Value,
+    pub event_rsvps: serde_json::Value,
+    pub poll_votes: serde_json::Value,
+    pub blocks: serde_json::Value,
+    pub genre_interests: serde_json::Value,
+    pub api_keys: serde_json::Value,
+    pub username_history: serde_json::Value,
+    pub reports: serde_json::Value,
+    pub curator_applications: serde_json::Value,
+}
+
+impl UserExport {
+    pub fn new(profile: User, data: UserData) -> Self {
+        Self {
+            exported_at: Utc::now(),
+            profile: profile.into(),
+            club_memberships: data.club_memberships,
+            event_rsvps: data.event_rsvps,
+            poll_votes: data.poll_votes,
+            blocks: data.blocks,
+            genre_interests: data.genre_interests,
+            api_keys: data.api_keys,
+            username_history: data.username_history,
+            reports: data.reports,
+            curator_applications: data.curator_applications,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_user_validation_success() {
+        let valid_user = CreateUserRequest {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password: "Password123!".to_string(),
+            first_name: Some("Test".to_string()),
+            last_name: Some("User".to_string()),
+            bio: Some("Test user bio".to_string()),
+        };
+
+        assert!(valid_user.validate().is_ok());
+    }
+
+    #[test]
+    fn test_create_user_validation_invalid_email() {
+        let invalid_user = CreateUserRequest {
+            username: "testuser".to_string(),
+            email: "invalid-email".to_string(),
+            password: "Password123!".to_string(),
+            first_name: None,
+            last_name: None,
+            bio: None,
+        };
+
+        let result = invalid_user.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("email"));
+    }
+
+    #[test]
+    fn test_create_user_validation_password_too_short() {
+        let invalid_user = CreateUserRequest {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password: "short".to_string(),
+            first_name: None,
+            last_name: None,
+            bio: None,
+        };
+
+        let result = invalid_user.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("password"));
+    }
+
+    #[test]
+    fn test_create_user_validation_password_too_long() {
+        let long_password = "a".repeat(65); // 65 characters, exceeds max 64
+        let invalid_user = CreateUserRequest {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password: long_password,
+            first_name: None,
+            last_name: None,
+            bio: None,
+        };
+
+        let result = invalid_user.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("password"));
+    }
+
+    #[test]
+    fn test_create_user_validation_password_complexity() {
+        let invalid_user = CreateUserRequest {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password: "weakpassword".to_string(), // Missing digit and special
+            first_name: None,
+            last_name: None,
+            bio: None,
+        };
+
+        let result = invalid_user.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("password"));
+    }
+
+    fn public_user() -> PublicUser {
+        PublicUser {
+            id: Uuid::nil(),
+            username: "reader".to_string(),
+            first_name: None,
+            last_name: None,
+            bio: Some("Bio".to_string()),
+            bio_html: Some("<p>Bio</p>".to_string()),
+            profile_extras: serde_json::json!({}),
+            badge: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_user_fields_parse() {
+        assert_eq!(UserFields::parse(None), Ok(UserFields::ALL));
+        let fields = UserFields::parse(Some("id, username,,firstName")).unwrap();
+        assert!(fields.id && fields.username && fields.first_name);
+        assert!(!fields.bio && !fields.created_at);
+        assert_eq!(
+            UserFields::parse(Some("id,avatarUrl")),
+            Err("avatarUrl".to_string())
+        );
+    }
+
+    #[test]
+    fn test_public_user_view_skips_fields() {
+        let fields = UserFields::parse(Some("id,username,firstName")).unwrap();
+        let json = serde_json::to_value(PublicUserView::new(public_user(), fields)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"id": Uuid::nil(), "username": "reader", "firstName": null})
+        );
+
+        // Every field keeps the shape of PublicUser
+        let user = public_user();
+        let expected = serde_json::to_value(&user).unwrap();
+        let json = serde_json::to_value(PublicUserView::new(user, UserFields::ALL)).unwrap();
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn test_sign_in_request_validation_success() {
+        let valid_signin = SignInRequest {
+            email: "test@example.com".to_string(),
+            password: "anypassword".to_string(),
+        };
+
+        assert!(valid_signin.validate().is_ok());
+    }
+
+    #[test]
+    fn test_sign_in_request_validation_invalid_email() {
+        let invalid_signin = SignInRequest {
+            email: "invalid-email".to_string(),
+            password: "anypassword".to_string(),
+        };
+
+        let result = invalid_signin.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("email"));
+    }
+
+    #[test]
+    fn test_sign_in_request_validation_empty_email() {
+        let invalid_signin = SignInRequest {
+            email: "".to_string(),
+            password: "anypassword".to_string(),
+        };
+
+        let result = invalid_signin.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        assert!(errors.field_errors().contains_key("email"));
+    }
+
+    #[test]
+    fn test_sign_in_request_validation_empty_password() {
+        let invalid_signin = SignInRequest {
+            email: "test@example.com".to_string(),
+            password: "".to_string(),
+        };
+
+        // SignInRequest only validates email field, password can be empty for validation
+        // (actual password verification happens in service layer)
+        assert!(invalid_signin.validate().is_ok());
+    }
+
+    #[test]
+    fn test_sign_up_request_validation_success() {
+        let valid_signup = SignUpRequest {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password: "Password123!".to_string(),
+            first_name: Some("Test".to_string()),
+            last_name: Some("User".to_string()),
+            bio: Some("Test user bio".to_string()),
+            profile_extras: BTreeMap::new(),
+        };
+
+        assert!(valid_signup.validate().is_ok());
+    }
+
+    #[test]
+    fn test_sign_up_request_validation_all_fields_invalid() {
+        let invalid_signup = SignUpRequest {
+            username: "testuser".to_string(),
+            email: "invalid-email".to_string(),
+            password: "weak".to_string(),
+            first_name: None,
+            last_name: None,
+            bio: None,
+            profile_extras: BTreeMap::new(),
+        };
+
+        let result = invalid_signup.validate();
+        assert!(result.is_err());
+
+        let errors = result.unwrap_err();
+        // Should have validation errors for both email and password
+        assert!(errors.field_errors().contains_key("email"));
+        assert!(errors.field_errors().contains_key("password"));
+    }
+
+    #[test]
+    fn test_update_user_request_validation() {
+        let valid_update = UpdateUserRequest {
+            username: None,
+            email: None,
+            password: None,
+            old_password: None,
+            first_name: Some("Test".to_string()),
+            last_name: None,
+            bio: None,
+        };
+        assert!(valid_update.validate().is_ok());
+
+        let invalid_update = UpdateUserRequest {
+            email: Some("invalid-email".to_string()),
+            password: Some("weak".to_string()),
+            ..valid_update
+        };
+        let errors = invalid_update.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("email"));
+        assert!(errors.field_errors().contains_key("password"));
+    }
+
+    #[test]
+    fn test_list_users_request_validation() {
+        let valid_request = ListUsersRequest {
+            page: 1,
+            per_page: 20,
+            search_query: None,
+            updated_since: None,
+        };
+        assert!(valid_request.validate().is_ok());
+
+        let invalid_request = ListUsersRequest {
+            page: 0,
+            per_page: 500,
+            search_query: None,
+            updated_since: None,
+        };
+        let errors = invalid_request.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("page"));
+        assert!(errors.field_errors().contains_key("per_page"));
+    }
+
+    #[test]
+    fn test_list_users_request_rejects_short_search() {
+        let request = ListUsersRequest {
+            page: 1,
+            per_page: 20,
+            search_query: Some("ab".to_string()),
+            updated_since: None,
+        };
+        let errors = request.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("search_query"));
+    }
+
+    #[test]
+    fn test_list_users_request_blank_search_is_none() {
+        for search in ["\"\"", "\"   \"", "null"] {
+            let json = format!(r#"{{"page": 1, "perPage": 20, "searchQuery": {search}}}"#);
+            let request: ListUsersRequest = serde_json::from_str(&json).unwrap();
+            assert!(request.search_query.is_none());
+            assert!(request.validate().is_ok());
+        }
+        let request: ListUsersRequest =
+            serde_json::from_str(r#"{"page": 1, "perPage": 20, "searchQuery": " dune "}"#)
+                .unwrap();
+        assert_eq!(request.search_query.as_deref(), Some("dune"));
+    }
+
+    #[test]
+    fn test_user_response_in_user_timezone() {
+        let user = User {
+            timezone: "Asia/Tokyo".to_string(),
+            created_at: DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                .unwrap()
+                .to_utc(),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(UserResponse::from(user)).unwrap();
+        assert_eq!(json["createdAt"], "2026-01-01T09:00:00+09:00");
+        assert_eq!(json["timezone"], "Asia/Tokyo");
+    }
+
+    #[test]
+    fn test_public_user_hides_email() {
+        let user = User {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            ..Default::default()
+        };
+        let list = UserListResponse::from(UserList {
+            users: vec![user],
+            total_count: 1,
+            limit: 20,
+            offset: 0,
+        });
+
+        let json = serde_json::to_string(&list).unwrap();
+        assert!(json.contains("testuser"));
+        assert!(!json.contains("test@example.com"));
+        assert!(!json.contains("email"));
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const API_KEY_SCOPES: [&str; 2] = ["read", "write"];
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateApiKey {
+    pub user_id: Uuid,
+    pub key: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const BADGE_VERIFIED: &str = "verified";
+pub const BADGE_CURATOR: &str = "curator";
+pub const BADGES: [&str; 2] = [BADGE_VERIFIED, BADGE_CURATOR];
+
+pub const APPLICATION_APPROVED: &str = "approved";
+pub const APPLICATION_REJECTED: &str = "rejected";
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CuratorApplication {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub motivation: String,
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingCuratorApplication {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    pub motivation: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::i18n::Locale;
+
+/// Someone the user blocked or muted.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserBlock {
+    pub target_id: Uuid,
+    pub username: String,
+    /// Stored as [`BlockKind::code`]
+    pub kind: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UserBlock {
+    pub fn kind(&self) -> BlockKind {
+        BlockKind::from_code(&self.kind).unwrap_or_default()
+    }
+}
+
+/// Muting hides the other user from the muter. Blocking also hides the
+/// blocker from the blocked user, who is not told about it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockKind {
+    #[default]
+    Block,
+    Mute,
+}
+
+impl BlockKind {
+    pub const ALL: [BlockKind; 2] = [BlockKind::Block, BlockKind::Mute];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            BlockKind::Block => "block",
+            BlockKind::Mute => "mute",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.code() == code.trim())
+    }
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("block-kind-{}", self.code()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_kind_codes() {
+        for kind in BlockKind::ALL {
+            assert_eq!(BlockKind::from_code(kind.code()), Some(kind));
+        }
+        assert_eq!(BlockKind::from_code("ignore"), None);
+    }
+}
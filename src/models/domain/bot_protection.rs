@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of the signup form input real users never see or fill in.
+pub const HONEYPOT_FIELD: &str = "website";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaProvider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    pub fn script_src(&self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "https://js.hcaptcha.com/1/api.js",
+            CaptchaProvider::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/api.js",
+        }
+    }
+    /// Class of the element the provider script renders its widget into
+    pub fn widget_class(&self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "h-captcha",
+            CaptchaProvider::Turnstile => "cf-turnstile",
+        }
+    }
+    /// Origins the widget loads its script and frame from and calls
+    /// back to, which the CSP has to allow.
+    pub fn csp_origins(&self) -> &'static [&'static str] {
+        match self {
+            CaptchaProvider::HCaptcha => &["https://hcaptcha.com", "https://*.hcaptcha.com"],
+            CaptchaProvider::Turnstile => &["https://challenges.cloudflare.com"],
+        }
+    }
+    pub fn verify_url(&self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            CaptchaProvider::Turnstile => {
+                "https://challenges.cloudflare.com/turnstile/v0/siteverify"
+            }
+        }
+    }
+}
+
+/// `[bot_protection]` configuration section. The CAPTCHA is off unless a
+/// provider and both keys are set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BotProtectionSettings {
+    pub captcha_provider: Option<CaptchaProvider>,
+    pub captcha_site_key: String,
+    pub captcha_secret_key: String,
+}
+
+impl BotProtectionSettings {
+    /// Provider and site key for rendering the widget, when enabled.
+    pub fn captcha(&self) -> Option<CaptchaWidget> {
+        let provider = self.captcha_provider?;
+        if self.captcha_site_key.is_empty() || self.captcha_secret_key.is_empty() {
+            return None;
+        }
+        Some(CaptchaWidget {
+            provider,
+            site_key: self.captcha_site_key.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptchaWidget {
+    pub provider: CaptchaProvider,
+    pub site_key: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_captcha_disabled_by_default() {
+        assert!(BotProtectionSettings::default().captcha().is_none());
+    }
+
+    #[test]
+    fn test_captcha_requires_both_keys() {
+        let toml = r#"
+            [bot_protection]
+            captcha_provider = "turnstile"
+            captcha_site_key = "site"
+        "#;
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap();
+        let mut settings = config
+            .get::<BotProtectionSettings>("bot_protection")
+            .unwrap();
+        assert_eq!(settings.captcha_provider, Some(CaptchaProvider::Turnstile));
+        assert!(settings.captcha().is_none());
+
+        settings.captcha_secret_key = "secret".into();
+        let widget = settings.captcha().unwrap();
+        assert_eq!(widget.provider.widget_class(), "cf-turnstile");
+        assert_eq!(widget.site_key, "site");
+    }
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::i18n::Locale;
+
+pub const CLUB_SLUG_MIN_LEN: usize = 3;
+pub const CLUB_SLUG_MAX_LEN: usize = 48;
+pub const CLUB_NAME_MAX_LEN: usize = 80;
+pub const CLUB_DESCRIPTION_MAX_LEN: usize = 2000;
+
+/// Slugs that would shadow routes under `/clubs`.
+const RESERVED_CLUB_SLUGS: [&str; 2] = ["new", "validate"];
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Club {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    /// `None` once the creator's account is deleted
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A club in the clubs directory.
+#[derive(Debug, Clone, FromRow)]
+pub struct ClubSummary {
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub member_count: i64,
+    /// Whether the viewer belongs to the club
+    pub is_member: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ClubMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl ClubMember {
+    pub fn role(&self) -> ClubRole {
+        ClubRole::from_code(&self.role).unwrap_or_default()
+    }
+}
+
+/// Club administration rights, stored as [`ClubRole::code`]. Owners manage
+/// everyone, admins manage members.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClubRole {
+    #[default]
+    Member,
+    Admin,
+    Owner,
+}
+
+impl ClubRole {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClubRole::Member => "member",
+            ClubRole::Admin => "admin",
+            ClubRole::Owner => "owner",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "member" => Some(ClubRole::Member),
+            "admin" => Some(ClubRole::Admin),
+            "owner" => Some(ClubRole::Owner),
+            _ => None,
+        }
+    }
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("club-role-{}", self.code()))
+    }
+    /// Whether someone with this role may manage a member with `other`:
+    /// only members below them, so co-owners cannot remove or demote each
+    /// other and a club always keeps its owners.
+    pub fn can_manage(&self, other: ClubRole) -> bool {
+        match self {
+            ClubRole::Owner => other != ClubRole::Owner,
+            ClubRole::Admin => other == ClubRole::Member,
+            ClubRole::Member => false,
+        }
+    }
+    pub fn promoted(&self) -> Option<Self> {
+        match self {
+            ClubRole::Member => Some(ClubRole::Admin),
+            ClubRole::Admin => Some(ClubRole::Owner),
+            ClubRole::Owner => None,
+        }
+    }
+    pub fn demoted(&self) -> Option<Self> {
+        match self {
+            ClubRole::Member => None,
+            ClubRole::Admin => Some(ClubRole::Member),
+            ClubRole::Owner => Some(ClubRole::Admin),
+        }
+    }
+}
+
+/// What a club manager does to a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClubMemberAction {
+    Promote,
+    Demote,
+    Remove,
+}
+
+impl ClubMemberAction {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "promote" => Some(ClubMemberAction::Promote),
+            "demote" => Some(ClubMemberAction::Demote),
+            "remove" => Some(ClubMemberAction::Remove),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClubSlugError {
+    Length,
+    Charset,
+    Reserved,
+}
+
+impl ClubSlugError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            ClubSlugError::Length => locale.t_args(
+                "club-slug-length",
+                &[
+                    ("min", CLUB_SLUG_MIN_LEN.to_string()),
+                    ("max", CLUB_SLUG_MAX_LEN.to_string()),
+                ],
+            ),
+            ClubSlugError::Charset => locale.t("club-slug-charset"),
+            ClubSlugError::Reserved => locale.t("club-slug-reserved"),
+        }
+    }
+}
+
+/// Slugs end up in URLs: lowercase latin letters, digits and inner hyphens.
+pub fn check_club_slug(slug: &str) -> Result<(), ClubSlugError> {
+    let len = slug.chars().count();
+    if !(CLUB_SLUG_MIN_LEN..=CLUB_SLUG_MAX_LEN).contains(&len) {
+        return Err(ClubSlugError::Length);
+    }
+    let valid_chars = slug
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_chars || slug.starts_with('-') || slug.ends_with('-') {
+        return Err(ClubSlugError::Charset);
+    }
+    if RESERVED_CLUB_SLUGS.contains(&slug) {
+        return Err(ClubSlugError::Reserved);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_club_slug_rules() {
+        assert!(check_club_slug("book-club-42").is_ok());
+        assert_eq!(check_club_slug("ab"), Err(ClubSlugError::Length));
+        assert_eq!(check_club_slug("Book-Club"), Err(ClubSlugError::Charset));
+        assert_eq!(check_club_slug("-books"), Err(ClubSlugError::Charset));
+        assert_eq!(check_club_slug("книги"), Err(ClubSlugError::Charset));
+        assert_eq!(check_club_slug("new"), Err(ClubSlugError::Reserved));
+    }
+
+    #[test]
+    fn test_club_roles() {
+        for role in [ClubRole::Member, ClubRole::Admin, ClubRole::Owner] {
+            assert_eq!(ClubRole::from_code(role.code()), Some(role));
+        }
+        assert!(!ClubRole::Owner.can_manage(ClubRole::Owner));
+        assert!(ClubRole::Owner.can_manage(ClubRole::Admin));
+        assert!(ClubRole::Admin.can_manage(ClubRole::Member));
+        assert!(!ClubRole::Admin.can_manage(ClubRole::Admin));
+        assert!(!ClubRole::Member.can_manage(ClubRole::Member));
+        assert_eq!(ClubRole::Member.promoted(), Some(ClubRole::Admin));
+        assert_eq!(ClubRole::Owner.promoted(), None);
+        assert_eq!(ClubRole::Owner.demoted(), Some(ClubRole::Admin));
+        assert_eq!(ClubRole::Member.demoted(), None);
+    }
+}
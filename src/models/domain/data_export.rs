@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Hours the download link of a built export works.
+pub const DATA_EXPORT_TTL_HOURS: i64 = 72;
+
+/// A requested copy of a user's data, built by a background job and
+/// downloaded through an emailed link.
+#[derive(Debug, Clone, FromRow)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// `None` while the export waits for the job
+    pub ready_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A JSON array of records as CSV, with a column for every key seen in
+/// the order of first appearance. Nested values are written as JSON.
+pub fn records_to_csv(records: &serde_json::Value) -> String {
+    let records: Vec<_> = records
+        .as_array()
+        .map(|r| r.iter().filter_map(|r| r.as_object()).collect())
+        .unwrap_or_default();
+    if records.is_empty() {
+        return String::new();
+    }
+    let mut columns: Vec<&String> = Vec::new();
+    for record in &records {
+        for key in record.keys() {
+            if !columns.contains(&key) {
+                columns.push(key);
+            }
+        }
+    }
+    let mut csv = csv_row(columns.iter().map(|c| c.to_string()));
+    for record in &records {
+        csv.push_str(&csv_row(columns.iter().map(|c| match record.get(*c) {
+            None | Some(serde_json::Value::Null) => String::new(),
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        })));
+    }
+    csv
+}
+
+fn csv_row(fields: impl Iterator<Item = String>) -> String {
+    let fields: Vec<String> = fields
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f
+            }
+        })
+        .collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_records_to_csv() {
+        let records = json!([
+            { "club": "Readers", "role": "owner" },
+            { "club": "Quotes, \"mostly\"", "joinedAt": null, "tags": ["a"] },
+        ]);
+        assert_eq!(
+            records_to_csv(&records),
+            "club,role,joinedAt,tags\r\n\
+             Readers,owner,,\r\n\
+             \"Quotes, \"\"mostly\"\"\",,,\"[\"\"a\"\"]\"\r\n"
+        );
+        assert!(records_to_csv(&json!([])).is_empty());
+    }
+}
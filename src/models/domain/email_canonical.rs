@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// Domains whose mailbox ignores dots and `+tags` in the local part.
+const GMAIL_DOMAINS: [&str; 2] = ["gmail.com", "googlemail.com"];
+
+/// `[email_canonical]` configuration section: how addresses are folded before
+/// checking whether an account already uses them. The folded address is only
+/// compared, users keep seeing the one they typed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailCanonicalSettings {
+    /// `j.doe+news@googlemail.com` is `jdoe@gmail.com`
+    pub fold_gmail: bool,
+    /// `reader+news@example.com` is `reader@example.com` on every domain
+    pub fold_plus_tags: bool,
+}
+
+impl Default for EmailCanonicalSettings {
+    fn default() -> Self {
+        Self {
+            fold_gmail: true,
+            fold_plus_tags: false,
+        }
+    }
+}
+
+/// The address as stored in `users.email_canonical`: trimmed, lowercased and
+/// folded per `settings`. Two accounts may not share it.
+pub fn canonical_email(email: &str, settings: &EmailCanonicalSettings) -> String {
+    let email = email.trim().to_lowercase();
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return email;
+    };
+    let domain = domain.trim_end_matches('.');
+    if settings.fold_gmail && GMAIL_DOMAINS.contains(&domain) {
+        let local = untagged(local).replace('.', "");
+        return format!("{local}@{}", GMAIL_DOMAINS[0]);
+    }
+    if settings.fold_plus_tags {
+        return format!("{}@{domain}", untagged(local));
+    }
+    format!("{local}@{domain}")
+}
+
+/// `local` without its `+tag`; a local part that is all tag is kept.
+fn untagged(local: &str) -> &str {
+    match local.split_once('+') {
+        Some((base, _)) if !base.is_empty() => base,
+        _ => local,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_email() {
+        let defaults = EmailCanonicalSettings::default();
+        assert_eq!(
+            canonical_email(" J.Doe+News@GoogleMail.com ", &defaults),
+            "jdoe@gmail.com"
+        );
+        assert_eq!(
+            canonical_email("jdoe@gmail.com.", &defaults),
+            "jdoe@gmail.com"
+        );
+        assert_eq!(
+            canonical_email("Reader+news@Example.com", &defaults),
+            "reader+news@example.com"
+        );
+        assert_eq!(canonical_email("not an email", &defaults), "not an email");
+
+        let plus_tags = EmailCanonicalSettings {
+            fold_gmail: false,
+            fold_plus_tags: true,
+        };
+        assert_eq!(
+            canonical_email("reader+news@example.com", &plus_tags),
+            "reader@example.com"
+        );
+        assert_eq!(
+            canonical_email("j.doe+news@gmail.com", &plus_tags),
+            "j.doe@gmail.com"
+        );
+        assert_eq!(
+            canonical_email("+news@example.com", &plus_tags),
+            "+news@example.com"
+        );
+    }
+}
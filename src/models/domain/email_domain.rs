@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+
+/// Disposable email domains shipped with the app, one per line.
+pub const BUNDLED_DISPOSABLE_DOMAINS: &str = include_str!("disposable_domains.txt");
+
+/// `[email_domains]` configuration section: which email domains may sign up.
+/// Entries cover their subdomains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailDomainSettings {
+    /// Only these domains may sign up; empty allows any
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    /// Rejects the disposable domains in the bundled list and the refreshed one
+    pub block_disposable: bool,
+    /// Plain text list, one domain per line, fetched by the
+    /// `disposable_domains` task; empty keeps the bundled list
+    pub disposable_list_url: String,
+}
+
+impl Default for EmailDomainSettings {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            block_disposable: true,
+            disposable_list_url: String::new(),
+        }
+    }
+}
+
+/// Why an email domain cannot sign up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmailDomainError {
+    /// Not in a non-empty allow list
+    NotAllowed,
+    Denied,
+    Disposable,
+}
+
+impl EmailDomainError {
+    /// Reason in audit logs
+    pub fn code(&self) -> &'static str {
+        match self {
+            EmailDomainError::NotAllowed => "domain_not_allowed",
+            EmailDomainError::Denied => "domain_denied",
+            EmailDomainError::Disposable => "disposable_email",
+        }
+    }
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            EmailDomainError::NotAllowed | EmailDomainError::Denied => {
+                locale.t("email-domain-not-allowed")
+            }
+            EmailDomainError::Disposable => locale.t("email-disposable"),
+        }
+    }
+}
+
+/// Lowercased part after the last `@`, without a trailing dot.
+pub fn email_domain(email: &str) -> Option<String> {
+    let (_, domain) = email.trim().rsplit_once('@')?;
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    Some(domain).filter(|d| !d.is_empty())
+}
+
+/// Whether `domain` is `listed` or one of its subdomains.
+pub fn domain_matches(domain: &str, listed: &str) -> bool {
+    domain == listed
+        || domain
+            .strip_suffix(listed)
+            .is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// Domains of a plain text list: one per line, `#` starts a comment.
+pub fn parse_domain_list(text: &str) -> HashSet<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.trim_end_matches('.').to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_domain() {
+        assert_eq!(
+            email_domain("Reader@Example.COM").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(email_domain("a@b@mail.ru.").as_deref(), Some("mail.ru"));
+        assert_eq!(email_domain("reader"), None);
+        assert_eq!(email_domain("reader@"), None);
+    }
+
+    #[test]
+    fn test_domain_matches() {
+        assert!(domain_matches("yopmail.com", "yopmail.com"));
+        assert!(domain_matches("inbox.yopmail.com", "yopmail.com"));
+        assert!(!domain_matches("notyopmail.com", "yopmail.com"));
+    }
+
+    #[test]
+    fn test_bundled_list() {
+        let domains = parse_domain_list(BUNDLED_DISPOSABLE_DOMAINS);
+        assert!(domains.contains("mailinator.com"));
+        assert!(
+            domains
+                .iter()
+                .all(|d| !d.starts_with('#') && !d.contains(' '))
+        );
+    }
+}
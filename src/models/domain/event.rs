@@ -0,0 +1,149 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::i18n::Locale;
+
+pub const EVENT_TITLE_MAX_LEN: usize = 120;
+pub const EVENT_VENUE_MAX_LEN: usize = 200;
+pub const EVENT_DESCRIPTION_MAX_LEN: usize = 4000;
+/// Format of `<input type="datetime-local">` values.
+pub const EVENT_DATETIME_INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Event {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    /// Free-form place, such as a hall within the venue
+    pub venue: String,
+    pub starts_at: DateTime<Utc>,
+    /// `None` once the creator's account is deleted
+    pub created_by: Option<Uuid>,
+    /// Set for events organized by a club
+    pub club_id: Option<Uuid>,
+    /// Set for events held at a listed venue
+    pub venue_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An event as listed on the events pages.
+#[derive(Debug, Clone, FromRow)]
+pub struct EventSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub venue: String,
+    pub starts_at: DateTime<Utc>,
+    pub club_slug: Option<String>,
+    pub club_name: Option<String>,
+    pub venue_slug: Option<String>,
+    pub venue_name: Option<String>,
+    pub going_count: i64,
+    pub interested_count: i64,
+    /// The viewer's RSVP, stored as [`RsvpStatus::code`]
+    pub viewer_status: Option<String>,
+}
+
+impl EventSummary {
+    pub fn viewer_rsvp(&self) -> Option<RsvpStatus> {
+        self.viewer_status
+            .as_deref()
+            .and_then(RsvpStatus::from_code)
+    }
+    pub fn has_started(&self) -> bool {
+        self.starts_at <= Utc::now()
+    }
+}
+
+/// Someone who answered an event's RSVP, to be reminded of it.
+#[derive(Debug, Clone, FromRow)]
+pub struct EventRecipient {
+    pub username: String,
+    pub email: String,
+    pub timezone: String,
+    /// Stored locale code, `None` when the user never chose one
+    pub locale: Option<String>,
+    pub status: String,
+}
+
+impl EventRecipient {
+    /// The recipient's chosen locale, the default one otherwise.
+    pub fn locale(&self) -> Locale {
+        self.locale
+            .as_deref()
+            .and_then(Locale::from_code)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RsvpStatus {
+    Going,
+    Interested,
+}
+
+impl RsvpStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RsvpStatus::Going => "going",
+            RsvpStatus::Interested => "interested",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "going" => Some(RsvpStatus::Going),
+            "interested" => Some(RsvpStatus::Interested),
+            _ => None,
+        }
+    }
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("event-rsvp-{}", self.code()))
+    }
+}
+
+/// Reads a `datetime-local` input as wall-clock time in `tz`. Times that do
+/// not exist in `tz` (skipped by a DST change) are rejected; ambiguous ones
+/// resolve to the earlier instant.
+pub fn parse_local_datetime(input: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(input.trim(), EVENT_DATETIME_INPUT_FORMAT).ok()?;
+    let local = naive.and_local_timezone(tz).earliest()?;
+    Some(local.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_local_datetime() {
+        let moscow = chrono_tz::Europe::Moscow;
+        assert_eq!(
+            parse_local_datetime("2026-11-20T19:30", moscow),
+            Some(Utc.with_ymd_and_hms(2026, 11, 20, 16, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_local_datetime(" 2026-11-20T19:30 ", Tz::UTC),
+            Some(Utc.with_ymd_and_hms(2026, 11, 20, 19, 30, 0).unwrap())
+        );
+        // Skipped by the spring DST change in Berlin
+        assert_eq!(
+            parse_local_datetime("2026-03-29T02:30", chrono_tz::Europe::Berlin),
+            None
+        );
+        assert_eq!(parse_local_datetime("20.11.2026 19:30", Tz::UTC), None);
+        assert_eq!(parse_local_datetime("", Tz::UTC), None);
+    }
+
+    #[test]
+    fn test_rsvp_codes() {
+        for status in [RsvpStatus::Going, RsvpStatus::Interested] {
+            assert_eq!(RsvpStatus::from_code(status.code()), Some(status));
+        }
+        assert_eq!(RsvpStatus::from_code("maybe"), None);
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::domain::{ClubSlugError, check_club_slug};
+
+pub const GENRE_NAME_MAX_LEN: usize = 80;
+
+/// A curated genre or interest, possibly under a broader one.
+#[derive(Debug, Clone, FromRow)]
+pub struct Genre {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    /// `None` for top-level genres
+    pub parent_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A genre in [`genre_tree`] order.
+#[derive(Debug, Clone)]
+pub struct GenreNode {
+    pub genre: Genre,
+    /// 0 for top-level genres
+    pub depth: usize,
+}
+
+impl GenreNode {
+    /// The name indented by depth, for flat lists and selects.
+    pub fn label(&self) -> String {
+        format!("{}{}", "— ".repeat(self.depth), self.genre.name)
+    }
+}
+
+/// Genre addresses follow the club address rules.
+pub fn check_genre_slug(slug: &str) -> Result<(), ClubSlugError> {
+    check_club_slug(slug)
+}
+
+/// Depth-first order with each genre followed by its subgenres, siblings
+/// keeping the order of `genres`. Genres whose parent is missing count as
+/// top-level.
+pub fn genre_tree(genres: Vec<Genre>) -> Vec<GenreNode> {
+    let known: Vec<Uuid> = genres.iter().map(|g| g.id).collect();
+    let mut children: HashMap<Option<Uuid>, Vec<Genre>> = HashMap::new();
+    for genre in genres {
+        let parent = genre.parent_id.filter(|id| known.contains(id));
+        children.entry(parent).or_default().push(genre);
+    }
+    let mut tree = Vec::with_capacity(known.len());
+    // Siblings are pushed reversed so they pop in their original order
+    let mut stack: Vec<(Genre, usize)> = children
+        .remove(&None)
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .map(|g| (g, 0))
+        .collect();
+    while let Some((genre, depth)) = stack.pop() {
+        if let Some(subgenres) = children.remove(&Some(genre.id)) {
+            stack.extend(subgenres.into_iter().rev().map(|g| (g, depth + 1)));
+        }
+        tree.push(GenreNode { genre, depth });
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genre(name: &str, parent_id: Option<Uuid>) -> Genre {
+        Genre {
+            id: Uuid::new_v4(),
+            slug: name.to_string(),
+            name: name.to_string(),
+            parent_id,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_genre_tree() {
+        let fiction = genre("fiction", None);
+        let film = genre("film", None);
+        let fantasy = genre("fantasy", Some(fiction.id));
+        let noir = genre("noir", Some(film.id));
+        let scifi = genre("science-fiction", Some(fiction.id));
+        let cyberpunk = genre("cyberpunk", Some(scifi.id));
+        let orphan = genre("orphan", Some(Uuid::new_v4()));
+        let genres = vec![cyberpunk, fantasy, fiction, film, noir, orphan, scifi];
+
+        let tree: Vec<_> = genre_tree(genres)
+            .into_iter()
+            .map(|node| (node.genre.name, node.depth))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![
+                ("fiction".to_string(), 0),
+                ("fantasy".to_string(), 1),
+                ("science-fiction".to_string(), 1),
+                ("cyberpunk".to_string(), 2),
+                ("film".to_string(), 0),
+                ("noir".to_string(), 1),
+                ("orphan".to_string(), 0),
+            ]
+        );
+    }
+}
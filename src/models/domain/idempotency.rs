@@ -0,0 +1,16 @@
+use sqlx::FromRow;
+
+/// How long a key replays its response.
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i32 = 24;
+/// Longer `Idempotency-Key` headers are rejected.
+pub const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+
+/// What a key was first used for, and the response once there is one.
+#[derive(Debug, Clone, FromRow)]
+pub struct IdempotentResponse {
+    pub request_hash: String,
+    /// `None` while the first request is still running
+    pub status: Option<i16>,
+    pub content_type: Option<String>,
+    pub body: Option<Vec<u8>>,
+}
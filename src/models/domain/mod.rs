@@ -0,0 +1,42 @@
+mod api_key;
+mod badge;
+mod block;
+mod bot_protection;
+mod club;
+mod data_export;
+mod email_canonical;
+mod email_change;
+mod email_domain;
+mod event;
+mod genre;
+mod idempotency;
+mod poll;
+mod report;
+mod search;
+mod signup;
+mod system;
+mod theme;
+mod user;
+mod username;
+mod venue;
+pub use api_key::*;
+pub use badge::*;
+pub use block::*;
+pub use bot_protection::*;
+pub use club::*;
+pub use data_export::*;
+pub use email_canonical::*;
+pub use email_change::*;
+pub use email_domain::*;
+pub use event::*;
+pub use genre::*;
+pub use idempotency::*;
+pub use poll::*;
+pub use report::*;
+pub use search::*;
+pub use signup::*;
+pub use system::*;
+pub use theme::*;
+pub use user::*;
+pub use username::*;
+pub use venue::*;
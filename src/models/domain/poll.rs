@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::i18n::Locale;
+
+pub const POLL_QUESTION_MAX_LEN: usize = 200;
+pub const POLL_OPTION_MAX_LEN: usize = 100;
+pub const POLL_MIN_OPTIONS: usize = 2;
+pub const POLL_MAX_OPTIONS: usize = 10;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Poll {
+    pub id: Uuid,
+    pub club_id: Uuid,
+    pub question: String,
+    /// Whether voters may pick several options
+    pub multiple: bool,
+    /// Stored as [`PollResults::code`]
+    pub results: String,
+    /// `None` keeps the poll open
+    pub closes_at: Option<DateTime<Utc>>,
+    /// `None` once the creator's account is deleted
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Poll {
+    pub fn results(&self) -> PollResults {
+        PollResults::from_code(&self.results).unwrap_or_default()
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closes_at.is_some_and(|at| at <= Utc::now())
+    }
+    /// Whether someone who has or has not `voted` may see the counts.
+    pub fn results_visible(&self, voted: bool) -> bool {
+        match self.results() {
+            PollResults::Always => true,
+            PollResults::AfterVote => voted || self.is_closed(),
+            PollResults::AfterClose => self.is_closed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PollOption {
+    pub id: Uuid,
+    pub poll_id: Uuid,
+    /// 1-based, in the order the creator listed the options
+    pub position: i32,
+    pub text: String,
+    pub votes: i64,
+}
+
+/// When voters get to see the counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollResults {
+    #[default]
+    Always,
+    /// Once the viewer has voted, and to everyone after the poll closes
+    AfterVote,
+    AfterClose,
+}
+
+impl PollResults {
+    pub const ALL: [PollResults; 3] = [
+        PollResults::Always,
+        PollResults::AfterVote,
+        PollResults::AfterClose,
+    ];
+    pub fn code(&self) -> &'static str {
+        match self {
+            PollResults::Always => "always",
+            PollResults::AfterVote => "after_vote",
+            PollResults::AfterClose => "after_close",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "always" => Some(PollResults::Always),
+            "after_vote" => Some(PollResults::AfterVote),
+            "after_close" => Some(PollResults::AfterClose),
+            _ => None,
+        }
+    }
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("poll-results-{}", self.code()))
+    }
+}
+
+/// A poll as shown to one viewer.
+#[derive(Debug, Clone)]
+pub struct PollView {
+    pub poll: Poll,
+    pub options: Vec<PollOption>,
+    /// People who voted, each counted once in multiple choice polls
+    pub voters: i64,
+    /// Options the viewer voted for
+    pub choices: Vec<Uuid>,
+    /// Whether the viewer is a club member and the poll is open
+    pub can_vote: bool,
+}
+
+impl PollView {
+    pub fn chose(&self, option: &PollOption) -> bool {
+        self.choices.contains(&option.id)
+    }
+    pub fn results_visible(&self) -> bool {
+        self.poll.results_visible(!self.choices.is_empty())
+    }
+    /// Share of voters who picked `option`, rounded to a whole percent.
+    pub fn percent(&self, option: &PollOption) -> i64 {
+        if self.voters == 0 {
+            return 0;
+        }
+        (option.votes * 100 + self.voters / 2) / self.voters
+    }
+}
+
+/// The viewer's choices after they click `option_id`: clicking a chosen
+/// option withdraws it, and a single choice poll keeps only the new one.
+pub fn toggle_choice(choices: &[Uuid], option_id: Uuid, multiple: bool) -> Vec<Uuid> {
+    if choices.contains(&option_id) {
+        return choices
+            .iter()
+            .copied()
+            .filter(|id| *id != option_id)
+            .collect();
+    }
+    if multiple {
+        let mut choices = choices.to_vec();
+        choices.push(option_id);
+        choices
+    } else {
+        vec![option_id]
+    }
+}
+
+/// Options from a textarea, one per line, with blank lines and repeats
+/// (ignoring case) dropped.
+pub fn parse_poll_options(input: &str) -> Vec<String> {
+    let mut options: Vec<String> = Vec::new();
+    for line in input.lines().map(str::trim) {
+        let key = line.to_lowercase();
+        if !line.is_empty() && !options.iter().any(|o| o.to_lowercase() == key) {
+            options.push(line.to_string());
+        }
+    }
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn poll(results: PollResults, closes_at: Option<DateTime<Utc>>) -> Poll {
+        Poll {
+            id: Uuid::new_v4(),
+            club_id: Uuid::new_v4(),
+            question: "Next book?".to_string(),
+            multiple: false,
+            results: results.code().to_string(),
+            closes_at,
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_results_visibility() {
+        let past = Some(Utc::now() - Duration::hours(1));
+        let future = Some(Utc::now() + Duration::hours(1));
+
+        assert!(poll(PollResults::Always, future).results_visible(false));
+
+        let after_vote = poll(PollResults::AfterVote, future);
+        assert!(!after_vote.results_visible(false));
+        assert!(after_vote.results_visible(true));
+        assert!(poll(PollResults::AfterVote, past).results_visible(false));
+
+        assert!(!poll(PollResults::AfterClose, None).results_visible(true));
+        assert!(!poll(PollResults::AfterClose, future).results_visible(true));
+        assert!(poll(PollResults::AfterClose, past).results_visible(false));
+    }
+
+    #[test]
+    fn test_toggle_choice() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(toggle_choice(&[], a, false), vec![a]);
+        assert_eq!(toggle_choice(&[a], b, false), vec![b]);
+        assert!(toggle_choice(&[a], a, false).is_empty());
+        assert_eq!(toggle_choice(&[a], b, true), vec![a, b]);
+        assert_eq!(toggle_choice(&[a, b], a, true), vec![b]);
+    }
+
+    #[test]
+    fn test_parse_poll_options() {
+        assert_eq!(
+            parse_poll_options(" Dune \n\nSolaris\r\ndune\n  Roadside Picnic  "),
+            vec!["Dune", "Solaris", "Roadside Picnic"]
+        );
+        assert!(parse_poll_options("\n  \n").is_empty());
+    }
+
+    #[test]
+    fn test_results_codes() {
+        for results in PollResults::ALL {
+            assert_eq!(PollResults::from_code(results.code()), Some(results));
+        }
+        assert_eq!(PollResults::from_code("never"), None);
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const REPORT_OPEN: &str = "open";
+pub const REPORT_DISMISSED: &str = "dismissed";
+pub const REPORT_ACTIONED: &str = "actioned";
+
+/// Kind of user content a report points at; stored as [`ReportTarget::code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTarget {
+    Profile,
+    Review,
+    Comment,
+}
+
+impl ReportTarget {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReportTarget::Profile => "profile",
+            ReportTarget::Review => "review",
+            ReportTarget::Comment => "comment",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "profile" => Some(ReportTarget::Profile),
+            "review" => Some(ReportTarget::Review),
+            "comment" => Some(ReportTarget::Comment),
+            _ => None,
+        }
+    }
+}
+
+/// What a moderator does with the open reports on a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationAction {
+    /// Close the reports and show the content again
+    Dismiss,
+    Hide,
+    /// Hide the profile and ban its owner
+    Ban,
+}
+
+impl ModerationAction {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "dismiss" => Some(ModerationAction::Dismiss),
+            "hide" => Some(ModerationAction::Hide),
+            "ban" => Some(ModerationAction::Ban),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Report {
+    pub id: Uuid,
+    pub target_kind: String,
+    pub target_id: Uuid,
+    pub reporter_id: Uuid,
+    pub reason: String,
+    pub status: String,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A reported target in the moderation queue with its open reports.
+#[derive(Debug, Clone, FromRow)]
+pub struct ModerationQueueItem {
+    pub target_kind: String,
+    pub target_id: Uuid,
+    /// Username for profiles
+    pub target_label: Option<String>,
+    pub report_count: i64,
+    pub reasons: Vec<String>,
+    pub last_reported_at: DateTime<Utc>,
+    pub hidden: bool,
+}
+
+/// `[moderation]` configuration section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModerationSettings {
+    /// Distinct open reports after which content is hidden until reviewed;
+    /// 0 turns automatic hiding off
+    pub auto_hide_threshold: i64,
+    pub max_reason_length: usize,
+}
+
+impl Default for ModerationSettings {
+    fn default() -> Self {
+        Self {
+            auto_hide_threshold: 3,
+            max_reason_length: 500,
+        }
+    }
+}
+
+impl ModerationSettings {
+    pub fn should_hide(&self, report_count: i64) -> bool {
+        self.auto_hide_threshold > 0 && report_count >= self.auto_hide_threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_target_codes() {
+        for target in [
+            ReportTarget::Profile,
+            ReportTarget::Review,
+            ReportTarget::Comment,
+        ] {
+            assert_eq!(ReportTarget::from_code(target.code()), Some(target));
+        }
+        assert_eq!(ReportTarget::from_code("list"), None);
+    }
+
+    #[test]
+    fn test_should_hide() {
+        let settings = ModerationSettings::default();
+        assert!(!settings.should_hide(2));
+        assert!(settings.should_hide(3));
+        let disabled = ModerationSettings {
+            auto_hide_threshold: 0,
+            ..Default::default()
+        };
+        assert!(!disabled.should_hide(100));
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{i18n::Locale, models::domain::User};
+
+/// A user offered by the search box; never carries contact details.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct UserSuggestion {
+    pub id: Uuid,
+    pub username: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub badge: Option<String>,
+    /// Relevance from the [`SearchSettings`] weights; higher first
+    pub score: f64,
+}
+
+impl UserSuggestion {
+    pub fn full_name(&self) -> Option<String> {
+        let name = [self.first_name.as_deref(), self.last_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(name).filter(|n| !n.is_empty())
+    }
+}
+
+/// `[search]` configuration section: how much a match in each user field
+/// counts. Typo tolerant matches are scored by trigram similarity times the
+/// same weights, so they rank below exact ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchSettings {
+    pub username_weight: f64,
+    pub name_weight: f64,
+    pub bio_weight: f64,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            username_weight: 3.0,
+            name_weight: 2.0,
+            bio_weight: 1.0,
+        }
+    }
+}
+
+/// Search box results grouped by kind. Items, lists and tags join as their
+/// storages land.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchSuggestions {
+    pub users: Vec<UserSuggestion>,
+}
+
+impl SearchSuggestions {
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+}
+
+/// Who is offered a palette action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAudience {
+    Everyone,
+    /// Not signed in
+    Visitors,
+    /// Signed in, outside guest mode
+    Members,
+    Admins,
+}
+
+/// A page the command palette jumps to, labelled by the message `key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaletteAction {
+    pub key: &'static str,
+    pub url: &'static str,
+    pub audience: PaletteAudience,
+}
+
+impl PaletteAction {
+    pub const ALL: [PaletteAction; 18] = [
+        Self::new("nav-home", "/", PaletteAudience::Everyone),
+        Self::new("nav-clubs", "/clubs", PaletteAudience::Everyone),
+        Self::new("nav-events", "/events", PaletteAudience::Everyone),
+        Self::new("nav-venues", "/venues", PaletteAudience::Everyone),
+        Self::new("sign-in", "/login", PaletteAudience::Visitors),
+        Self::new("sign-up", "/signup", PaletteAudience::Visitors),
+        Self::new(
+            "preferences-title",
+            "/settings/preferences",
+            PaletteAudience::Members,
+        ),
+        Self::new("blocks-title", "/settings/blocks", PaletteAudience::Members),
+        Self::new(
+            "interests-title",
+            "/settings/interests",
+            PaletteAudience::Members,
+        ),
+        Self::new(
+            "palette-api-keys",
+            "/settings/api-keys",
+            PaletteAudience::Members,
+        ),
+        Self::new(
+            "palette-curator",
+            "/settings/curator",
+            PaletteAudience::Members,
+        ),
+        Self::new(
+            "palette-export",
+            "/settings/export",
+            PaletteAudience::Members,
+        ),
+        Self::new(
+            "moderation-title",
+            "/admin/moderation",
+            PaletteAudience::Admins,
+        ),
+        Self::new(
+            "palette-admin-badges",
+            "/admin/badges",
+            PaletteAudience::Admins,
+        ),
+        Self::new(
+            "palette-admin-venues",
+            "/admin/venues",
+            PaletteAudience::Admins,
+        ),
+        Self::new(
+            "palette-admin-genres",
+            "/admin/genres",
+            PaletteAudience::Admins,
+        ),
+        Self::new(
+            "palette-admin-system",
+            "/admin/system",
+            PaletteAudience::Admins,
+        ),
+        Self::new(
+            "palette-admin-traffic",
+            "/admin/traffic",
+            PaletteAudience::Admins,
+        ),
+    ];
+
+    const fn new(key: &'static str, url: &'static str, audience: PaletteAudience) -> Self {
+        Self { key, url, audience }
+    }
+    /// Actions for a viewer; `guest` is guest mode on a shared device.
+    pub fn available(viewer: Option<&User>, guest: bool) -> Vec<PaletteAction> {
+        Self::ALL
+            .into_iter()
+            .filter(|action| match action.audience {
+                PaletteAudience::Everyone => true,
+                PaletteAudience::Visitors => viewer.is_none(),
+                PaletteAudience::Members => viewer.is_some() && !guest,
+                PaletteAudience::Admins => viewer.is_some_and(|u| u.is_admin) && !guest,
+            })
+            .collect()
+    }
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(self.key)
+    }
+}
+
+/// A palette action as offered to the viewer.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub url: &'static str,
+}
+
+/// Command palette results: matching actions, best first, and people.
+/// Recent items and lists join as their storages land.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Palette {
+    pub actions: Vec<PaletteEntry>,
+    pub users: Vec<UserSuggestion>,
+}
+
+/// How well `query` matches `text` as a case-insensitive subsequence, or
+/// `None` when some of its characters are missing. Runs of consecutive
+/// characters and matches at word starts score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut text_chars = text.chars().flat_map(char::to_lowercase);
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        if wanted.is_whitespace() {
+            continue;
+        }
+        loop {
+            let c = text_chars.next()?;
+            let word_start = previous.is_none_or(|p| !p.is_alphanumeric());
+            previous = Some(c);
+            if c == wanted {
+                score += 1;
+                if consecutive {
+                    score += 2;
+                }
+                if word_start {
+                    score += 3;
+                }
+                consecutive = true;
+                break;
+            }
+            consecutive = false;
+        }
+    }
+    Some(score)
+}
+
+/// Escapes `%`, `_` and `\` so `query` matches literally inside a LIKE pattern.
+pub fn escape_like(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("an_na%"), r"an\_na\%");
+        assert_eq!(escape_like(r"a\b"), r"a\\b");
+        assert_eq!(escape_like("плain"), "плain");
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Clubs"), Some(0));
+        assert!(fuzzy_score("clb", "Clubs").is_some());
+        assert!(fuzzy_score("КЛУ", "Клубы").is_some());
+        assert_eq!(fuzzy_score("bulc", "Clubs"), None);
+        // Prefixes beat scattered matches
+        assert!(fuzzy_score("se", "Settings") > fuzzy_score("se", "Blocked users"));
+        // Word starts beat the middle of words
+        assert!(fuzzy_score("u", "Blocked users") > fuzzy_score("u", "Clubs"));
+    }
+
+    #[test]
+    fn test_palette_actions_by_audience() {
+        let visitor = PaletteAction::available(None, false);
+        assert!(visitor.iter().any(|a| a.url == "/login"));
+        assert!(visitor.iter().all(|a| !a.url.starts_with("/settings")));
+
+        let mut user = User::default();
+        let member = PaletteAction::available(Some(&user), false);
+        assert!(member.iter().any(|a| a.url == "/settings/blocks"));
+        assert!(member.iter().all(|a| a.url != "/login"));
+        assert!(member.iter().all(|a| !a.url.starts_with("/admin")));
+        let guest = PaletteAction::available(Some(&user), true);
+        assert!(guest.iter().all(|a| !a.url.starts_with("/settings")));
+
+        user.is_admin = true;
+        let admin = PaletteAction::available(Some(&user), false);
+        assert!(admin.iter().any(|a| a.url == "/admin/moderation"));
+    }
+
+    #[test]
+    fn test_full_name() {
+        let mut user = UserSuggestion {
+            id: Uuid::nil(),
+            username: "reader".to_string(),
+            first_name: Some("Anna".to_string()),
+            last_name: None,
+            badge: None,
+            score: 0.0,
+        };
+        assert_eq!(user.full_name().as_deref(), Some("Anna"));
+        user.first_name = None;
+        assert_eq!(user.full_name(), None);
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Built-in profile fields an instance may collect at signup.
+/// `city` has no column of its own and is kept in `profile_extras`.
+pub const STANDARD_PROFILE_FIELDS: [&str; 4] = ["first_name", "last_name", "bio", "city"];
+pub const CITY_FIELD: &str = "city";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileFieldKind {
+    #[default]
+    Text,
+    Textarea,
+    Number,
+    Url,
+}
+
+impl ProfileFieldKind {
+    /// `type` of the rendered `<input>`, or `textarea`
+    pub fn input_type(&self) -> &'static str {
+        match self {
+            ProfileFieldKind::Text => "text",
+            ProfileFieldKind::Textarea => "textarea",
+            ProfileFieldKind::Number => "number",
+            ProfileFieldKind::Url => "url",
+        }
+    }
+}
+
+/// Instance-defined profile field, stored under `key` in `profile_extras`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileField {
+    pub key: String,
+    pub label: String,
+    #[serde(default)]
+    pub kind: ProfileFieldKind,
+}
+
+/// `[signup]` configuration section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignupSettings {
+    /// Enabled entries of [`STANDARD_PROFILE_FIELDS`]
+    #[serde(default = "default_fields")]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub custom_fields: Vec<ProfileField>,
+}
+
+fn default_fields() -> Vec<String> {
+    ["first_name", "last_name", "bio"]
+        .map(String::from)
+        .to_vec()
+}
+
+impl Default for SignupSettings {
+    fn default() -> Self {
+        Self {
+            fields: default_fields(),
+            custom_fields: Vec::new(),
+        }
+    }
+}
+
+impl SignupSettings {
+    /// Keys end up in datastar signal names and JSON, keep them to identifiers.
+    pub fn drop_invalid_fields(&mut self) {
+        self.custom_fields.retain(|f| {
+            let valid = !f.key.is_empty()
+                && !STANDARD_PROFILE_FIELDS.contains(&f.key.as_str())
+                && f.key
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                tracing::warn!("ignoring custom profile field with key `{}`", f.key);
+            }
+            valid
+        });
+    }
+
+    pub fn collects(&self, field: &str) -> bool {
+        self.fields.iter().any(|f| f == field)
+    }
+
+    /// Builds the `profile_extras` document from submitted values, dropping
+    /// fields the instance does not collect and empty values.
+    pub fn extras_from(&self, values: &BTreeMap<String, String>) -> Result<Value, String> {
+        let mut extras = Map::new();
+        if self.collects(CITY_FIELD)
+            && let Some(city) = values.get(CITY_FIELD).map(|v| v.trim())
+            && !city.is_empty()
+        {
+            extras.insert(CITY_FIELD.to_string(), Value::String(city.to_string()));
+        }
+        for field in &self.custom_fields {
+            let Some(value) = values.get(&field.key).map(|v| v.trim()) else {
+                continue;
+            };
+            if value.is_empty() {
+                continue;
+            }
+            let value = match field.kind {
+                ProfileFieldKind::Number => value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("{}: ожидается число", field.label))?,
+                ProfileFieldKind::Url if !value.starts_with("http") => {
+                    return Err(format!("{}: ожидается ссылка", field.label));
+                }
+                _ => Value::String(value.to_string()),
+            };
+            extras.insert(field.key.clone(), value);
+        }
+        Ok(Value::Object(extras))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> SignupSettings {
+        SignupSettings {
+            fields: vec!["first_name".into(), "city".into()],
+            custom_fields: vec![
+                ProfileField {
+                    key: "genre".into(),
+                    label: "Жанр".into(),
+                    kind: ProfileFieldKind::Text,
+                },
+                ProfileField {
+                    key: "books_per_year".into(),
+                    label: "Книг в год".into(),
+                    kind: ProfileFieldKind::Number,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_extras_keep_only_configured_fields() {
+        let values = BTreeMap::from([
+            ("city".to_string(), " Казань ".to_string()),
+            ("genre".to_string(), "фэнтези".to_string()),
+            ("books_per_year".to_string(), "12".to_string()),
+            ("unknown".to_string(), "x".to_string()),
+        ]);
+        let extras = settings().extras_from(&values).unwrap();
+        assert_eq!(
+            extras,
+            serde_json::json!({"city": "Казань", "genre": "фэнтези", "books_per_year": 12.0})
+        );
+    }
+
+    #[test]
+    fn test_extras_reject_wrong_type() {
+        let values = BTreeMap::from([("books_per_year".to_string(), "много".to_string())]);
+        assert!(settings().extras_from(&values).is_err());
+    }
+
+    #[test]
+    fn test_city_dropped_when_disabled() {
+        let values = BTreeMap::from([("city".to_string(), "Казань".to_string())]);
+        let extras = SignupSettings::default().extras_from(&values).unwrap();
+        assert_eq!(extras, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_drop_invalid_fields() {
+        let toml = r#"
+            [signup]
+            fields = ["bio"]
+
+            [[signup.custom_fields]]
+            key = "Bad Key"
+            label = "Bad"
+        "#;
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap();
+        let mut settings = config.get::<SignupSettings>("signup").unwrap();
+        settings.drop_invalid_fields();
+        assert!(settings.collects("bio"));
+        assert!(!settings.collects("first_name"));
+        assert!(settings.custom_fields.is_empty());
+    }
+}
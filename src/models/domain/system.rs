@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: DateTime<Utc>,
+    pub success: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: Option<DateTime<Utc>>,
+    pub applied: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+    pub min_connections: u32,
+    pub max_connections: u32,
+}
+
+/// What a scheduled task run did as named counts, e.g. rows purged per table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskOutcome {
+    pub counts: Vec<(&'static str, u64)>,
+}
+
+impl TaskOutcome {
+    pub fn with_count(mut self, name: &'static str, count: u64) -> Self {
+        self.counts.push((name, count));
+        self
+    }
+}
+
+impl Display for TaskOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.counts.is_empty() {
+            return write!(f, "done");
+        }
+        let counts: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(name, count)| format!("{name}: {count}"))
+            .collect();
+        write!(f, "{}", counts.join(", "))
+    }
+}
+
+/// Last run of a scheduled task, see [`Scheduler`](crate::services::Scheduler).
+#[derive(Debug, Clone)]
+pub struct TaskStatus {
+    pub name: &'static str,
+    /// `None` when the task is disabled
+    pub interval_secs: Option<u64>,
+    pub runs: u64,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_outcome: Option<TaskOutcome>,
+    pub last_error: Option<String>,
+    /// Sums of the outcome counts over all runs since startup
+    pub totals: BTreeMap<&'static str, u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SystemInfo {
+    pub version: &'static str,
+    pub commit: &'static str,
+    pub environment: String,
+    pub settings: Vec<(String, String)>,
+    pub migrations: Vec<MigrationStatus>,
+    pub pool: PoolStats,
+    pub tasks: Vec<TaskStatus>,
+}
+
+/// Requests to one route over the traffic window.
+#[derive(Debug, Clone)]
+pub struct RouteTraffic {
+    pub route: String,
+    pub requests: usize,
+    /// Share of 5xx responses
+    pub error_rate: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Requests by one signed-in user over the traffic window.
+#[derive(Debug, Clone)]
+pub struct UserTraffic {
+    pub user_id: uuid::Uuid,
+    pub requests: usize,
+    pub error_rate: f64,
+}
+
+/// Database time spent on one query since startup.
+#[derive(Debug, Clone)]
+pub struct QueryTraffic {
+    /// Query file, or `<storage>::<method>` for queries built in code
+    pub query: &'static str,
+    pub calls: u64,
+    pub errors: u64,
+    /// Calls over `database.slow_query_ms`
+    pub slow: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Summary of recent requests kept by the in-memory traffic stats.
+#[derive(Debug, Clone)]
+pub struct TrafficReport {
+    pub window_minutes: u64,
+    pub requests: usize,
+    pub error_rate: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    /// Slowest first, by p95
+    pub routes: Vec<RouteTraffic>,
+    /// Busiest first
+    pub users: Vec<UserTraffic>,
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Cookie remembering the theme of visitors who are not signed in.
+pub const THEME_COOKIE: &str = "theme";
+
+/// Colour scheme picked by the visitor; none means following the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_codes() {
+        for theme in [Theme::Light, Theme::Dark] {
+            assert_eq!(Theme::from_code(theme.code()), Some(theme));
+        }
+        assert_eq!(Theme::from_code("system"), None);
+    }
+}
@@ -0,0 +1,244 @@
+use anyhow::Result;
+use axum_session_auth::Authentication;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Theme;
+use crate::{
+    i18n::{self, Locale},
+    markdown,
+    services::UsersService,
+};
+
+/// Owner of content left behind by anonymized accounts, created with the first one.
+pub const GHOST_USER_ID: Uuid = Uuid::from_u128(1);
+
+/// Username an anonymized account is renamed to; unique per account and
+/// outside the username rules, so it cannot be claimed again.
+pub fn anonymized_username(id: Uuid) -> String {
+    format!("_deleted_{}", id.simple())
+}
+
+/// Placeholder for the scrubbed email, kept unique per account.
+pub fn anonymized_email(id: Uuid) -> String {
+    format!("{}@anonymized.invalid", id.simple())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    /// Markdown source, see [`crate::markdown`]
+    pub bio: Option<String>,
+    /// Sanitized HTML rendered from `bio` when it was saved
+    pub bio_html: Option<String>,
+    /// Instance-defined profile fields, see [`SignupSettings`](super::SignupSettings)
+    pub profile_extras: serde_json::Value,
+    pub is_admin: bool,
+    pub badge: Option<String>,
+    /// Interface language code, see [`Locale`]; negotiated per request when unset
+    pub locale: Option<String>,
+    /// IANA timezone dates are shown in
+    pub timezone: String,
+    /// See [`Theme`]; follows the system when unset
+    pub theme: Option<String>,
+    /// Set by moderators; banned users cannot sign in
+    pub banned_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    /// Touched by a database trigger on every change to the row
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What is stored about a user besides the profile, for data exports. Each
+/// section is a JSON array of records.
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct UserData {
+    pub club_memberships: serde_json::Value,
+    pub event_rsvps: serde_json::Value,
+    pub poll_votes: serde_json::Value,
+    /// Blocks and mutes
+    pub blocks: serde_json::Value,
+    pub genre_interests: serde_json::Value,
+    /// Without the key hashes
+    pub api_keys: serde_json::Value,
+    pub username_history: serde_json::Value,
+    /// Reports the user filed
+    pub reports: serde_json::Value,
+    pub curator_applications: serde_json::Value,
+}
+
+impl User {
+    pub fn preferred_locale(&self) -> Option<Locale> {
+        self.locale.as_deref().and_then(Locale::from_code)
+    }
+    pub fn preferred_theme(&self) -> Option<Theme> {
+        self.theme.as_deref().and_then(Theme::from_code)
+    }
+    /// HTML for the bio; bios saved before markdown support are rendered now.
+    pub fn rendered_bio(&self) -> Option<String> {
+        self.bio_html
+            .clone()
+            .or_else(|| self.bio.as_deref().map(markdown::render))
+    }
+    pub fn is_banned(&self) -> bool {
+        self.banned_at.is_some()
+    }
+    pub fn is_ghost(&self) -> bool {
+        self.id == GHOST_USER_ID
+    }
+    pub fn tz(&self) -> Tz {
+        i18n::parse_timezone(&self.timezone).unwrap_or(i18n::DEFAULT_TIMEZONE)
+    }
+}
+
+impl Default for User {
+    fn default() -> Self {
+        Self {
+            id: Uuid::nil(),
+            username: String::new(),
+            email: String::new(),
+            first_name: None,
+            last_name: None,
+            bio: None,
+            bio_html: None,
+            profile_extras: serde_json::json!({}),
+            is_admin: false,
+            badge: None,
+            locale: None,
+            timezone: i18n::DEFAULT_TIMEZONE.name().to_string(),
+            theme: None,
+            banned_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Authentication<User, String, UsersService> for User {
+    async fn load_user(userid: String, service: Option<&UsersService>) -> Result<User> {
+        let user = service.unwrap().get_by_id(&userid).await?;
+        // A ban ends existing sessions on their next request
+        if user.is_banned() {
+            anyhow::bail!("user {userid} is banned");
+        }
+        Ok(user)
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.id != Uuid::nil()
+    }
+
+    fn is_active(&self) -> bool {
+        self.id != Uuid::nil()
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.id == Uuid::nil()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateUser {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+    pub profile_extras: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateUser {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub password: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub bio: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct UserSearch {
+    pub search: Option<String>,
+    pub is_admin: Option<bool>,
+    pub created_since: Option<DateTime<Utc>>,
+    /// Users changed at or after this moment, for clients syncing changes
+    pub updated_since: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl Default for UserSearch {
+    fn default() -> Self {
+        Self {
+            search: None,
+            is_admin: None,
+            created_since: None,
+            updated_since: None,
+            limit: Some(20),
+            offset: Some(0),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UserList {
+    pub users: Vec<User>,
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::check_username_rules;
+
+    #[test]
+    fn test_user_search_default_values() {
+        let default_search = UserSearch::default();
+        assert_eq!(default_search.search, None);
+        assert_eq!(default_search.is_admin, None);
+        assert_eq!(default_search.created_since, None);
+        assert_eq!(default_search.limit, Some(20));
+        assert_eq!(default_search.offset, Some(0));
+    }
+
+    #[test]
+    fn test_user_search_custom_values() {
+        let custom_search = UserSearch {
+            search: Some("query".to_string()),
+            limit: Some(10),
+            offset: Some(50),
+            ..Default::default()
+        };
+
+        assert_eq!(custom_search.search, Some("query".to_string()));
+        assert_eq!(custom_search.limit, Some(10));
+        assert_eq!(custom_search.offset, Some(50));
+    }
+
+    #[test]
+    fn test_anonymized_identity() {
+        let id = Uuid::new_v4();
+        let username = anonymized_username(id);
+        assert!(check_username_rules(&username).is_err());
+        assert_ne!(username, anonymized_username(Uuid::new_v4()));
+        assert!(anonymized_email(id).ends_with("@anonymized.invalid"));
+    }
+
+    #[test]
+    fn test_anonymous_user_default() {
+        let user = User::default();
+        assert!(user.is_anonymous());
+        assert!(!user.is_authenticated());
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+
+pub const USERNAME_MIN_LEN: usize = 3;
+pub const USERNAME_MAX_LEN: usize = 32;
+
+/// Names that would shadow routes or impersonate the service.
+pub const RESERVED_USERNAMES: [&str; 16] = [
+    "admin",
+    "administrator",
+    "api",
+    "culturelist",
+    "help",
+    "login",
+    "moderator",
+    "public",
+    "root",
+    "settings",
+    "signout",
+    "signup",
+    "support",
+    "system",
+    "u",
+    "user",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UsernameRuleError {
+    Length,
+    Charset,
+    Reserved,
+}
+
+impl UsernameRuleError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            UsernameRuleError::Length => locale.t_args(
+                "username-length",
+                &[
+                    ("min", USERNAME_MIN_LEN.to_string()),
+                    ("max", USERNAME_MAX_LEN.to_string()),
+                ],
+            ),
+            UsernameRuleError::Charset => locale.t("username-charset"),
+            UsernameRuleError::Reserved => locale.t("username-reserved"),
+        }
+    }
+}
+
+/// Trims surrounding whitespace; case is preserved for display and
+/// uniqueness is enforced case-insensitively by the database.
+pub fn normalize_username(username: &str) -> String {
+    username.trim().to_string()
+}
+
+pub fn check_username_rules(username: &str) -> Result<(), UsernameRuleError> {
+    let len = username.chars().count();
+    if !(USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&len) {
+        return Err(UsernameRuleError::Length);
+    }
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    let starts_alphanumeric = username
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric());
+    if !valid_chars || !starts_alphanumeric {
+        return Err(UsernameRuleError::Charset);
+    }
+    let lower = username.to_ascii_lowercase();
+    if RESERVED_USERNAMES.contains(&lower.as_str()) {
+        return Err(UsernameRuleError::Reserved);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_username_rules() {
+        assert!(check_username_rules("reader_42").is_ok());
+        assert!(check_username_rules("Anna.K").is_ok());
+        assert_eq!(check_username_rules("ab"), Err(UsernameRuleError::Length));
+        assert_eq!(
+            check_username_rules(&"a".repeat(33)),
+            Err(UsernameRuleError::Length)
+        );
+        assert_eq!(
+            check_username_rules("читатель"),
+            Err(UsernameRuleError::Charset)
+        );
+        assert_eq!(
+            check_username_rules("_hidden"),
+            Err(UsernameRuleError::Charset)
+        );
+        assert_eq!(
+            check_username_rules("with space"),
+            Err(UsernameRuleError::Charset)
+        );
+        assert_eq!(
+            check_username_rules("Admin"),
+            Err(UsernameRuleError::Reserved)
+        );
+    }
+
+    #[test]
+    fn test_normalize_username() {
+        assert_eq!(normalize_username("  Reader "), "Reader");
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{ClubSlugError, check_club_slug},
+};
+
+pub const VENUE_NAME_MAX_LEN: usize = 120;
+pub const VENUE_ADDRESS_MAX_LEN: usize = 300;
+/// Radius of the "near me" search.
+pub const VENUE_SEARCH_RADIUS_M: f64 = 10_000.0;
+
+/// Slugs that would shadow routes under `/venues`.
+const RESERVED_VENUE_SLUGS: [&str; 1] = ["near"];
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Venue {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    /// Stored as [`VenueKind::code`]
+    pub kind: String,
+    pub address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// `None` once the creator's account is deleted
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Venue {
+    pub fn kind(&self) -> VenueKind {
+        VenueKind::from_code(&self.kind).unwrap_or_default()
+    }
+}
+
+/// A venue found by the "near me" search.
+#[derive(Debug, Clone, FromRow)]
+pub struct NearbyVenue {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub kind: String,
+    pub address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Great-circle distance from the searched point
+    pub distance_m: f64,
+}
+
+impl NearbyVenue {
+    pub fn kind(&self) -> VenueKind {
+        VenueKind::from_code(&self.kind).unwrap_or_default()
+    }
+    /// Rounded for display: meters up to a kilometer, then tenths of km.
+    pub fn distance(&self, locale: Locale) -> String {
+        if self.distance_m < 1000.0 {
+            let meters = ((self.distance_m / 10.0).round() * 10.0) as u64;
+            locale.t_args("venue-distance-m", &[("distance", meters.to_string())])
+        } else {
+            let km = format!("{:.1}", self.distance_m / 1000.0);
+            locale.t_args("venue-distance-km", &[("distance", km)])
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VenueKind {
+    Theater,
+    Museum,
+    Cinema,
+    Library,
+    ConcertHall,
+    #[default]
+    Other,
+}
+
+impl VenueKind {
+    pub const ALL: [VenueKind; 6] = [
+        VenueKind::Theater,
+        VenueKind::Museum,
+        VenueKind::Cinema,
+        VenueKind::Library,
+        VenueKind::ConcertHall,
+        VenueKind::Other,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            VenueKind::Theater => "theater",
+            VenueKind::Museum => "museum",
+            VenueKind::Cinema => "cinema",
+            VenueKind::Library => "library",
+            VenueKind::ConcertHall => "concert_hall",
+            VenueKind::Other => "other",
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.code() == code.trim())
+    }
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("venue-kind-{}", self.code().replace('_', "-")))
+    }
+}
+
+/// Venue addresses follow the club address rules.
+pub fn check_venue_slug(slug: &str) -> Result<(), ClubSlugError> {
+    check_club_slug(slug)?;
+    if RESERVED_VENUE_SLUGS.contains(&slug) {
+        return Err(ClubSlugError::Reserved);
+    }
+    Ok(())
+}
+
+/// Whether `latitude` and `longitude` are degrees on Earth.
+pub fn valid_coordinates(latitude: f64, longitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_venue_kinds() {
+        for kind in VenueKind::ALL {
+            assert_eq!(VenueKind::from_code(kind.code()), Some(kind));
+        }
+        assert_eq!(VenueKind::from_code("stadium"), None);
+    }
+
+    #[test]
+    fn test_venue_slug_rules() {
+        assert!(check_venue_slug("bolshoi-theatre").is_ok());
+        assert_eq!(check_venue_slug("near"), Err(ClubSlugError::Reserved));
+        assert_eq!(check_venue_slug("Bolshoi"), Err(ClubSlugError::Charset));
+    }
+
+    #[test]
+    fn test_coordinates() {
+        assert!(valid_coordinates(55.76, 37.62));
+        assert!(valid_coordinates(-90.0, 180.0));
+        assert!(!valid_coordinates(91.0, 0.0));
+        assert!(!valid_coordinates(0.0, -181.0));
+        assert!(!valid_coordinates(f64::NAN, 0.0));
+    }
+}
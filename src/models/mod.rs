@@ -1,2 +1,2 @@
-mod user;
-pub use user::*;
+pub mod api;
+pub mod domain;
@@ -0,0 +1,67 @@
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use axum_session_auth::Authentication;
+use tracing::{Instrument, info, info_span};
+
+use super::{AuthLayer, REQUEST_ID_HEADER};
+use crate::{AppState, extractors::ClientIp, storage::query_stats};
+
+/// One `access` event per request with the fields log collectors index on:
+/// request id, signed-in user, client address, matched route, status,
+/// latency and the count and time of storage queries. All but the queries
+/// feed the in-memory traffic stats behind `/admin/traffic`. Events logged
+/// while handling the request, such as `audit` ones, carry the client
+/// address through the `client` span.
+pub async fn access_log(
+    State(state): State<Arc<AppState>>,
+    auth: AuthLayer,
+    ClientIp(client_ip): ClientIp,
+    request: Request,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let method = request.method().clone();
+    // The route template, not the path, so ids do not explode cardinality
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .map(str::to_string);
+    let user_id = auth
+        .current_user
+        .as_ref()
+        .filter(|user| user.is_authenticated())
+        .map(|user| user.id);
+    let client_ip = client_ip.map(|ip| ip.to_string());
+    let span = info_span!("client", client_ip);
+    let (response, queries) = query_stats::in_request(next.run(request).instrument(span)).await;
+    let latency = started.elapsed();
+    let status = response.status().as_u16();
+    // Unmatched paths (404s, static files) share one bucket
+    state
+        .traffic_stats
+        .record(route.as_deref().unwrap_or("*"), user_id, status, latency);
+    info!(
+        target: "access",
+        request_id,
+        user_id = user_id.map(|id| id.to_string()),
+        client_ip,
+        %method,
+        route,
+        status,
+        latency_ms = latency.as_millis() as u64,
+        db_queries = queries.count,
+        db_ms = queries.elapsed.as_millis() as u64,
+        "request finished"
+    );
+    response
+}
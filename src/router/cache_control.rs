@@ -0,0 +1,287 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, HttpBody, to_bytes},
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderValue, Method, StatusCode, Uri, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_csrf::CsrfToken;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    AppState, assets,
+    i18n::Locale,
+    models::domain::{THEME_COOKIE, Theme},
+    router::{AuthLayer, context::cookie, security::CspNonce},
+    services::CachedPage,
+};
+
+/// Larger bodies are passed through without an ETag rather than buffered.
+const ETAG_MAX_BODY_BYTES: u64 = 1024 * 1024;
+/// Upper bound for buffering a page rendered for the page cache.
+const PAGE_CACHE_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Stand-ins for the per-request values in pages rendered for the cache.
+pub const NONCE_PLACEHOLDER: &str = "__page_cache_nonce__";
+pub const CSRF_PLACEHOLDER: &str = "__page_cache_csrf__";
+
+/// Request extension telling [`PageContext`](super::PageContext) to render
+/// placeholders instead of the nonce and CSRF token.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderForCache;
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// Unhashed files may change under the same URL
+const REVALIDATE: &str = "public, max-age=0, must-revalidate";
+
+/// In front of the `/public` file service: serves fingerprinted names from
+/// the underlying file and marks them cacheable forever.
+pub async fn fingerprinted_assets(mut req: Request, next: Next) -> Response {
+    let logical = req
+        .uri()
+        .path()
+        .strip_prefix("/assets/")
+        .and_then(assets::resolve);
+    if let Some(logical) = logical {
+        let mut path = format!("/assets/{logical}");
+        if let Some(query) = req.uri().query() {
+            path = format!("{path}?{query}");
+        }
+        if let Ok(uri) = path.parse::<Uri>() {
+            *req.uri_mut() = uri;
+        }
+    }
+    let mut res = next.run(req).await;
+    if res.status().is_success() {
+        let value = if logical.is_some() {
+            IMMUTABLE
+        } else {
+            REVALIDATE
+        };
+        res.headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static(value));
+    }
+    res
+}
+
+/// HTML is always revalidated so it picks up new asset URLs after a deploy.
+pub async fn html_no_cache(req: Request, next: Next) -> Response {
+    let mut res = next.run(req).await;
+    let is_html = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if is_html && !res.headers().contains_key(header::CACHE_CONTROL) {
+        res.headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    }
+    res
+}
+
+/// Weak ETags for JSON responses, answering a matching `If-None-Match` with
+/// 304 so polling clients skip unchanged bodies. Pages are left out: the CSP
+/// nonce and CSRF token make every rendering unique.
+pub async fn etag(req: Request, next: Next) -> Response {
+    if !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return next.run(req).await;
+    }
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+    let res = next.run(req).await;
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let small = res
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len <= ETAG_MAX_BODY_BYTES);
+    if res.status() != StatusCode::OK
+        || res.headers().contains_key(header::ETAG)
+        || !is_json
+        || !small
+    {
+        return res;
+    }
+    let (mut parts, body) = res.into_parts();
+    let Ok(bytes) = to_bytes(body, ETAG_MAX_BODY_BYTES as usize).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let tag = weak_etag(&bytes);
+    let Ok(tag_value) = HeaderValue::from_str(&tag) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    let not_modified = if_none_match
+        .as_ref()
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| etag_matches(v, &tag));
+    if not_modified {
+        let mut res = StatusCode::NOT_MODIFIED.into_response();
+        for name in [header::CACHE_CONTROL, header::VARY] {
+            if let Some(value) = parts.headers.get(&name) {
+                res.headers_mut().insert(name, value.clone());
+            }
+        }
+        res.headers_mut().insert(header::ETAG, tag_value);
+        return res;
+    }
+    parts.headers.insert(header::ETAG, tag_value);
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Serves configured public pages to anonymous visitors from the page cache,
+/// rendering them once per locale, theme and URI.
+pub async fn page_cache(
+    State(state): State<Arc<AppState>>,
+    auth: AuthLayer,
+    req: Request,
+    next: Next,
+) -> Response {
+    let cache = &state.page_cache;
+    let signed_in = auth.current_user.as_ref().is_some_and(|u| !u.id.is_nil());
+    if req.method() != Method::GET || signed_in || !cache.is_cacheable(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let (mut parts, body) = req.into_parts();
+    let locale = Locale::from_request_parts(&mut parts, &state)
+        .await
+        .unwrap_or_default();
+    let theme = cookie(&parts, THEME_COOKIE).and_then(Theme::from_code);
+    let key = format!(
+        "{}|{}|{}",
+        locale.code(),
+        theme.map_or("", |t| t.code()),
+        parts.uri
+    );
+    let nonce = parts
+        .extensions
+        .get::<CspNonce>()
+        .map(|n| n.0.clone())
+        .unwrap_or_default();
+    let csrf_token = CsrfToken::from_request_parts(&mut parts, &state)
+        .await
+        .ok()
+        .and_then(|token| token.authenticity_token().ok())
+        .unwrap_or_default();
+
+    if let Some(page) = cache.get(&key).await {
+        return cached_response(&page, &nonce, &csrf_token);
+    }
+    parts.extensions.insert(RenderForCache);
+    let res = next.run(Request::from_parts(parts, body)).await;
+    let content_type = res.headers().get(header::CONTENT_TYPE).cloned();
+    let Some(content_type) = content_type.filter(|v| v.as_bytes().starts_with(b"text/html")) else {
+        return res;
+    };
+    let (mut parts, body) = res.into_parts();
+    let Ok(body) = to_bytes(body, PAGE_CACHE_MAX_BODY_BYTES).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let page = CachedPage { content_type, body };
+    let filled = fill_placeholders(&page.body, &nonce, &csrf_token);
+    if parts.status == StatusCode::OK {
+        cache.insert(key, page).await;
+    }
+    // Keeps the status and cookies of the response that rendered it
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Response::from_parts(parts, Body::from(filled))
+}
+
+fn cached_response(page: &CachedPage, nonce: &str, csrf_token: &str) -> Response {
+    let mut res = Body::from(fill_placeholders(&page.body, nonce, csrf_token)).into_response();
+    res.headers_mut()
+        .insert(header::CONTENT_TYPE, page.content_type.clone());
+    res.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    res
+}
+
+fn fill_placeholders(body: &[u8], nonce: &str, csrf_token: &str) -> String {
+    String::from_utf8_lossy(body)
+        .replace(NONCE_PLACEHOLDER, nonce)
+        .replace(CSRF_PLACEHOLDER, csrf_token)
+}
+
+fn weak_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{b:02x}")).collect();
+    format!("W/\"{hex}\"")
+}
+
+/// Weak tag for a row's version, so handlers can answer 304 from
+/// `updated_at` without rendering the body.
+pub(crate) fn version_etag(id: Uuid, updated_at: DateTime<Utc>) -> String {
+    format!("W/\"{}-{}\"", id.simple(), updated_at.timestamp_micros())
+}
+
+/// `Last-Modified` value for `updated_at`.
+pub(crate) fn http_date(updated_at: DateTime<Utc>) -> String {
+    updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Weak comparison of an `If-None-Match` list against `tag`.
+pub(crate) fn etag_matches(if_none_match: &str, tag: &str) -> bool {
+    let opaque = |t: &str| t.trim().trim_start_matches("W/").to_string();
+    if_none_match.trim() == "*"
+        || if_none_match
+            .split(',')
+            .any(|candidate| opaque(candidate) == opaque(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_placeholders() {
+        let body = format!(
+            "<script nonce=\"{NONCE_PLACEHOLDER}\"></script><input value=\"{CSRF_PLACEHOLDER}\">"
+        );
+        assert_eq!(
+            fill_placeholders(body.as_bytes(), "n0nce", "t0ken"),
+            "<script nonce=\"n0nce\"></script><input value=\"t0ken\">"
+        );
+    }
+
+    #[test]
+    fn test_weak_etag() {
+        let tag = weak_etag(b"{\"id\":1}");
+        assert!(tag.starts_with("W/\"") && tag.ends_with('"'));
+        assert_eq!(tag, weak_etag(b"{\"id\":1}"));
+        assert_ne!(tag, weak_etag(b"{\"id\":2}"));
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let tag = weak_etag(b"body");
+        let opaque = tag.trim_start_matches("W/");
+        assert!(etag_matches(&tag, &tag));
+        assert!(etag_matches(opaque, &tag));
+        assert!(etag_matches(&format!("W/\"other\", {tag}"), &tag));
+        assert!(etag_matches("*", &tag));
+        assert!(!etag_matches("W/\"other\"", &tag));
+    }
+
+    #[test]
+    fn test_version_etag() {
+        let updated_at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let tag = version_etag(Uuid::nil(), updated_at);
+        assert!(etag_matches(&tag, &tag));
+        assert_ne!(
+            tag,
+            version_etag(Uuid::nil(), updated_at + chrono::Duration::microseconds(1))
+        );
+        assert_eq!(http_date(updated_at), "Thu, 01 Jan 2026 00:00:00 GMT");
+    }
+}
@@ -0,0 +1,182 @@
+use std::{borrow::Borrow, convert::Infallible};
+
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_csrf::CsrfToken;
+use axum_session::Session;
+use axum_session_sqlx::SessionPgPool;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    i18n::{self, LOCALE_KEY, Locale},
+    models::domain::{THEME_COOKIE, Theme, User},
+    router::{
+        AuthLayer, PageMeta,
+        cache_control::{CSRF_PLACEHOLDER, NONCE_PLACEHOLDER, RenderForCache},
+        security::CspNonce,
+    },
+};
+
+/// Session key holding the unix timestamp a guest session expires at.
+pub const GUEST_UNTIL_KEY: &str = "guest_until";
+/// Session key holding the id of the admin signed in as another user.
+pub const IMPERSONATOR_KEY: &str = "impersonator_id";
+
+/// Per-request data every page layout needs.
+#[derive(Debug, Clone, Default)]
+pub struct PageContext {
+    pub user: Option<User>,
+    /// Signed in through guest mode on a shared device
+    pub guest: bool,
+    /// An admin is signed in as `user`
+    pub impersonating: bool,
+    /// CSP nonce for inline and module `<script>` tags
+    pub nonce: String,
+    /// Authenticity token for forms in the layout, such as sign out
+    pub csrf_token: String,
+    pub locale: Locale,
+    /// Chosen colour scheme, rendered on `<html>` so the first paint uses it
+    pub theme: Option<Theme>,
+    /// Open Graph and JSON-LD, set by handlers of public pages
+    pub meta: Option<PageMeta>,
+}
+
+impl PageContext {
+    /// Message `key` in the visitor's locale, for templates.
+    pub fn t(&self, key: &str) -> String {
+        self.locale.t(key)
+    }
+    /// Takes the arguments by value, as template array literals are.
+    pub fn t_args<const N: usize>(&self, key: &str, args: [(&'static str, String); N]) -> String {
+        self.locale.t_args(key, &args)
+    }
+    /// `at` in the signed-in user's timezone, UTC for visitors. Takes values
+    /// and references alike, as template bindings can be either.
+    pub fn datetime(&self, at: impl Borrow<DateTime<Utc>>) -> String {
+        self.locale.format_datetime(at.borrow(), self.timezone())
+    }
+    /// The signed-in user's timezone, UTC for visitors.
+    pub fn timezone(&self) -> Tz {
+        self.user.as_ref().map_or(i18n::DEFAULT_TIMEZONE, User::tz)
+    }
+}
+
+impl<S> FromRequestParts<S> for PageContext
+where
+    S: Send + Sync,
+{
+    type Rejection = <AuthLayer as FromRequestParts<S>>::Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = AuthLayer::from_request_parts(parts, state).await?;
+        let guest = auth.session.get::<i64>(GUEST_UNTIL_KEY).is_some();
+        let impersonating = auth.session.get::<String>(IMPERSONATOR_KEY).is_some();
+        let nonce = parts
+            .extensions
+            .get::<CspNonce>()
+            .map(|n| n.0.clone())
+            .unwrap_or_default();
+        // Shares the request's token with the handler's own `CsrfToken`
+        let csrf_token = CsrfToken::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|token| token.authenticity_token().ok())
+            .unwrap_or_default();
+        // Cached pages get both filled in per response by the page cache
+        let (nonce, csrf_token) = if parts.extensions.get::<RenderForCache>().is_some() {
+            (NONCE_PLACEHOLDER.to_string(), CSRF_PLACEHOLDER.to_string())
+        } else {
+            (nonce, csrf_token)
+        };
+        let locale = Locale::from_request_parts(parts, state)
+            .await
+            .unwrap_or_default();
+        // The cookie covers visitors and choices made before signing in
+        let theme = auth
+            .current_user
+            .as_ref()
+            .and_then(User::preferred_theme)
+            .or_else(|| cookie(parts, THEME_COOKIE).and_then(Theme::from_code));
+        Ok(Self {
+            user: auth.current_user,
+            guest,
+            impersonating,
+            nonce,
+            csrf_token,
+            locale,
+            theme,
+            meta: None,
+        })
+    }
+}
+
+/// Value of the request cookie `name`.
+pub(crate) fn cookie<'a>(parts: &'a Parts, name: &str) -> Option<&'a str> {
+    parts
+        .headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            (key == name).then_some(value)
+        })
+}
+
+/// The signed-in user's stored locale or the one chosen in the session, else
+/// negotiated from `Accept-Language`.
+impl<S> FromRequestParts<S> for Locale
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let stored = AuthLayer::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|auth| auth.current_user)
+            .and_then(|user| user.preferred_locale());
+        if let Some(locale) = stored {
+            return Ok(locale);
+        }
+        let preference = Session::<SessionPgPool>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|session| session.get::<String>(LOCALE_KEY));
+        let accept_language = parts
+            .headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+        Ok(Locale::negotiate(preference.as_deref(), accept_language))
+    }
+}
+
+/// Logs out guest sessions once their deadline has passed.
+pub async fn expire_guest_session(auth: AuthLayer, req: Request, next: Next) -> Response {
+    if auth
+        .session
+        .get::<i64>(GUEST_UNTIL_KEY)
+        .is_some_and(|until| until <= Utc::now().timestamp())
+    {
+        auth.logout_user();
+        auth.session.destroy();
+    }
+    next.run(req).await
+}
+
+/// Guards account-level actions (credentials, keys, exports) from admins
+/// signed in as someone else.
+pub async fn deny_while_impersonating(auth: AuthLayer, req: Request, next: Next) -> Response {
+    if let Some(admin_id) = auth.session.get::<String>(IMPERSONATOR_KEY) {
+        tracing::info!(target: "audit", admin_id = %admin_id, path = %req.uri().path(), "impersonation blocked action");
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    next.run(req).await
+}
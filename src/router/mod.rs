@@ -1,17 +1,25 @@
-use crate::{AppState, models::User, services::UsersService};
+use crate::{
+    AppState, controllers,
+    i18n::{LOCALE_KEY, Locale},
+    models::domain::User,
+    services::UsersService,
+};
 use askama::Template;
 use askama_web::WebTemplate;
 use axum::{
-    Router,
+    Form, Router,
+    extract::State,
     handler::HandlerWithoutStateExt,
-    http::{Method, header},
+    http::{Method, StatusCode, header},
     response::{IntoResponse, Redirect},
     routing::*,
 };
-use axum_csrf::{CsrfConfig, CsrfLayer, Key};
+use axum_csrf::{CsrfConfig, CsrfLayer, CsrfToken, Key};
 use axum_session::{SessionLayer, SessionStore};
 use axum_session_auth::{AuthConfig, AuthSession, AuthSessionLayer};
 use axum_session_sqlx::SessionPgPool;
+use sentry::integrations::tower::{NewSentryLayer, SentryHttpLayer};
+use serde::Deserialize;
 use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::{
@@ -25,19 +33,35 @@ use tower_http::{
 };
 use tracing::{error, info_span};
 
+mod access_log;
+mod cache_control;
+mod context;
 mod pages;
+mod security;
+
+pub use crate::meta::PageMeta;
+pub(crate) use cache_control::{etag_matches, http_date, version_etag};
+pub use context::{GUEST_UNTIL_KEY, IMPERSONATOR_KEY, PageContext};
+pub use security::SecurityHeaders;
 
-const REQUEST_ID_HEADER: &str = "cult-request-id";
+pub(crate) const REQUEST_ID_HEADER: &str = "cult-request-id";
+const USER_CACHE_MINUTES: i64 = 5;
 
 pub type AuthLayer = AuthSession<User, String, SessionPgPool, UsersService>;
 
 pub fn init(
     allowed_origin: &str,
     session_store: SessionStore<SessionPgPool>,
+    cookie_key: &str,
+    security_headers: SecurityHeaders,
     app_state: AppState,
 ) -> Router {
-    let auth_config =
-        AuthConfig::<String>::default().with_anonymous_user_id(Some(uuid::Uuid::nil().to_string()));
+    // Loaded users are memoized across requests; handlers that change a user's
+    // profile must call `AuthLayer::cache_clear_user` so the next request reloads it.
+    let auth_config = AuthConfig::<String>::default()
+        .with_anonymous_user_id(Some(uuid::Uuid::nil().to_string()))
+        .with_cache(true)
+        .with_max_age(chrono::Duration::minutes(USER_CACHE_MINUTES));
     let auth_layer = AuthSessionLayer::<User, String, SessionPgPool, UsersService>::new(Some(
         app_state.users_service.clone(),
     ))
@@ -76,12 +100,19 @@ pub fn init(
     let cors_layer = CorsLayer::new()
         .allow_origin([allowed_origin.parse().unwrap()])
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers([header::ACCEPT, header::AUTHORIZATION])
+        .allow_headers([header::ACCEPT, header::AUTHORIZATION, header::IF_NONE_MATCH])
+        .expose_headers([header::ETAG])
         .max_age(std::time::Duration::from_secs(60 * 60))
         .allow_credentials(true);
     let compression_layer = CompressionLayer::new();
 
-    let cookie_key = Key::generate(); // Consider storing this in config for production
+    // Without `cookies.key` CSRF cookies stop validating on every restart;
+    // its length is checked by `Settings::load`
+    let cookie_key = if cookie_key.is_empty() {
+        Key::generate()
+    } else {
+        Key::from(cookie_key.as_bytes())
+    };
     let csrf_config = CsrfConfig::default()
         .with_key(Some(cookie_key))
         .with_cookie_name("csrf-token") // optional: customize cookie name
@@ -92,11 +123,54 @@ pub fn init(
         .precompressed_gzip()
         .precompressed_br()
         .fallback(page_not_found.into_service());
+    let static_files_service = ServiceBuilder::new()
+        .layer(axum::middleware::from_fn(
+            cache_control::fingerprinted_assets,
+        ))
+        .service(static_files_service);
+
+    // Account settings stay off limits to admins impersonating the account
+    let settings_routes = Router::new()
+        .route(
+            "/export",
+            get(pages::settings::export).post(pages::settings::request_export),
+        )
+        .route("/export/{token}", get(pages::settings::download_export))
+        .route(
+            "/preferences",
+            get(pages::settings::preferences).post(pages::settings::save_preferences),
+        )
+        .route(
+            "/email/confirm/{token}",
+            get(pages::settings::confirm_email),
+        )
+        .route(
+            "/api-keys",
+            get(pages::api_keys::page).post(pages::api_keys::create),
+        )
+        .route("/api-keys/{id}/delete", post(pages::api_keys::delete))
+        .route(
+            "/curator",
+            get(pages::badges::curator_page).post(pages::badges::curator_apply),
+        )
+        .route(
+            "/blocks",
+            get(pages::blocks::page).post(pages::blocks::create),
+        )
+        .route("/blocks/{id}/remove", post(pages::blocks::remove))
+        .route("/interests", get(pages::genres::interests_page))
+        .route(
+            "/interests/{id}/{action}",
+            post(pages::genres::set_interest),
+        )
+        .route_layer(axum::middleware::from_fn(context::deny_while_impersonating));
 
     let state = Arc::new(app_state);
     Router::new()
         .route("/", get(pages::home::page))
-        .route("/signout", get(sign_out))
+        .route("/signout", post(sign_out))
+        .route("/locale", post(set_locale))
+        .route("/theme", post(pages::theme::toggle))
         .route(
             "/login",
             get(pages::login::page).post(pages::login::login_form),
@@ -108,8 +182,78 @@ pub fn init(
         )
         .route("/signup/validate", get(pages::signup::signup_form_validate))
         .route("/signup/reset", get(pages::signup::signup_form_reset))
+        .route("/u/{username}", get(pages::users::by_username))
+        .route("/report", post(pages::moderation::report))
+        .route("/preview/markdown/{signal}", get(pages::preview::markdown))
+        .route("/search/suggest", get(pages::search::suggest))
+        .route("/_palette", get(pages::search::palette))
+        .route(
+            "/clubs",
+            get(pages::clubs::directory).post(pages::clubs::create),
+        )
+        .route("/clubs/{slug}", get(pages::clubs::page))
+        .route("/clubs/{slug}/join", post(pages::clubs::join))
+        .route("/clubs/{slug}/leave", post(pages::clubs::leave))
+        .route(
+            "/clubs/{slug}/members/{user_id}/{action}",
+            post(pages::clubs::manage_member),
+        )
+        .route("/clubs/{slug}/polls", post(pages::polls::create))
+        .route("/clubs/{slug}/polls/live", get(pages::polls::live))
+        .route("/polls/{id}/vote/{option_id}", post(pages::polls::vote))
+        .route(
+            "/events",
+            get(pages::events::upcoming).post(pages::events::create),
+        )
+        .route("/events/{id}", get(pages::events::page))
+        .route("/events/{id}/rsvp/{status}", post(pages::events::rsvp))
+        .route("/venues", get(pages::venues::directory))
+        .route("/venues/near", get(pages::venues::near))
+        .route("/venues/{slug}", get(pages::venues::page))
+        .nest("/settings", settings_routes)
+        .route("/admin/system", get(pages::admin::system))
+        .route("/admin/traffic", get(pages::admin::traffic))
+        .route("/admin/impersonate", post(pages::impersonation::start))
+        .route("/admin/impersonate/stop", post(pages::impersonation::stop))
+        .route(
+            "/admin/badges",
+            get(pages::badges::admin_page).post(pages::badges::admin_assign),
+        )
+        .route(
+            "/admin/badges/applications/{id}/{decision}",
+            post(pages::badges::admin_resolve),
+        )
+        .route(
+            "/admin/venues",
+            get(pages::venues::admin_page).post(pages::venues::admin_create),
+        )
+        .route(
+            "/admin/genres",
+            get(pages::genres::admin_page).post(pages::genres::admin_save),
+        )
+        .route(
+            "/admin/genres/{id}/delete",
+            post(pages::genres::admin_delete),
+        )
+        .route("/admin/moderation", get(pages::moderation::admin_page))
+        .route(
+            "/admin/moderation/{kind}/{id}/{action}",
+            post(pages::moderation::admin_resolve),
+        )
+        .merge(controllers::batch_routes(state.clone()))
         .nest_service("/public", static_files_service)
-        .with_state(state)
+        .with_state(state.clone())
+        .layer(axum::middleware::from_fn(context::expire_guest_session))
+        .layer(axum::middleware::from_fn(cache_control::html_no_cache))
+        .layer(axum::middleware::from_fn(cache_control::etag))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            cache_control::page_cache,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state,
+            access_log::access_log,
+        ))
         .layer(auth_layer)
         .layer(SessionLayer::new(session_store))
         .layer(CsrfLayer::new(csrf_config))
@@ -117,7 +261,14 @@ pub fn init(
         .layer(compression_layer)
         .layer(cors_layer)
         .layer(timeout_layer)
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(security_headers),
+            security::security_headers,
+        ))
         .layer(request_id_middleware)
+        // Reports from inside a request, panics included, carry its method, URL and route
+        .layer(SentryHttpLayer::new())
+        .layer(NewSentryLayer::<axum::extract::Request>::new_from_top())
         .layer(catch_panic_layer)
         .fallback(page_not_found)
 }
@@ -128,20 +279,245 @@ struct PageNotFound {
     title: String,
     description: String,
     uri: String,
-    user: Option<User>,
+    ctx: PageContext,
 }
 
-async fn page_not_found(auth: AuthLayer, uri: axum::http::Uri) -> impl IntoResponse {
-    let user = auth.current_user;
+async fn page_not_found(ctx: PageContext, uri: axum::http::Uri) -> impl IntoResponse {
     PageNotFound {
-        title: "Страница не найдена".to_string(),
+        title: ctx.t("not-found-title"),
         description: "".to_string(),
         uri: uri.to_string(),
-        user,
+        ctx,
     }
 }
 
-async fn sign_out(auth: AuthLayer) -> impl IntoResponse {
+#[derive(Deserialize)]
+struct SignOutForm {
+    csrf_token: String,
+}
+
+async fn sign_out(
+    auth: AuthLayer,
+    token: CsrfToken,
+    Form(form): Form<SignOutForm>,
+) -> impl IntoResponse {
+    if token.verify(&form.csrf_token).is_err() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
     auth.logout_user();
-    Redirect::to("/")
+    auth.session.destroy();
+    Redirect::to("/").into_response()
+}
+
+#[derive(Deserialize)]
+struct LocaleForm {
+    locale: String,
+    csrf_token: String,
+}
+
+/// Remembers the chosen language for the session, and for the account when
+/// signed in, then returns to the page the switcher was used on.
+async fn set_locale(
+    auth: AuthLayer,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Form(form): Form<LocaleForm>,
+) -> impl IntoResponse {
+    if token.verify(&form.csrf_token).is_err() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if let Some(locale) = Locale::from_code(&form.locale) {
+        auth.session.set(LOCALE_KEY, locale.code());
+        // An admin in support mode must not change the user's settings
+        let impersonating = auth.session.get::<String>(IMPERSONATOR_KEY).is_some();
+        if let Some(user) = auth.current_user.as_ref().filter(|_| !impersonating) {
+            match state
+                .users_service
+                .set_preferences(user.id, locale.code(), &user.timezone)
+                .await
+            {
+                Ok(_) => auth.cache_clear_user(user.id.to_string()),
+                Err(e) => error!("{e:?}"),
+            }
+        }
+    }
+    // Only a local path is kept, so the redirect never leaves the site
+    let back = headers
+        .get(header::REFERER)
+        .and_then(|referer| referer.to_str().ok())
+        .and_then(|referer| referer.parse::<axum::http::Uri>().ok())
+        .and_then(|uri| uri.path_and_query().map(|p| p.to_string()))
+        .filter(|path| !path.starts_with("//"))
+        .unwrap_or_else(|| "/".to_string());
+    Redirect::to(&back).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Request, request::Builder},
+    };
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{App, configuration::Settings, smoke::csrf_token};
+
+    const PASSWORD: &str = "Router-Test-42!";
+
+    /// Stands in for a browser on `oneshot` requests: sends back the cookies
+    /// the site sets.
+    struct Browser {
+        router: Router,
+        cookies: BTreeMap<String, String>,
+    }
+
+    struct Page {
+        status: StatusCode,
+        location: Option<String>,
+        body: String,
+    }
+
+    impl Browser {
+        async fn new(pool: sqlx::PgPool) -> anyhow::Result<Self> {
+            let app = App::new(pool, None, &config::Config::default(), &Settings::default())?;
+            let (router, _scheduler) = app.router(app.session_store(false).await?).await?;
+            Ok(Self {
+                router,
+                cookies: BTreeMap::new(),
+            })
+        }
+
+        async fn send(&mut self, req: Builder, body: Body) -> anyhow::Result<Page> {
+            let cookie = self
+                .cookies
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("; ");
+            let res = self
+                .router
+                .clone()
+                .oneshot(req.header(header::COOKIE, cookie).body(body)?)
+                .await?;
+            for set_cookie in res.headers().get_all(header::SET_COOKIE) {
+                let pair = set_cookie.to_str()?.split(';').next().unwrap_or_default();
+                if let Some((name, value)) = pair.split_once('=') {
+                    self.cookies
+                        .insert(name.trim().to_string(), value.trim().to_string());
+                }
+            }
+            let status = res.status();
+            let location = res
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let body = to_bytes(res.into_body(), usize::MAX).await?;
+            Ok(Page {
+                status,
+                location,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+
+        async fn get(&mut self, path: &str) -> anyhow::Result<Page> {
+            self.send(Request::get(path), Body::empty()).await
+        }
+
+        async fn post_json(&mut self, path: &str, body: serde_json::Value) -> anyhow::Result<Page> {
+            let req = Request::post(path).header(header::CONTENT_TYPE, "application/json");
+            self.send(req, Body::from(body.to_string())).await
+        }
+
+        async fn csrf_token(&mut self, path: &str) -> anyhow::Result<String> {
+            let page = self.get(path).await?;
+            assert_eq!(page.status, StatusCode::OK);
+            csrf_token(&page.body).ok_or_else(|| anyhow::anyhow!("no csrf token on {path}"))
+        }
+
+        async fn sign_up(&mut self, username: &str, csrf_token: &str) -> anyhow::Result<Page> {
+            self.post_json(
+                "/signup",
+                json!({
+                    "username": username,
+                    "email": format!("{username}@example.com"),
+                    "password": PASSWORD,
+                    "confirm_password": PASSWORD,
+                    "csrf_token": csrf_token,
+                }),
+            )
+            .await
+        }
+    }
+
+    fn redirected_home(page: &Page) -> bool {
+        page.status.is_redirection() && page.location.as_deref() == Some("/")
+    }
+
+    #[sqlx::test]
+    async fn test_signup_then_login(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let mut browser = Browser::new(pool.clone()).await?;
+
+        let token = browser.csrf_token("/signup").await?;
+        let page = browser.sign_up("router_reader", &token).await?;
+        assert!(redirected_home(&page), "signup failed: {}", page.body);
+        let home = browser.get("/").await?;
+        assert!(home.body.contains("router_reader"));
+
+        let mut other = Browser::new(pool).await?;
+        let token = other.csrf_token("/login").await?;
+        let page = other
+            .post_json(
+                "/login",
+                json!({
+                    "email": "router_reader@example.com",
+                    "password": PASSWORD,
+                    "csrf_token": token,
+                }),
+            )
+            .await?;
+        assert!(redirected_home(&page), "login failed: {}", page.body);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_signup_rejects_forged_csrf_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let mut browser = Browser::new(pool.clone()).await?;
+
+        browser.csrf_token("/signup").await?;
+        let page = browser.sign_up("forged_reader", "forged").await?;
+        assert_eq!(page.status, StatusCode::OK);
+        assert!(page.body.contains(&Locale::default().t("invalid-csrf")));
+
+        let registered: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(registered, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_api_errors_carry_request_id(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let mut browser = Browser::new(pool).await?;
+
+        let page = browser
+            .post_json("/api/v1/batch", json!({ "operations": [] }))
+            .await?;
+        assert_eq!(page.status, StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = serde_json::from_str(&page.body)?;
+        assert_eq!(body["error"]["code"], "missing_api_key");
+        assert!(body["error"]["request_id"].is_string());
+
+        Ok(())
+    }
 }
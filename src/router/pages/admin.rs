@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use tracing::{error, instrument};
+
+use crate::{
+    AppState,
+    models::domain::{QueryTraffic, SystemInfo, TrafficReport},
+    router::PageContext,
+    storage::query_stats,
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/admin/system.html")]
+struct SystemPage {
+    title: String,
+    description: String,
+    info: SystemInfo,
+    ctx: PageContext,
+}
+
+#[instrument(name = "admin system page", skip_all)]
+pub async fn system(ctx: PageContext, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    if !user.is_admin {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match state.system_service.info().await {
+        Ok(info) => SystemPage {
+            title: "Система".to_string(),
+            description: "".to_string(),
+            info,
+            ctx,
+        }
+        .into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/admin/traffic.html")]
+struct TrafficPage {
+    title: String,
+    description: String,
+    report: TrafficReport,
+    /// Since startup, most total time first
+    queries: Vec<QueryTraffic>,
+    ctx: PageContext,
+}
+
+#[instrument(name = "admin traffic page", skip_all)]
+pub async fn traffic(ctx: PageContext, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    if !user.is_admin {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    TrafficPage {
+        title: ctx.t("traffic-title"),
+        description: "".to_string(),
+        report: state.traffic_stats.report(),
+        queries: query_stats::report(),
+        ctx,
+    }
+    .into_response()
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::ApiKey,
+    router::{AuthLayer, PageContext},
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/api_keys/page.html")]
+struct ApiKeysPage {
+    title: String,
+    description: String,
+    section: ApiKeysSection,
+    ctx: PageContext,
+}
+
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/settings/api_keys/section.html")]
+pub struct ApiKeysSection {
+    keys: Vec<ApiKey>,
+    created: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    /// Dates are shown in the viewer's timezone
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ApiKeyForm {
+    pub name: String,
+    pub scope_read: bool,
+    pub scope_write: bool,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "api keys page", skip_all)]
+pub async fn page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    let keys = state
+        .api_keys_service
+        .list(user.id)
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        ApiKeysPage {
+            title: "API-ключи".to_string(),
+            description: "".to_string(),
+            section: ApiKeysSection {
+                keys,
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "api keys create", skip_all)]
+pub async fn create(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<ApiKeyForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user else {
+        return Redirect::to("/login").into_response();
+    };
+    let mut section = ApiKeysSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx,
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some("Invalid CSRF token".into());
+    } else {
+        let scopes = [("read", form.scope_read), ("write", form.scope_write)]
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(scope, _)| scope.to_string())
+            .collect();
+        match state
+            .api_keys_service
+            .create(user.id, form.name, scopes)
+            .await
+        {
+            Ok(created) => section.created = Some(created.plaintext),
+            Err(e) => {
+                error!("{e:?}");
+                section.error = Some("Не удалось создать ключ: укажите название и права".into());
+            }
+        }
+    }
+    section.keys = state
+        .api_keys_service
+        .list(user.id)
+        .await
+        .unwrap_or_default();
+    section.into_response()
+}
+
+#[instrument(name = "api keys delete", skip_all)]
+pub async fn delete(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    ReadSignals(form): ReadSignals<ApiKeyForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user else {
+        return Redirect::to("/login").into_response();
+    };
+    let mut section = ApiKeysSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx,
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some("Invalid CSRF token".into());
+    } else if let Err(e) = state.api_keys_service.delete(id, user.id).await {
+        error!("{e:?}");
+        section.error = Some("Ключ не найден".into());
+    }
+    section.keys = state
+        .api_keys_service
+        .list(user.id)
+        .await
+        .unwrap_or_default();
+    section.into_response()
+}
@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::PendingCuratorApplication,
+    router::{AuthLayer, PageContext},
+    services::BadgesServiceError,
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/curator/page.html")]
+struct CuratorPage {
+    title: String,
+    description: String,
+    section: CuratorSection,
+    ctx: PageContext,
+}
+
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/settings/curator/section.html")]
+pub struct CuratorSection {
+    badge: Option<String>,
+    pending: bool,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct CuratorForm {
+    pub motivation: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "curator application page", skip_all)]
+pub async fn curator_page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    let pending = state
+        .badges_service
+        .pending_application(user.id)
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default()
+        .is_some();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        CuratorPage {
+            title: "Стать куратором".to_string(),
+            description: "".to_string(),
+            section: CuratorSection {
+                badge: user.badge.clone(),
+                pending,
+                csrf_token: authenticity_token,
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "curator application form", skip_all)]
+pub async fn curator_apply(
+    auth: AuthLayer,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<CuratorForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user else {
+        return Redirect::to("/login").into_response();
+    };
+    let mut section = CuratorSection {
+        badge: user.badge.clone(),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some("Invalid CSRF token".into());
+        return section.into_response();
+    }
+    match state.badges_service.apply(&user, &form.motivation).await {
+        Ok(_) => {
+            section.pending = true;
+            section.message = Some("Заявка отправлена".into());
+        }
+        Err(BadgesServiceError::WrongInput(err)) => section.error = Some(err),
+        Err(e) => {
+            error!("{e:?}");
+            section.error = Some("Не удалось отправить заявку".into());
+        }
+    }
+    section.into_response()
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/admin/badges/page.html")]
+struct BadgesPage {
+    title: String,
+    description: String,
+    section: BadgesSection,
+    ctx: PageContext,
+}
+
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/admin/badges/section.html")]
+pub struct BadgesSection {
+    applications: Vec<PendingCuratorApplication>,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    /// Dates are shown in the viewer's timezone
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct BadgeForm {
+    pub username: String,
+    pub badge: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "admin badges page", skip_all)]
+pub async fn admin_page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    if !ctx.user.as_ref().is_some_and(|u| u.is_admin) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let applications = state
+        .badges_service
+        .pending_applications()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        BadgesPage {
+            title: "Значки".to_string(),
+            description: "".to_string(),
+            section: BadgesSection {
+                applications,
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "admin badges assign", skip_all)]
+pub async fn admin_assign(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<BadgeForm>,
+) -> impl IntoResponse {
+    if !auth.current_user.as_ref().is_some_and(|u| u.is_admin) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let mut section = BadgesSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx,
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some("Invalid CSRF token".into());
+    } else {
+        let badge = Some(form.badge.as_str()).filter(|b| !b.is_empty());
+        match state.badges_service.assign(&form.username, badge).await {
+            Ok(user) => {
+                auth.cache_clear_user(user.id.to_string());
+                section.message = Some(format!("Значок {} обновлён", user.username))
+            }
+            Err(BadgesServiceError::NotFound) => {
+                section.error = Some("Пользователь не найден".into())
+            }
+            Err(e) => {
+                error!("{e:?}");
+                section.error = Some("Не удалось назначить значок".into());
+            }
+        }
+    }
+    section.applications = state
+        .badges_service
+        .pending_applications()
+        .await
+        .unwrap_or_default();
+    section.into_response()
+}
+
+#[instrument(name = "admin badges resolve", skip_all)]
+pub async fn admin_resolve(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path((id, decision)): Path<(Uuid, String)>,
+    ReadSignals(form): ReadSignals<BadgeForm>,
+) -> impl IntoResponse {
+    let Some(admin) = auth.current_user.clone().filter(|u| u.is_admin) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let approve = match decision.as_str() {
+        "approve" => true,
+        "reject" => false,
+        _ => return StatusCode::BAD_REQUEST.into_response(),
+    };
+    let mut section = BadgesSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx,
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some("Invalid CSRF token".into());
+    } else {
+        match state.badges_service.resolve(id, approve, admin.id).await {
+            Ok(application) if approve => {
+                auth.cache_clear_user(application.user_id.to_string());
+                section.message = Some("Заявка одобрена".into())
+            }
+            Ok(_) => section.message = Some("Заявка отклонена".into()),
+            Err(e) => {
+                error!("{e:?}");
+                section.error = Some("Заявка не найдена".into());
+            }
+        }
+    }
+    section.applications = state
+        .badges_service
+        .pending_applications()
+        .await
+        .unwrap_or_default();
+    section.into_response()
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::{BlockKind, User, UserBlock},
+    router::{AuthLayer, PageContext},
+    services::BlocksServiceError,
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/blocks/page.html")]
+struct BlocksPage {
+    title: String,
+    description: String,
+    section: BlocksSection,
+    ctx: PageContext,
+}
+
+/// Blocked and muted users with the form to add one, patched after every change.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/settings/blocks/section.html")]
+pub struct BlocksSection {
+    blocks: Vec<UserBlock>,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct BlockForm {
+    pub username: String,
+    /// [`BlockKind::code`]
+    pub kind: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "blocks page", skip_all)]
+pub async fn page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    let blocks = state
+        .blocks_service
+        .list(user)
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        BlocksPage {
+            title: ctx.t("blocks-title"),
+            description: "".to_string(),
+            section: BlocksSection {
+                blocks,
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "block user", skip_all)]
+pub async fn create(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<BlockForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        let kind = BlockKind::from_code(&form.kind).unwrap_or_default();
+        state
+            .blocks_service
+            .set(&user, &form.username, kind)
+            .await
+            .map(|_| {
+                ctx.t_args(
+                    &format!("block-{}-done", kind.code()),
+                    [("username", form.username.trim().to_string())],
+                )
+            })
+            .map_err(|e| error_message(e, &ctx))
+    };
+    blocks_section(&state, &ctx, &token, &user, result).await
+}
+
+#[instrument(name = "unblock user", skip_all)]
+pub async fn remove(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(target_id): Path<Uuid>,
+    ReadSignals(form): ReadSignals<BlockForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .blocks_service
+            .remove(&user, target_id)
+            .await
+            .map(|_| ctx.t("block-removed"))
+            .map_err(|e| error_message(e, &ctx))
+    };
+    blocks_section(&state, &ctx, &token, &user, result).await
+}
+
+fn error_message(e: BlocksServiceError, ctx: &PageContext) -> String {
+    if let BlocksServiceError::DatabaseError(err) = &e {
+        error!("{err}");
+    }
+    e.message(ctx.locale)
+}
+
+/// The refreshed section with the outcome of a change.
+async fn blocks_section(
+    state: &AppState,
+    ctx: &PageContext,
+    token: &CsrfToken,
+    user: &User,
+    result: Result<String, String>,
+) -> axum::response::Response {
+    let mut section = BlocksSection {
+        blocks: state
+            .blocks_service
+            .list(user)
+            .await
+            .inspect_err(|e| error!("{e:?}"))
+            .unwrap_or_default(),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    match result {
+        Ok(message) => section.message = Some(message),
+        Err(error) => section.error = Some(error),
+    }
+    section.into_response()
+}
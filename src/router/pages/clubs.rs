@@ -0,0 +1,387 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::{ClubMember, ClubMemberAction, ClubRole, ClubSummary, EventSummary},
+    router::{
+        AuthLayer, PageContext, PageMeta,
+        pages::{
+            events::EventCreateSection,
+            polls::{PollCreateSection, PollSection},
+        },
+    },
+    services::{ClubView, ClubsServiceError},
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/clubs/directory.html")]
+struct DirectoryPage {
+    title: String,
+    description: String,
+    clubs: Vec<ClubSummary>,
+    create: ClubCreateSection,
+    ctx: PageContext,
+}
+
+/// Form for starting a club, shown to signed-in visitors.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/clubs/create.html")]
+pub struct ClubCreateSection {
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ClubForm {
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "clubs directory", skip_all)]
+pub async fn directory(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let clubs = state
+        .clubs_service
+        .directory(ctx.user.as_ref())
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        DirectoryPage {
+            title: ctx.t("clubs-title"),
+            description: ctx.t("clubs-description"),
+            clubs,
+            create: ClubCreateSection {
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "create club", skip_all)]
+pub async fn create(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<ClubForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let mut section = ClubCreateSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some(ctx.t("invalid-csrf"));
+        return section.into_response();
+    }
+    match state
+        .clubs_service
+        .create(&user, &form.slug, &form.name, &form.description)
+        .await
+    {
+        Ok(club) => Redirect::to(&format!("/clubs/{}", club.slug)).into_response(),
+        Err(e) => {
+            if let ClubsServiceError::DatabaseError(err) = &e {
+                error!("{err}");
+            }
+            section.error = Some(e.message(ctx.locale));
+            section.into_response()
+        }
+    }
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/clubs/page.html")]
+struct ClubPage {
+    title: String,
+    description: String,
+    section: ClubMembersSection,
+    events: Vec<EventSummary>,
+    /// Shown to club admins and owners
+    event_create: Option<EventCreateSection>,
+    polls: Vec<PollSection>,
+    /// Shown to club admins and owners
+    poll_create: Option<PollCreateSection>,
+    ctx: PageContext,
+}
+
+/// A member with the buttons the viewer may use on them.
+#[derive(Debug, Clone)]
+pub struct ClubMemberRow {
+    pub member: ClubMember,
+    pub role_label: String,
+    pub can_promote: bool,
+    pub can_demote: bool,
+    pub can_remove: bool,
+}
+
+/// Membership controls and the member list, patched after every change.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/clubs/members.html")]
+pub struct ClubMembersSection {
+    slug: String,
+    members: Vec<ClubMemberRow>,
+    viewer_role: Option<ClubRole>,
+    signed_in: bool,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+impl ClubMembersSection {
+    fn new(view: ClubView, ctx: &PageContext, csrf_token: String) -> Self {
+        let viewer_id = ctx.user.as_ref().map(|u| u.id);
+        let viewer_role = view.viewer_role;
+        let members = view
+            .members
+            .into_iter()
+            .map(|member| {
+                let role = member.role();
+                let manageable = Some(member.user_id) != viewer_id
+                    && viewer_role.is_some_and(|viewer| viewer.can_manage(role));
+                let owner = viewer_role == Some(ClubRole::Owner);
+                ClubMemberRow {
+                    role_label: role.label(ctx.locale),
+                    can_promote: manageable && owner && role.promoted().is_some(),
+                    can_demote: manageable && owner && role.demoted().is_some(),
+                    can_remove: manageable,
+                    member,
+                }
+            })
+            .collect();
+        Self {
+            slug: view.club.slug,
+            members,
+            viewer_role,
+            signed_in: ctx.user.is_some() && !ctx.guest,
+            csrf_token,
+            ctx: ctx.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[instrument(name = "club page", skip_all)]
+pub async fn page(
+    mut ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let view = match state.clubs_service.view(&slug, ctx.user.as_ref()).await {
+        Ok(view) => view,
+        Err(ClubsServiceError::NotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // Slugs are case-insensitive; keep one address per club
+    if view.club.slug != slug {
+        return Redirect::permanent(&format!("/clubs/{}", view.club.slug)).into_response();
+    }
+    let events = state
+        .events_service
+        .club_events(view.club.id, ctx.user.as_ref())
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    let polls = state
+        .polls_service
+        .club_polls(view.club.id, ctx.user.as_ref().filter(|_| !ctx.guest))
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|poll| PollSection {
+            view: poll,
+            error: None,
+            csrf_token: authenticity_token.clone(),
+            ctx: ctx.clone(),
+        })
+        .collect();
+    let title = view.club.name.clone();
+    let description = view.club.description.clone();
+    ctx.meta = Some(PageMeta::website(title.clone(), description.clone()));
+    let event_create = view
+        .viewer_role
+        .filter(|role| *role >= ClubRole::Admin && !ctx.guest)
+        .map(|_| EventCreateSection {
+            club: Some(view.club.slug.clone()),
+            csrf_token: authenticity_token.clone(),
+            ctx: ctx.clone(),
+            ..Default::default()
+        });
+    let poll_create = view
+        .viewer_role
+        .filter(|role| *role >= ClubRole::Admin && !ctx.guest)
+        .map(|_| PollCreateSection {
+            club: view.club.slug.clone(),
+            csrf_token: authenticity_token.clone(),
+            ctx: ctx.clone(),
+            ..Default::default()
+        });
+    let section = ClubMembersSection::new(view, &ctx, authenticity_token);
+    (
+        token,
+        ClubPage {
+            title,
+            description,
+            section,
+            events,
+            event_create,
+            polls,
+            poll_create,
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ClubMembershipForm {
+    pub csrf_token: String,
+}
+
+#[instrument(name = "join club", skip_all)]
+pub async fn join(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+    ReadSignals(form): ReadSignals<ClubMembershipForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .clubs_service
+            .join(&user, &slug)
+            .await
+            .map(|_| ctx.t("club-joined"))
+            .map_err(|e| error_message(e, &ctx))
+    };
+    members_section(&state, &ctx, &token, &slug, result).await
+}
+
+#[instrument(name = "leave club", skip_all)]
+pub async fn leave(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+    ReadSignals(form): ReadSignals<ClubMembershipForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .clubs_service
+            .leave(&user, &slug)
+            .await
+            .map(|_| ctx.t("club-left"))
+            .map_err(|e| error_message(e, &ctx))
+    };
+    members_section(&state, &ctx, &token, &slug, result).await
+}
+
+#[instrument(name = "manage club member", skip_all)]
+pub async fn manage_member(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path((slug, member_id, action)): Path<(String, Uuid, String)>,
+    ReadSignals(form): ReadSignals<ClubMembershipForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let Some(action) = ClubMemberAction::from_code(&action) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .clubs_service
+            .manage_member(&user, &slug, member_id, action)
+            .await
+            .map(|_| ctx.t("club-member-updated"))
+            .map_err(|e| error_message(e, &ctx))
+    };
+    members_section(&state, &ctx, &token, &slug, result).await
+}
+
+fn error_message(e: ClubsServiceError, ctx: &PageContext) -> String {
+    if let ClubsServiceError::DatabaseError(err) = &e {
+        error!("{err}");
+    }
+    e.message(ctx.locale)
+}
+
+/// The refreshed members section with the outcome of a change.
+async fn members_section(
+    state: &AppState,
+    ctx: &PageContext,
+    token: &CsrfToken,
+    slug: &str,
+    result: Result<String, String>,
+) -> axum::response::Response {
+    let view = match state.clubs_service.view(slug, ctx.user.as_ref()).await {
+        Ok(view) => view,
+        Err(ClubsServiceError::NotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut section =
+        ClubMembersSection::new(view, ctx, token.authenticity_token().unwrap_or_default());
+    match result {
+        Ok(message) => section.message = Some(message),
+        Err(error) => section.error = Some(error),
+    }
+    section.into_response()
+}
@@ -0,0 +1,293 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::{
+        EventSummary, RsvpStatus, User, check_club_slug, check_venue_slug, parse_local_datetime,
+    },
+    router::{AuthLayer, PageContext, PageMeta},
+    services::{EventsServiceError, NewEvent},
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/events/upcoming.html")]
+struct UpcomingPage {
+    title: String,
+    description: String,
+    events: Vec<EventSummary>,
+    create: EventCreateSection,
+    ctx: PageContext,
+}
+
+/// Form for announcing an event. With `club` set the event is organized by
+/// that club, which only its admins and owners may do; with `place` it is
+/// held at that listed venue.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/events/create.html")]
+pub struct EventCreateSection {
+    pub club: Option<String>,
+    pub place: Option<String>,
+    pub error: Option<String>,
+    pub csrf_token: String,
+    pub ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct EventForm {
+    pub title: String,
+    pub description: String,
+    pub venue: String,
+    /// `datetime-local` value in the creator's timezone
+    pub starts_at: String,
+    /// Slug of the organizing club, empty for personal events
+    pub club: String,
+    /// Slug of the listed venue, empty for other places
+    pub place: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "upcoming events", skip_all)]
+pub async fn upcoming(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let events = state
+        .events_service
+        .upcoming(ctx.user.as_ref())
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        UpcomingPage {
+            title: ctx.t("events-title"),
+            description: ctx.t("events-description"),
+            events,
+            create: EventCreateSection {
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "create event", skip_all)]
+pub async fn create(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<EventForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let club = Some(form.club.trim()).filter(|s| !s.is_empty());
+    let place = Some(form.place.trim()).filter(|s| !s.is_empty());
+    let mut section = EventCreateSection {
+        // Echoed into the form's signals, so only well-formed slugs
+        club: club
+            .filter(|slug| check_club_slug(slug).is_ok())
+            .map(str::to_string),
+        place: place
+            .filter(|slug| check_venue_slug(slug).is_ok())
+            .map(str::to_string),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some(ctx.t("invalid-csrf"));
+        return section.into_response();
+    }
+    let event = NewEvent {
+        title: &form.title,
+        description: &form.description,
+        venue: &form.venue,
+        starts_at: parse_local_datetime(&form.starts_at, ctx.timezone()),
+        club,
+        place,
+    };
+    match state.events_service.create(&user, event).await {
+        Ok(event) => Redirect::to(&format!("/events/{}", event.id)).into_response(),
+        Err(e) => {
+            section.error = Some(error_message(e, &ctx));
+            section.into_response()
+        }
+    }
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/events/page.html")]
+struct EventPage {
+    title: String,
+    description: String,
+    starts_at: String,
+    venue: String,
+    club: Option<(String, String)>,
+    place: Option<(String, String)>,
+    section: EventRsvpSection,
+    ctx: PageContext,
+}
+
+/// RSVP buttons and counts, patched after every answer.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/events/rsvp.html")]
+pub struct EventRsvpSection {
+    id: Uuid,
+    going_count: i64,
+    interested_count: i64,
+    viewer_rsvp: Option<RsvpStatus>,
+    started: bool,
+    signed_in: bool,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+impl EventRsvpSection {
+    fn new(event: &EventSummary, ctx: &PageContext, csrf_token: String) -> Self {
+        Self {
+            id: event.id,
+            going_count: event.going_count,
+            interested_count: event.interested_count,
+            viewer_rsvp: event.viewer_rsvp(),
+            started: event.has_started(),
+            signed_in: ctx.user.is_some() && !ctx.guest,
+            csrf_token,
+            ctx: ctx.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[instrument(name = "event page", skip_all)]
+pub async fn page(
+    mut ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let event = match state.events_service.view(id, ctx.user.as_ref()).await {
+        Ok(event) => event,
+        Err(EventsServiceError::NotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    ctx.meta = Some(PageMeta::website(
+        event.title.clone(),
+        event.description.clone(),
+    ));
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    let section = EventRsvpSection::new(&event, &ctx, authenticity_token);
+    (
+        token,
+        EventPage {
+            starts_at: ctx.datetime(event.starts_at),
+            venue: event.venue,
+            club: event.club_slug.zip(event.club_name),
+            place: event.venue_slug.zip(event.venue_name),
+            title: event.title,
+            description: event.description,
+            section,
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct RsvpForm {
+    pub csrf_token: String,
+}
+
+/// `status` is a [`RsvpStatus::code`], or `none` to withdraw the answer.
+#[instrument(name = "rsvp to event", skip_all)]
+pub async fn rsvp(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path((id, status)): Path<(Uuid, String)>,
+    ReadSignals(form): ReadSignals<RsvpForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let status = match status.as_str() {
+        "none" => None,
+        code => match RsvpStatus::from_code(code) {
+            Some(status) => Some(status),
+            None => return StatusCode::NOT_FOUND.into_response(),
+        },
+    };
+    let csrf_token = token.authenticity_token().unwrap_or_default();
+    if token.verify(&form.csrf_token).is_err() {
+        let error = ctx.t("invalid-csrf");
+        return rsvp_section(&state, &ctx, &user, id, csrf_token, error).await;
+    }
+    match state.events_service.rsvp(&user, id, status).await {
+        Ok(event) => {
+            let mut section = EventRsvpSection::new(&event, &ctx, csrf_token);
+            section.message = Some(ctx.t("event-rsvp-saved"));
+            section.into_response()
+        }
+        Err(EventsServiceError::NotFound) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            let error = error_message(e, &ctx);
+            rsvp_section(&state, &ctx, &user, id, csrf_token, error).await
+        }
+    }
+}
+
+fn error_message(e: EventsServiceError, ctx: &PageContext) -> String {
+    if let EventsServiceError::DatabaseError(err) = &e {
+        error!("{err}");
+    }
+    e.message(ctx.locale)
+}
+
+/// The current RSVP section with the reason an answer was not recorded.
+async fn rsvp_section(
+    state: &AppState,
+    ctx: &PageContext,
+    user: &User,
+    id: Uuid,
+    csrf_token: String,
+    error: String,
+) -> axum::response::Response {
+    let event = match state.events_service.view(id, Some(user)).await {
+        Ok(event) => event,
+        Err(EventsServiceError::NotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut section = EventRsvpSection::new(&event, ctx, csrf_token);
+    section.error = Some(error);
+    section.into_response()
+}
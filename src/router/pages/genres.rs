@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::{GenreNode, User},
+    router::{AuthLayer, PageContext},
+    services::{GenresServiceError, NewGenre},
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/admin/genres/page.html")]
+struct AdminPage {
+    title: String,
+    description: String,
+    section: AdminSection,
+    ctx: PageContext,
+}
+
+/// The genre tree and the form adding or editing a genre, patched after
+/// every change.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/admin/genres/section.html")]
+pub struct AdminSection {
+    genres: Vec<GenreNode>,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct GenreForm {
+    /// The genre to edit; empty adds one
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    /// Empty for a top-level genre
+    pub parent_id: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "admin genres page", skip_all)]
+pub async fn admin_page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    if !ctx.user.as_ref().is_some_and(|u| u.is_admin) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let genres = state
+        .genres_service
+        .tree()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        AdminPage {
+            title: ctx.t("genres-admin-title"),
+            description: "".to_string(),
+            section: AdminSection {
+                genres,
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "admin genres save", skip_all)]
+pub async fn admin_save(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<GenreForm>,
+) -> impl IntoResponse {
+    let Some(admin) = auth.current_user.clone().filter(|u| u.is_admin) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        let genre = NewGenre {
+            slug: &form.slug,
+            name: &form.name,
+            parent_id: parse_id(&form.parent_id),
+        };
+        match parse_id(&form.id) {
+            Some(id) => state
+                .genres_service
+                .update(&admin, id, genre)
+                .await
+                .map(|genre| ctx.t_args("genre-saved", [("name", genre.name)])),
+            None => state
+                .genres_service
+                .create(&admin, genre)
+                .await
+                .map(|genre| ctx.t_args("genre-added", [("name", genre.name)])),
+        }
+        .map_err(|e| error_message(e, &ctx))
+    };
+    admin_section(&state, &ctx, &token, result).await
+}
+
+#[instrument(name = "admin genres delete", skip_all)]
+pub async fn admin_delete(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    ReadSignals(form): ReadSignals<GenreForm>,
+) -> impl IntoResponse {
+    let Some(admin) = auth.current_user.clone().filter(|u| u.is_admin) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let result = if token.verify(&form.csrf_token).is_err() {
+        Err(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .genres_service
+            .delete(&admin, id)
+            .await
+            .map(|_| ctx.t("genre-deleted"))
+            .map_err(|e| error_message(e, &ctx))
+    };
+    admin_section(&state, &ctx, &token, result).await
+}
+
+/// The refreshed admin section with the outcome of a change.
+async fn admin_section(
+    state: &AppState,
+    ctx: &PageContext,
+    token: &CsrfToken,
+    result: Result<String, String>,
+) -> axum::response::Response {
+    let mut section = AdminSection {
+        genres: state
+            .genres_service
+            .tree()
+            .await
+            .inspect_err(|e| error!("{e:?}"))
+            .unwrap_or_default(),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    match result {
+        Ok(message) => section.message = Some(message),
+        Err(error) => section.error = Some(error),
+    }
+    section.into_response()
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/interests/page.html")]
+struct InterestsPage {
+    title: String,
+    description: String,
+    section: InterestsSection,
+    ctx: PageContext,
+}
+
+/// Every genre with whether the user picked it, patched after every change.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/settings/interests/section.html")]
+pub struct InterestsSection {
+    genres: Vec<InterestOption>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Debug, Clone)]
+struct InterestOption {
+    node: GenreNode,
+    picked: bool,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct InterestForm {
+    pub csrf_token: String,
+}
+
+#[instrument(name = "interests page", skip_all)]
+pub async fn interests_page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    let section = interests_section(&state, &ctx, user, authenticity_token).await;
+    (
+        token,
+        InterestsPage {
+            title: ctx.t("interests-title"),
+            description: "".to_string(),
+            section,
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+/// `/settings/interests/{id}/{add|remove}`
+#[instrument(name = "interests set", skip_all)]
+pub async fn set_interest(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path((genre_id, action)): Path<(Uuid, String)>,
+    ReadSignals(form): ReadSignals<InterestForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let interested = match action.as_str() {
+        "add" => true,
+        "remove" => false,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let error = if token.verify(&form.csrf_token).is_err() {
+        Some(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .genres_service
+            .set_interest(&user, genre_id, interested)
+            .await
+            .err()
+            .map(|e| error_message(e, &ctx))
+    };
+    let mut section = interests_section(
+        &state,
+        &ctx,
+        &user,
+        token.authenticity_token().unwrap_or_default(),
+    )
+    .await;
+    section.error = error;
+    section.into_response()
+}
+
+async fn interests_section(
+    state: &AppState,
+    ctx: &PageContext,
+    user: &User,
+    csrf_token: String,
+) -> InterestsSection {
+    let interests = state
+        .genres_service
+        .interests(user)
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let genres = state
+        .genres_service
+        .tree()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|node| InterestOption {
+            picked: interests.contains(&node.genre.id),
+            node,
+        })
+        .collect();
+    InterestsSection {
+        genres,
+        error: None,
+        csrf_token,
+        ctx: ctx.clone(),
+    }
+}
+
+fn parse_id(input: &str) -> Option<Uuid> {
+    input.trim().parse().ok()
+}
+
+fn error_message(e: GenresServiceError, ctx: &PageContext) -> String {
+    if let GenresServiceError::DatabaseError(err) = &e {
+        error!("{err}");
+    }
+    e.message(ctx.locale)
+}
@@ -1,22 +1,34 @@
 use askama::Template;
 use askama_web::WebTemplate;
 use axum::response::IntoResponse;
+use serde_json::json;
 
-use crate::{models::User, router::AuthLayer};
+use crate::{
+    assets::asset,
+    router::{PageContext, PageMeta},
+};
 
 #[derive(Template, WebTemplate)]
 #[template(path = "pages/home/page.html")]
 struct Home<'a> {
     title: &'a str,
     description: &'a str,
-    user: Option<User>,
+    ctx: PageContext,
 }
 
-pub async fn page(auth: AuthLayer) -> impl IntoResponse {
-    let current = auth.current_user;
+pub async fn page(mut ctx: PageContext) -> impl IntoResponse {
+    ctx.meta = Some(
+        PageMeta::website("КультурЛист", ctx.t("tagline"))
+            .with_image(asset("icons/logo.svg"))
+            .with_json_ld(json!({
+                "@context": "https://schema.org",
+                "@type": "WebSite",
+                "name": "КультурЛист",
+            })),
+    );
     Home {
         title: "КультурЛист | Главная",
         description: "Это главная страница",
-        user: current,
+        ctx,
     }
 }
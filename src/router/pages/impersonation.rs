@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{
+    Form,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::{
+    AppState,
+    router::{AuthLayer, IMPERSONATOR_KEY},
+    services::UsernameServiceError,
+};
+
+#[derive(Deserialize, Debug)]
+pub struct ImpersonateForm {
+    pub username: String,
+    pub csrf_token: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StopForm {
+    pub csrf_token: String,
+}
+
+/// Signs an admin in as another user, remembering the admin in the session.
+#[instrument(name = "impersonation start", skip_all)]
+pub async fn start(
+    auth: AuthLayer,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Form(form): Form<ImpersonateForm>,
+) -> impl IntoResponse {
+    let Some(admin) = auth.current_user.clone().filter(|u| u.is_admin) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    if token.verify(&form.csrf_token).is_err()
+        || auth.session.get::<String>(IMPERSONATOR_KEY).is_some()
+    {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let target = match state.username_service.resolve(form.username.trim()).await {
+        Ok(resolved) => resolved.user,
+        Err(UsernameServiceError::NotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // Admin accounts keep their privileges out of reach of other admins;
+    // banned and anonymized accounts cannot be signed in to at all
+    if target.is_admin || target.is_banned() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    info!(target: "audit", admin_id = %admin.id, user_id = %target.id, "impersonation started");
+    auth.session.set(IMPERSONATOR_KEY, admin.id.to_string());
+    auth.login_user(target.id.to_string());
+    Redirect::to("/").into_response()
+}
+
+/// Returns to the admin's own account.
+#[instrument(name = "impersonation stop", skip_all)]
+pub async fn stop(
+    auth: AuthLayer,
+    token: CsrfToken,
+    Form(form): Form<StopForm>,
+) -> impl IntoResponse {
+    if token.verify(&form.csrf_token).is_err() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let Some(admin_id) = auth.session.get::<String>(IMPERSONATOR_KEY) else {
+        return Redirect::to("/").into_response();
+    };
+    let user_id = auth.current_user.as_ref().map(|u| u.id.to_string());
+    info!(target: "audit", admin_id = %admin_id, user_id = ?user_id, "impersonation stopped");
+    auth.session.remove(IMPERSONATOR_KEY);
+    auth.login_user(admin_id);
+    Redirect::to("/admin/system").into_response()
+}
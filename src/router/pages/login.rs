@@ -7,16 +7,22 @@ use axum::{
     response::{IntoResponse, Redirect},
 };
 use axum_csrf::CsrfToken;
+use chrono::{TimeDelta, Utc};
 use datastar::axum::ReadSignals;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::{
     AppState,
-    models::{SignInRequest, User},
-    router::AuthLayer,
+    extractors::Validated,
+    i18n::Locale,
+    models::api::SignInRequest,
+    router::{AuthLayer, GUEST_UNTIL_KEY, PageContext},
+    validation::{password_policy, validate_password},
 };
 
+const GUEST_SESSION_TTL: TimeDelta = TimeDelta::minutes(15);
+
 #[derive(Template, WebTemplate, Default)]
 #[template(path = "pages/login/page.html")]
 struct Login {
@@ -27,22 +33,23 @@ struct Login {
     password: String,
     password_error: Option<String>,
     csrf_token: String,
-    user: Option<User>,
+    guest: bool,
+    remember: bool,
+    ctx: PageContext,
 }
 
-pub async fn page(auth: AuthLayer, token: CsrfToken) -> impl IntoResponse {
-    let user = auth.current_user;
-    if user.as_ref().is_some() {
+pub async fn page(ctx: PageContext, token: CsrfToken) -> impl IntoResponse {
+    if ctx.user.as_ref().is_some() {
         return Redirect::to("/").into_response();
     }
     let authenticity_token = token.authenticity_token().unwrap_or_default();
     (
         token,
         Login {
-            title: "Войти".to_string(),
+            title: ctx.t("sign-in"),
             description: "".to_string(),
             csrf_token: authenticity_token,
-            user,
+            ctx,
             ..Default::default()
         },
     )
@@ -55,55 +62,38 @@ pub struct LoginForm {
     #[validate(email)]
     pub email: String,
     pub email_error: Option<String>,
-    #[validate(length(min = 8, max = 64), custom(function = "validate_password"))]
+    #[validate(custom(function = "validate_password"))]
     pub password: String,
     pub password_error: Option<String>,
     pub csrf_token: String,
-}
-
-fn validate_password(password: &str) -> Result<(), validator::ValidationError> {
-    let mut errors = Vec::new();
-
-    if !password.chars().any(|c| c.is_uppercase()) {
-        errors.push("uppercase letter required");
-    }
-    if !password.chars().any(|c| c.is_lowercase()) {
-        errors.push("lowercase letter required");
-    }
-    if !password.chars().any(|c| c.is_ascii_digit()) {
-        errors.push("digit required");
-    }
-    if !password
-        .chars()
-        .any(|c| "!@#$%^&*()_+-=[]{}|;:,.<>?".contains(c))
-    {
-        errors.push("special character required");
-    }
-
-    if !errors.is_empty() {
-        let error_message = errors.join(", ");
-        let mut error = validator::ValidationError::new("password_requirements");
-        error.message = Some(format!("Password requirements not met: {error_message}").into());
-        return Err(error);
-    }
-
-    Ok(())
+    /// Shared-device login: short-lived session that is never remembered
+    #[serde(default)]
+    pub guest: bool,
+    /// Keep the session across browser restarts, see `[session]` in config
+    #[serde(default)]
+    pub remember: bool,
+    #[serde(skip)]
+    pub locale: Locale,
 }
 
 #[axum::debug_handler]
 pub async fn login_form(
     auth: AuthLayer,
     token: CsrfToken,
+    locale: Locale,
     State(state): State<Arc<AppState>>,
-    ReadSignals(form): ReadSignals<LoginForm>,
+    Validated(form): Validated<LoginForm>,
 ) -> impl IntoResponse {
     if token.verify(&form.csrf_token).is_err() {
         return LoginForm {
             email: form.email,
-            email_error: Some("Invalid CSRF token".to_string()),
+            email_error: Some(locale.t("invalid-csrf")),
             password: form.password,
             password_error: None,
             csrf_token: token.authenticity_token().unwrap_or_default(),
+            guest: form.guest,
+            remember: form.remember,
+            locale,
         }
         .into_response();
     }
@@ -122,6 +112,12 @@ pub async fn login_form(
         {
             Ok(res) => {
                 auth.login_user(res.user.id.to_string());
+                // Guests never get the long-lived session
+                auth.remember_user(form.remember && !form.guest);
+                if form.guest {
+                    let until = Utc::now() + GUEST_SESSION_TTL;
+                    auth.session.set(GUEST_UNTIL_KEY, until.timestamp());
+                }
                 Redirect::to("/").into_response()
             }
             Err(e) => match e {
@@ -131,6 +127,9 @@ pub async fn login_form(
                     password: form.password,
                     password_error: Some(err),
                     csrf_token: token.authenticity_token().unwrap_or_default(),
+                    guest: form.guest,
+                    remember: form.remember,
+                    locale,
                 }
                 .into_response(),
                 _ => LoginForm {
@@ -139,6 +138,9 @@ pub async fn login_form(
                     password: form.password,
                     password_error: Some(e.to_string()),
                     csrf_token: token.authenticity_token().unwrap_or_default(),
+                    guest: form.guest,
+                    remember: form.remember,
+                    locale,
                 }
                 .into_response(),
             },
@@ -150,12 +152,16 @@ pub async fn login_form(
             password: form.password,
             password_error: form.password_error,
             csrf_token: token.authenticity_token().unwrap_or_default(),
+            guest: form.guest,
+            remember: form.remember,
+            locale,
         }
         .into_response()
     }
 }
 pub async fn login_form_validate(
     token: CsrfToken,
+    locale: Locale,
     ReadSignals(data): ReadSignals<LoginForm>,
 ) -> impl IntoResponse {
     match data.validate() {
@@ -166,13 +172,16 @@ pub async fn login_form_validate(
             password_error: if data
                 .password_error
                 .as_ref()
-                .is_some_and(|e| e.contains("Требования"))
+                .is_some_and(|e| *e == password_policy().describe(locale))
             {
                 None
             } else {
                 data.password_error
             },
             csrf_token: token.authenticity_token().unwrap_or_default(),
+            guest: data.guest,
+            remember: data.remember,
+            locale,
         },
         Err(err) => {
             let errors = err.into_errors();
@@ -183,13 +192,13 @@ pub async fn login_form_validate(
                     if let validator::ValidationErrorsKind::Field(_) = err
                         && !data.email.is_empty()
                     {
-                        email_error = Some("Введите корректный email".into())
+                        email_error = Some(locale.t("invalid-email"))
                     }
                 } else if field == "password"
                     && let validator::ValidationErrorsKind::Field(_) = err
                     && !data.password.is_empty()
                 {
-                    password_error = Some("Требования к паролю: Заглавная буква, цифра, спецсимвол, длина от 8 до 64 символов".into())
+                    password_error = Some(password_policy().describe(locale))
                 }
             }
             LoginForm {
@@ -198,6 +207,9 @@ pub async fn login_form_validate(
                 password: data.password,
                 password_error,
                 csrf_token: token.authenticity_token().unwrap_or_default(),
+                guest: data.guest,
+                remember: data.remember,
+                locale,
             }
         }
     }
@@ -1,3 +1,19 @@
+pub mod admin;
+pub mod api_keys;
+pub mod badges;
+pub mod blocks;
+pub mod clubs;
+pub mod events;
+pub mod genres;
 pub mod home;
+pub mod impersonation;
 pub mod login;
+pub mod moderation;
+pub mod polls;
+pub mod preview;
+pub mod search;
+pub mod settings;
 pub mod signup;
+pub mod theme;
+pub mod users;
+pub mod venues;
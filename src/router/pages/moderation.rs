@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::{ModerationAction, ModerationQueueItem, ReportTarget},
+    router::{AuthLayer, PageContext},
+    services::ModerationServiceError,
+};
+
+/// Report button with its form, placed next to reportable content.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "components/report.html")]
+pub struct ReportSection {
+    target_kind: String,
+    target_id: Uuid,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ReportForm {
+    pub target_kind: String,
+    pub target_id: String,
+    pub reason: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "report content", skip_all)]
+pub async fn report(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<ReportForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let target = ReportTarget::from_code(&form.target_kind);
+    let target_id = form.target_id.parse::<Uuid>().ok();
+    let mut section = ReportSection {
+        target_kind: form.target_kind.clone(),
+        target_id: target_id.unwrap_or_default(),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some(ctx.t("invalid-csrf"));
+        return section.into_response();
+    }
+    let (Some(target), Some(target_id)) = (target, target_id) else {
+        section.error = Some(ModerationServiceError::NotFound.message(ctx.locale));
+        return section.into_response();
+    };
+    match state
+        .moderation_service
+        .report(&user, target, target_id, &form.reason)
+        .await
+    {
+        Ok(_) => section.message = Some(ctx.t("report-sent")),
+        Err(e) => {
+            if let ModerationServiceError::DatabaseError(err) = &e {
+                error!("{err}");
+            }
+            section.error = Some(e.message(ctx.locale));
+        }
+    }
+    section.into_response()
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/admin/moderation/page.html")]
+struct ModerationPage {
+    title: String,
+    description: String,
+    section: ModerationSection,
+    ctx: PageContext,
+}
+
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/admin/moderation/section.html")]
+pub struct ModerationSection {
+    queue: Vec<ModerationQueueItem>,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ModerationForm {
+    pub csrf_token: String,
+}
+
+#[instrument(name = "admin moderation page", skip_all)]
+pub async fn admin_page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    if !ctx.user.as_ref().is_some_and(|u| u.is_admin) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let queue = state
+        .moderation_service
+        .queue()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        ModerationPage {
+            title: ctx.t("moderation-title"),
+            description: "".to_string(),
+            section: ModerationSection {
+                queue,
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "admin moderation resolve", skip_all)]
+pub async fn admin_resolve(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path((kind, id, action)): Path<(String, Uuid, String)>,
+    ReadSignals(form): ReadSignals<ModerationForm>,
+) -> impl IntoResponse {
+    let Some(admin) = auth.current_user.clone().filter(|u| u.is_admin) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let (Some(target), Some(action)) = (
+        ReportTarget::from_code(&kind),
+        ModerationAction::from_code(&action),
+    ) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut section = ModerationSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some(ctx.t("invalid-csrf"));
+    } else {
+        match state
+            .moderation_service
+            .resolve(&admin, target, id, action)
+            .await
+        {
+            Ok(banned) => {
+                if let Some(banned) = banned {
+                    auth.cache_clear_user(banned.id.to_string());
+                }
+                section.message = Some(ctx.t("moderation-resolved"));
+            }
+            Err(e) => {
+                if let ModerationServiceError::DatabaseError(err) = &e {
+                    error!("{err}");
+                }
+                section.error = Some(e.message(ctx.locale));
+            }
+        }
+    }
+    section.queue = state.moderation_service.queue().await.unwrap_or_default();
+    section.into_response()
+}
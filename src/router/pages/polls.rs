@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    models::domain::{PollResults, PollView, parse_local_datetime},
+    router::{AuthLayer, PageContext},
+    services::{NewPoll, PollsServiceError},
+};
+
+/// The form club admins create polls with.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/polls/create.html")]
+pub struct PollCreateSection {
+    pub club: String,
+    pub error: Option<String>,
+    pub csrf_token: String,
+    pub ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PollForm {
+    pub question: String,
+    /// One option per line
+    pub options: String,
+    pub multiple: bool,
+    /// A [`PollResults::code`]
+    pub results: String,
+    /// `datetime-local` value; empty keeps the poll open
+    pub closes_at: String,
+    pub csrf_token: String,
+}
+
+/// `/clubs/{slug}/polls`
+#[instrument(name = "create poll", skip_all)]
+pub async fn create(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+    ReadSignals(form): ReadSignals<PollForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let mut section = PollCreateSection {
+        club: slug.clone(),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some(ctx.t("invalid-csrf"));
+        return section.into_response();
+    }
+    let closes_at = match form.closes_at.trim() {
+        "" => None,
+        input => match parse_local_datetime(input, ctx.timezone()) {
+            Some(closes_at) => Some(closes_at),
+            None => {
+                section.error = Some(PollsServiceError::InvalidDeadline.message(ctx.locale));
+                return section.into_response();
+            }
+        },
+    };
+    let poll = NewPoll {
+        question: &form.question,
+        options: &form.options,
+        multiple: form.multiple,
+        results: PollResults::from_code(&form.results).unwrap_or_default(),
+        closes_at,
+    };
+    match state.polls_service.create(&user, &slug, poll).await {
+        Ok(_) => Redirect::to(&format!("/clubs/{slug}")).into_response(),
+        Err(e) => {
+            section.error = Some(error_message(e, &ctx));
+            section.into_response()
+        }
+    }
+}
+
+/// One poll with the viewer's choices, patched after their votes and
+/// whenever someone else votes while the page is open.
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/polls/poll.html")]
+pub struct PollSection {
+    pub view: PollView,
+    pub error: Option<String>,
+    pub csrf_token: String,
+    pub ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct VoteForm {
+    pub csrf_token: String,
+}
+
+/// `/polls/{id}/vote/{option_id}`
+#[instrument(name = "vote in poll", skip_all)]
+pub async fn vote(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path((id, option_id)): Path<(Uuid, Uuid)>,
+    ReadSignals(form): ReadSignals<VoteForm>,
+) -> impl IntoResponse {
+    let Some(user) = auth.current_user.filter(|_| !ctx.guest) else {
+        return Redirect::to("/login").into_response();
+    };
+    let error = if token.verify(&form.csrf_token).is_err() {
+        Some(ctx.t("invalid-csrf"))
+    } else {
+        state
+            .polls_service
+            .vote(&user, id, option_id)
+            .await
+            .err()
+            .map(|e| error_message(e, &ctx))
+    };
+    let csrf_token = token.authenticity_token().unwrap_or_default();
+    match poll_section(&state, &ctx, csrf_token, id).await {
+        Ok(mut section) => {
+            section.error = error;
+            section.into_response()
+        }
+        Err(status) => status.into_response(),
+    }
+}
+
+/// `/clubs/{slug}/polls/live`: keeps the counts of the club's polls
+/// current while its page is open by patching a poll after every vote.
+#[instrument(name = "club polls live", skip_all)]
+pub async fn live(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    use {
+        asynk_strim::{Yielder, stream_fn},
+        axum::response::{
+            Sse,
+            sse::{Event, KeepAlive},
+        },
+        core::convert::Infallible,
+        datastar::prelude::PatchElements,
+        tokio::sync::broadcast::error::RecvError,
+    };
+    let club = match state.polls_service.club(&slug).await {
+        Ok(club) => club,
+        Err(PollsServiceError::ClubNotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let csrf_token = token.authenticity_token().unwrap_or_default();
+    let mut updates = state.polls_service.subscribe();
+    Sse::new(stream_fn(
+        move |mut yielder: Yielder<Result<Event, Infallible>>| async move {
+            loop {
+                let polls = match updates.recv().await {
+                    Ok(update) if update.club_id != club.id => continue,
+                    Ok(update) => vec![update.poll_id],
+                    // Missed votes may have been in any of the club's polls
+                    Err(RecvError::Lagged(_)) => state
+                        .polls_service
+                        .club_polls(club.id, None)
+                        .await
+                        .inspect_err(|e| error!("{e:?}"))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|view| view.poll.id)
+                        .collect(),
+                    Err(RecvError::Closed) => break,
+                };
+                for id in polls {
+                    let Ok(section) = poll_section(&state, &ctx, csrf_token.clone(), id).await
+                    else {
+                        continue;
+                    };
+                    match section.render() {
+                        Ok(html) => {
+                            let patch = PatchElements::new(html);
+                            yielder
+                                .yield_item(Ok(patch.write_as_axum_sse_event()))
+                                .await;
+                        }
+                        Err(e) => error!("{e:?}"),
+                    }
+                }
+            }
+        },
+    ))
+    .keep_alive(KeepAlive::default())
+    .into_response()
+}
+
+/// The poll as the viewer sees it now.
+async fn poll_section(
+    state: &AppState,
+    ctx: &PageContext,
+    csrf_token: String,
+    id: Uuid,
+) -> Result<PollSection, StatusCode> {
+    let viewer = ctx.user.as_ref().filter(|_| !ctx.guest);
+    match state.polls_service.view(id, viewer).await {
+        Ok(view) => Ok(PollSection {
+            view,
+            error: None,
+            csrf_token,
+            ctx: ctx.clone(),
+        }),
+        Err(PollsServiceError::NotFound) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("{e:?}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn error_message(e: PollsServiceError, ctx: &PageContext) -> String {
+    if let PollsServiceError::DatabaseError(err) = &e {
+        error!("{err}");
+    }
+    e.message(ctx.locale)
+}
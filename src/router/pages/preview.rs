@@ -0,0 +1,38 @@
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{extract::Path, http::StatusCode, response::IntoResponse};
+use datastar::axum::ReadSignals;
+use tracing::instrument;
+
+use crate::markdown;
+
+/// Signals whose markdown can be previewed, each patched into `#<signal>-preview`.
+const PREVIEW_SIGNALS: [&str; 2] = ["bio", "body"];
+
+#[derive(Template, WebTemplate)]
+#[template(path = "components/markdown_preview.html")]
+struct MarkdownPreview {
+    signal: String,
+    html: String,
+}
+
+/// Renders the markdown in the `signal` signal the way it will be saved,
+/// for editors showing a live preview next to the textarea.
+#[instrument(name = "markdown preview", skip_all)]
+pub async fn markdown(
+    Path(signal): Path<String>,
+    ReadSignals(signals): ReadSignals<serde_json::Value>,
+) -> impl IntoResponse {
+    if !PREVIEW_SIGNALS.contains(&signal.as_str()) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let source = signals
+        .get(&signal)
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+    MarkdownPreview {
+        html: markdown::render(source),
+        signal,
+    }
+    .into_response()
+}
@@ -0,0 +1,104 @@
+use std::{sync::Arc, time::Duration};
+
+use askama::Template;
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+
+use crate::{
+    AppState,
+    models::{api::PaletteResponse, domain::SearchSuggestions},
+    router::PageContext,
+};
+
+/// Datastar cancels the previous request when the query changes, so
+/// waiting before the lookup drops keystrokes that were superseded.
+const SUGGEST_DEBOUNCE: Duration = Duration::from_millis(150);
+
+#[derive(Template)]
+#[template(path = "components/search_suggestions.html")]
+struct SuggestionsTemplate {
+    query: String,
+    suggestions: SearchSuggestions,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct SuggestSignals {
+    pub search_query: String,
+}
+
+/// `/search/suggest`: patches `#search-suggestions` with grouped results for
+/// the header search box.
+#[instrument(name = "search suggest", skip_all)]
+pub async fn suggest(
+    ctx: PageContext,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(signals): ReadSignals<SuggestSignals>,
+) -> impl IntoResponse {
+    use {
+        asynk_strim::{Yielder, stream_fn},
+        axum::response::{Sse, sse::Event},
+        core::convert::Infallible,
+        datastar::prelude::PatchElements,
+    };
+    Sse::new(stream_fn(
+        move |mut yielder: Yielder<Result<Event, Infallible>>| async move {
+            tokio::time::sleep(SUGGEST_DEBOUNCE).await;
+            let suggestions = state
+                .search_service
+                .suggest(&signals.search_query, ctx.user.as_ref())
+                .await
+                .inspect_err(|e| error!("{e:?}"))
+                .unwrap_or_default();
+            let template = SuggestionsTemplate {
+                query: signals.search_query.trim().to_string(),
+                suggestions,
+                ctx,
+            };
+            match template.render() {
+                Ok(html) => {
+                    let patch = PatchElements::new(html);
+                    yielder
+                        .yield_item(Ok(patch.write_as_axum_sse_event()))
+                        .await;
+                }
+                Err(e) => error!("{e:?}"),
+            }
+        },
+    ))
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PaletteQuery {
+    pub q: String,
+}
+
+/// `/_palette`: the viewer's quick actions and people matching `q`, as JSON
+/// for the client-side command palette.
+#[instrument(name = "command palette", skip_all)]
+pub async fn palette(
+    ctx: PageContext,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PaletteQuery>,
+) -> impl IntoResponse {
+    match state
+        .search_service
+        .palette(&query.q, ctx.user.as_ref(), ctx.guest, ctx.locale)
+        .await
+    {
+        Ok(palette) => Json(PaletteResponse::from(palette)).into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
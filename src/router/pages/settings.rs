@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    Form,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use chrono_tz::{TZ_VARIANTS, Tz};
+use serde::Deserialize;
+use tracing::{error, instrument};
+
+use crate::{
+    AppState,
+    i18n::{LOCALE_KEY, Locale},
+    router::{AuthLayer, PageContext},
+    services::{DataExportsServiceError, EmailChangeServiceError, UsersServiceError},
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/export.html")]
+struct ExportPage {
+    title: String,
+    description: String,
+    /// An export waits for the background job
+    pending: bool,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+impl ExportPage {
+    fn new(ctx: PageContext, csrf_token: String) -> Self {
+        Self {
+            title: ctx.t("export-title"),
+            description: "".to_string(),
+            pending: false,
+            error: None,
+            csrf_token,
+            ctx,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ExportForm {
+    pub csrf_token: String,
+}
+
+#[instrument(name = "settings export page", skip_all)]
+pub async fn export(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.clone() else {
+        return Redirect::to("/login").into_response();
+    };
+    let mut page = ExportPage::new(ctx, token.authenticity_token().unwrap_or_default());
+    page.pending = state
+        .data_exports_service
+        .pending(&user)
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default()
+        .is_some();
+    (token, page).into_response()
+}
+
+/// Queues the export; the link arrives by email once it is built.
+#[instrument(name = "settings export request", skip_all)]
+pub async fn request_export(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Form(form): Form<ExportForm>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.clone() else {
+        return Redirect::to("/login").into_response();
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match state.data_exports_service.request(&user).await {
+        Ok(()) => Redirect::to("/settings/export").into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            let mut page =
+                ExportPage::new(ctx.clone(), token.authenticity_token().unwrap_or_default());
+            page.error = Some(e.message(ctx.locale));
+            page.into_response()
+        }
+    }
+}
+
+/// Target of the emailed link; the archive goes only to its owner's session.
+#[instrument(name = "settings export download", skip_all)]
+pub async fn download_export(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(link): Path<String>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.clone() else {
+        return Redirect::to("/login").into_response();
+    };
+    match state.data_exports_service.download(&user, &link).await {
+        Ok(archive) => (
+            [
+                (header::CONTENT_TYPE, "application/zip"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"culturelist-export.zip\"",
+                ),
+            ],
+            archive,
+        )
+            .into_response(),
+        Err(e) => {
+            let status = match &e {
+                DataExportsServiceError::NotFound => StatusCode::NOT_FOUND,
+                DataExportsServiceError::DatabaseError(err) => {
+                    error!("{err}");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            };
+            let mut page =
+                ExportPage::new(ctx.clone(), token.authenticity_token().unwrap_or_default());
+            page.error = Some(e.message(ctx.locale));
+            (status, page).into_response()
+        }
+    }
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/email_confirm.html")]
+struct EmailConfirmPage {
+    title: String,
+    description: String,
+    email: String,
+    error: Option<String>,
+    ctx: PageContext,
+}
+
+/// Target of the link mailed to the new address; the token itself is the
+/// proof, so no session or CSRF token is required.
+#[instrument(name = "settings email confirm", skip_all)]
+pub async fn confirm_email(
+    ctx: PageContext,
+    auth: AuthLayer,
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    let (email, error) = match state.email_change_service.confirm(&token).await {
+        Ok(user) => {
+            auth.cache_clear_user(user.id.to_string());
+            (user.email, None)
+        }
+        Err(e) => {
+            if let EmailChangeServiceError::DatabaseError(err) = &e {
+                error!("{err}");
+            }
+            (String::new(), Some(e.message(ctx.locale)))
+        }
+    };
+    EmailConfirmPage {
+        title: ctx.t("email-change-title"),
+        description: "".to_string(),
+        email,
+        error,
+        ctx,
+    }
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/settings/preferences.html")]
+struct PreferencesPage {
+    title: String,
+    description: String,
+    /// Stored locale code, empty when negotiated from the browser
+    locale: String,
+    timezone: String,
+    timezones: &'static [Tz],
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+impl PreferencesPage {
+    fn new(ctx: PageContext, csrf_token: String) -> Self {
+        let user = ctx.user.clone().unwrap_or_default();
+        Self {
+            title: ctx.t("preferences-title"),
+            description: "".to_string(),
+            locale: user.locale.unwrap_or_default(),
+            timezone: user.timezone,
+            timezones: &TZ_VARIANTS,
+            error: None,
+            csrf_token,
+            ctx,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PreferencesForm {
+    #[serde(default)]
+    pub locale: String,
+    pub timezone: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "settings preferences page", skip_all)]
+pub async fn preferences(ctx: PageContext, token: CsrfToken) -> impl IntoResponse {
+    if ctx.user.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (token, PreferencesPage::new(ctx, authenticity_token)).into_response()
+}
+
+#[instrument(name = "settings preferences save", skip_all)]
+pub async fn save_preferences(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Form(form): Form<PreferencesForm>,
+) -> impl IntoResponse {
+    let Some(user) = ctx.user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match state
+        .users_service
+        .set_preferences(user.id, &form.locale, &form.timezone)
+        .await
+    {
+        Ok(updated) => {
+            auth.cache_clear_user(updated.id.to_string());
+            // The session choice would otherwise outlive going back to "auto"
+            match updated.locale.as_deref().and_then(Locale::from_code) {
+                Some(locale) => auth.session.set(LOCALE_KEY, locale.code()),
+                None => auth.session.remove(LOCALE_KEY),
+            }
+            Redirect::to("/settings/preferences").into_response()
+        }
+        Err(e) => {
+            if !matches!(e, UsersServiceError::WrongCredentials(_)) {
+                error!("{e:?}");
+            }
+            let mut page =
+                PreferencesPage::new(ctx.clone(), token.authenticity_token().unwrap_or_default());
+            page.locale = form.locale;
+            page.timezone = form.timezone;
+            page.error = Some(ctx.t("preferences-invalid"));
+            page.into_response()
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use askama::Template;
 use askama_web::WebTemplate;
@@ -14,8 +14,15 @@ use validator::Validate;
 
 use crate::{
     AppState,
-    models::{SignUpRequest, User},
-    router::AuthLayer,
+    extractors::{ClientIp, Validated},
+    i18n::Locale,
+    models::{
+        api::SignUpRequest,
+        domain::{CaptchaWidget, SignupSettings},
+    },
+    router::{AuthLayer, PageContext},
+    services::{BotProtectionError, UsersServiceError},
+    validation::{password_policy, password_strength, validate_password},
 };
 
 #[derive(Template, WebTemplate, Default)]
@@ -24,25 +31,32 @@ struct SignupPage {
     title: String,
     description: String,
     form: SignupForm,
-    user: Option<User>,
+    captcha: Option<CaptchaWidget>,
+    ctx: PageContext,
 }
 #[instrument(name = "sign up page", skip_all)]
-pub async fn page(auth: AuthLayer, token: CsrfToken) -> impl IntoResponse {
-    let user = auth.current_user;
-    if user.as_ref().is_some() {
+pub async fn page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    if ctx.user.as_ref().is_some() {
         return Redirect::to("/").into_response();
     }
     let authenticity_token = token.authenticity_token().unwrap_or_default();
     (
         token,
         SignupPage {
-            title: "Зарегистрироваться".to_string(),
+            title: ctx.t("sign-up"),
             description: "".to_string(),
             form: SignupForm {
                 csrf_token: authenticity_token,
+                settings: state.users_service.signup_settings().clone(),
+                locale: ctx.locale,
                 ..Default::default()
             },
-            user,
+            captcha: state.bot_protection_service.captcha(),
+            ctx,
         },
     )
         .into_response()
@@ -57,50 +71,28 @@ pub struct SignupForm {
     #[validate(email)]
     pub email: String,
     pub email_error: Option<String>,
-    #[validate(
-        length(min = 8, max = 64),
-        custom(function = "validate_signup_password")
-    )]
+    #[validate(custom(function = "validate_password"))]
     pub password: String,
-    #[validate(
-        length(min = 8, max = 64),
-        custom(function = "validate_signup_password")
-    )]
+    #[validate(custom(function = "validate_password"))]
     pub confirm_password: String,
     pub password_error: Option<String>,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub bio: Option<String>,
+    /// Values of `city` and instance-defined fields
+    #[serde(default)]
+    pub profile_extras: BTreeMap<String, String>,
+    pub profile_error: Option<String>,
     pub csrf_token: String,
-}
-
-fn validate_signup_password(password: &str) -> Result<(), validator::ValidationError> {
-    let mut errors = Vec::new();
-
-    if !password.chars().any(|c| c.is_uppercase()) {
-        errors.push("uppercase letter required");
-    }
-    if !password.chars().any(|c| c.is_lowercase()) {
-        errors.push("lowercase letter required");
-    }
-    if !password.chars().any(|c| c.is_ascii_digit()) {
-        errors.push("digit required");
-    }
-    if !password
-        .chars()
-        .any(|c| "!@#$%^&*()_+-=[]{}|;:,.<>?".contains(c))
-    {
-        errors.push("special character required");
-    }
-
-    if !errors.is_empty() {
-        let error_message = errors.join(", ");
-        let mut error = validator::ValidationError::new("password_requirements");
-        error.message = Some(format!("Password requirements not met: {error_message}").into());
-        return Err(error);
-    }
-
-    Ok(())
+    /// Honeypot, hidden from people and left empty by them
+    #[serde(default)]
+    pub website: String,
+    #[serde(default)]
+    pub captcha_token: String,
+    #[serde(skip)]
+    pub settings: SignupSettings,
+    #[serde(skip)]
+    pub locale: Locale,
 }
 
 #[axum::debug_handler]
@@ -108,14 +100,25 @@ fn validate_signup_password(password: &str) -> Result<(), validator::ValidationE
 pub async fn signup_form(
     auth: AuthLayer,
     token: CsrfToken,
+    locale: Locale,
+    ClientIp(client_ip): ClientIp,
     State(state): State<Arc<AppState>>,
-    ReadSignals(form): ReadSignals<SignupForm>,
+    Validated(mut form): Validated<SignupForm>,
 ) -> impl IntoResponse {
     info!("sign up post requested");
+    form.locale = locale;
     info!("{form:#?}");
     if token.verify(&form.csrf_token).is_err() {
         let mut nf = form.clone();
-        nf.username_error = Some("wrong csrf".into());
+        nf.settings = state.users_service.signup_settings().clone();
+        nf.username_error = Some(locale.t("invalid-csrf"));
+        return nf.into_response();
+    }
+    if let Err(e) = state.username_service.check_available(&form.username).await {
+        let mut nf = form.clone();
+        nf.settings = state.users_service.signup_settings().clone();
+        nf.username_error = Some(e.message(locale));
+        nf.csrf_token = token.authenticity_token().unwrap_or_default();
         return nf.into_response();
     }
     if (form.email_error.as_ref().is_none()
@@ -124,16 +127,34 @@ pub async fn signup_form(
             || form.password_error.as_ref().is_some_and(|e| e.is_empty()))
     {
         let csrt_token = token.authenticity_token().unwrap();
+        if let Err(e) = state
+            .bot_protection_service
+            .check(&form.website, &form.captcha_token, client_ip)
+            .await
+        {
+            if let BotProtectionError::ProviderError(err) = &e {
+                error!("captcha verification failed: {err}");
+            }
+            let mut nf = form.clone();
+            nf.settings = state.users_service.signup_settings().clone();
+            nf.profile_error = Some(e.message(locale));
+            nf.csrf_token = csrt_token;
+            return nf.into_response();
+        }
         match state
             .users_service
-            .sign_up(SignUpRequest {
-                username: form.username.clone(),
-                email: form.email.clone(),
-                password: form.password.clone(),
-                first_name: form.first_name.clone(),
-                last_name: form.last_name.clone(),
-                bio: form.bio.clone(),
-            })
+            .sign_up(
+                SignUpRequest {
+                    username: form.username.clone(),
+                    email: form.email.clone(),
+                    password: form.password.clone(),
+                    first_name: form.first_name.clone(),
+                    last_name: form.last_name.clone(),
+                    bio: form.bio.clone(),
+                    profile_extras: form.profile_extras.clone(),
+                },
+                locale,
+            )
             .await
         {
             Ok(res) => {
@@ -143,8 +164,13 @@ pub async fn signup_form(
             Err(e) => {
                 error!("{e:?}");
                 let mut nf = form.clone();
+                nf.settings = state.users_service.signup_settings().clone();
                 if e.to_string().contains("already exists") {
-                    nf.email_error = Some("Почта уже зарегистрирована".into())
+                    nf.email_error = Some(locale.t("email-taken"))
+                } else if let UsersServiceError::EmailDomain(e) = e {
+                    nf.email_error = Some(e.message(locale));
+                } else if let UsersServiceError::WrongCredentials(message) = e {
+                    nf.profile_error = Some(message);
                 } else {
                     nf.username_error = Some(e.to_string());
                 }
@@ -158,6 +184,7 @@ pub async fn signup_form(
         let pe = form.password_error.as_ref();
         error!("email error: {ee:?}\npassword error: {pe:?}");
         let mut nf = form.clone();
+        nf.settings = state.users_service.signup_settings().clone();
         nf.csrf_token = token.authenticity_token().unwrap();
         nf.into_response()
     }
@@ -167,10 +194,13 @@ struct FormErrors<'a> {
     username_error: &'a str,
     email_error: &'a str,
     password_error: &'a str,
+    password_strength: u8,
+    password_strength_label: &'a str,
 }
 #[axum::debug_handler]
 #[instrument(name = "signup form validate", skip_all)]
 pub async fn signup_form_validate(
+    locale: Locale,
     State(state): State<Arc<AppState>>,
     ReadSignals(data): ReadSignals<SignupForm>,
 ) -> impl IntoResponse {
@@ -182,18 +212,32 @@ pub async fn signup_form_validate(
     };
     Sse::new(stream_fn(
         move |mut yielder: Yielder<Result<Event, Infallible>>| async move {
-            let mut errors = FormErrors::default();
-            if !data.username.is_empty() {
-                let username_exists = state
-                    .users_service
-                    .check_username_exists(&data.username)
-                    .await
-                    .unwrap_or_default();
-                if username_exists {
-                    errors.username_error = "Имя пользователя уже занято";
-                } else {
-                    errors.username_error = "";
-                }
+            let username_error = if data.username.is_empty() {
+                None
+            } else {
+                let checked = state.username_service.check_available(&data.username).await;
+                Some(checked.err().map(|e| e.message(locale)).unwrap_or_default())
+            };
+            let (password_strength, password_strength_label) =
+                password_strength(&data.password, &[&data.username, &data.email], locale);
+            let password_requirements = password_policy().describe(locale);
+            let invalid_email = locale.t("invalid-email");
+            let email_domain_error = state
+                .users_service
+                .check_email_domain(&data.email)
+                .err()
+                .map(|e| e.message(locale))
+                .unwrap_or_default();
+            let passwords_mismatch = locale.t("passwords-mismatch");
+            let mut errors = FormErrors {
+                // Replaced below when the address itself is malformed
+                email_error: &email_domain_error,
+                password_strength,
+                password_strength_label: &password_strength_label,
+                ..Default::default()
+            };
+            if let Some(username_error) = username_error.as_deref() {
+                errors.username_error = username_error;
                 let patch = PatchSignals::new(serde_json::to_string(&errors).unwrap_or_default());
                 let sse_event = patch.write_as_axum_sse_event();
                 yielder.yield_item(Ok(sse_event)).await;
@@ -201,11 +245,11 @@ pub async fn signup_form_validate(
             if let Err(err) = data.validate() {
                 for field in err.errors().keys() {
                     if field == "email" && !data.email.is_empty() {
-                        errors.email_error = "Введите корректный email";
+                        errors.email_error = &invalid_email;
                     } else if (field == "password" && !data.password.is_empty())
                         || (field == "confirm_password" && !data.confirm_password.is_empty())
                     {
-                        errors.password_error = "Требования к паролю: Заглавная буква, цифра, спецсимвол, длина от 8 до 64 символов";
+                        errors.password_error = &password_requirements;
                     }
                 }
                 let patch = PatchSignals::new(serde_json::to_string(&errors).unwrap_or_default());
@@ -215,7 +259,7 @@ pub async fn signup_form_validate(
                 && !data.confirm_password.is_empty()
                 && data.password != data.confirm_password
             {
-                errors.password_error = "Пароли не совпадают";
+                errors.password_error = &passwords_mismatch;
                 let patch = PatchSignals::new(serde_json::to_string(&errors).unwrap_or_default());
                 let sse_event = patch.write_as_axum_sse_event();
                 yielder.yield_item(Ok(sse_event)).await;
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+
+use crate::{
+    AppState,
+    models::domain::{THEME_COOKIE, Theme},
+    router::{AuthLayer, IMPERSONATOR_KEY},
+};
+
+const THEME_COOKIE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ThemeSignals {
+    /// Theme to switch to; anything else returns to the system scheme
+    pub theme: String,
+    pub csrf_token: String,
+}
+
+/// Stores the theme the toggle in the footer switched to, on the account
+/// when signed in and in a cookie either way.
+#[instrument(name = "theme toggle", skip_all)]
+pub async fn toggle(
+    auth: AuthLayer,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(signals): ReadSignals<ThemeSignals>,
+) -> impl IntoResponse {
+    use {
+        asynk_strim::{Yielder, stream_fn},
+        axum::response::{Sse, sse::Event},
+        core::convert::Infallible,
+        datastar::prelude::PatchSignals,
+    };
+    if token.verify(&signals.csrf_token).is_err() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let theme = Theme::from_code(&signals.theme);
+    let impersonating = auth.session.get::<String>(IMPERSONATOR_KEY).is_some();
+    if let Some(user) = auth.current_user.as_ref().filter(|_| !impersonating) {
+        match state.users_service.set_theme(user.id, theme).await {
+            Ok(_) => auth.cache_clear_user(user.id.to_string()),
+            Err(e) => error!("{e:?}"),
+        }
+    }
+    let cookie = match theme {
+        Some(theme) => format!(
+            "{THEME_COOKIE}={}; Path=/; Max-Age={THEME_COOKIE_MAX_AGE_SECS}; SameSite=Lax",
+            theme.code()
+        ),
+        None => format!("{THEME_COOKIE}=; Path=/; Max-Age=0; SameSite=Lax"),
+    };
+    let patch = serde_json::json!({ "theme": theme.map(|t| t.code()).unwrap_or_default() });
+    (
+        [(header::SET_COOKIE, cookie)],
+        Sse::new(stream_fn(
+            move |mut yielder: Yielder<Result<Event, Infallible>>| async move {
+                let patch = PatchSignals::new(patch.to_string());
+                yielder
+                    .yield_item(Ok(patch.write_as_axum_sse_event()))
+                    .await;
+            },
+        )),
+    )
+        .into_response()
+}
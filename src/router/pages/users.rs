@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use tracing::{error, instrument};
+
+use crate::{
+    AppState,
+    models::domain::{ReportTarget, User},
+    router::{PageContext, PageMeta},
+    services::UsernameServiceError,
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/users/profile.html")]
+struct ProfilePage {
+    title: String,
+    description: String,
+    profile: User,
+    full_name: Option<String>,
+    ctx: PageContext,
+}
+
+/// `/u/{username}`: the public profile. Previous names redirect permanently
+/// to the current one. Profiles hidden by moderation are not found for
+/// everyone but admins, and blocked users do not find the profile of
+/// whoever blocked them.
+#[instrument(name = "user by username", skip_all)]
+pub async fn by_username(
+    mut ctx: PageContext,
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    let resolved = state.username_service.resolve(&username).await;
+    if let Ok(resolved) = resolved.as_ref()
+        && !ctx.user.as_ref().is_some_and(|u| u.is_admin)
+    {
+        match state
+            .moderation_service
+            .is_hidden(ReportTarget::Profile, resolved.user.id)
+            .await
+        {
+            Ok(false) => {}
+            Ok(true) => return StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                error!("{e:?}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    }
+    if let Ok(resolved) = resolved.as_ref() {
+        match state
+            .blocks_service
+            .hides_from(resolved.user.id, ctx.user.as_ref())
+            .await
+        {
+            Ok(false) => {}
+            Ok(true) => return StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                error!("{e:?}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    }
+    match resolved {
+        Ok(resolved) if resolved.renamed => {
+            Redirect::permanent(&format!("/u/{}", resolved.user.username)).into_response()
+        }
+        Ok(resolved) => {
+            let profile = resolved.user;
+            let full_name = [profile.first_name.as_deref(), profile.last_name.as_deref()]
+                .into_iter()
+                .flatten()
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let full_name = Some(full_name).filter(|name| !name.is_empty());
+            let title = profile.username.clone();
+            let description = full_name.clone().unwrap_or_default();
+            ctx.meta = Some(PageMeta::website(title.clone(), description.clone()));
+            ProfilePage {
+                title,
+                description,
+                profile,
+                full_name,
+                ctx,
+            }
+            .into_response()
+        }
+        Err(UsernameServiceError::NotFound) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
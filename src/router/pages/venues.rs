@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use axum_csrf::CsrfToken;
+use datastar::axum::ReadSignals;
+use serde::Deserialize;
+use tracing::{error, instrument};
+
+use crate::{
+    AppState,
+    models::domain::{EventSummary, NearbyVenue, Venue, VenueKind},
+    router::{AuthLayer, PageContext, PageMeta, pages::events::EventCreateSection},
+    services::{NewVenue, VenuesServiceError},
+};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/venues/directory.html")]
+struct DirectoryPage {
+    title: String,
+    description: String,
+    venues: Vec<Venue>,
+    near: NearSection,
+    ctx: PageContext,
+}
+
+#[instrument(name = "venues directory", skip_all)]
+pub async fn directory(ctx: PageContext, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let venues = state
+        .venues_service
+        .directory()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    DirectoryPage {
+        title: ctx.t("venues-title"),
+        description: ctx.t("venues-description"),
+        venues,
+        near: NearSection {
+            ctx: ctx.clone(),
+            ..Default::default()
+        },
+        ctx,
+    }
+}
+
+/// "Near me" results, `None` until a search ran.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/venues/near.html")]
+pub struct NearSection {
+    venues: Option<Vec<NearbyVenue>>,
+    error: Option<String>,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct NearSignals {
+    /// Filled in from the browser's geolocation or typed in
+    pub latitude: String,
+    pub longitude: String,
+}
+
+/// `/venues/near`: patches `#venues-near` with venues around the point.
+#[instrument(name = "venues near", skip_all)]
+pub async fn near(
+    ctx: PageContext,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(signals): ReadSignals<NearSignals>,
+) -> impl IntoResponse {
+    let mut section = NearSection {
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    let point = (
+        parse_degrees(&signals.latitude),
+        parse_degrees(&signals.longitude),
+    );
+    let result = match point {
+        (Some(latitude), Some(longitude)) => state.venues_service.near(latitude, longitude).await,
+        _ => Err(VenuesServiceError::InvalidCoordinates),
+    };
+    match result {
+        Ok(venues) => section.venues = Some(venues),
+        Err(e) => section.error = Some(error_message(e, &ctx)),
+    }
+    section
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/venues/page.html")]
+struct VenuePage {
+    title: String,
+    description: String,
+    venue: Venue,
+    events: Vec<EventSummary>,
+    /// Shown to signed-in visitors
+    event_create: Option<EventCreateSection>,
+    ctx: PageContext,
+}
+
+#[instrument(name = "venue page", skip_all)]
+pub async fn page(
+    mut ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let view = match state.venues_service.view(&slug, ctx.user.as_ref()).await {
+        Ok(view) => view,
+        Err(VenuesServiceError::NotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("{e:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // Slugs are case-insensitive; keep one address per venue
+    if view.venue.slug != slug {
+        return Redirect::permanent(&format!("/venues/{}", view.venue.slug)).into_response();
+    }
+    let title = view.venue.name.clone();
+    let description = view.venue.address.clone();
+    ctx.meta = Some(PageMeta::website(title.clone(), description.clone()));
+    let event_create = (ctx.user.is_some() && !ctx.guest).then(|| EventCreateSection {
+        place: Some(view.venue.slug.clone()),
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    });
+    (
+        token,
+        VenuePage {
+            title,
+            description,
+            venue: view.venue,
+            events: view.events,
+            event_create,
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "pages/admin/venues/page.html")]
+struct AdminPage {
+    title: String,
+    description: String,
+    section: AdminSection,
+    ctx: PageContext,
+}
+
+/// Venue list and the form adding one, patched after every addition.
+#[derive(Template, WebTemplate, Default)]
+#[template(path = "pages/admin/venues/section.html")]
+pub struct AdminSection {
+    venues: Vec<Venue>,
+    message: Option<String>,
+    error: Option<String>,
+    csrf_token: String,
+    ctx: PageContext,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct VenueForm {
+    pub slug: String,
+    pub name: String,
+    pub kind: String,
+    pub address: String,
+    pub latitude: String,
+    pub longitude: String,
+    pub csrf_token: String,
+}
+
+#[instrument(name = "admin venues page", skip_all)]
+pub async fn admin_page(
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    if !ctx.user.as_ref().is_some_and(|u| u.is_admin) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let venues = state
+        .venues_service
+        .directory()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    let authenticity_token = token.authenticity_token().unwrap_or_default();
+    (
+        token,
+        AdminPage {
+            title: ctx.t("venues-title"),
+            description: "".to_string(),
+            section: AdminSection {
+                venues,
+                csrf_token: authenticity_token,
+                ctx: ctx.clone(),
+                ..Default::default()
+            },
+            ctx,
+        },
+    )
+        .into_response()
+}
+
+#[instrument(name = "admin venues create", skip_all)]
+pub async fn admin_create(
+    auth: AuthLayer,
+    ctx: PageContext,
+    token: CsrfToken,
+    State(state): State<Arc<AppState>>,
+    ReadSignals(form): ReadSignals<VenueForm>,
+) -> impl IntoResponse {
+    let Some(admin) = auth.current_user.clone().filter(|u| u.is_admin) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    let mut section = AdminSection {
+        csrf_token: token.authenticity_token().unwrap_or_default(),
+        ctx: ctx.clone(),
+        ..Default::default()
+    };
+    if token.verify(&form.csrf_token).is_err() {
+        section.error = Some(ctx.t("invalid-csrf"));
+    } else {
+        let venue = NewVenue {
+            slug: &form.slug,
+            name: &form.name,
+            kind: &form.kind,
+            address: &form.address,
+            latitude: parse_degrees(&form.latitude),
+            longitude: parse_degrees(&form.longitude),
+        };
+        match state.venues_service.create(&admin, venue).await {
+            Ok(venue) => section.message = Some(ctx.t_args("venue-added", [("name", venue.name)])),
+            Err(e) => section.error = Some(error_message(e, &ctx)),
+        }
+    }
+    section.venues = state
+        .venues_service
+        .directory()
+        .await
+        .inspect_err(|e| error!("{e:?}"))
+        .unwrap_or_default();
+    section.into_response()
+}
+
+/// Accepts a decimal comma, as coordinates copied from Russian maps have one.
+fn parse_degrees(input: &str) -> Option<f64> {
+    input.trim().replace(',', ".").parse().ok()
+}
+
+fn error_message(e: VenuesServiceError, ctx: &PageContext) -> String {
+    if let VenuesServiceError::DatabaseError(err) = &e {
+        error!("{err}");
+    }
+    e.message(ctx.locale)
+}
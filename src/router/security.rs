@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+use crate::models::domain::CaptchaProvider;
+
+/// Placeholder in `security.content_security_policy` replaced by the
+/// per-request nonce.
+const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+/// Datastar evaluates `data-*` expressions with `Function`, hence `'unsafe-eval'`.
+const DEFAULT_CSP: &str = "default-src 'self'; script-src 'self' 'nonce-{nonce}' 'unsafe-eval'; \
+     style-src 'self'; img-src 'self' data:; connect-src 'self'; object-src 'none'; \
+     base-uri 'self'; form-action 'self'; frame-ancestors 'none'";
+
+/// Directives a CAPTCHA widget needs its provider's origins in.
+const CAPTCHA_DIRECTIVES: [&str; 3] = ["script-src", "frame-src", "connect-src"];
+
+/// `[security]` configuration section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SecurityHeaders {
+    pub content_security_policy: String,
+    /// 0 disables `Strict-Transport-Security`
+    pub hsts_max_age_secs: u64,
+    pub hsts_include_subdomains: bool,
+    pub referrer_policy: String,
+    /// `DENY` or `SAMEORIGIN`
+    pub frame_options: String,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            content_security_policy: DEFAULT_CSP.to_string(),
+            hsts_max_age_secs: 0,
+            hsts_include_subdomains: false,
+            referrer_policy: "strict-origin-when-cross-origin".to_string(),
+            frame_options: "DENY".to_string(),
+        }
+    }
+}
+
+impl SecurityHeaders {
+    /// Adds the origins of the `[bot_protection]` provider to the policy.
+    /// Fails when the policy forbids them outright with `'none'`, as the
+    /// CAPTCHA could never load.
+    pub fn with_captcha(mut self, provider: Option<CaptchaProvider>) -> Result<Self, String> {
+        let Some(provider) = provider else {
+            return Ok(self);
+        };
+        let mut directives: Vec<String> = self
+            .content_security_policy
+            .split(';')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(str::to_string)
+            .collect();
+        let fallback = directives
+            .iter()
+            .find_map(|d| d.strip_prefix("default-src "))
+            .map(str::to_string);
+        for name in CAPTCHA_DIRECTIVES {
+            let position = directives
+                .iter()
+                .position(|d| d.split_whitespace().next() == Some(name));
+            let index = match (position, &fallback) {
+                (Some(index), _) => index,
+                // Without the directive the browser applies default-src
+                (None, Some(sources)) => {
+                    directives.push(format!("{name} {sources}"));
+                    directives.len() - 1
+                }
+                (None, None) => continue,
+            };
+            let mut sources: Vec<&str> = directives[index].split_whitespace().skip(1).collect();
+            if sources.contains(&"'none'") {
+                return Err(format!(
+                    "security.content_security_policy: {name} 'none' blocks the \
+                     bot_protection CAPTCHA"
+                ));
+            }
+            for origin in provider.csp_origins() {
+                if !sources.contains(origin) {
+                    sources.push(origin);
+                }
+            }
+            directives[index] = format!("{name} {}", sources.join(" "));
+        }
+        self.content_security_policy = directives.join("; ");
+        Ok(self)
+    }
+
+    fn headers(&self, nonce: &str) -> Vec<(HeaderName, String)> {
+        let mut headers = vec![
+            (
+                header::CONTENT_SECURITY_POLICY,
+                self.content_security_policy
+                    .replace(NONCE_PLACEHOLDER, nonce),
+            ),
+            (header::REFERRER_POLICY, self.referrer_policy.clone()),
+            (header::X_FRAME_OPTIONS, self.frame_options.clone()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ];
+        if self.hsts_max_age_secs > 0 {
+            let mut hsts = format!("max-age={}", self.hsts_max_age_secs);
+            if self.hsts_include_subdomains {
+                hsts.push_str("; includeSubDomains");
+            }
+            headers.push((header::STRICT_TRANSPORT_SECURITY, hsts));
+        }
+        headers
+    }
+}
+
+/// Nonce allowing the page's own `<script>` tags under the CSP, available to
+/// templates as `ctx.nonce`.
+#[derive(Debug, Clone)]
+pub struct CspNonce(pub String);
+
+pub async fn security_headers(
+    State(config): State<Arc<SecurityHeaders>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    req.extensions_mut().insert(CspNonce(nonce.clone()));
+    let mut res = next.run(req).await;
+    let res_headers = res.headers_mut();
+    for (name, value) in config.headers(&nonce) {
+        match HeaderValue::try_from(value) {
+            // Handlers may set a stricter policy of their own
+            Ok(value) => {
+                res_headers.entry(name).or_insert(value);
+            }
+            Err(e) => tracing::error!("invalid {name} header value: {e}"),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use config::Config;
+
+    use super::*;
+
+    #[test]
+    fn test_default_headers() {
+        let headers = SecurityHeaders::default().headers("abc");
+        let csp = &headers
+            .iter()
+            .find(|(name, _)| name == header::CONTENT_SECURITY_POLICY)
+            .unwrap()
+            .1;
+        assert!(csp.contains("'nonce-abc'"));
+        assert!(csp.contains("frame-ancestors 'none'"));
+        assert!(
+            !headers
+                .iter()
+                .any(|(name, _)| name == header::STRICT_TRANSPORT_SECURITY)
+        );
+    }
+
+    fn directive(headers: &SecurityHeaders, name: &str) -> String {
+        headers
+            .content_security_policy
+            .split("; ")
+            .find(|d| d.starts_with(&format!("{name} ")))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_with_captcha_allows_provider() {
+        let headers = SecurityHeaders::default();
+        assert_eq!(
+            headers.clone().with_captcha(None).unwrap().content_security_policy,
+            DEFAULT_CSP
+        );
+
+        let headers = headers
+            .with_captcha(Some(CaptchaProvider::Turnstile))
+            .unwrap();
+        let origin = "https://challenges.cloudflare.com";
+        assert_eq!(
+            directive(&headers, "script-src"),
+            format!("script-src 'self' 'nonce-{{nonce}}' 'unsafe-eval' {origin}")
+        );
+        assert_eq!(
+            directive(&headers, "connect-src"),
+            format!("connect-src 'self' {origin}")
+        );
+        // Missing directives start from default-src
+        assert_eq!(
+            directive(&headers, "frame-src"),
+            format!("frame-src 'self' {origin}")
+        );
+        assert!(headers.content_security_policy.contains("frame-ancestors 'none'"));
+    }
+
+    #[test]
+    fn test_with_captcha_rejects_none() {
+        let headers = SecurityHeaders {
+            content_security_policy: "default-src 'self'; frame-src 'none'".to_string(),
+            ..Default::default()
+        };
+        assert!(headers.with_captcha(Some(CaptchaProvider::HCaptcha)).is_err());
+    }
+
+    #[test]
+    fn test_headers_from_config() {
+        let config = Config::builder()
+            .set_override("security.hsts_max_age_secs", 31536000)
+            .unwrap()
+            .set_override("security.hsts_include_subdomains", true)
+            .unwrap()
+            .set_override("security.frame_options", "SAMEORIGIN")
+            .unwrap()
+            .build()
+            .unwrap();
+        let headers = config
+            .get::<SecurityHeaders>("security")
+            .unwrap()
+            .headers("abc");
+        assert!(headers.contains(&(
+            header::STRICT_TRANSPORT_SECURITY,
+            "max-age=31536000; includeSubDomains".to_string()
+        )));
+        assert!(headers.contains(&(header::X_FRAME_OPTIONS, "SAMEORIGIN".to_string())));
+        assert!(headers.contains(&(
+            header::REFERRER_POLICY,
+            "strict-origin-when-cross-origin".to_string()
+        )));
+    }
+}
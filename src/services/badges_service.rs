@@ -0,0 +1,156 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    models::domain::{
+        APPLICATION_APPROVED, APPLICATION_REJECTED, BADGE_CURATOR, BADGES, CuratorApplication,
+        PendingCuratorApplication, User,
+    },
+    services::PageCache,
+    storage::{CuratorApplicationsStorage, UsersStorage},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BadgesServiceError {
+    NotFound,
+    WrongInput(String),
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for BadgesServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for BadgesServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for BadgesServiceError {}
+
+#[derive(Clone, Debug)]
+pub struct BadgesService {
+    storage: CuratorApplicationsStorage,
+    users: UsersStorage,
+    page_cache: PageCache,
+}
+
+impl BadgesService {
+    pub fn new(storage: CuratorApplicationsStorage, users: UsersStorage) -> Self {
+        Self {
+            storage,
+            users,
+            page_cache: PageCache::default(),
+        }
+    }
+    /// Badges show on public pages, so changes drop the cached ones.
+    pub fn with_page_cache(mut self, page_cache: PageCache) -> Self {
+        self.page_cache = page_cache;
+        self
+    }
+    pub async fn apply(
+        &self,
+        user: &User,
+        motivation: &str,
+    ) -> Result<CuratorApplication, BadgesServiceError> {
+        let motivation = motivation.trim();
+        if motivation.is_empty() {
+            return Err(BadgesServiceError::WrongInput(
+                "Motivation is required".into(),
+            ));
+        }
+        if user.badge.as_deref() == Some(BADGE_CURATOR) {
+            return Err(BadgesServiceError::WrongInput("Already a curator".into()));
+        }
+        let pending = || BadgesServiceError::WrongInput("Application already pending".into());
+        if self.storage.get_pending_by_user(user.id).await?.is_some() {
+            return Err(pending());
+        }
+        // Two requests at once both get past the check above
+        match self.storage.create(user.id, motivation).await {
+            Ok(application) => Ok(application),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(pending()),
+            Err(e) => Err(e.into()),
+        }
+    }
+    pub async fn pending_application(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<CuratorApplication>, BadgesServiceError> {
+        let application = self.storage.get_pending_by_user(user_id).await?;
+        Ok(application)
+    }
+    pub async fn pending_applications(
+        &self,
+    ) -> Result<Vec<PendingCuratorApplication>, BadgesServiceError> {
+        let applications = self.storage.list_pending().await?;
+        Ok(applications)
+    }
+    /// Sets `badge` on the user with `username`; `None` removes the badge.
+    pub async fn assign(
+        &self,
+        username: &str,
+        badge: Option<&str>,
+    ) -> Result<User, BadgesServiceError> {
+        if badge.is_some_and(|b| !BADGES.contains(&b)) {
+            return Err(BadgesServiceError::WrongInput("Unknown badge".into()));
+        }
+        let user = self
+            .users
+            .get_by_username(username)
+            .await?
+            .ok_or(BadgesServiceError::NotFound)?;
+        let updated = self
+            .users
+            .set_badge(user.id, badge)
+            .await?
+            .ok_or(BadgesServiceError::NotFound)?;
+        self.page_cache.invalidate_all();
+        Ok(updated)
+    }
+    /// Approving grants the curator badge unless the user already has a
+    /// badge, which an admin may still change with [`Self::assign`].
+    pub async fn resolve(
+        &self,
+        application_id: Uuid,
+        approve: bool,
+        admin_id: Uuid,
+    ) -> Result<CuratorApplication, BadgesServiceError> {
+        let status = if approve {
+            APPLICATION_APPROVED
+        } else {
+            APPLICATION_REJECTED
+        };
+        // The decision and the badge land together or not at all
+        let application = self
+            .users
+            .with_tx(|conn| {
+                Box::pin(async move {
+                    let application = CuratorApplicationsStorage::resolve_in(
+                        &mut *conn,
+                        application_id,
+                        status,
+                        admin_id,
+                    )
+                    .await?;
+                    if approve && let Some(application) = application.as_ref() {
+                        UsersStorage::grant_badge_in(
+                            &mut *conn,
+                            application.user_id,
+                            Some(BADGE_CURATOR),
+                        )
+                        .await?;
+                    }
+                    Ok(application)
+                })
+            })
+            .await?
+            .ok_or(BadgesServiceError::NotFound)?;
+        if approve {
+            self.page_cache.invalidate_all();
+        }
+        Ok(application)
+    }
+}
@@ -0,0 +1,96 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{BlockKind, User, UserBlock},
+    storage::{UserBlocksStorage, UsersStorage},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BlocksServiceError {
+    /// No user with that username
+    NotFound,
+    /// Users cannot block or mute themselves
+    SelfBlock,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for BlocksServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for BlocksServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for BlocksServiceError {}
+
+impl BlocksServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            BlocksServiceError::NotFound => locale.t("block-user-not-found"),
+            BlocksServiceError::SelfBlock => locale.t("block-self"),
+            BlocksServiceError::DatabaseError(_) => locale.t("block-failed"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BlocksService {
+    storage: UserBlocksStorage,
+    users: UsersStorage,
+}
+
+impl BlocksService {
+    pub fn new(storage: UserBlocksStorage, users: UsersStorage) -> Self {
+        Self { storage, users }
+    }
+    pub async fn list(&self, user: &User) -> Result<Vec<UserBlock>, BlocksServiceError> {
+        let blocks = self.storage.list(user.id).await?;
+        Ok(blocks)
+    }
+    /// Blocks or mutes `username`, replacing an earlier relation with them.
+    pub async fn set(
+        &self,
+        user: &User,
+        username: &str,
+        kind: BlockKind,
+    ) -> Result<(), BlocksServiceError> {
+        let target = self
+            .users
+            .get_by_username(username.trim().trim_start_matches('@'))
+            .await?
+            .ok_or(BlocksServiceError::NotFound)?;
+        if target.id == user.id {
+            return Err(BlocksServiceError::SelfBlock);
+        }
+        self.storage.set(user.id, target.id, kind.code()).await?;
+        info!(target: "audit", user_id = %user.id, target_id = %target.id, kind = kind.code(), "user blocked");
+        Ok(())
+    }
+    pub async fn remove(&self, user: &User, target_id: Uuid) -> Result<(), BlocksServiceError> {
+        if !self.storage.remove(user.id, target_id).await? {
+            return Err(BlocksServiceError::NotFound);
+        }
+        info!(target: "audit", user_id = %user.id, target_id = %target_id, "user unblocked");
+        Ok(())
+    }
+    /// Whether `owner` blocked `viewer`, hiding the owner's pages from them.
+    /// Anonymous visitors are never blocked.
+    pub async fn hides_from(
+        &self,
+        owner_id: Uuid,
+        viewer: Option<&User>,
+    ) -> Result<bool, BlocksServiceError> {
+        let Some(viewer) = viewer else {
+            return Ok(false);
+        };
+        let blocked = self.storage.is_blocked(owner_id, viewer.id).await?;
+        Ok(blocked)
+    }
+}
@@ -0,0 +1,134 @@
+use std::{error::Error, fmt::Display, net::IpAddr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{BotProtectionSettings, CaptchaWidget},
+};
+
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BotProtectionError {
+    /// The honeypot field was filled in
+    Honeypot,
+    CaptchaFailed,
+    ProviderError(String),
+}
+impl Display for BotProtectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for BotProtectionError {}
+
+impl BotProtectionError {
+    /// Text shown on the signup form.
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            BotProtectionError::ProviderError(_) => locale.t("captcha-unavailable"),
+            _ => locale.t("captcha-retry"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Honeypot and optional CAPTCHA checks run before creating accounts.
+#[derive(Clone, Debug)]
+pub struct BotProtectionService {
+    settings: BotProtectionSettings,
+    client: reqwest::Client,
+}
+
+impl BotProtectionService {
+    pub fn new(settings: BotProtectionSettings) -> Self {
+        Self {
+            settings,
+            client: reqwest::Client::new(),
+        }
+    }
+    pub fn captcha(&self) -> Option<CaptchaWidget> {
+        self.settings.captcha()
+    }
+    /// Runs the honeypot check, then the CAPTCHA when one is configured.
+    /// `client_ip` lets the provider check the solver is the submitter.
+    pub async fn check(
+        &self,
+        honeypot: &str,
+        captcha_token: &str,
+        client_ip: Option<IpAddr>,
+    ) -> Result<(), BotProtectionError> {
+        if !honeypot.is_empty() {
+            info!(target: "audit", reason = "honeypot", "bot check failed");
+            return Err(BotProtectionError::Honeypot);
+        }
+        let Some(widget) = self.settings.captcha() else {
+            return Ok(());
+        };
+        if captcha_token.is_empty() {
+            return Err(BotProtectionError::CaptchaFailed);
+        }
+        let remote_ip = client_ip.map(|ip| ip.to_string());
+        let mut form = vec![
+            ("secret", self.settings.captcha_secret_key.as_str()),
+            ("response", captcha_token),
+            ("sitekey", widget.site_key.as_str()),
+        ];
+        if let Some(remote_ip) = &remote_ip {
+            form.push(("remoteip", remote_ip));
+        }
+        let res = self
+            .client
+            .post(widget.provider.verify_url())
+            .timeout(VERIFY_TIMEOUT)
+            .form(&form)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| BotProtectionError::ProviderError(e.to_string()))?
+            .json::<VerifyResponse>()
+            .await
+            .map_err(|e| BotProtectionError::ProviderError(e.to_string()))?;
+        if !res.success {
+            info!(target: "audit", reason = "captcha", errors = ?res.error_codes, "bot check failed");
+            return Err(BotProtectionError::CaptchaFailed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_honeypot() {
+        let service = BotProtectionService::new(BotProtectionSettings::default());
+        assert!(service.check("", "", None).await.is_ok());
+        assert!(matches!(
+            service.check("https://spam.example", "", None).await,
+            Err(BotProtectionError::Honeypot)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_captcha_token_required_when_enabled() {
+        let service = BotProtectionService::new(BotProtectionSettings {
+            captcha_provider: Some(crate::models::domain::CaptchaProvider::HCaptcha),
+            captcha_site_key: "site".into(),
+            captcha_secret_key: "secret".into(),
+        });
+        assert!(matches!(
+            service.check("", "", None).await,
+            Err(BotProtectionError::CaptchaFailed)
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        CLUB_DESCRIPTION_MAX_LEN, CLUB_NAME_MAX_LEN, Club, ClubMember, ClubMemberAction, ClubRole,
+        ClubSlugError, ClubSummary, User, check_club_slug,
+    },
+    storage::ClubsStorage,
+};
+
+/// Clubs shown in the directory.
+const DIRECTORY_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClubsServiceError {
+    NotFound,
+    InvalidSlug(ClubSlugError),
+    SlugTaken,
+    EmptyName,
+    NameTooLong,
+    DescriptionTooLong,
+    /// The actor's role does not allow the action
+    Forbidden,
+    /// The club would be left without an owner
+    LastOwner,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for ClubsServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for ClubsServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for ClubsServiceError {}
+
+impl ClubsServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            ClubsServiceError::NotFound => locale.t("club-not-found"),
+            ClubsServiceError::InvalidSlug(e) => e.message(locale),
+            ClubsServiceError::SlugTaken => locale.t("club-slug-taken"),
+            ClubsServiceError::EmptyName => locale.t("club-name-required"),
+            ClubsServiceError::NameTooLong => locale.t_args(
+                "club-name-too-long",
+                &[("max", CLUB_NAME_MAX_LEN.to_string())],
+            ),
+            ClubsServiceError::DescriptionTooLong => locale.t_args(
+                "club-description-too-long",
+                &[("max", CLUB_DESCRIPTION_MAX_LEN.to_string())],
+            ),
+            ClubsServiceError::Forbidden => locale.t("club-forbidden"),
+            ClubsServiceError::LastOwner => locale.t("club-last-owner"),
+            ClubsServiceError::DatabaseError(_) => locale.t("club-failed"),
+        }
+    }
+}
+
+/// A club page: the club, its members and the viewer's role in it.
+#[derive(Debug, Clone)]
+pub struct ClubView {
+    pub club: Club,
+    pub members: Vec<ClubMember>,
+    pub viewer_role: Option<ClubRole>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClubsService {
+    storage: ClubsStorage,
+}
+
+impl ClubsService {
+    pub fn new(storage: ClubsStorage) -> Self {
+        Self { storage }
+    }
+    pub async fn directory(
+        &self,
+        viewer: Option<&User>,
+    ) -> Result<Vec<ClubSummary>, ClubsServiceError> {
+        let clubs = self
+            .storage
+            .list(viewer.map(|u| u.id), DIRECTORY_LIMIT)
+            .await?;
+        Ok(clubs)
+    }
+    pub async fn create(
+        &self,
+        owner: &User,
+        slug: &str,
+        name: &str,
+        description: &str,
+    ) -> Result<Club, ClubsServiceError> {
+        let slug = slug.trim();
+        let name = name.trim();
+        let description = description.trim();
+        check_club_slug(slug).map_err(ClubsServiceError::InvalidSlug)?;
+        if name.is_empty() {
+            return Err(ClubsServiceError::EmptyName);
+        }
+        if name.chars().count() > CLUB_NAME_MAX_LEN {
+            return Err(ClubsServiceError::NameTooLong);
+        }
+        if description.chars().count() > CLUB_DESCRIPTION_MAX_LEN {
+            return Err(ClubsServiceError::DescriptionTooLong);
+        }
+        let club = self
+            .storage
+            .create(slug, name, description, owner.id)
+            .await?
+            .ok_or(ClubsServiceError::SlugTaken)?;
+        info!(target: "audit", user_id = %owner.id, club_id = %club.id, slug = %club.slug, "club created");
+        Ok(club)
+    }
+    pub async fn view(
+        &self,
+        slug: &str,
+        viewer: Option<&User>,
+    ) -> Result<ClubView, ClubsServiceError> {
+        let club = self.get(slug).await?;
+        let members = self.storage.list_members(club.id).await?;
+        let viewer_role = viewer.and_then(|viewer| {
+            members
+                .iter()
+                .find(|m| m.user_id == viewer.id)
+                .map(ClubMember::role)
+        });
+        Ok(ClubView {
+            club,
+            members,
+            viewer_role,
+        })
+    }
+    pub async fn join(&self, user: &User, slug: &str) -> Result<(), ClubsServiceError> {
+        let club = self.get(slug).await?;
+        self.storage
+            .add_member(club.id, user.id, ClubRole::Member.code())
+            .await?;
+        Ok(())
+    }
+    pub async fn leave(&self, user: &User, slug: &str) -> Result<(), ClubsServiceError> {
+        let club = self.get(slug).await?;
+        let role = self.role(club.id, user.id).await?;
+        if role == Some(ClubRole::Owner) && self.storage.count_owners(club.id).await? <= 1 {
+            return Err(ClubsServiceError::LastOwner);
+        }
+        self.storage.remove_member(club.id, user.id).await?;
+        Ok(())
+    }
+    /// Owners promote and demote; owners and admins remove members below
+    /// them, see [`ClubRole::can_manage`]. Leaving is [`Self::leave`].
+    pub async fn manage_member(
+        &self,
+        actor: &User,
+        slug: &str,
+        member_id: Uuid,
+        action: ClubMemberAction,
+    ) -> Result<(), ClubsServiceError> {
+        let club = self.get(slug).await?;
+        if member_id == actor.id {
+            return Err(ClubsServiceError::Forbidden);
+        }
+        let actor_role = self
+            .role(club.id, actor.id)
+            .await?
+            .ok_or(ClubsServiceError::Forbidden)?;
+        let member_role = self
+            .role(club.id, member_id)
+            .await?
+            .ok_or(ClubsServiceError::NotFound)?;
+        if !actor_role.can_manage(member_role) {
+            return Err(ClubsServiceError::Forbidden);
+        }
+        match action {
+            ClubMemberAction::Remove => {
+                self.storage.remove_member(club.id, member_id).await?;
+            }
+            ClubMemberAction::Promote | ClubMemberAction::Demote => {
+                if actor_role != ClubRole::Owner {
+                    return Err(ClubsServiceError::Forbidden);
+                }
+                let role = match action {
+                    ClubMemberAction::Promote => member_role.promoted(),
+                    _ => member_role.demoted(),
+                }
+                .ok_or(ClubsServiceError::Forbidden)?;
+                self.storage
+                    .set_role(club.id, member_id, role.code())
+                    .await?;
+            }
+        }
+        info!(target: "audit", user_id = %actor.id, club_id = %club.id, %member_id, action = ?action, "club member changed");
+        Ok(())
+    }
+    async fn get(&self, slug: &str) -> Result<Club, ClubsServiceError> {
+        self.storage
+            .get_by_slug(slug)
+            .await?
+            .ok_or(ClubsServiceError::NotFound)
+    }
+    async fn role(
+        &self,
+        club_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<ClubRole>, ClubsServiceError> {
+        let role = self.storage.get_role(club_id, user_id).await?;
+        Ok(role.as_deref().and_then(ClubRole::from_code))
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{Cursor, Write},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    i18n::Locale,
+    models::{
+        api::UserExport,
+        domain::{DATA_EXPORT_TTL_HOURS, DataExport, TaskOutcome, User, records_to_csv},
+    },
+    services::{
+        Task, UsersService,
+        mailer::{Email, Mailer},
+    },
+    storage::DataExportsStorage,
+};
+
+/// Exports built per run; the rest wait for the next run.
+const EXPORT_BATCH_SIZE: i64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DataExportsServiceError {
+    /// The link is unknown, expired or someone else's
+    NotFound,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for DataExportsServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for DataExportsServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for DataExportsServiceError {}
+
+impl DataExportsServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            DataExportsServiceError::NotFound => locale.t("export-link-invalid"),
+            DataExportsServiceError::DatabaseError(_) => locale.t("export-failed"),
+        }
+    }
+}
+
+/// Data export requests and downloads; [`DataExportsTask`] builds the
+/// archives in the background.
+#[derive(Clone, Debug)]
+pub struct DataExportsService {
+    storage: DataExportsStorage,
+}
+
+impl DataExportsService {
+    pub fn new(storage: DataExportsStorage) -> Self {
+        Self { storage }
+    }
+    /// Queues an export of the user's data; asking again while it waits
+    /// does nothing.
+    pub async fn request(&self, user: &User) -> Result<(), DataExportsServiceError> {
+        if self.storage.request(user.id).await?.is_some() {
+            info!(target: "audit", user_id = %user.id, "data export requested");
+        }
+        Ok(())
+    }
+    /// The export waiting for the background job, if any.
+    pub async fn pending(
+        &self,
+        user: &User,
+    ) -> Result<Option<DataExport>, DataExportsServiceError> {
+        Ok(self.storage.get_pending(user.id).await?)
+    }
+    /// The ZIP archive behind an emailed link; only its owner may download it.
+    pub async fn download(
+        &self,
+        user: &User,
+        token: &str,
+    ) -> Result<Vec<u8>, DataExportsServiceError> {
+        let archive = self
+            .storage
+            .get_archive(token, user.id)
+            .await?
+            .ok_or(DataExportsServiceError::NotFound)?;
+        info!(target: "audit", user_id = %user.id, "data export downloaded");
+        Ok(archive)
+    }
+}
+
+/// Builds queued data exports as ZIP archives and emails their owners a
+/// download link that works for [`DATA_EXPORT_TTL_HOURS`].
+pub struct DataExportsTask {
+    storage: DataExportsStorage,
+    users: UsersService,
+    mailer: Mailer,
+    base_url: String,
+}
+
+impl DataExportsTask {
+    pub fn new(
+        storage: DataExportsStorage,
+        users: UsersService,
+        mailer: Mailer,
+        base_url: String,
+    ) -> Self {
+        Self {
+            storage,
+            users,
+            mailer,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for DataExportsTask {
+    fn name(&self) -> &'static str {
+        "data_exports"
+    }
+    fn default_interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+    async fn run(&self) -> anyhow::Result<TaskOutcome> {
+        let mut built = 0;
+        for export in self.storage.list_pending(EXPORT_BATCH_SIZE).await? {
+            let id = export.user_id.to_string();
+            let user = self.users.get_by_id(&id).await?;
+            let archive = build_archive(&self.users.export(&id).await?)?;
+            let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+            let expires_at = Utc::now() + chrono::Duration::hours(DATA_EXPORT_TTL_HOURS);
+            // Another instance may have built it meanwhile
+            if self
+                .storage
+                .complete(export.id, &archive, &token, expires_at)
+                .await?
+                .is_none()
+            {
+                continue;
+            }
+            let locale = user.preferred_locale().unwrap_or_default();
+            let link = format!("{}/settings/export/{token}", self.base_url);
+            self.mailer
+                .send(Email {
+                    to: user.email.clone(),
+                    subject: locale.t("export-ready-subject"),
+                    body: locale.t_args(
+                        "export-ready-body",
+                        &[
+                            ("username", user.username.clone()),
+                            ("link", link),
+                            ("hours", DATA_EXPORT_TTL_HOURS.to_string()),
+                        ],
+                    ),
+                })
+                .await;
+            info!(target: "audit", user_id = %user.id, "data export built");
+            built += 1;
+        }
+        Ok(TaskOutcome::default().with_count("exports", built))
+    }
+}
+
+/// `export.json` with everything, plus a CSV file per list of records.
+fn build_archive(export: &UserExport) -> anyhow::Result<Vec<u8>> {
+    let json = serde_json::to_value(export)?;
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    zip.start_file("export.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&json)?)?;
+    if let Some(sections) = json.as_object() {
+        for (name, records) in sections.iter().filter(|(_, v)| v.is_array()) {
+            zip.start_file(format!("{name}.csv"), options)?;
+            zip.write_all(records_to_csv(records).as_bytes())?;
+        }
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::models::domain::UserData;
+
+    #[test]
+    fn test_build_archive() {
+        let data = UserData {
+            club_memberships: serde_json::json!([{ "club": "Readers", "role": "owner" }]),
+            ..Default::default()
+        };
+        let export = UserExport::new(User::default(), data);
+        let archive = build_archive(&export).unwrap();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        let names: Vec<_> = zip.file_names().collect();
+        assert!(names.contains(&"export.json"));
+        assert!(names.contains(&"clubMemberships.csv"));
+        let mut csv = String::new();
+        zip.by_name("clubMemberships.csv")
+            .unwrap()
+            .read_to_string(&mut csv)
+            .unwrap();
+        assert_eq!(csv, "club,role\r\nReaders,owner\r\n");
+    }
+}
@@ -0,0 +1,176 @@
+use std::{error::Error, fmt::Display};
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{EmailChange, User},
+    services::mailer::{Email, Mailer},
+    storage::{EmailChangesStorage, UsersStorage},
+};
+
+const CONFIRMATION_TTL: Duration = Duration::hours(24);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EmailChangeServiceError {
+    NotFound,
+    Expired,
+    Taken,
+    /// The new address is the current one
+    SameEmail,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for EmailChangeServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        match value {
+            sqlx::Error::Database(e) if e.is_unique_violation() => Self::Taken,
+            other => Self::DatabaseError(other.to_string()),
+        }
+    }
+}
+impl Display for EmailChangeServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for EmailChangeServiceError {}
+
+impl EmailChangeServiceError {
+    /// Text shown on the confirmation page.
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            EmailChangeServiceError::NotFound => locale.t("email-change-invalid"),
+            EmailChangeServiceError::Expired => locale.t("email-change-expired"),
+            EmailChangeServiceError::Taken => locale.t("email-taken"),
+            EmailChangeServiceError::SameEmail => locale.t("email-change-current"),
+            EmailChangeServiceError::DatabaseError(_) => locale.t("email-change-failed"),
+        }
+    }
+}
+
+/// Email changes wait for confirmation from the new address; the old address
+/// is told about the request so a hijacked session cannot move it silently.
+#[derive(Clone, Debug)]
+pub struct EmailChangeService {
+    storage: EmailChangesStorage,
+    users: UsersStorage,
+    mailer: Mailer,
+    base_url: String,
+    /// Don't reveal whether the new address is registered
+    privacy_mode: bool,
+}
+
+impl EmailChangeService {
+    pub fn new(
+        storage: EmailChangesStorage,
+        users: UsersStorage,
+        mailer: Mailer,
+        base_url: String,
+    ) -> Self {
+        Self {
+            storage,
+            users,
+            mailer,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            privacy_mode: false,
+        }
+    }
+    pub fn with_privacy_mode(mut self, privacy_mode: bool) -> Self {
+        self.privacy_mode = privacy_mode;
+        self
+    }
+    /// Mails the confirmation link to `new_email`. In privacy mode an
+    /// address registered to someone else gets the same answer as a free
+    /// one, `Ok(None)`, and no mail.
+    pub async fn request(
+        &self,
+        user_id: Uuid,
+        new_email: &str,
+    ) -> Result<Option<EmailChange>, EmailChangeServiceError> {
+        let user = self
+            .users
+            .get_by_id(user_id)
+            .await?
+            .ok_or(EmailChangeServiceError::NotFound)?;
+        let new_email = new_email.trim().to_lowercase();
+        if new_email == user.email {
+            return Err(EmailChangeServiceError::SameEmail);
+        }
+        // Respelling one's own address, say adding dots to a gmail one, is fine
+        if self
+            .users
+            .get_by_canonical_email(&new_email)
+            .await?
+            .is_some_and(|existing| existing.id != user.id)
+        {
+            info!(target: "audit", user_id = %user.id, reason = "email_exists", "email change rejected");
+            if self.privacy_mode {
+                return Ok(None);
+            }
+            return Err(EmailChangeServiceError::Taken);
+        }
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let change = self
+            .storage
+            .create(user.id, &new_email, &token, Utc::now() + CONFIRMATION_TTL)
+            .await?;
+        info!(target: "audit", user_id = %user.id, "email change requested");
+
+        let link = format!("{}/settings/email/confirm/{token}", self.base_url);
+        let locale = user.preferred_locale().unwrap_or_default();
+        let username = ("username", user.username.clone());
+        self.mailer
+            .send(Email {
+                to: new_email.clone(),
+                subject: locale.t("email-change-confirm-subject"),
+                body: locale.t_args(
+                    "email-change-confirm-body",
+                    &[username.clone(), ("link", link)],
+                ),
+            })
+            .await;
+        self.mailer
+            .send(Email {
+                to: user.email.clone(),
+                subject: locale.t("email-change-notice-subject"),
+                body: locale.t_args("email-change-notice-body", &[username, ("email", new_email)]),
+            })
+            .await;
+        Ok(Some(change))
+    }
+    /// Swaps the user's email to the one confirmed by `token`.
+    pub async fn confirm(&self, token: &str) -> Result<User, EmailChangeServiceError> {
+        let change = self
+            .storage
+            .get_pending_by_token(token)
+            .await?
+            .ok_or(EmailChangeServiceError::NotFound)?;
+        if change.expires_at <= Utc::now() {
+            return Err(EmailChangeServiceError::Expired);
+        }
+        let (id, user_id, new_email) = (change.id, change.user_id, change.new_email);
+        let email_canonical = self.users.canonical_email(&new_email);
+        let user = self
+            .users
+            .with_tx(|conn| {
+                Box::pin(async move {
+                    // Another confirmation of the same link may have won the race
+                    if EmailChangesStorage::confirm_in(&mut *conn, id)
+                        .await?
+                        .is_none()
+                    {
+                        return Ok(None);
+                    }
+                    UsersStorage::set_email_in(&mut *conn, user_id, &new_email, &email_canonical)
+                        .await
+                })
+            })
+            .await?
+            .ok_or(EmailChangeServiceError::NotFound)?;
+        info!(target: "audit", user_id = %user.id, "email changed");
+        Ok(user)
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::{Context, bail};
+
+use crate::{
+    models::domain::{
+        BUNDLED_DISPOSABLE_DOMAINS, EmailDomainError, EmailDomainSettings, TaskOutcome,
+        domain_matches, email_domain, parse_domain_list,
+    },
+    services::Task,
+};
+
+/// Decides which email domains may sign up, see [`EmailDomainSettings`].
+/// The disposable list starts as the bundled one and is replaced by
+/// [`DisposableDomainsTask`].
+#[derive(Clone, Debug)]
+pub struct EmailDomainPolicy {
+    settings: Arc<EmailDomainSettings>,
+    disposable: Arc<RwLock<Arc<HashSet<String>>>>,
+}
+
+impl Default for EmailDomainPolicy {
+    fn default() -> Self {
+        Self::new(EmailDomainSettings::default())
+    }
+}
+
+impl EmailDomainPolicy {
+    pub fn new(mut settings: EmailDomainSettings) -> Self {
+        for list in [&mut settings.allow, &mut settings.deny] {
+            for domain in list.iter_mut() {
+                *domain = domain.trim().trim_end_matches('.').to_lowercase();
+            }
+        }
+        Self {
+            settings: Arc::new(settings),
+            disposable: Arc::new(RwLock::new(Arc::new(parse_domain_list(
+                BUNDLED_DISPOSABLE_DOMAINS,
+            )))),
+        }
+    }
+    /// Addresses without a domain pass; their format is validated elsewhere.
+    pub fn check(&self, email: &str) -> Result<(), EmailDomainError> {
+        let Some(domain) = email_domain(email) else {
+            return Ok(());
+        };
+        let listed = |list: &[String]| list.iter().any(|l| domain_matches(&domain, l));
+        if !self.settings.allow.is_empty() && !listed(&self.settings.allow) {
+            return Err(EmailDomainError::NotAllowed);
+        }
+        if listed(&self.settings.deny) {
+            return Err(EmailDomainError::Denied);
+        }
+        // Explicitly allowed domains win over the disposable list
+        if self.settings.block_disposable
+            && self.settings.allow.is_empty()
+            && self.is_disposable(&domain)
+        {
+            return Err(EmailDomainError::Disposable);
+        }
+        Ok(())
+    }
+    fn is_disposable(&self, domain: &str) -> bool {
+        let disposable = self.disposable_domains();
+        // Walk up the labels: mail.yopmail.com, yopmail.com, com
+        let mut rest = domain;
+        loop {
+            if disposable.contains(rest) {
+                return true;
+            }
+            match rest.split_once('.') {
+                Some((_, parent)) => rest = parent,
+                None => return false,
+            }
+        }
+    }
+    fn disposable_domains(&self) -> Arc<HashSet<String>> {
+        self.disposable
+            .read()
+            .expect("disposable domains lock poisoned")
+            .clone()
+    }
+    /// Replaces the disposable list, keeping the bundled domains.
+    pub fn set_disposable(&self, mut domains: HashSet<String>) -> usize {
+        domains.extend(parse_domain_list(BUNDLED_DISPOSABLE_DOMAINS));
+        let count = domains.len();
+        *self
+            .disposable
+            .write()
+            .expect("disposable domains lock poisoned") = Arc::new(domains);
+        count
+    }
+}
+
+/// Downloads `email_domains.disposable_list_url` into the
+/// [`EmailDomainPolicy`]. Only registered when the URL is set.
+pub struct DisposableDomainsTask {
+    policy: EmailDomainPolicy,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl DisposableDomainsTask {
+    pub fn new(policy: EmailDomainPolicy, url: String) -> Self {
+        Self {
+            policy,
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for DisposableDomainsTask {
+    fn name(&self) -> &'static str {
+        "disposable_domains"
+    }
+    fn default_interval(&self) -> Duration {
+        Duration::from_secs(24 * 60 * 60)
+    }
+    async fn run(&self) -> anyhow::Result<TaskOutcome> {
+        let text = self
+            .client
+            .get(&self.url)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .context("fetching the disposable domain list")?
+            .text()
+            .await?;
+        let domains = parse_domain_list(&text);
+        // An empty or broken download must not unblock everything
+        if domains.is_empty() {
+            bail!("the disposable domain list at {} is empty", self.url);
+        }
+        let fetched = domains.len() as u64;
+        let total = self.policy.set_disposable(domains) as u64;
+        Ok(TaskOutcome::default()
+            .with_count("fetched", fetched)
+            .with_count("domains", total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_lists(allow: &[&str], deny: &[&str]) -> EmailDomainPolicy {
+        EmailDomainPolicy::new(EmailDomainSettings {
+            allow: allow.iter().map(|d| d.to_string()).collect(),
+            deny: deny.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_default_policy_blocks_disposable_domains() {
+        let policy = EmailDomainPolicy::default();
+        assert_eq!(policy.check("reader@example.com"), Ok(()));
+        assert_eq!(
+            policy.check("reader@yopmail.com"),
+            Err(EmailDomainError::Disposable)
+        );
+        assert_eq!(
+            policy.check("reader@inbox.Mailinator.com"),
+            Err(EmailDomainError::Disposable)
+        );
+        assert_eq!(policy.check("not an email"), Ok(()));
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let policy = with_lists(&["University.edu"], &[]);
+        assert_eq!(policy.check("student@university.edu"), Ok(()));
+        assert_eq!(policy.check("student@cs.university.edu"), Ok(()));
+        assert_eq!(
+            policy.check("reader@example.com"),
+            Err(EmailDomainError::NotAllowed)
+        );
+
+        let policy = with_lists(&[], &["spam.example"]);
+        assert_eq!(
+            policy.check("bot@spam.example"),
+            Err(EmailDomainError::Denied)
+        );
+        assert_eq!(policy.check("reader@example.com"), Ok(()));
+    }
+
+    #[test]
+    fn test_set_disposable_keeps_bundled_domains() {
+        let policy = EmailDomainPolicy::default();
+        let clone = policy.clone();
+        policy.set_disposable(HashSet::from(["throwaway.example".to_string()]));
+        assert_eq!(
+            clone.check("reader@throwaway.example"),
+            Err(EmailDomainError::Disposable)
+        );
+        assert_eq!(
+            clone.check("reader@yopmail.com"),
+            Err(EmailDomainError::Disposable)
+        );
+    }
+}
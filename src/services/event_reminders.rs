@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use crate::{
+    i18n,
+    models::domain::{RsvpStatus, TaskOutcome},
+    services::{
+        Task,
+        mailer::{Email, Mailer},
+    },
+    storage::EventsStorage,
+};
+
+/// How long before the start people are reminded of an event.
+const REMINDER_LEAD: Duration = Duration::from_secs(24 * 60 * 60);
+/// Events reminded of per run; the rest wait for the next run.
+const REMINDER_BATCH_SIZE: i64 = 50;
+
+/// Emails everyone who answered an event's RSVP a day before it starts.
+/// Each event is reminded of once, even if the RSVPs change afterwards.
+pub struct EventRemindersTask {
+    storage: EventsStorage,
+    mailer: Mailer,
+    base_url: String,
+}
+
+impl EventRemindersTask {
+    pub fn new(storage: EventsStorage, mailer: Mailer, base_url: String) -> Self {
+        Self {
+            storage,
+            mailer,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for EventRemindersTask {
+    fn name(&self) -> &'static str {
+        "event_reminders"
+    }
+    fn default_interval(&self) -> Duration {
+        Duration::from_secs(15 * 60)
+    }
+    async fn run(&self) -> anyhow::Result<TaskOutcome> {
+        let events = self
+            .storage
+            .list_due_reminders(REMINDER_LEAD.as_secs_f64(), REMINDER_BATCH_SIZE)
+            .await?;
+        let mut emails = 0;
+        for event in &events {
+            // Marked first: a failure below must not mail everyone twice
+            self.storage.mark_reminded(event.id).await?;
+            let link = format!("{}/events/{}", self.base_url, event.id);
+            let place = if event.venue.is_empty() {
+                String::new()
+            } else {
+                format!(", {}", event.venue)
+            };
+            for recipient in self.storage.list_recipients(event.id).await? {
+                let locale = recipient.locale();
+                let tz =
+                    i18n::parse_timezone(&recipient.timezone).unwrap_or(i18n::DEFAULT_TIMEZONE);
+                let key = match RsvpStatus::from_code(&recipient.status) {
+                    Some(RsvpStatus::Going) => "event-reminder-going",
+                    _ => "event-reminder-interested",
+                };
+                let title = ("title", event.title.clone());
+                let body = locale.t_args(
+                    key,
+                    &[
+                        ("username", recipient.username),
+                        title.clone(),
+                        ("starts_at", locale.format_datetime(&event.starts_at, tz)),
+                        ("place", place.clone()),
+                        ("link", link.clone()),
+                    ],
+                );
+                self.mailer
+                    .send(Email {
+                        to: recipient.email,
+                        subject: locale.t_args("event-reminder-subject", &[title]),
+                        body,
+                    })
+                    .await;
+                emails += 1;
+            }
+        }
+        Ok(TaskOutcome::default()
+            .with_count("events", events.len() as u64)
+            .with_count("emails", emails))
+    }
+}
@@ -0,0 +1,226 @@
+use std::{error::Error, fmt::Display};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        ClubRole, EVENT_DESCRIPTION_MAX_LEN, EVENT_TITLE_MAX_LEN, EVENT_VENUE_MAX_LEN, Event,
+        EventSummary, RsvpStatus, User,
+    },
+    storage::{ClubsStorage, CreateEvent, EventsStorage, VenuesStorage},
+};
+
+/// Events shown on the upcoming events page.
+const UPCOMING_LIMIT: i64 = 100;
+/// Upcoming events shown on a club page.
+const CLUB_EVENTS_LIMIT: i64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EventsServiceError {
+    NotFound,
+    ClubNotFound,
+    VenueNotFound,
+    EmptyTitle,
+    TitleTooLong,
+    VenueTooLong,
+    DescriptionTooLong,
+    InvalidStart,
+    /// The start is not in the future
+    StartInPast,
+    /// Only club admins and owners create club events
+    Forbidden,
+    /// RSVPs close once the event starts
+    Started,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for EventsServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for EventsServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for EventsServiceError {}
+
+impl EventsServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            EventsServiceError::NotFound => locale.t("event-not-found"),
+            EventsServiceError::ClubNotFound => locale.t("club-not-found"),
+            EventsServiceError::VenueNotFound => locale.t("venue-not-found"),
+            EventsServiceError::EmptyTitle => locale.t("event-title-required"),
+            EventsServiceError::TitleTooLong => locale.t_args(
+                "event-title-too-long",
+                &[("max", EVENT_TITLE_MAX_LEN.to_string())],
+            ),
+            EventsServiceError::VenueTooLong => locale.t_args(
+                "event-venue-too-long",
+                &[("max", EVENT_VENUE_MAX_LEN.to_string())],
+            ),
+            EventsServiceError::DescriptionTooLong => locale.t_args(
+                "event-description-too-long",
+                &[("max", EVENT_DESCRIPTION_MAX_LEN.to_string())],
+            ),
+            EventsServiceError::InvalidStart => locale.t("event-start-invalid"),
+            EventsServiceError::StartInPast => locale.t("event-start-past"),
+            EventsServiceError::Forbidden => locale.t("event-forbidden"),
+            EventsServiceError::Started => locale.t("event-started"),
+            EventsServiceError::DatabaseError(_) => locale.t("event-failed"),
+        }
+    }
+}
+
+/// What the creator filled in; `starts_at` is already resolved from the
+/// creator's timezone.
+#[derive(Debug, Clone)]
+pub struct NewEvent<'a> {
+    pub title: &'a str,
+    pub description: &'a str,
+    pub venue: &'a str,
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Slug of the organizing club
+    pub club: Option<&'a str>,
+    /// Slug of the listed venue it is held at
+    pub place: Option<&'a str>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EventsService {
+    storage: EventsStorage,
+    clubs: ClubsStorage,
+    venues: VenuesStorage,
+}
+
+impl EventsService {
+    pub fn new(storage: EventsStorage, clubs: ClubsStorage, venues: VenuesStorage) -> Self {
+        Self {
+            storage,
+            clubs,
+            venues,
+        }
+    }
+    pub async fn upcoming(
+        &self,
+        viewer: Option<&User>,
+    ) -> Result<Vec<EventSummary>, EventsServiceError> {
+        let events = self
+            .storage
+            .list_upcoming(viewer.map(|u| u.id), UPCOMING_LIMIT)
+            .await?;
+        Ok(events)
+    }
+    pub async fn club_events(
+        &self,
+        club_id: Uuid,
+        viewer: Option<&User>,
+    ) -> Result<Vec<EventSummary>, EventsServiceError> {
+        let events = self
+            .storage
+            .list_upcoming_by_club(club_id, viewer.map(|u| u.id), CLUB_EVENTS_LIMIT)
+            .await?;
+        Ok(events)
+    }
+    pub async fn create(
+        &self,
+        creator: &User,
+        event: NewEvent<'_>,
+    ) -> Result<Event, EventsServiceError> {
+        let title = event.title.trim();
+        let description = event.description.trim();
+        let venue = event.venue.trim();
+        if title.is_empty() {
+            return Err(EventsServiceError::EmptyTitle);
+        }
+        if title.chars().count() > EVENT_TITLE_MAX_LEN {
+            return Err(EventsServiceError::TitleTooLong);
+        }
+        if venue.chars().count() > EVENT_VENUE_MAX_LEN {
+            return Err(EventsServiceError::VenueTooLong);
+        }
+        if description.chars().count() > EVENT_DESCRIPTION_MAX_LEN {
+            return Err(EventsServiceError::DescriptionTooLong);
+        }
+        let starts_at = event.starts_at.ok_or(EventsServiceError::InvalidStart)?;
+        if starts_at <= Utc::now() {
+            return Err(EventsServiceError::StartInPast);
+        }
+        let club_id = match event.club.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(slug) => {
+                let club = self
+                    .clubs
+                    .get_by_slug(slug)
+                    .await?
+                    .ok_or(EventsServiceError::ClubNotFound)?;
+                let role = self.clubs.get_role(club.id, creator.id).await?;
+                let role = role.as_deref().and_then(ClubRole::from_code);
+                if !role.is_some_and(|r| r >= ClubRole::Admin) {
+                    return Err(EventsServiceError::Forbidden);
+                }
+                Some(club.id)
+            }
+            None => None,
+        };
+        let venue_id = match event.place.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(slug) => {
+                let venue = self
+                    .venues
+                    .get_by_slug(slug)
+                    .await?
+                    .ok_or(EventsServiceError::VenueNotFound)?;
+                Some(venue.id)
+            }
+            None => None,
+        };
+        let event = self
+            .storage
+            .create(CreateEvent {
+                title,
+                description,
+                venue,
+                starts_at,
+                created_by: creator.id,
+                club_id,
+                venue_id,
+            })
+            .await?;
+        info!(target: "audit", user_id = %creator.id, event_id = %event.id, club_id = ?event.club_id, "event created");
+        Ok(event)
+    }
+    pub async fn view(
+        &self,
+        id: Uuid,
+        viewer: Option<&User>,
+    ) -> Result<EventSummary, EventsServiceError> {
+        self.storage
+            .get(id, viewer.map(|u| u.id))
+            .await?
+            .ok_or(EventsServiceError::NotFound)
+    }
+    /// Records the user's answer; `None` withdraws it. Returns the event
+    /// with updated counts.
+    pub async fn rsvp(
+        &self,
+        user: &User,
+        id: Uuid,
+        status: Option<RsvpStatus>,
+    ) -> Result<EventSummary, EventsServiceError> {
+        let event = self.view(id, Some(user)).await?;
+        if event.has_started() {
+            return Err(EventsServiceError::Started);
+        }
+        match status {
+            Some(status) => self.storage.set_rsvp(id, user.id, status.code()).await?,
+            None => {
+                self.storage.remove_rsvp(id, user.id).await?;
+            }
+        }
+        self.view(id, Some(user)).await
+    }
+}
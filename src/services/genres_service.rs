@@ -0,0 +1,221 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        ClubSlugError, GENRE_NAME_MAX_LEN, Genre, GenreNode, User, check_genre_slug, genre_tree,
+    },
+    storage::GenresStorage,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GenresServiceError {
+    NotFound,
+    InvalidSlug(ClubSlugError),
+    SlugTaken,
+    EmptyName,
+    NameTooLong,
+    /// The parent does not exist, or is the genre itself or a subgenre of it
+    InvalidParent,
+    /// Subgenres are moved or deleted first
+    HasSubgenres,
+    /// Only admins manage genres
+    Forbidden,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for GenresServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for GenresServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for GenresServiceError {}
+
+impl GenresServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            GenresServiceError::NotFound => locale.t("genre-not-found"),
+            GenresServiceError::InvalidSlug(e) => e.message(locale),
+            GenresServiceError::SlugTaken => locale.t("genre-slug-taken"),
+            GenresServiceError::EmptyName => locale.t("genre-name-required"),
+            GenresServiceError::NameTooLong => locale.t_args(
+                "genre-name-too-long",
+                &[("max", GENRE_NAME_MAX_LEN.to_string())],
+            ),
+            GenresServiceError::InvalidParent => locale.t("genre-parent-invalid"),
+            GenresServiceError::HasSubgenres => locale.t("genre-has-subgenres"),
+            GenresServiceError::Forbidden => locale.t("genre-forbidden"),
+            GenresServiceError::DatabaseError(_) => locale.t("genre-failed"),
+        }
+    }
+}
+
+/// What an admin filled in about a genre.
+#[derive(Debug, Clone)]
+pub struct NewGenre<'a> {
+    pub slug: &'a str,
+    pub name: &'a str,
+    /// `None` for a top-level genre
+    pub parent_id: Option<Uuid>,
+}
+
+/// The curated genre taxonomy, managed by admins, and the genres users pick
+/// as their interests.
+#[derive(Clone, Debug)]
+pub struct GenresService {
+    storage: GenresStorage,
+}
+
+impl GenresService {
+    pub fn new(storage: GenresStorage) -> Self {
+        Self { storage }
+    }
+    /// All genres, each followed by its subgenres.
+    pub async fn tree(&self) -> Result<Vec<GenreNode>, GenresServiceError> {
+        let genres = self.storage.list().await?;
+        Ok(genre_tree(genres))
+    }
+    pub async fn create(
+        &self,
+        admin: &User,
+        genre: NewGenre<'_>,
+    ) -> Result<Genre, GenresServiceError> {
+        let (slug, name) = self.check(admin, &genre).await?;
+        let created = self
+            .storage
+            .create(slug, name, genre.parent_id)
+            .await?
+            .ok_or(GenresServiceError::SlugTaken)?;
+        info!(target: "audit", user_id = %admin.id, genre_id = %created.id, slug = %created.slug, "genre created");
+        Ok(created)
+    }
+    /// Renames the genre and moves it under `genre.parent_id`.
+    pub async fn update(
+        &self,
+        admin: &User,
+        id: Uuid,
+        genre: NewGenre<'_>,
+    ) -> Result<Genre, GenresServiceError> {
+        let (slug, name) = self.check(admin, &genre).await?;
+        self.storage
+            .get_by_id(id)
+            .await?
+            .ok_or(GenresServiceError::NotFound)?;
+        // The genre exists, so no row means the parent is inside its subtree
+        let updated = self
+            .storage
+            .update(id, slug, name, genre.parent_id)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(e) if e.is_unique_violation() => {
+                    GenresServiceError::SlugTaken
+                }
+                other => other.into(),
+            })?
+            .ok_or(GenresServiceError::InvalidParent)?;
+        info!(target: "audit", user_id = %admin.id, genre_id = %updated.id, slug = %updated.slug, "genre updated");
+        Ok(updated)
+    }
+    /// Deleting a genre also drops it from users' interests.
+    pub async fn delete(&self, admin: &User, id: Uuid) -> Result<(), GenresServiceError> {
+        if !admin.is_admin {
+            return Err(GenresServiceError::Forbidden);
+        }
+        let deleted = self.storage.delete(id).await.map_err(|e| match e {
+            sqlx::Error::Database(e) if e.is_foreign_key_violation() => {
+                GenresServiceError::HasSubgenres
+            }
+            other => other.into(),
+        })?;
+        if !deleted {
+            return Err(GenresServiceError::NotFound);
+        }
+        info!(target: "audit", user_id = %admin.id, genre_id = %id, "genre deleted");
+        Ok(())
+    }
+    /// Trimmed slug and name once `admin` may save `genre`.
+    async fn check<'a>(
+        &self,
+        admin: &User,
+        genre: &NewGenre<'a>,
+    ) -> Result<(&'a str, &'a str), GenresServiceError> {
+        if !admin.is_admin {
+            return Err(GenresServiceError::Forbidden);
+        }
+        let slug = genre.slug.trim();
+        let name = genre.name.trim();
+        check_genre_slug(slug).map_err(GenresServiceError::InvalidSlug)?;
+        if name.is_empty() {
+            return Err(GenresServiceError::EmptyName);
+        }
+        if name.chars().count() > GENRE_NAME_MAX_LEN {
+            return Err(GenresServiceError::NameTooLong);
+        }
+        if let Some(parent_id) = genre.parent_id
+            && self.storage.get_by_id(parent_id).await?.is_none()
+        {
+            return Err(GenresServiceError::InvalidParent);
+        }
+        Ok((slug, name))
+    }
+    /// Ids of the genres `user` picked.
+    pub async fn interests(&self, user: &User) -> Result<Vec<Uuid>, GenresServiceError> {
+        let ids = self.storage.interest_ids(user.id).await?;
+        Ok(ids)
+    }
+    /// Adds the genre to the user's interests, or removes it.
+    pub async fn set_interest(
+        &self,
+        user: &User,
+        genre_id: Uuid,
+        interested: bool,
+    ) -> Result<(), GenresServiceError> {
+        if interested {
+            self.storage
+                .get_by_id(genre_id)
+                .await?
+                .ok_or(GenresServiceError::NotFound)?;
+            self.storage.add_interest(user.id, genre_id).await?;
+        } else {
+            self.storage.remove_interest(user.id, genre_id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_update_to_taken_slug(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let service = GenresService::new(GenresStorage::new(pool).await?);
+        let admin = User {
+            is_admin: true,
+            ..Default::default()
+        };
+        let genre = |slug| NewGenre {
+            slug,
+            name: "Genre",
+            parent_id: None,
+        };
+        service.create(&admin, genre("fantasy")).await?;
+        let other = service.create(&admin, genre("horror")).await?;
+
+        let renamed = service.update(&admin, other.id, genre("fantasy")).await;
+        assert!(matches!(renamed, Err(GenresServiceError::SlugTaken)));
+        let renamed = service.update(&admin, other.id, genre("gothic")).await?;
+        assert_eq!(renamed.slug, "gothic");
+
+        Ok(())
+    }
+}
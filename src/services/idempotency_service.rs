@@ -0,0 +1,205 @@
+use std::{error::Error, fmt::Display};
+
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    models::domain::{IDEMPOTENCY_KEY_MAX_LEN, IDEMPOTENCY_KEY_TTL_HOURS, IdempotentResponse},
+    storage::IdempotencyKeysStorage,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IdempotencyServiceError {
+    InvalidKey,
+    /// The key was first used for a different request
+    KeyReused,
+    /// The first request with the key has not finished yet
+    InProgress,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for IdempotencyServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for IdempotencyServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for IdempotencyServiceError {}
+
+/// A request carrying an `Idempotency-Key`.
+#[derive(Debug, Clone)]
+pub struct IdempotentRequest {
+    /// The client's key hashed with its credentials, so clients never see
+    /// each other's responses
+    key: String,
+    request_hash: String,
+}
+
+impl IdempotentRequest {
+    /// `credentials` are the request's auth headers, empty for anonymous calls.
+    pub fn new(
+        key: &str,
+        credentials: &[&[u8]],
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<Self, IdempotencyServiceError> {
+        let valid = !key.is_empty()
+            && key.len() <= IDEMPOTENCY_KEY_MAX_LEN
+            && key.bytes().all(|b| b.is_ascii_graphic());
+        if !valid {
+            return Err(IdempotencyServiceError::InvalidKey);
+        }
+        let mut scoped = Sha256::new();
+        for credential in credentials {
+            scoped.update(credential);
+            scoped.update(b"\n");
+        }
+        scoped.update(key.as_bytes());
+        let request_hash = Sha256::new()
+            .chain_update(method.as_bytes())
+            .chain_update(b" ")
+            .chain_update(path.as_bytes())
+            .chain_update(b"\n")
+            .chain_update(body)
+            .finalize();
+        Ok(Self {
+            key: format!("{:x}", scoped.finalize()),
+            request_hash: format!("{request_hash:x}"),
+        })
+    }
+}
+
+/// What to do with an [`IdempotentRequest`].
+#[derive(Debug, Clone)]
+pub enum IdempotencyDecision {
+    /// First use of the key: run the request and [`IdempotencyService::complete`] it
+    Run,
+    /// A retry: answer with the stored response
+    Replay(IdempotentResponse),
+}
+
+#[derive(Clone, Debug)]
+pub struct IdempotencyService {
+    storage: IdempotencyKeysStorage,
+}
+
+impl IdempotencyService {
+    pub fn new(storage: IdempotencyKeysStorage) -> Self {
+        Self { storage }
+    }
+    pub async fn begin(
+        &self,
+        request: &IdempotentRequest,
+    ) -> Result<IdempotencyDecision, IdempotencyServiceError> {
+        if self
+            .storage
+            .claim(
+                &request.key,
+                &request.request_hash,
+                IDEMPOTENCY_KEY_TTL_HOURS,
+            )
+            .await?
+        {
+            return Ok(IdempotencyDecision::Run);
+        }
+        // Released between the claim and now; let the client retry
+        let Some(stored) = self.storage.get(&request.key).await? else {
+            return Err(IdempotencyServiceError::InProgress);
+        };
+        if stored.request_hash != request.request_hash {
+            return Err(IdempotencyServiceError::KeyReused);
+        }
+        if stored.status.is_none() {
+            return Err(IdempotencyServiceError::InProgress);
+        }
+        Ok(IdempotencyDecision::Replay(stored))
+    }
+    /// Stores the response for retries. Server errors free the key instead,
+    /// so a retry runs the request again.
+    pub async fn complete(
+        &self,
+        request: &IdempotentRequest,
+        status: StatusCode,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), IdempotencyServiceError> {
+        if status.is_server_error() {
+            self.storage.release(&request.key).await?;
+        } else {
+            self.storage
+                .complete(&request.key, status.as_u16() as i16, content_type, body)
+                .await?;
+        }
+        Ok(())
+    }
+    /// Frees the key after the response could not be stored.
+    pub async fn abandon(
+        &self,
+        request: &IdempotentRequest,
+    ) -> Result<(), IdempotencyServiceError> {
+        self.storage.release(&request.key).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotent_request_hashes() {
+        let first = IdempotentRequest::new(
+            "key-1",
+            &[b"Bearer a".as_slice()],
+            "POST",
+            "/api/v1/users",
+            b"{}",
+        )
+        .unwrap();
+        let retry = IdempotentRequest::new(
+            "key-1",
+            &[b"Bearer a".as_slice()],
+            "POST",
+            "/api/v1/users",
+            b"{}",
+        )
+        .unwrap();
+        assert_eq!(first.key, retry.key);
+        assert_eq!(first.request_hash, retry.request_hash);
+
+        // Another client's key of the same name is a different key
+        let other = IdempotentRequest::new(
+            "key-1",
+            &[b"Bearer b".as_slice()],
+            "POST",
+            "/api/v1/users",
+            b"{}",
+        )
+        .unwrap();
+        assert_ne!(first.key, other.key);
+
+        let changed = IdempotentRequest::new(
+            "key-1",
+            &[b"Bearer a".as_slice()],
+            "POST",
+            "/api/v1/users",
+            b"{\"a\":1}",
+        )
+        .unwrap();
+        assert_ne!(first.request_hash, changed.request_hash);
+    }
+
+    #[test]
+    fn test_idempotency_key_rules() {
+        let request = |key: &str| IdempotentRequest::new(key, &[], "POST", "/", b"");
+        assert!(request("8e6f0a3c-2b1d-4c57-9d0e-1f2a3b4c5d6e").is_ok());
+        assert!(request("").is_err());
+        assert!(request("with space").is_err());
+        assert!(request(&"k".repeat(IDEMPOTENCY_KEY_MAX_LEN + 1)).is_err());
+    }
+}
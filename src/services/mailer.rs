@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outgoing mail. There is no SMTP transport yet: in development messages
+/// are written to files so their links can be followed, and elsewhere they
+/// are dropped. Bodies carry secret tokens, so they never reach the log.
+#[derive(Clone, Debug, Default)]
+pub struct Mailer {
+    /// Where development messages go, `None` drops them
+    outbox: Option<PathBuf>,
+}
+
+impl Mailer {
+    /// Writes messages to `culturelist-mail` in the temp dir when
+    /// `environment` is development.
+    pub fn new(environment: &str) -> Self {
+        let outbox =
+            (environment == "development").then(|| std::env::temp_dir().join("culturelist-mail"));
+        Self { outbox }
+    }
+    pub async fn send(&self, email: Email) {
+        let Some(outbox) = &self.outbox else {
+            warn!(target: "mail", subject = %email.subject, "no mail transport, message dropped");
+            return;
+        };
+        let path = outbox.join(format!("{}.eml", Uuid::new_v4()));
+        let message = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            email.to, email.subject, email.body
+        );
+        let written = match tokio::fs::create_dir_all(outbox).await {
+            Ok(()) => tokio::fs::write(&path, message).await,
+            Err(e) => Err(e),
+        };
+        let (to, subject) = (&email.to, &email.subject);
+        match written {
+            Ok(()) => info!(target: "mail", %to, %subject, path = %path.display(), "written"),
+            Err(e) => warn!(target: "mail", %to, %subject, "failed to write message: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_development_writes_outbox() {
+        let outbox = std::env::temp_dir().join(format!("mail-{}", Uuid::new_v4()));
+        let mailer = Mailer {
+            outbox: Some(outbox.clone()),
+        };
+        mailer
+            .send(Email {
+                to: "reader@example.com".into(),
+                subject: "Hello".into(),
+                body: "secret link".into(),
+            })
+            .await;
+        let mut entries = std::fs::read_dir(&outbox).unwrap();
+        let message = std::fs::read_to_string(entries.next().unwrap().unwrap().path()).unwrap();
+        assert!(message.starts_with("To: reader@example.com\nSubject: Hello\n\n"));
+        assert!(message.contains("secret link"));
+        std::fs::remove_dir_all(outbox).unwrap();
+
+        assert!(Mailer::new("production").outbox.is_none());
+    }
+}
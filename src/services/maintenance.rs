@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use crate::{
+    models::domain::{IDEMPOTENCY_KEY_TTL_HOURS, TaskOutcome},
+    services::Task,
+    smoke::{SMOKE_EMAIL_DOMAIN, SMOKE_USERNAME_PREFIX},
+    storage::{
+        DataExportsStorage, EmailChangesStorage, IdempotencyKeysStorage, SystemStorage,
+        UsersStorage,
+    },
+};
+
+/// Rows deleted per statement, so a large backlog never holds long locks.
+const CLEANUP_BATCH_SIZE: i64 = 1000;
+
+/// Purges expired sessions, email change tokens, idempotency keys, data
+/// exports and the accounts `app smoke` signs up, in batches. Password reset
+/// and email verification tokens are not stored yet.
+pub struct CleanupTask {
+    system: SystemStorage,
+    email_changes: EmailChangesStorage,
+    idempotency_keys: IdempotencyKeysStorage,
+    data_exports: DataExportsStorage,
+    users: UsersStorage,
+}
+
+impl CleanupTask {
+    pub fn new(
+        system: SystemStorage,
+        email_changes: EmailChangesStorage,
+        idempotency_keys: IdempotencyKeysStorage,
+        data_exports: DataExportsStorage,
+        users: UsersStorage,
+    ) -> Self {
+        Self {
+            system,
+            email_changes,
+            idempotency_keys,
+            data_exports,
+            users,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for CleanupTask {
+    fn name(&self) -> &'static str {
+        "cleanup"
+    }
+    fn default_interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+    async fn run(&self) -> anyhow::Result<TaskOutcome> {
+        let sessions = purge_sessions(&self.system).await?;
+        let email_changes = purge(|| self.email_changes.delete_expired(CLEANUP_BATCH_SIZE)).await?;
+        let idempotency_keys = purge(|| {
+            self.idempotency_keys
+                .delete_expired(IDEMPOTENCY_KEY_TTL_HOURS, CLEANUP_BATCH_SIZE)
+        })
+        .await?;
+        let data_exports = purge(|| self.data_exports.delete_expired(CLEANUP_BATCH_SIZE)).await?;
+        let smoke_accounts = purge(|| {
+            self.users.delete_disposable(
+                SMOKE_USERNAME_PREFIX,
+                SMOKE_EMAIL_DOMAIN,
+                CLEANUP_BATCH_SIZE,
+            )
+        })
+        .await?;
+        Ok(TaskOutcome::default()
+            .with_count("sessions", sessions)
+            .with_count("email_changes", email_changes)
+            .with_count("idempotency_keys", idempotency_keys)
+            .with_count("data_exports", data_exports)
+            .with_count("smoke_accounts", smoke_accounts))
+    }
+}
+
+/// Deletes every expired session, returning how many were removed.
+pub async fn purge_sessions(system: &SystemStorage) -> sqlx::Result<u64> {
+    purge(|| system.delete_expired_sessions(CLEANUP_BATCH_SIZE)).await
+}
+
+/// Repeats `delete_batch` until a batch comes back short, returning the
+/// total number of deleted rows.
+async fn purge<F, Fut>(delete_batch: F) -> sqlx::Result<u64>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = sqlx::Result<u64>>,
+{
+    let mut total = 0;
+    loop {
+        let deleted = delete_batch().await?;
+        total += deleted;
+        if deleted < CLEANUP_BATCH_SIZE as u64 {
+            return Ok(total);
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[tokio::test]
+    async fn test_purge_stops_on_short_batch() -> anyhow::Result<()> {
+        let calls = AtomicU64::new(0);
+        let total = purge(|| async {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            Ok(if call < 2 {
+                CLEANUP_BATCH_SIZE as u64
+            } else {
+                7
+            })
+        })
+        .await?;
+        assert_eq!(total, 2 * CLEANUP_BATCH_SIZE as u64 + 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+}
@@ -1,2 +1,50 @@
+mod api_keys_service;
+mod badges_service;
+mod blocks_service;
+mod bot_protection_service;
+mod clubs_service;
+mod data_exports;
+mod email_change_service;
+mod email_domains;
+mod event_reminders;
+mod events_service;
+mod genres_service;
+mod idempotency_service;
+mod mailer;
+mod maintenance;
+mod moderation_service;
+mod page_cache;
+mod polls_service;
+mod scheduler;
+mod search_service;
+mod system_service;
+mod traffic_stats;
+mod username_service;
 mod users_service;
+mod venues_service;
+pub use api_keys_service::{ApiKeysService, ApiKeysServiceError, CreatedApiKey};
+pub use badges_service::{BadgesService, BadgesServiceError};
+pub use blocks_service::{BlocksService, BlocksServiceError};
+pub use bot_protection_service::{BotProtectionError, BotProtectionService};
+pub use clubs_service::{ClubView, ClubsService, ClubsServiceError};
+pub use data_exports::{DataExportsService, DataExportsServiceError, DataExportsTask};
+pub use email_change_service::{EmailChangeService, EmailChangeServiceError};
+pub use email_domains::{DisposableDomainsTask, EmailDomainPolicy};
+pub use event_reminders::EventRemindersTask;
+pub use events_service::{EventsService, EventsServiceError, NewEvent};
+pub use genres_service::{GenresService, GenresServiceError, NewGenre};
+pub use idempotency_service::{
+    IdempotencyDecision, IdempotencyService, IdempotencyServiceError, IdempotentRequest,
+};
+pub use mailer::Mailer;
+pub use maintenance::{CleanupTask, purge_sessions};
+pub use moderation_service::{ModerationService, ModerationServiceError};
+pub use page_cache::{CachedPage, PageCache, PageCacheSettings};
+pub use polls_service::{NewPoll, PollUpdate, PollsService, PollsServiceError};
+pub use scheduler::{Scheduler, SchedulerSettings, Task};
+pub use search_service::{MIN_QUERY_CHARS, SearchService};
+pub use system_service::SystemService;
+pub use traffic_stats::TrafficStats;
+pub use username_service::{UsernameService, UsernameServiceError};
 pub use users_service::{UsersService, UsersServiceError};
+pub use venues_service::{NewVenue, VenueView, VenuesService, VenuesServiceError};
@@ -0,0 +1,205 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        ModerationAction, ModerationQueueItem, ModerationSettings, REPORT_ACTIONED,
+        REPORT_DISMISSED, Report, ReportTarget, User,
+    },
+    services::PageCache,
+    storage::{ReportsStorage, UsersStorage},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ModerationServiceError {
+    NotFound,
+    /// The reporter already has an open report on the target
+    AlreadyReported,
+    OwnContent,
+    EmptyReason,
+    ReasonTooLong(usize),
+    /// Only profiles have an owner to ban
+    NotBannable,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for ModerationServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for ModerationServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for ModerationServiceError {}
+
+impl ModerationServiceError {
+    /// Text shown next to the report form or in the moderation queue.
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            ModerationServiceError::NotFound => locale.t("report-not-found"),
+            ModerationServiceError::AlreadyReported => locale.t("report-duplicate"),
+            ModerationServiceError::OwnContent => locale.t("report-own"),
+            ModerationServiceError::EmptyReason => locale.t("report-reason-required"),
+            ModerationServiceError::ReasonTooLong(max) => {
+                locale.t_args("report-reason-too-long", &[("max", max.to_string())])
+            }
+            ModerationServiceError::NotBannable => locale.t("moderation-not-bannable"),
+            ModerationServiceError::DatabaseError(_) => locale.t("moderation-failed"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ModerationService {
+    storage: ReportsStorage,
+    users: UsersStorage,
+    settings: ModerationSettings,
+    page_cache: PageCache,
+}
+
+impl ModerationService {
+    pub fn new(storage: ReportsStorage, users: UsersStorage, settings: ModerationSettings) -> Self {
+        Self {
+            storage,
+            users,
+            settings,
+            page_cache: PageCache::default(),
+        }
+    }
+    /// Hidden content disappears from public pages, so hiding drops the cached ones.
+    pub fn with_page_cache(mut self, page_cache: PageCache) -> Self {
+        self.page_cache = page_cache;
+        self
+    }
+    /// Files a report and hides the target once enough distinct users
+    /// reported it, see [`ModerationSettings::auto_hide_threshold`].
+    pub async fn report(
+        &self,
+        reporter: &User,
+        target: ReportTarget,
+        target_id: Uuid,
+        reason: &str,
+    ) -> Result<Report, ModerationServiceError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(ModerationServiceError::EmptyReason);
+        }
+        if reason.chars().count() > self.settings.max_reason_length {
+            return Err(ModerationServiceError::ReasonTooLong(
+                self.settings.max_reason_length,
+            ));
+        }
+        match target {
+            ReportTarget::Profile => {
+                if target_id == reporter.id {
+                    return Err(ModerationServiceError::OwnContent);
+                }
+                self.users
+                    .get_by_id(target_id)
+                    .await?
+                    .ok_or(ModerationServiceError::NotFound)?;
+            }
+            // No reviews or comments are stored yet
+            ReportTarget::Review | ReportTarget::Comment => {
+                return Err(ModerationServiceError::NotFound);
+            }
+        }
+        let report = self
+            .storage
+            .create(target.code(), target_id, reporter.id, reason)
+            .await?
+            .ok_or(ModerationServiceError::AlreadyReported)?;
+        info!(target: "audit", reporter_id = %reporter.id, target = target.code(), %target_id, "content reported");
+        let count = self.storage.count_open(target.code(), target_id).await?;
+        if self.settings.should_hide(count) {
+            self.storage.hide(target.code(), target_id).await?;
+            self.page_cache.invalidate_all();
+            info!(target: "audit", target = target.code(), %target_id, reports = count, "content hidden automatically");
+        }
+        Ok(report)
+    }
+    pub async fn queue(&self) -> Result<Vec<ModerationQueueItem>, ModerationServiceError> {
+        let queue = self.storage.list_queue().await?;
+        Ok(queue)
+    }
+    pub async fn is_hidden(
+        &self,
+        target: ReportTarget,
+        target_id: Uuid,
+    ) -> Result<bool, ModerationServiceError> {
+        let hidden = self.storage.is_hidden(target.code(), target_id).await?;
+        Ok(hidden)
+    }
+    /// Closes the open reports on the target with `action`. Returns the
+    /// banned user, whose cached sessions the caller must drop.
+    pub async fn resolve(
+        &self,
+        admin: &User,
+        target: ReportTarget,
+        target_id: Uuid,
+        action: ModerationAction,
+    ) -> Result<Option<User>, ModerationServiceError> {
+        if action == ModerationAction::Ban && target != ReportTarget::Profile {
+            return Err(ModerationServiceError::NotBannable);
+        }
+        let admin_id = admin.id;
+        let status = match action {
+            ModerationAction::Dismiss => REPORT_DISMISSED,
+            ModerationAction::Hide | ModerationAction::Ban => REPORT_ACTIONED,
+        };
+        // The decision, the visibility and the ban land together or not at all
+        let (closed, banned) = self
+            .users
+            .with_tx(|conn| {
+                Box::pin(async move {
+                    let closed = ReportsStorage::resolve_target_in(
+                        &mut *conn,
+                        target.code(),
+                        target_id,
+                        status,
+                        admin_id,
+                    )
+                    .await?;
+                    let mut banned = None;
+                    match action {
+                        ModerationAction::Dismiss => {
+                            ReportsStorage::unhide_in(&mut *conn, target.code(), target_id).await?
+                        }
+                        ModerationAction::Hide => {
+                            ReportsStorage::hide_in(
+                                &mut *conn,
+                                target.code(),
+                                target_id,
+                                Some(admin_id),
+                            )
+                            .await?
+                        }
+                        ModerationAction::Ban => {
+                            ReportsStorage::hide_in(
+                                &mut *conn,
+                                target.code(),
+                                target_id,
+                                Some(admin_id),
+                            )
+                            .await?;
+                            banned = UsersStorage::ban_in(&mut *conn, target_id).await?;
+                        }
+                    }
+                    Ok((closed, banned))
+                })
+            })
+            .await?;
+        if closed == 0 && banned.is_none() {
+            return Err(ModerationServiceError::NotFound);
+        }
+        self.page_cache.invalidate_all();
+        info!(target: "audit", admin_id = %admin_id, target = target.code(), %target_id, action = ?action, reports = closed, "reports resolved");
+        Ok(banned)
+    }
+}
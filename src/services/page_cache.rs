@@ -0,0 +1,120 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{body::Bytes, http::HeaderValue};
+use moka::future::Cache;
+use serde::Deserialize;
+
+/// `[cache]` configuration section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PageCacheSettings {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+    pub max_entries: u64,
+    /// Paths served from the cache to anonymous visitors; a trailing `*`
+    /// matches any path with that prefix
+    pub paths: Vec<String>,
+}
+
+impl Default for PageCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: 30,
+            max_entries: 1000,
+            paths: vec!["/".to_string()],
+        }
+    }
+}
+
+impl PageCacheSettings {
+    fn matches(&self, path: &str) -> bool {
+        self.paths
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => path == pattern,
+            })
+    }
+}
+
+/// A rendered page with per-request values left as placeholders.
+#[derive(Debug, Clone)]
+pub struct CachedPage {
+    pub content_type: HeaderValue,
+    pub body: Bytes,
+}
+
+/// Rendered public pages for anonymous visitors, keyed by what the page
+/// depends on. Services that change what those pages show call
+/// [`PageCache::invalidate_all`].
+#[derive(Debug, Clone)]
+pub struct PageCache {
+    settings: Arc<PageCacheSettings>,
+    pages: Cache<String, Arc<CachedPage>>,
+}
+
+impl Default for PageCache {
+    /// Disabled cache for services built without one.
+    fn default() -> Self {
+        Self::new(PageCacheSettings {
+            enabled: false,
+            ..Default::default()
+        })
+    }
+}
+
+impl PageCache {
+    pub fn new(settings: PageCacheSettings) -> Self {
+        let pages = Cache::builder()
+            .max_capacity(settings.max_entries)
+            .time_to_live(Duration::from_secs(settings.ttl_secs))
+            .build();
+        Self {
+            settings: Arc::new(settings),
+            pages,
+        }
+    }
+    pub fn is_cacheable(&self, path: &str) -> bool {
+        self.settings.enabled && self.settings.matches(path)
+    }
+    pub async fn get(&self, key: &str) -> Option<Arc<CachedPage>> {
+        self.pages.get(key).await
+    }
+    pub async fn insert(&self, key: String, page: CachedPage) {
+        self.pages.insert(key, Arc::new(page)).await;
+    }
+    pub fn invalidate_all(&self) {
+        self.pages.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_patterns() {
+        let cache = PageCache::new(PageCacheSettings {
+            paths: vec!["/".to_string(), "/u/*".to_string()],
+            ..Default::default()
+        });
+        assert!(cache.is_cacheable("/"));
+        assert!(cache.is_cacheable("/u/reader"));
+        assert!(!cache.is_cacheable("/login"));
+        assert!(!PageCache::default().is_cacheable("/"));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_all() {
+        let cache = PageCache::new(PageCacheSettings::default());
+        let page = CachedPage {
+            content_type: HeaderValue::from_static("text/html"),
+            body: Bytes::from_static(b"<p>hi</p>"),
+        };
+        cache.insert("ru||/".to_string(), page).await;
+        assert!(cache.get("ru||/").await.is_some());
+        cache.invalidate_all();
+        assert!(cache.get("ru||/").await.is_none());
+    }
+}
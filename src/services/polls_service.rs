@@ -0,0 +1,284 @@
+use std::{error::Error, fmt::Display};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        Club, ClubRole, POLL_MAX_OPTIONS, POLL_MIN_OPTIONS, POLL_OPTION_MAX_LEN,
+        POLL_QUESTION_MAX_LEN, Poll, PollResults, PollView, User, parse_poll_options,
+        toggle_choice,
+    },
+    storage::{ClubsStorage, CreatePoll, PollsStorage},
+};
+
+/// Polls shown on a club page.
+const CLUB_POLLS_LIMIT: i64 = 10;
+/// Vote notifications a slow live subscriber may fall behind by before it
+/// skips to the latest counts.
+const UPDATES_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PollsServiceError {
+    NotFound,
+    ClubNotFound,
+    EmptyQuestion,
+    QuestionTooLong,
+    TooFewOptions,
+    TooManyOptions,
+    OptionTooLong,
+    /// The deadline was filled in but could not be read
+    InvalidDeadline,
+    /// The deadline is not in the future
+    DeadlineInPast,
+    /// Only club admins and owners create polls
+    Forbidden,
+    /// Only club members vote
+    NotMember,
+    /// Voting ends at the deadline
+    Closed,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for PollsServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for PollsServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for PollsServiceError {}
+
+impl PollsServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            PollsServiceError::NotFound => locale.t("poll-not-found"),
+            PollsServiceError::ClubNotFound => locale.t("club-not-found"),
+            PollsServiceError::EmptyQuestion => locale.t("poll-question-required"),
+            PollsServiceError::QuestionTooLong => locale.t_args(
+                "poll-question-too-long",
+                &[("max", POLL_QUESTION_MAX_LEN.to_string())],
+            ),
+            PollsServiceError::TooFewOptions => locale.t_args(
+                "poll-too-few-options",
+                &[("min", POLL_MIN_OPTIONS.to_string())],
+            ),
+            PollsServiceError::TooManyOptions => locale.t_args(
+                "poll-too-many-options",
+                &[("max", POLL_MAX_OPTIONS.to_string())],
+            ),
+            PollsServiceError::OptionTooLong => locale.t_args(
+                "poll-option-too-long",
+                &[("max", POLL_OPTION_MAX_LEN.to_string())],
+            ),
+            PollsServiceError::InvalidDeadline => locale.t("poll-deadline-invalid"),
+            PollsServiceError::DeadlineInPast => locale.t("poll-deadline-past"),
+            PollsServiceError::Forbidden => locale.t("poll-forbidden"),
+            PollsServiceError::NotMember => locale.t("poll-members-only"),
+            PollsServiceError::Closed => locale.t("poll-closed"),
+            PollsServiceError::DatabaseError(_) => locale.t("poll-failed"),
+        }
+    }
+}
+
+/// What the creator filled in; `closes_at` is already resolved from the
+/// creator's timezone.
+#[derive(Debug, Clone)]
+pub struct NewPoll<'a> {
+    pub question: &'a str,
+    /// One option per line
+    pub options: &'a str,
+    pub multiple: bool,
+    pub results: PollResults,
+    /// `None` keeps the poll open
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+/// A poll that got a vote.
+#[derive(Debug, Clone, Copy)]
+pub struct PollUpdate {
+    pub club_id: Uuid,
+    pub poll_id: Uuid,
+}
+
+/// Club polls. Every vote is announced on a broadcast channel so open
+/// pages can refresh the counts, see [`Self::subscribe`].
+#[derive(Clone, Debug)]
+pub struct PollsService {
+    storage: PollsStorage,
+    clubs: ClubsStorage,
+    updates: broadcast::Sender<PollUpdate>,
+}
+
+impl PollsService {
+    pub fn new(storage: PollsStorage, clubs: ClubsStorage) -> Self {
+        let (updates, _) = broadcast::channel(UPDATES_CAPACITY);
+        Self {
+            storage,
+            clubs,
+            updates,
+        }
+    }
+    /// Polls as they get votes.
+    pub fn subscribe(&self) -> broadcast::Receiver<PollUpdate> {
+        self.updates.subscribe()
+    }
+    pub async fn club(&self, slug: &str) -> Result<Club, PollsServiceError> {
+        self.clubs
+            .get_by_slug(slug.trim())
+            .await?
+            .ok_or(PollsServiceError::ClubNotFound)
+    }
+    pub async fn create(
+        &self,
+        creator: &User,
+        club_slug: &str,
+        poll: NewPoll<'_>,
+    ) -> Result<Poll, PollsServiceError> {
+        let question = poll.question.trim();
+        if question.is_empty() {
+            return Err(PollsServiceError::EmptyQuestion);
+        }
+        if question.chars().count() > POLL_QUESTION_MAX_LEN {
+            return Err(PollsServiceError::QuestionTooLong);
+        }
+        let options = parse_poll_options(poll.options);
+        if options.len() < POLL_MIN_OPTIONS {
+            return Err(PollsServiceError::TooFewOptions);
+        }
+        if options.len() > POLL_MAX_OPTIONS {
+            return Err(PollsServiceError::TooManyOptions);
+        }
+        if options
+            .iter()
+            .any(|o| o.chars().count() > POLL_OPTION_MAX_LEN)
+        {
+            return Err(PollsServiceError::OptionTooLong);
+        }
+        if poll.closes_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(PollsServiceError::DeadlineInPast);
+        }
+        let club = self.club(club_slug).await?;
+        if !self
+            .role(club.id, creator.id)
+            .await?
+            .is_some_and(|r| r >= ClubRole::Admin)
+        {
+            return Err(PollsServiceError::Forbidden);
+        }
+        let created = self
+            .storage
+            .create(CreatePoll {
+                club_id: club.id,
+                question,
+                options: &options,
+                multiple: poll.multiple,
+                results: poll.results.code(),
+                closes_at: poll.closes_at,
+                created_by: creator.id,
+            })
+            .await?;
+        info!(target: "audit", user_id = %creator.id, poll_id = %created.id, club_id = %club.id, "poll created");
+        Ok(created)
+    }
+    /// The newest polls of the club, as `viewer` sees them.
+    pub async fn club_polls(
+        &self,
+        club_id: Uuid,
+        viewer: Option<&User>,
+    ) -> Result<Vec<PollView>, PollsServiceError> {
+        let polls = self.storage.list_by_club(club_id, CLUB_POLLS_LIMIT).await?;
+        let member = match viewer {
+            Some(viewer) => self.role(club_id, viewer.id).await?.is_some(),
+            None => false,
+        };
+        let mut views = Vec::with_capacity(polls.len());
+        for poll in polls {
+            views.push(self.poll_view(poll, viewer, member).await?);
+        }
+        Ok(views)
+    }
+    pub async fn view(
+        &self,
+        id: Uuid,
+        viewer: Option<&User>,
+    ) -> Result<PollView, PollsServiceError> {
+        let poll = self.get(id).await?;
+        let member = match viewer {
+            Some(viewer) => self.role(poll.club_id, viewer.id).await?.is_some(),
+            None => false,
+        };
+        self.poll_view(poll, viewer, member).await
+    }
+    /// Votes for the option, or withdraws the vote if `user` already chose
+    /// it; see [`toggle_choice`].
+    pub async fn vote(
+        &self,
+        user: &User,
+        poll_id: Uuid,
+        option_id: Uuid,
+    ) -> Result<(), PollsServiceError> {
+        let poll = self.get(poll_id).await?;
+        if poll.is_closed() {
+            return Err(PollsServiceError::Closed);
+        }
+        if self.role(poll.club_id, user.id).await?.is_none() {
+            return Err(PollsServiceError::NotMember);
+        }
+        let options = self.storage.list_options(poll.id).await?;
+        if !options.iter().any(|o| o.id == option_id) {
+            return Err(PollsServiceError::NotFound);
+        }
+        self.storage
+            .set_choices(poll.id, user.id, |choices| {
+                toggle_choice(choices, option_id, poll.multiple)
+            })
+            .await?;
+        // Nobody listening is fine
+        let _ = self.updates.send(PollUpdate {
+            club_id: poll.club_id,
+            poll_id: poll.id,
+        });
+        Ok(())
+    }
+    async fn poll_view(
+        &self,
+        poll: Poll,
+        viewer: Option<&User>,
+        member: bool,
+    ) -> Result<PollView, PollsServiceError> {
+        let options = self.storage.list_options(poll.id).await?;
+        let voters = self.storage.count_voters(poll.id).await?;
+        let choices = match viewer {
+            Some(viewer) => self.storage.list_choices(poll.id, viewer.id).await?,
+            None => Vec::new(),
+        };
+        Ok(PollView {
+            can_vote: member && !poll.is_closed(),
+            poll,
+            options,
+            voters,
+            choices,
+        })
+    }
+    async fn get(&self, id: Uuid) -> Result<Poll, PollsServiceError> {
+        self.storage
+            .get_by_id(id)
+            .await?
+            .ok_or(PollsServiceError::NotFound)
+    }
+    async fn role(
+        &self,
+        club_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<ClubRole>, PollsServiceError> {
+        let role = self.clubs.get_role(club_id, user_id).await?;
+        Ok(role.as_deref().and_then(ClubRole::from_code))
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde::Deserialize;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{error, info};
+
+use crate::models::domain::{TaskOutcome, TaskStatus};
+
+/// `[scheduler]` configuration section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SchedulerSettings {
+    pub enabled: bool,
+    /// Seconds between runs by task name, overriding the task's default;
+    /// 0 disables the task
+    pub intervals: BTreeMap<String, u64>,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intervals: BTreeMap::new(),
+        }
+    }
+}
+
+impl SchedulerSettings {
+    fn interval(&self, task: &dyn Task) -> Option<Duration> {
+        if !self.enabled {
+            return None;
+        }
+        match self.intervals.get(task.name()) {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(*secs)),
+            None => Some(task.default_interval()),
+        }
+    }
+}
+
+/// Periodic background work run by the [`Scheduler`].
+#[async_trait::async_trait]
+pub trait Task: Send + Sync + 'static {
+    /// Key in `[scheduler.intervals]`
+    fn name(&self) -> &'static str;
+    fn default_interval(&self) -> Duration;
+    /// Returns counts of the work done, summed up on the admin page.
+    async fn run(&self) -> anyhow::Result<TaskOutcome>;
+}
+
+/// Runs registered [`Task`]s on their intervals in tokio tasks owned by the
+/// app, keeping the outcome of each last run.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    settings: Arc<SchedulerSettings>,
+    tasks: Vec<Arc<dyn Task>>,
+    statuses: Arc<RwLock<BTreeMap<&'static str, TaskStatus>>>,
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl Debug for Scheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<_> = self.tasks.iter().map(|t| t.name()).collect();
+        f.debug_struct("Scheduler")
+            .field("settings", &self.settings)
+            .field("tasks", &names)
+            .finish()
+    }
+}
+
+impl Scheduler {
+    pub fn new(settings: SchedulerSettings) -> Self {
+        Self {
+            settings: Arc::new(settings),
+            ..Default::default()
+        }
+    }
+    pub fn with_task(mut self, task: impl Task) -> Self {
+        let task: Arc<dyn Task> = Arc::new(task);
+        let status = TaskStatus {
+            name: task.name(),
+            interval_secs: self.settings.interval(task.as_ref()).map(|i| i.as_secs()),
+            runs: 0,
+            last_started_at: None,
+            last_finished_at: None,
+            last_outcome: None,
+            last_error: None,
+            totals: BTreeMap::new(),
+        };
+        self.statuses
+            .write()
+            .expect("scheduler status lock poisoned")
+            .insert(task.name(), status);
+        self.tasks.push(task);
+        self
+    }
+    /// Spawns one loop per enabled task; the first run happens right away.
+    pub fn start(&self) {
+        let mut handles = self
+            .handles
+            .lock()
+            .expect("scheduler handles lock poisoned");
+        for task in &self.tasks {
+            let Some(interval) = self.settings.interval(task.as_ref()) else {
+                info!(task = task.name(), "scheduled task disabled");
+                continue;
+            };
+            let task = task.clone();
+            let statuses = self.statuses.clone();
+            handles.push(tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                loop {
+                    ticker.tick().await;
+                    run_once(task.as_ref(), &statuses).await;
+                }
+            }));
+        }
+    }
+    /// Stops the task loops; a run in progress is cancelled.
+    pub fn shutdown(&self) {
+        let mut handles = self
+            .handles
+            .lock()
+            .expect("scheduler handles lock poisoned");
+        for handle in handles.drain(..) {
+            handle.abort();
+        }
+    }
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        self.statuses
+            .read()
+            .expect("scheduler status lock poisoned")
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+async fn run_once(task: &dyn Task, statuses: &RwLock<BTreeMap<&'static str, TaskStatus>>) {
+    update(statuses, task.name(), |status| {
+        status.last_started_at = Some(Utc::now())
+    });
+    let result = task.run().await;
+    match &result {
+        Ok(outcome) => {
+            for (name, count) in &outcome.counts {
+                info!(target: "metrics", task = task.name(), counter = name, count, "scheduled task count");
+            }
+            info!(task = task.name(), %outcome, "scheduled task finished");
+        }
+        Err(e) => error!(task = task.name(), "scheduled task failed: {e:?}"),
+    }
+    update(statuses, task.name(), |status| {
+        status.runs += 1;
+        status.last_finished_at = Some(Utc::now());
+        match result {
+            Ok(outcome) => {
+                for (name, count) in &outcome.counts {
+                    *status.totals.entry(*name).or_default() += count;
+                }
+                status.last_outcome = Some(outcome);
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e.to_string()),
+        }
+    });
+}
+
+fn update(
+    statuses: &RwLock<BTreeMap<&'static str, TaskStatus>>,
+    name: &'static str,
+    f: impl FnOnce(&mut TaskStatus),
+) {
+    if let Some(status) = statuses
+        .write()
+        .expect("scheduler status lock poisoned")
+        .get_mut(name)
+    {
+        f(status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    struct Counter(Arc<AtomicU64>);
+
+    #[async_trait::async_trait]
+    impl Task for Counter {
+        fn name(&self) -> &'static str {
+            "counter"
+        }
+        fn default_interval(&self) -> Duration {
+            Duration::from_secs(3600)
+        }
+        async fn run(&self) -> anyhow::Result<TaskOutcome> {
+            let n = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            if n > 2 {
+                anyhow::bail!("third run");
+            }
+            Ok(TaskOutcome::default().with_count("rows", 10))
+        }
+    }
+
+    #[test]
+    fn test_intervals() {
+        let counter = Counter(Arc::default());
+        let mut settings = SchedulerSettings::default();
+        assert_eq!(settings.interval(&counter), Some(Duration::from_secs(3600)));
+        settings.intervals.insert("counter".into(), 60);
+        assert_eq!(settings.interval(&counter), Some(Duration::from_secs(60)));
+        settings.intervals.insert("counter".into(), 0);
+        assert_eq!(settings.interval(&counter), None);
+        settings.intervals.clear();
+        settings.enabled = false;
+        assert_eq!(settings.interval(&counter), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_once_records_status() {
+        let runs = Arc::new(AtomicU64::new(0));
+        let scheduler = Scheduler::new(SchedulerSettings::default()).with_task(Counter(runs));
+        let task = scheduler.tasks[0].clone();
+
+        run_once(task.as_ref(), &scheduler.statuses).await;
+        let status = &scheduler.statuses()[0];
+        assert_eq!(status.runs, 1);
+        assert_eq!(
+            status.last_outcome.as_ref().unwrap().to_string(),
+            "rows: 10"
+        );
+        assert!(status.last_error.is_none());
+
+        run_once(task.as_ref(), &scheduler.statuses).await;
+        run_once(task.as_ref(), &scheduler.statuses).await;
+        let status = &scheduler.statuses()[0];
+        assert_eq!(status.runs, 3);
+        assert_eq!(status.totals.get("rows"), Some(&20));
+        assert!(status.last_outcome.is_some());
+        assert_eq!(status.last_error.as_deref(), Some("third run"));
+    }
+}
@@ -0,0 +1,102 @@
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        Palette, PaletteAction, PaletteEntry, SearchSuggestions, User, UserSuggestion, escape_like,
+        fuzzy_score,
+    },
+    storage::UsersStorage,
+};
+
+/// Shorter queries match too much to be useful.
+pub const MIN_QUERY_CHARS: usize = 2;
+/// Suggestions per group.
+const SUGGESTIONS_PER_GROUP: i64 = 5;
+/// Usernames offered while typing a mention.
+const MENTION_SUGGESTIONS: i64 = 10;
+
+#[derive(Clone, Debug)]
+pub struct SearchService {
+    users: UsersStorage,
+}
+
+impl SearchService {
+    pub fn new(users: UsersStorage) -> Self {
+        Self { users }
+    }
+    /// Grouped suggestions for the search box; empty for short queries.
+    /// When nothing contains the query, users with similar names are offered
+    /// in case of a typo. Users the viewer blocked or muted, or who blocked
+    /// them, are left out.
+    pub async fn suggest(
+        &self,
+        query: &str,
+        viewer: Option<&User>,
+    ) -> sqlx::Result<SearchSuggestions> {
+        let query = query.trim();
+        if query.chars().count() < MIN_QUERY_CHARS {
+            return Ok(SearchSuggestions::default());
+        }
+        let pattern = escape_like(query);
+        let viewer_id = viewer.map(|u| u.id);
+        let mut users = self
+            .users
+            .suggest(&pattern, viewer_id, SUGGESTIONS_PER_GROUP)
+            .await?;
+        if users.is_empty() {
+            users = self
+                .users
+                .suggest_similar(query, viewer_id, SUGGESTIONS_PER_GROUP)
+                .await?;
+        }
+        Ok(SearchSuggestions { users })
+    }
+    /// Usernames starting with `query` for mention and share autocompletion,
+    /// without the users `viewer_id` blocked or muted or who blocked them.
+    /// A leading `@` is ignored; an empty query suggests nobody.
+    pub async fn mention_suggestions(
+        &self,
+        query: &str,
+        viewer_id: Uuid,
+    ) -> sqlx::Result<Vec<UserSuggestion>> {
+        let query = query.trim().trim_start_matches('@');
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.users
+            .suggest_usernames(&escape_like(query), Some(viewer_id), MENTION_SUGGESTIONS)
+            .await
+    }
+    /// Command palette results for `query`: the viewer's quick actions ranked
+    /// by fuzzy match on their labels and addresses, and people from
+    /// [`Self::suggest`]. An empty query lists every action.
+    pub async fn palette(
+        &self,
+        query: &str,
+        viewer: Option<&User>,
+        guest: bool,
+        locale: Locale,
+    ) -> sqlx::Result<Palette> {
+        let query = query.trim();
+        let mut ranked: Vec<_> = PaletteAction::available(viewer, guest)
+            .into_iter()
+            .filter_map(|action| {
+                let label = action.label(locale);
+                let score = fuzzy_score(query, &label).max(fuzzy_score(query, action.url))?;
+                Some((
+                    score,
+                    PaletteEntry {
+                        label,
+                        url: action.url,
+                    },
+                ))
+            })
+            .collect();
+        // Stable, so equal scores keep the order of PaletteAction::ALL
+        ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        let actions = ranked.into_iter().map(|(_, entry)| entry).collect();
+        let users = self.suggest(query, viewer).await?.users;
+        Ok(Palette { actions, users })
+    }
+}
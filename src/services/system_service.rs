@@ -0,0 +1,41 @@
+use crate::{models::domain::SystemInfo, services::Scheduler, storage::SystemStorage};
+
+#[derive(Clone, Debug)]
+pub struct SystemService {
+    storage: SystemStorage,
+    environment: String,
+    settings: Vec<(String, String)>,
+    scheduler: Scheduler,
+}
+
+impl SystemService {
+    pub fn new(
+        storage: SystemStorage,
+        environment: String,
+        settings: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            storage,
+            environment,
+            settings,
+            scheduler: Scheduler::default(),
+        }
+    }
+    /// Scheduled tasks whose last runs the admin page shows.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+    pub async fn info(&self) -> sqlx::Result<SystemInfo> {
+        let migrations = self.storage.migrations().await?;
+        Ok(SystemInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: option_env!("GIT_COMMIT_HASH").unwrap_or("unknown"),
+            environment: self.environment.clone(),
+            settings: self.settings.clone(),
+            migrations,
+            pool: self.storage.pool_stats(),
+            tasks: self.scheduler.statuses(),
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+use crate::models::domain::{RouteTraffic, TrafficReport, UserTraffic};
+
+/// How far back the report looks.
+pub const TRAFFIC_WINDOW: Duration = Duration::from_secs(60 * 60);
+/// Oldest samples are dropped beyond this, bounding memory under heavy load.
+const MAX_SAMPLES: usize = 100_000;
+/// Rows per table in the report.
+const REPORT_ROWS: usize = 20;
+
+#[derive(Debug, Clone)]
+struct Sample {
+    at: Instant,
+    route: Arc<str>,
+    user_id: Option<Uuid>,
+    status: u16,
+    latency: Duration,
+}
+
+/// Rolling in-memory record of recent requests, fed by the access log and
+/// summarized on the admin traffic page. Per process: each instance of the
+/// app keeps its own.
+#[derive(Debug, Clone, Default)]
+pub struct TrafficStats {
+    samples: Arc<Mutex<VecDeque<Sample>>>,
+}
+
+impl TrafficStats {
+    pub fn record(&self, route: &str, user_id: Option<Uuid>, status: u16, latency: Duration) {
+        self.record_at(Instant::now(), route, user_id, status, latency);
+    }
+    fn record_at(
+        &self,
+        at: Instant,
+        route: &str,
+        user_id: Option<Uuid>,
+        status: u16,
+        latency: Duration,
+    ) {
+        let mut samples = self.samples.lock().expect("traffic stats lock poisoned");
+        prune(&mut samples, at);
+        if samples.len() >= MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(Sample {
+            at,
+            route: route.into(),
+            user_id,
+            status,
+            latency,
+        });
+    }
+    pub fn report(&self) -> TrafficReport {
+        self.report_at(Instant::now())
+    }
+    fn report_at(&self, now: Instant) -> TrafficReport {
+        // Copy out so requests are not blocked while sorting
+        let samples: Vec<Sample> = {
+            let mut samples = self.samples.lock().expect("traffic stats lock poisoned");
+            prune(&mut samples, now);
+            samples.iter().cloned().collect()
+        };
+
+        let mut by_route: HashMap<Arc<str>, Vec<&Sample>> = HashMap::new();
+        let mut by_user: HashMap<Uuid, Vec<&Sample>> = HashMap::new();
+        for sample in &samples {
+            by_route
+                .entry(sample.route.clone())
+                .or_default()
+                .push(sample);
+            if let Some(user_id) = sample.user_id {
+                by_user.entry(user_id).or_default().push(sample);
+            }
+        }
+
+        let mut routes: Vec<RouteTraffic> = by_route
+            .into_iter()
+            .map(|(route, samples)| {
+                let mut latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+                latencies.sort_unstable();
+                RouteTraffic {
+                    route: route.to_string(),
+                    requests: samples.len(),
+                    error_rate: error_rate(&samples),
+                    p50_ms: percentile_ms(&latencies, 50),
+                    p95_ms: percentile_ms(&latencies, 95),
+                    p99_ms: percentile_ms(&latencies, 99),
+                }
+            })
+            .collect();
+        routes.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+        routes.truncate(REPORT_ROWS);
+
+        let mut users: Vec<UserTraffic> = by_user
+            .into_iter()
+            .map(|(user_id, samples)| UserTraffic {
+                user_id,
+                requests: samples.len(),
+                error_rate: error_rate(&samples),
+            })
+            .collect();
+        users.sort_by(|a, b| b.requests.cmp(&a.requests));
+        users.truncate(REPORT_ROWS);
+
+        let mut latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+        latencies.sort_unstable();
+        let all: Vec<&Sample> = samples.iter().collect();
+        TrafficReport {
+            window_minutes: TRAFFIC_WINDOW.as_secs() / 60,
+            requests: samples.len(),
+            error_rate: error_rate(&all),
+            p50_ms: percentile_ms(&latencies, 50),
+            p95_ms: percentile_ms(&latencies, 95),
+            p99_ms: percentile_ms(&latencies, 99),
+            routes,
+            users,
+        }
+    }
+}
+
+fn prune(samples: &mut VecDeque<Sample>, now: Instant) {
+    while samples
+        .front()
+        .is_some_and(|s| now.duration_since(s.at) > TRAFFIC_WINDOW)
+    {
+        samples.pop_front();
+    }
+}
+
+/// Share of server errors (5xx); client errors are the client's business.
+fn error_rate(samples: &[&Sample]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let errors = samples.iter().filter(|s| s.status >= 500).count();
+    errors as f64 / samples.len() as f64
+}
+
+/// Nearest-rank percentile of sorted `latencies`, in milliseconds.
+fn percentile_ms(latencies: &[Duration], percentile: usize) -> f64 {
+    if latencies.is_empty() {
+        return 0.0;
+    }
+    let rank = (percentile * latencies.len()).div_ceil(100).max(1);
+    latencies[rank - 1].as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let latencies: Vec<Duration> = (1..=100).map(ms).collect();
+        assert_eq!(percentile_ms(&latencies, 50), 50.0);
+        assert_eq!(percentile_ms(&latencies, 95), 95.0);
+        assert_eq!(percentile_ms(&latencies, 99), 99.0);
+        assert_eq!(percentile_ms(&[ms(7)], 99), 7.0);
+        assert_eq!(percentile_ms(&[], 50), 0.0);
+    }
+
+    #[test]
+    fn test_report_groups_and_sorts() {
+        let stats = TrafficStats::default();
+        let now = Instant::now();
+        let user = Uuid::new_v4();
+        for i in 0..10 {
+            stats.record_at(now, "/", Some(user), 200, ms(i));
+        }
+        stats.record_at(now, "/slow", None, 500, ms(900));
+        stats.record_at(now, "/slow", None, 200, ms(800));
+
+        let report = stats.report_at(now);
+        assert_eq!(report.requests, 12);
+        assert_eq!(report.routes[0].route, "/slow");
+        assert_eq!(report.routes[0].error_rate, 0.5);
+        assert_eq!(report.routes[1].requests, 10);
+        assert_eq!(report.users.len(), 1);
+        assert_eq!(report.users[0].requests, 10);
+    }
+
+    #[test]
+    fn test_old_samples_expire() {
+        let stats = TrafficStats::default();
+        let start = Instant::now();
+        stats.record_at(start, "/", None, 200, ms(1));
+        let later = start + TRAFFIC_WINDOW + Duration::from_secs(1);
+        stats.record_at(later, "/", None, 200, ms(1));
+        assert_eq!(stats.report_at(later).requests, 1);
+    }
+}
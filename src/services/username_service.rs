@@ -0,0 +1,148 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{User, UsernameRuleError, check_username_rules, normalize_username},
+    services::PageCache,
+    storage::UsersStorage,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UsernameServiceError {
+    NotFound,
+    Taken,
+    Rule(UsernameRuleError),
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for UsernameServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        match value {
+            // users_username_lower_idx lost a race with another rename/signup
+            sqlx::Error::Database(e) if e.is_unique_violation() => Self::Taken,
+            other => Self::DatabaseError(other.to_string()),
+        }
+    }
+}
+impl From<UsernameRuleError> for UsernameServiceError {
+    fn from(value: UsernameRuleError) -> Self {
+        Self::Rule(value)
+    }
+}
+impl Display for UsernameServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for UsernameServiceError {}
+
+impl UsernameServiceError {
+    /// Text shown next to the username input.
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            UsernameServiceError::Taken => locale.t("username-taken"),
+            UsernameServiceError::Rule(rule) => rule.message(locale),
+            _ => locale.t("username-check-failed"),
+        }
+    }
+}
+
+/// A user found by name; `renamed` is set when `username` is a previous name.
+#[derive(Debug, Clone)]
+pub struct ResolvedUsername {
+    pub user: User,
+    pub renamed: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct UsernameService {
+    users: UsersStorage,
+    page_cache: PageCache,
+}
+
+impl UsernameService {
+    pub fn new(users: UsersStorage) -> Self {
+        Self {
+            users,
+            page_cache: PageCache::default(),
+        }
+    }
+    /// Names show on public pages, so renames drop the cached ones.
+    pub fn with_page_cache(mut self, page_cache: PageCache) -> Self {
+        self.page_cache = page_cache;
+        self
+    }
+    /// Checks `username` against the naming rules and existing users,
+    /// returning the normalized name.
+    pub async fn check_available(&self, username: &str) -> Result<String, UsernameServiceError> {
+        let username = normalize_username(username);
+        check_username_rules(&username)?;
+        if self.users.get_by_username(&username).await?.is_some() {
+            return Err(UsernameServiceError::Taken);
+        }
+        Ok(username)
+    }
+    /// Renames the user and remembers the old name so links to it keep working.
+    pub async fn rename(
+        &self,
+        user_id: Uuid,
+        username: &str,
+    ) -> Result<User, UsernameServiceError> {
+        let current = self
+            .users
+            .get_by_id(user_id)
+            .await?
+            .ok_or(UsernameServiceError::NotFound)?;
+        let username = normalize_username(username);
+        if current.username == username {
+            return Ok(current);
+        }
+        // Changing only the case keeps the same identity
+        if !current.username.eq_ignore_ascii_case(&username) {
+            self.check_available(&username).await?;
+        } else {
+            check_username_rules(&username)?;
+        }
+        let old_username = current.username;
+        let updated = self
+            .users
+            .with_tx(|conn| {
+                Box::pin(async move {
+                    let updated =
+                        UsersStorage::set_username_in(&mut *conn, user_id, &username).await?;
+                    UsersStorage::record_username_change_in(&mut *conn, user_id, &old_username)
+                        .await?;
+                    Ok(updated)
+                })
+            })
+            .await?
+            .ok_or(UsernameServiceError::NotFound)?;
+        self.page_cache.invalidate_all();
+        Ok(updated)
+    }
+    /// Finds the user currently or previously known as `username`.
+    pub async fn resolve(&self, username: &str) -> Result<ResolvedUsername, UsernameServiceError> {
+        if let Some(user) = self.users.get_by_username(username).await? {
+            return Ok(ResolvedUsername {
+                user,
+                renamed: false,
+            });
+        }
+        let id = self
+            .users
+            .get_id_by_previous_username(username)
+            .await?
+            .ok_or(UsernameServiceError::NotFound)?;
+        let user = self
+            .users
+            .get_by_id(id)
+            .await?
+            .ok_or(UsernameServiceError::NotFound)?;
+        Ok(ResolvedUsername {
+            user,
+            renamed: true,
+        })
+    }
+}
@@ -1,17 +1,24 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, sync::Arc};
 
-use axum::{http::StatusCode, response::IntoResponse};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{EncodingKey, Header, encode};
 use serde::{Deserialize, Serialize};
-use validator::{Validate, ValidationErrors};
+use tracing::info;
+use validator::ValidationErrors;
 
 use crate::{
+    configuration::DEFAULT_JWT_SECRET,
+    error_reporting,
+    i18n::{self, Locale},
     models::{
-        CreateUser, SignInRequest, SignInResponse, SignUpRequest, SignUpResponse, UpdateUser, User,
-        UserListResponse, UserSearch,
+        api::{SignInRequest, SignInResponse, SignUpRequest, SignUpResponse, UserExport},
+        domain::{
+            CreateUser, EmailDomainError, SignupSettings, Theme, UpdateUser, User, UserList,
+            UserSearch, check_username_rules, normalize_username,
+        },
     },
-    storage::UsersStorage,
+    services::EmailDomainPolicy,
+    storage::UsersRepository,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,9 +27,12 @@ pub enum UsersServiceError {
     WrongCredentials(String),
     DatabaseError(String),
     VerificationError(String),
+    /// The email domain may not sign up, see [`EmailDomainPolicy`]
+    EmailDomain(EmailDomainError),
 }
 impl From<sqlx::Error> for UsersServiceError {
     fn from(value: sqlx::Error) -> Self {
+        error_reporting::capture_error(&value);
         Self::DatabaseError(value.to_string())
     }
 }
@@ -31,17 +41,6 @@ impl Display for UsersServiceError {
         write!(f, "{self:?}")
     }
 }
-impl IntoResponse for UsersServiceError {
-    fn into_response(self) -> axum::response::Response {
-        match self {
-            UsersServiceError::NotFound => StatusCode::NOT_FOUND.into_response(),
-            UsersServiceError::WrongCredentials(err) => {
-                (StatusCode::BAD_REQUEST, err).into_response()
-            }
-            _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
-        }
-    }
-}
 impl Error for UsersServiceError {}
 impl From<ValidationErrors> for UsersServiceError {
     fn from(value: ValidationErrors) -> Self {
@@ -64,6 +63,9 @@ impl From<ValidationErrors> for UsersServiceError {
     }
 }
 
+const INVALID_CREDENTIALS: &str = "Invalid email or password";
+const ACCOUNT_BANNED: &str = "This account has been banned";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user id
@@ -73,12 +75,44 @@ pub struct Claims {
 
 #[derive(Clone, Debug)]
 pub struct UsersService {
-    storage: UsersStorage,
+    storage: Arc<dyn UsersRepository>,
+    signup: SignupSettings,
+    email_domains: EmailDomainPolicy,
+    /// Unify auth errors so they don't reveal which emails are registered
+    privacy_mode: bool,
+    jwt_secret: String,
 }
 
 impl UsersService {
-    pub fn new(storage: UsersStorage) -> Self {
-        Self { storage }
+    /// `storage` is usually [`UsersStorage`](crate::storage::UsersStorage).
+    pub fn new(storage: impl UsersRepository, signup: SignupSettings) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            signup,
+            email_domains: EmailDomainPolicy::default(),
+            privacy_mode: false,
+            jwt_secret: DEFAULT_JWT_SECRET.to_string(),
+        }
+    }
+    pub fn with_privacy_mode(mut self, privacy_mode: bool) -> Self {
+        self.privacy_mode = privacy_mode;
+        self
+    }
+    pub fn with_jwt_secret(mut self, jwt_secret: String) -> Self {
+        self.jwt_secret = jwt_secret;
+        self
+    }
+    pub fn with_email_domains(mut self, email_domains: EmailDomainPolicy) -> Self {
+        self.email_domains = email_domains;
+        self
+    }
+
+    pub fn signup_settings(&self) -> &SignupSettings {
+        &self.signup
+    }
+    /// Whether `email` may sign up by its domain, for live form validation.
+    pub fn check_email_domain(&self, email: &str) -> Result<(), EmailDomainError> {
+        self.email_domains.check(email)
     }
 
     fn generate_jwt_token(&self, user: &User) -> Result<String, UsersServiceError> {
@@ -93,11 +127,10 @@ impl UsersService {
             exp: expiration,
         };
 
-        let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key".to_string());
         let token = encode(
             &Header::default(),
             &claims,
-            &EncodingKey::from_secret(secret.as_ref()),
+            &EncodingKey::from_secret(self.jwt_secret.as_ref()),
         )
         .map_err(|e| {
             UsersServiceError::DatabaseError(format!("Failed to generate token: {}", e))
@@ -110,80 +143,115 @@ impl UsersService {
         &self,
         credentials: SignInRequest,
     ) -> Result<SignInResponse, UsersServiceError> {
-        credentials.validate()?;
-
-        let user = self
-            .storage
-            .get_by_email(&credentials.email)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?
-            .ok_or(UsersServiceError::WrongCredentials(
-                "Invalid email or password".to_string(),
-            ))?;
+        let user = self.storage.get_by_email(&credentials.email).await?;
+        let Some(user) = user else {
+            if self.privacy_mode {
+                self.storage.verify_dummy(&credentials.password);
+            }
+            info!(target: "audit", email = %credentials.email, reason = "unknown_email", "sign in failed");
+            return Err(UsersServiceError::WrongCredentials(
+                INVALID_CREDENTIALS.to_string(),
+            ));
+        };
 
-        let is_valid = self
+        let is_valid = match self
             .storage
             .verify_user(&credentials.email, &credentials.password)
             .await
-            .map_err(|e| UsersServiceError::VerificationError(e.to_string()))?;
+        {
+            Ok(is_valid) => is_valid,
+            Err(e) if self.privacy_mode => {
+                info!(target: "audit", user_id = %user.id, reason = %e, "sign in failed");
+                false
+            }
+            Err(e) => return Err(UsersServiceError::VerificationError(e.to_string())),
+        };
 
         if !is_valid {
+            info!(target: "audit", user_id = %user.id, reason = "wrong_password", "sign in failed");
             return Err(UsersServiceError::WrongCredentials(
-                "Invalid email or password".to_string(),
+                INVALID_CREDENTIALS.to_string(),
+            ));
+        }
+
+        if user.is_banned() {
+            info!(target: "audit", user_id = %user.id, reason = "banned", "sign in failed");
+            return Err(UsersServiceError::WrongCredentials(
+                ACCOUNT_BANNED.to_string(),
             ));
         }
 
         let token = self.generate_jwt_token(&user)?;
-        Ok(SignInResponse { user, token })
+        Ok(SignInResponse {
+            user: user.into(),
+            token,
+        })
     }
 
+    /// Signs up a new user; rejections are worded in `locale`.
     pub async fn sign_up(
         &self,
-        user_data: SignUpRequest,
+        mut user_data: SignUpRequest,
+        locale: Locale,
     ) -> Result<SignUpResponse, UsersServiceError> {
-        user_data.validate()?;
-
-        // Check if user already exists
-        if let Ok(Some(_)) = self.storage.get_by_email(&user_data.email).await {
+        user_data.username = normalize_username(&user_data.username);
+        check_username_rules(&user_data.username)
+            .map_err(|e| UsersServiceError::WrongCredentials(e.message(locale)))?;
+        if let Err(e) = self.email_domains.check(&user_data.email) {
+            info!(target: "audit", email = %user_data.email, reason = e.code(), "sign up rejected");
+            return Err(UsersServiceError::EmailDomain(e));
+        }
+        // Check if user already exists, also under another spelling of the
+        // address such as a gmail +tag
+        if let Ok(Some(existing)) = self.storage.get_by_canonical_email(&user_data.email).await {
+            info!(target: "audit", user_id = %existing.id, reason = "email_exists", "sign up rejected");
+            if self.privacy_mode {
+                // Spend the time creating an account would have taken
+                self.storage.verify_dummy(&user_data.password);
+                return Err(UsersServiceError::WrongCredentials(
+                    locale.t("registration-failed"),
+                ));
+            }
             return Err(UsersServiceError::WrongCredentials(
                 "Email already exists".to_string(),
             ));
         }
 
-        let create_user = CreateUser {
-            username: user_data.username,
-            email: user_data.email,
-            password: user_data.password,
-            first_name: user_data.first_name,
-            last_name: user_data.last_name,
-            bio: user_data.bio,
-        };
+        let profile_extras = self
+            .signup
+            .extras_from(&user_data.profile_extras)
+            .map_err(UsersServiceError::WrongCredentials)?;
+        let mut data: CreateUser = user_data.into();
+        data.profile_extras = profile_extras;
+        // Fields the instance does not collect are never stored
+        if !self.signup.collects("first_name") {
+            data.first_name = None;
+        }
+        if !self.signup.collects("last_name") {
+            data.last_name = None;
+        }
+        if !self.signup.collects("bio") {
+            data.bio = None;
+        }
 
-        let user = self
-            .storage
-            .create(create_user)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?;
+        let user = self.storage.create(data).await?;
 
         let token = self.generate_jwt_token(&user)?;
-        Ok(SignUpResponse { user, token })
+        Ok(SignUpResponse {
+            user: user.into(),
+            token,
+        })
     }
 
     pub async fn create(&self, data: CreateUser) -> Result<User, UsersServiceError> {
-        data.validate()?;
-        let created = self
-            .storage
-            .create(data)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?;
+        let created = self.storage.create(data).await?;
         Ok(created)
     }
     pub async fn get_by_email(&self, email: &str) -> Result<User, UsersServiceError> {
         let existing = self
             .storage
             .get_by_email(email)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?
+            .await?
             .ok_or(UsersServiceError::NotFound)?;
         Ok(existing)
     }
@@ -193,8 +261,7 @@ impl UsersService {
         let existing = self
             .storage
             .get_by_id(parsed)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?
+            .await?
             .ok_or(UsersServiceError::NotFound)?;
         Ok(existing)
     }
@@ -203,7 +270,8 @@ impl UsersService {
         page: u32,
         per_page: u32,
         search_query: Option<String>,
-    ) -> Result<UserListResponse, UsersServiceError> {
+        updated_since: Option<DateTime<Utc>>,
+    ) -> Result<UserList, UsersServiceError> {
         if page == 0 {
             return Err(UsersServiceError::WrongCredentials(
                 "Page must be greater than zero".into(),
@@ -211,14 +279,12 @@ impl UsersService {
         }
         let filter = UserSearch {
             search: search_query,
+            updated_since,
             limit: Some(per_page as i64),
             offset: Some(((page - 1) * per_page) as i64),
+            ..Default::default()
         };
-        let result = self
-            .storage
-            .list_users(filter)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?;
+        let result = self.storage.list_users(filter).await?;
         if result.users.is_empty() {
             return Err(UsersServiceError::NotFound);
         }
@@ -252,12 +318,7 @@ impl UsersService {
                 }
             }
         }
-        match self
-            .storage
-            .update(existing_user.id, data)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?
-        {
+        match self.storage.update(existing_user.id, data).await? {
             Some(u) => Ok(u),
             None => Err(UsersServiceError::NotFound),
         }
@@ -268,13 +329,367 @@ impl UsersService {
         let deleted_id = self
             .storage
             .delete(parsed)
-            .await
-            .map_err(|e| UsersServiceError::DatabaseError(e.to_string()))?
+            .await?
             .ok_or(UsersServiceError::NotFound)?;
         Ok(deleted_id)
     }
-    pub async fn check_username_exists(&self, username: &str) -> Result<bool, UsersServiceError> {
-        let existing = self.storage.get_by_username(username).await?;
-        Ok(existing.is_some())
+    /// Makes the user with `email` an admin. Without such a user an account
+    /// is created, named `username` or after the email, and its generated
+    /// password is returned alongside.
+    pub async fn create_admin(
+        &self,
+        email: &str,
+        username: Option<&str>,
+    ) -> Result<(User, Option<String>), UsersServiceError> {
+        let (user, password) = match self.storage.get_by_email(email).await? {
+            Some(existing) => (existing, None),
+            None => {
+                let username = normalize_username(
+                    username.unwrap_or_else(|| email.split('@').next().unwrap_or_default()),
+                );
+                check_username_rules(&username).map_err(|e| {
+                    UsersServiceError::WrongCredentials(e.message(Locale::default()))
+                })?;
+                let password = uuid::Uuid::new_v4().simple().to_string();
+                let created = self
+                    .storage
+                    .create(CreateUser {
+                        username,
+                        email: email.to_string(),
+                        password: password.clone(),
+                        first_name: None,
+                        last_name: None,
+                        bio: None,
+                        profile_extras: serde_json::json!({}),
+                    })
+                    .await?;
+                (created, Some(password))
+            }
+        };
+        let admin = self
+            .storage
+            .make_admin(user.id)
+            .await?
+            .ok_or(UsersServiceError::NotFound)?;
+        info!(target: "audit", user_id = %admin.id, created = password.is_some(), "admin granted");
+        Ok((admin, password))
+    }
+    /// Erases a user's personal data on request, keeping what they created
+    /// under the ghost user. `user` is an id, email or username; admins must
+    /// be demoted first so an erasure never silently drops privileges.
+    pub async fn anonymize(&self, user: &str) -> Result<User, UsersServiceError> {
+        let user = user.trim();
+        let existing = if let Ok(id) = uuid::Uuid::parse_str(user) {
+            self.storage.get_by_id(id).await?
+        } else if user.contains('@') {
+            self.storage.get_by_email(user).await?
+        } else {
+            self.storage.get_by_username(user).await?
+        }
+        .ok_or(UsersServiceError::NotFound)?;
+        if existing.is_ghost() || existing.is_admin {
+            return Err(UsersServiceError::WrongCredentials(
+                "Admin and ghost accounts cannot be anonymized".into(),
+            ));
+        }
+        let anonymized = self
+            .storage
+            .anonymize(existing.id)
+            .await?
+            .ok_or(UsersServiceError::NotFound)?;
+        info!(target: "audit", user_id = %existing.id, "user anonymized");
+        Ok(anonymized)
+    }
+    /// The profile and every other record kept about the user.
+    pub async fn export(&self, id: &str) -> Result<UserExport, UsersServiceError> {
+        let profile = self.get_by_id(id).await?;
+        let data = self
+            .storage
+            .export_data(profile.id)
+            .await?
+            .ok_or(UsersServiceError::NotFound)?;
+        Ok(UserExport::new(profile, data))
+    }
+    /// Stores the interface language and timezone, normalized to the
+    /// canonical locale code and IANA name. An empty `locale` goes back to
+    /// negotiating it from the browser.
+    pub async fn set_preferences(
+        &self,
+        id: uuid::Uuid,
+        locale: &str,
+        timezone: &str,
+    ) -> Result<User, UsersServiceError> {
+        let locale = match locale.trim() {
+            "" => None,
+            code => Some(
+                Locale::from_code(code)
+                    .ok_or_else(|| UsersServiceError::WrongCredentials("Unknown locale".into()))?,
+            ),
+        };
+        let timezone = i18n::parse_timezone(timezone)
+            .ok_or_else(|| UsersServiceError::WrongCredentials("Unknown timezone".into()))?;
+        self.storage
+            .set_preferences(id, locale.map(|l| l.code()), timezone.name())
+            .await?
+            .ok_or(UsersServiceError::NotFound)
+    }
+    /// `None` goes back to following the system colour scheme.
+    pub async fn set_theme(
+        &self,
+        id: uuid::Uuid,
+        theme: Option<Theme>,
+    ) -> Result<User, UsersServiceError> {
+        self.storage
+            .set_theme(id, theme.map(|t| t.code()))
+            .await?
+            .ok_or(UsersServiceError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::domain::{EmailCanonicalSettings, UserData, canonical_email};
+
+    /// Users kept in memory; passwords are stored as given.
+    #[derive(Debug, Default)]
+    struct FakeUsers {
+        users: Mutex<Vec<(User, String)>>,
+    }
+
+    impl FakeUsers {
+        fn find(&self, matches: impl Fn(&User) -> bool) -> Option<User> {
+            let users = self.users.lock().unwrap();
+            users.iter().map(|(u, _)| u).find(|u| matches(u)).cloned()
+        }
+        fn modify(&self, id: Uuid, change: impl FnOnce(&mut User)) -> Option<User> {
+            let mut users = self.users.lock().unwrap();
+            let (user, _) = users.iter_mut().find(|(u, _)| u.id == id)?;
+            change(user);
+            user.updated_at = Utc::now();
+            Some(user.clone())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl UsersRepository for FakeUsers {
+        async fn create(&self, data: CreateUser) -> sqlx::Result<User> {
+            let user = User {
+                id: Uuid::new_v4(),
+                username: data.username,
+                email: data.email.to_lowercase(),
+                first_name: data.first_name,
+                last_name: data.last_name,
+                bio: data.bio,
+                profile_extras: data.profile_extras,
+                ..Default::default()
+            };
+            let mut users = self.users.lock().unwrap();
+            users.push((user.clone(), data.password));
+            Ok(user)
+        }
+        async fn verify_user(&self, email: &str, password: &str) -> sqlx::Result<bool> {
+            let users = self.users.lock().unwrap();
+            let (_, stored) = users
+                .iter()
+                .find(|(u, _)| u.email == email.to_lowercase())
+                .ok_or(sqlx::Error::RowNotFound)?;
+            Ok(stored == password)
+        }
+        fn verify_dummy(&self, _password: &str) {}
+        async fn get_by_email(&self, email: &str) -> sqlx::Result<Option<User>> {
+            Ok(self.find(|u| u.email == email.to_lowercase()))
+        }
+        async fn get_by_canonical_email(&self, email: &str) -> sqlx::Result<Option<User>> {
+            let settings = EmailCanonicalSettings::default();
+            let canonical = canonical_email(email, &settings);
+            Ok(self.find(|u| canonical_email(&u.email, &settings) == canonical))
+        }
+        async fn get_by_id(&self, id: Uuid) -> sqlx::Result<Option<User>> {
+            Ok(self.find(|u| u.id == id))
+        }
+        async fn get_by_username(&self, username: &str) -> sqlx::Result<Option<User>> {
+            Ok(self.find(|u| u.username.eq_ignore_ascii_case(username)))
+        }
+        async fn list_users(&self, data: UserSearch) -> sqlx::Result<UserList> {
+            let users = self.users.lock().unwrap();
+            let limit = data.limit.unwrap_or(20);
+            let offset = data.offset.unwrap_or(0);
+            Ok(UserList {
+                users: users
+                    .iter()
+                    .map(|(u, _)| u.clone())
+                    .skip(offset as usize)
+                    .take(limit as usize)
+                    .collect(),
+                total_count: users.len() as i64,
+                limit,
+                offset,
+            })
+        }
+        async fn update(&self, id: Uuid, data: UpdateUser) -> sqlx::Result<Option<User>> {
+            Ok(self.modify(id, |u| {
+                if let Some(bio) = data.bio {
+                    u.bio = Some(bio);
+                }
+            }))
+        }
+        async fn delete(&self, id: Uuid) -> sqlx::Result<Option<Uuid>> {
+            let mut users = self.users.lock().unwrap();
+            let before = users.len();
+            users.retain(|(u, _)| u.id != id);
+            Ok((users.len() < before).then_some(id))
+        }
+        async fn set_preferences(
+            &self,
+            id: Uuid,
+            locale: Option<&str>,
+            timezone: &str,
+        ) -> sqlx::Result<Option<User>> {
+            Ok(self.modify(id, |u| {
+                u.locale = locale.map(String::from);
+                u.timezone = timezone.to_string();
+            }))
+        }
+        async fn set_theme(&self, id: Uuid, theme: Option<&str>) -> sqlx::Result<Option<User>> {
+            Ok(self.modify(id, |u| u.theme = theme.map(String::from)))
+        }
+        async fn make_admin(&self, id: Uuid) -> sqlx::Result<Option<User>> {
+            Ok(self.modify(id, |u| u.is_admin = true))
+        }
+        async fn anonymize(&self, id: Uuid) -> sqlx::Result<Option<User>> {
+            Ok(self.modify(id, |u| {
+                u.username = crate::models::domain::anonymized_username(id);
+                u.email = crate::models::domain::anonymized_email(id);
+                u.banned_at = Some(Utc::now());
+            }))
+        }
+        async fn export_data(&self, id: Uuid) -> sqlx::Result<Option<UserData>> {
+            Ok(self.find(|u| u.id == id).map(|_| UserData::default()))
+        }
+    }
+
+    fn service() -> UsersService {
+        UsersService::new(FakeUsers::default(), SignupSettings::default())
+    }
+
+    fn sign_up_request(username: &str) -> SignUpRequest {
+        SignUpRequest {
+            username: username.to_string(),
+            email: format!("{username}@example.com"),
+            password: "Password-42!".to_string(),
+            first_name: None,
+            last_name: None,
+            bio: None,
+            profile_extras: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_then_sign_in() {
+        let service = service();
+        let signed_up = service
+            .sign_up(sign_up_request("reader"), Locale::default())
+            .await
+            .unwrap();
+        assert_eq!(signed_up.user.username, "reader");
+
+        let signed_in = service
+            .sign_in(SignInRequest {
+                email: "Reader@example.com".to_string(),
+                password: "Password-42!".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(signed_in.user.id, signed_up.user.id);
+
+        let wrong = service
+            .sign_in(SignInRequest {
+                email: "reader@example.com".to_string(),
+                password: "wrong".to_string(),
+            })
+            .await;
+        assert!(matches!(wrong, Err(UsersServiceError::WrongCredentials(_))));
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_rejects_taken_email_and_reserved_names() {
+        let service = service();
+        service
+            .sign_up(sign_up_request("reader"), Locale::default())
+            .await
+            .unwrap();
+        let taken = service
+            .sign_up(sign_up_request("reader"), Locale::default())
+            .await;
+        assert!(matches!(taken, Err(UsersServiceError::WrongCredentials(_))));
+        let reserved = service
+            .sign_up(sign_up_request("admin"), Locale::default())
+            .await;
+        assert!(matches!(
+            reserved,
+            Err(UsersServiceError::WrongCredentials(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_rejects_another_spelling_of_taken_email() {
+        let service = service();
+        let mut request = sign_up_request("reader");
+        request.email = "jane.doe@gmail.com".to_string();
+        service.sign_up(request, Locale::default()).await.unwrap();
+
+        let mut request = sign_up_request("reader2");
+        request.email = "JaneDoe+books@googlemail.com".to_string();
+        let taken = service.sign_up(request, Locale::default()).await;
+        assert!(matches!(taken, Err(UsersServiceError::WrongCredentials(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_admin_promotes_existing_user() {
+        let service = service();
+        let signed_up = service
+            .sign_up(sign_up_request("reader"), Locale::default())
+            .await
+            .unwrap();
+        let (admin, password) = service
+            .create_admin("reader@example.com", None)
+            .await
+            .unwrap();
+        assert_eq!(admin.id, signed_up.user.id);
+        assert!(admin.is_admin && password.is_none());
+
+        let (created, password) = service
+            .create_admin("curator@example.com", None)
+            .await
+            .unwrap();
+        assert_eq!(created.username, "curator");
+        assert!(created.is_admin && password.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_anonymize_refuses_admins() {
+        let service = service();
+        service
+            .sign_up(sign_up_request("reader"), Locale::default())
+            .await
+            .unwrap();
+        service
+            .create_admin("boss@example.com", None)
+            .await
+            .unwrap();
+
+        let anonymized = service.anonymize("reader").await.unwrap();
+        assert!(anonymized.is_banned());
+        assert!(service.get_by_email("reader@example.com").await.is_err());
+        assert!(matches!(
+            service.anonymize("boss@example.com").await,
+            Err(UsersServiceError::WrongCredentials(_))
+        ));
     }
 }
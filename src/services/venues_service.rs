@@ -0,0 +1,178 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    i18n::Locale,
+    models::domain::{
+        ClubSlugError, EventSummary, NearbyVenue, User, VENUE_ADDRESS_MAX_LEN, VENUE_NAME_MAX_LEN,
+        VENUE_SEARCH_RADIUS_M, Venue, VenueKind, check_venue_slug, valid_coordinates,
+    },
+    storage::{CreateVenue, EventsStorage, VenuesStorage},
+};
+
+/// Venues shown in the directory.
+const DIRECTORY_LIMIT: i64 = 500;
+/// Venues returned by the "near me" search.
+const NEAR_LIMIT: i64 = 20;
+/// Upcoming events shown on a venue page.
+const VENUE_EVENTS_LIMIT: i64 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VenuesServiceError {
+    NotFound,
+    InvalidSlug(ClubSlugError),
+    SlugTaken,
+    EmptyName,
+    NameTooLong,
+    AddressTooLong,
+    UnknownKind,
+    InvalidCoordinates,
+    /// Only admins add venues
+    Forbidden,
+    DatabaseError(String),
+}
+impl From<sqlx::Error> for VenuesServiceError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::DatabaseError(value.to_string())
+    }
+}
+impl Display for VenuesServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl Error for VenuesServiceError {}
+
+impl VenuesServiceError {
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            VenuesServiceError::NotFound => locale.t("venue-not-found"),
+            VenuesServiceError::InvalidSlug(e) => e.message(locale),
+            VenuesServiceError::SlugTaken => locale.t("venue-slug-taken"),
+            VenuesServiceError::EmptyName => locale.t("venue-name-required"),
+            VenuesServiceError::NameTooLong => locale.t_args(
+                "venue-name-too-long",
+                &[("max", VENUE_NAME_MAX_LEN.to_string())],
+            ),
+            VenuesServiceError::AddressTooLong => locale.t_args(
+                "venue-address-too-long",
+                &[("max", VENUE_ADDRESS_MAX_LEN.to_string())],
+            ),
+            VenuesServiceError::UnknownKind => locale.t("venue-kind-unknown"),
+            VenuesServiceError::InvalidCoordinates => locale.t("venue-coordinates-invalid"),
+            VenuesServiceError::Forbidden => locale.t("venue-forbidden"),
+            VenuesServiceError::DatabaseError(_) => locale.t("venue-failed"),
+        }
+    }
+}
+
+/// What an admin filled in about a venue.
+#[derive(Debug, Clone)]
+pub struct NewVenue<'a> {
+    pub slug: &'a str,
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub address: &'a str,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// A venue page: the venue and what is coming up there.
+#[derive(Debug, Clone)]
+pub struct VenueView {
+    pub venue: Venue,
+    pub events: Vec<EventSummary>,
+}
+
+#[derive(Clone, Debug)]
+pub struct VenuesService {
+    storage: VenuesStorage,
+    events: EventsStorage,
+}
+
+impl VenuesService {
+    pub fn new(storage: VenuesStorage, events: EventsStorage) -> Self {
+        Self { storage, events }
+    }
+    pub async fn directory(&self) -> Result<Vec<Venue>, VenuesServiceError> {
+        let venues = self.storage.list(DIRECTORY_LIMIT).await?;
+        Ok(venues)
+    }
+    /// Venues within [`VENUE_SEARCH_RADIUS_M`] of the point, nearest first.
+    pub async fn near(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<NearbyVenue>, VenuesServiceError> {
+        if !valid_coordinates(latitude, longitude) {
+            return Err(VenuesServiceError::InvalidCoordinates);
+        }
+        let venues = self
+            .storage
+            .list_near(latitude, longitude, VENUE_SEARCH_RADIUS_M, NEAR_LIMIT)
+            .await?;
+        Ok(venues)
+    }
+    pub async fn view(
+        &self,
+        slug: &str,
+        viewer: Option<&User>,
+    ) -> Result<VenueView, VenuesServiceError> {
+        let venue = self
+            .storage
+            .get_by_slug(slug)
+            .await?
+            .ok_or(VenuesServiceError::NotFound)?;
+        let events = self
+            .events
+            .list_upcoming_by_venue(venue.id, viewer.map(|u| u.id), VENUE_EVENTS_LIMIT)
+            .await?;
+        Ok(VenueView { venue, events })
+    }
+    pub async fn create(
+        &self,
+        admin: &User,
+        venue: NewVenue<'_>,
+    ) -> Result<Venue, VenuesServiceError> {
+        if !admin.is_admin {
+            return Err(VenuesServiceError::Forbidden);
+        }
+        let slug = venue.slug.trim();
+        let name = venue.name.trim();
+        let address = venue.address.trim();
+        check_venue_slug(slug).map_err(VenuesServiceError::InvalidSlug)?;
+        if name.is_empty() {
+            return Err(VenuesServiceError::EmptyName);
+        }
+        if name.chars().count() > VENUE_NAME_MAX_LEN {
+            return Err(VenuesServiceError::NameTooLong);
+        }
+        if address.chars().count() > VENUE_ADDRESS_MAX_LEN {
+            return Err(VenuesServiceError::AddressTooLong);
+        }
+        let kind = VenueKind::from_code(venue.kind).ok_or(VenuesServiceError::UnknownKind)?;
+        let (Some(latitude), Some(longitude)) = (venue.latitude, venue.longitude) else {
+            return Err(VenuesServiceError::InvalidCoordinates);
+        };
+        if !valid_coordinates(latitude, longitude) {
+            return Err(VenuesServiceError::InvalidCoordinates);
+        }
+        let venue = self
+            .storage
+            .create(CreateVenue {
+                slug,
+                name,
+                kind: kind.code(),
+                address,
+                latitude,
+                longitude,
+                created_by: admin.id,
+            })
+            .await?
+            .ok_or(VenuesServiceError::SlugTaken)?;
+        info!(target: "audit", user_id = %admin.id, venue_id = %venue.id, slug = %venue.slug, "venue created");
+        Ok(venue)
+    }
+}
@@ -0,0 +1,187 @@
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{ApiKey, CreateApiKey};
+
+/// Number of leading key characters stored in clear text for lookups:
+/// `cl_` and 13 random hex digits, so prefixes of new keys don't collide.
+const PREFIX_LEN: usize = 16;
+/// Prefix length of keys created before it was raised to [`PREFIX_LEN`].
+const LEGACY_PREFIX_LEN: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct ApiKeysStorage {
+    pool: Pool<Postgres>,
+}
+
+impl ApiKeysStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    pub async fn create(&self, data: CreateApiKey) -> Result<ApiKey> {
+        let prefix = key_prefix(&data.key, PREFIX_LEN)
+            .ok_or_else(|| sqlx::Error::Encode("API key is shorter than its prefix".into()))?;
+        let result = timed!(
+            sqlx::query_file_as!(
+                ApiKey,
+                "queries/api_keys/create.sql",
+                data.user_id,
+                data.name,
+                prefix,
+                hash_key(&data.key),
+                &data.scopes[..],
+                data.rate_limit_per_minute,
+            )
+            .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(result)
+    }
+    pub async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        let res = timed!(
+            sqlx::query_file_as!(ApiKey, "queries/api_keys/list_by_user.sql", user_id)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Finds the key matching the full plaintext `key`, if any.
+    pub async fn get_by_key(&self, key: &str) -> Result<Option<ApiKey>> {
+        let prefixes: Vec<&str> = [PREFIX_LEN, LEGACY_PREFIX_LEN]
+            .into_iter()
+            .filter_map(|len| key_prefix(key, len))
+            .collect();
+        if prefixes.is_empty() {
+            return Ok(None);
+        }
+        let hash = hash_key(key);
+        let res = timed!(
+            sqlx::query_file_as!(ApiKey, "queries/api_keys/get_by_prefix.sql", &prefixes[..])
+                .fetch_all(&self.pool)
+        )
+        .await?
+        .into_iter()
+        .find(|k| k.key_hash == hash);
+        Ok(res)
+    }
+    pub async fn touch(&self, id: Uuid) -> Result<()> {
+        timed!(sqlx::query_file!("queries/api_keys/touch.sql", id).execute(&self.pool)).await?;
+        Ok(())
+    }
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<Option<Uuid>> {
+        let result = timed!(
+            sqlx::query_file_scalar!("queries/api_keys/delete.sql", id, user_id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(result)
+    }
+}
+
+fn key_prefix(key: &str, len: usize) -> Option<&str> {
+    key.get(..len)
+}
+
+// API keys are high-entropy random strings, so a fast hash is sufficient
+// and keeps per-request authentication cheap compared to argon2.
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: "keyowner".to_string(),
+                email: "keyowner@example.com".to_string(),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    fn create_key(user_id: Uuid) -> CreateApiKey {
+        CreateApiKey {
+            user_id,
+            key: format!("cl_{}", Uuid::new_v4().simple()),
+            name: "ci".to_string(),
+            scopes: vec!["read".to_string()],
+            rate_limit_per_minute: 60,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_create_and_get_by_key(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user_id = create_user(&pool).await?;
+        let storage = ApiKeysStorage::new(pool).await?;
+
+        let data = create_key(user_id);
+        let created = storage.create(data.clone()).await?;
+        assert_ne!(created.key_hash, data.key);
+        assert!(created.has_scope("read"));
+
+        let found = storage.get_by_key(&data.key).await?;
+        assert_eq!(found.map(|k| k.id), Some(created.id));
+
+        let wrong = format!("{}x", &data.key[..data.key.len() - 1]);
+        assert!(storage.get_by_key(&wrong).await?.is_none());
+        assert!(storage.get_by_key("cl_").await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_legacy_prefix_still_authenticates(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user_id = create_user(&pool).await?;
+        let storage = ApiKeysStorage::new(pool.clone()).await?;
+
+        let data = create_key(user_id);
+        let created = storage.create(data.clone()).await?;
+        assert_eq!(created.prefix.len(), PREFIX_LEN);
+        sqlx::query("UPDATE api_keys SET prefix = $1 WHERE id = $2")
+            .bind(&data.key[..LEGACY_PREFIX_LEN])
+            .bind(created.id)
+            .execute(&pool)
+            .await?;
+
+        let found = storage.get_by_key(&data.key).await?;
+        assert_eq!(found.map(|k| k.id), Some(created.id));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_delete_only_own_key(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user_id = create_user(&pool).await?;
+        let storage = ApiKeysStorage::new(pool).await?;
+
+        let created = storage.create(create_key(user_id)).await?;
+        assert!(storage.delete(created.id, Uuid::new_v4()).await?.is_none());
+        assert_eq!(storage.delete(created.id, user_id).await?, Some(created.id));
+        assert!(storage.list_by_user(user_id).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_key_is_stable() {
+        assert_eq!(hash_key("cl_secret"), hash_key("cl_secret"));
+        assert_ne!(hash_key("cl_secret"), hash_key("cl_secret2"));
+        assert_eq!(hash_key("cl_secret").len(), 64);
+    }
+}
@@ -0,0 +1,197 @@
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{Club, ClubMember, ClubSummary};
+
+/// Clubs and their memberships.
+#[derive(Clone, Debug)]
+pub struct ClubsStorage {
+    pool: Pool<Postgres>,
+}
+
+impl ClubsStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// Creates the club owned by `owner_id`; `None` when the slug is taken.
+    pub async fn create(
+        &self,
+        slug: &str,
+        name: &str,
+        description: &str,
+        owner_id: Uuid,
+    ) -> Result<Option<Club>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                Club,
+                "queries/clubs/create.sql",
+                slug,
+                name,
+                description,
+                owner_id,
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn get_by_slug(&self, slug: &str) -> Result<Option<Club>> {
+        let res = timed!(
+            sqlx::query_file_as!(Club, "queries/clubs/get_by_slug.sql", slug)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// `viewer_id` marks the clubs the viewer belongs to.
+    pub async fn list(&self, viewer_id: Option<Uuid>, limit: i64) -> Result<Vec<ClubSummary>> {
+        let res = timed!(
+            sqlx::query_file_as!(ClubSummary, "queries/clubs/list.sql", viewer_id, limit)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Adds the member; an existing member keeps their role.
+    pub async fn add_member(&self, club_id: Uuid, user_id: Uuid, role: &str) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/club_members/add.sql", club_id, user_id, role)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn remove_member(&self, club_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let res = timed!(
+            sqlx::query_file!("queries/club_members/remove.sql", club_id, user_id)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+    /// `None` when the user is not a member.
+    pub async fn get_role(&self, club_id: Uuid, user_id: Uuid) -> Result<Option<String>> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/club_members/get_role.sql", club_id, user_id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn set_role(&self, club_id: Uuid, user_id: Uuid, role: &str) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/club_members/set_role.sql", club_id, user_id, role)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn count_owners(&self, club_id: Uuid) -> Result<i64> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/club_members/count_owners.sql", club_id)
+                .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_members(&self, club_id: Uuid) -> Result<Vec<ClubMember>> {
+        let res = timed!(
+            sqlx::query_file_as!(ClubMember, "queries/club_members/list.sql", club_id)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    #[sqlx::test]
+    async fn test_create_club_with_owner(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let owner = create_user(&pool, "owner").await?;
+        let storage = ClubsStorage::new(pool.clone()).await?;
+
+        let club = storage
+            .create("readers", "Readers", "We read", owner)
+            .await?
+            .expect("created");
+        assert_eq!(club.created_by, Some(owner));
+        assert_eq!(
+            storage.get_role(club.id, owner).await?.as_deref(),
+            Some("owner")
+        );
+        // Slugs are unique regardless of case
+        assert!(
+            storage
+                .create("Readers", "Other", "", owner)
+                .await?
+                .is_none()
+        );
+        assert_eq!(
+            storage.get_by_slug("READERS").await?.map(|c| c.id),
+            Some(club.id)
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_memberships(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let owner = create_user(&pool, "owner").await?;
+        let reader = create_user(&pool, "reader").await?;
+        let storage = ClubsStorage::new(pool.clone()).await?;
+        let club = storage
+            .create("readers", "Readers", "", owner)
+            .await?
+            .expect("created");
+
+        storage.add_member(club.id, reader, "member").await?;
+        // Joining again keeps the role
+        storage.set_role(club.id, reader, "admin").await?;
+        storage.add_member(club.id, reader, "member").await?;
+        assert_eq!(
+            storage.get_role(club.id, reader).await?.as_deref(),
+            Some("admin")
+        );
+        assert_eq!(storage.count_owners(club.id).await?, 1);
+
+        let members = storage.list_members(club.id).await?;
+        let names: Vec<_> = members.iter().map(|m| m.username.as_str()).collect();
+        assert_eq!(names, vec!["owner", "reader"]);
+
+        let clubs = storage.list(Some(reader), 10).await?;
+        assert_eq!(clubs.len(), 1);
+        assert_eq!(clubs[0].member_count, 2);
+        assert!(clubs[0].is_member);
+        assert!(!storage.list(None, 10).await?[0].is_member);
+
+        assert!(storage.remove_member(club.id, reader).await?);
+        assert!(!storage.remove_member(club.id, reader).await?);
+        assert_eq!(storage.get_role(club.id, reader).await?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use sqlx::{PgExecutor, Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{CuratorApplication, PendingCuratorApplication};
+
+#[derive(Clone, Debug)]
+pub struct CuratorApplicationsStorage {
+    pool: Pool<Postgres>,
+}
+
+impl CuratorApplicationsStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    pub async fn create(&self, user_id: Uuid, motivation: &str) -> Result<CuratorApplication> {
+        let result = timed!(
+            sqlx::query_file_as!(
+                CuratorApplication,
+                "queries/curator_applications/create.sql",
+                user_id,
+                motivation,
+            )
+            .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(result)
+    }
+    pub async fn get_pending_by_user(&self, user_id: Uuid) -> Result<Option<CuratorApplication>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                CuratorApplication,
+                "queries/curator_applications/get_pending_by_user.sql",
+                user_id
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_pending(&self) -> Result<Vec<PendingCuratorApplication>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                PendingCuratorApplication,
+                "queries/curator_applications/list_pending.sql"
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn resolve_in(
+        executor: impl PgExecutor<'_>,
+        id: Uuid,
+        status: &str,
+        reviewed_by: Uuid,
+    ) -> Result<Option<CuratorApplication>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                CuratorApplication,
+                "queries/curator_applications/resolve.sql",
+                id,
+                status,
+                reviewed_by,
+            )
+            .fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::DataExport;
+
+/// Queued and built data exports with their archives.
+#[derive(Clone, Debug)]
+pub struct DataExportsStorage {
+    pool: Pool<Postgres>,
+}
+
+impl DataExportsStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// Queues an export for the user; `None` when one is already waiting.
+    pub async fn request(&self, user_id: Uuid) -> Result<Option<DataExport>> {
+        let res = timed!(
+            sqlx::query_file_as!(DataExport, "queries/data_exports/request.sql", user_id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn get_pending(&self, user_id: Uuid) -> Result<Option<DataExport>> {
+        let res = timed!(
+            sqlx::query_file_as!(DataExport, "queries/data_exports/get_pending.sql", user_id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// The oldest waiting exports first.
+    pub async fn list_pending(&self, limit: i64) -> Result<Vec<DataExport>> {
+        let res = timed!(
+            sqlx::query_file_as!(DataExport, "queries/data_exports/list_pending.sql", limit)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Stores the archive downloadable with `token`; `None` when the export
+    /// was already completed.
+    pub async fn complete(
+        &self,
+        id: Uuid,
+        archive: &[u8],
+        token: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<DataExport>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                DataExport,
+                "queries/data_exports/complete.sql",
+                id,
+                archive,
+                hash_token(token),
+                expires_at,
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// The archive of the user's export with `token`, unless it expired.
+    pub async fn get_archive(&self, token: &str, user_id: Uuid) -> Result<Option<Vec<u8>>> {
+        let res = timed!(
+            sqlx::query_file_scalar!(
+                "queries/data_exports/get_archive.sql",
+                hash_token(token),
+                user_id
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Deletes at most `limit` exports whose link expired, returning how
+    /// many were removed.
+    pub async fn delete_expired(&self, limit: i64) -> Result<u64> {
+        let res = timed!(
+            sqlx::query_file!("queries/data_exports/delete_expired.sql", limit)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+// Tokens are random UUIDs, so like API keys a fast hash is enough
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    #[sqlx::test]
+    async fn test_export_lifecycle(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user = UsersStorage::new(pool.clone())
+            .await?
+            .create(CreateUser {
+                username: "reader".to_string(),
+                email: "reader@example.com".to_string(),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        let storage = DataExportsStorage::new(pool).await?;
+
+        let export = storage.request(user.id).await?.expect("queued");
+        // One export waits at a time
+        assert!(storage.request(user.id).await?.is_none());
+        assert_eq!(storage.list_pending(10).await?.len(), 1);
+
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        assert!(
+            storage
+                .complete(export.id, b"zip", "token", expires_at)
+                .await?
+                .is_some()
+        );
+        assert!(
+            storage
+                .complete(export.id, b"zip", "token", expires_at)
+                .await?
+                .is_none()
+        );
+        assert!(storage.get_pending(user.id).await?.is_none());
+        assert_eq!(
+            storage.get_archive("token", user.id).await?,
+            Some(b"zip".to_vec())
+        );
+        assert!(storage.get_archive("token", Uuid::new_v4()).await?.is_none());
+        assert!(storage.get_archive("other", user.id).await?.is_none());
+        assert_eq!(storage.delete_expired(10).await?, 0);
+
+        // A new export may be requested once the last one is built
+        assert!(storage.request(user.id).await?.is_some());
+
+        Ok(())
+    }
+}
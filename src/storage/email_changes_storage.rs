@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{PgExecutor, Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::EmailChange;
+
+#[derive(Clone, Debug)]
+pub struct EmailChangesStorage {
+    pool: Pool<Postgres>,
+}
+
+impl EmailChangesStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// Stores a pending change confirmed by `token`, dropping the user's
+    /// earlier unconfirmed ones so only the latest link works.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        new_email: &str,
+        token: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<EmailChange> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                EmailChange,
+                "queries/email_changes/create.sql",
+                user_id,
+                new_email.to_lowercase(),
+                hash_token(token),
+                expires_at,
+            )
+            .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn get_pending_by_token(&self, token: &str) -> Result<Option<EmailChange>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                EmailChange,
+                "queries/email_changes/get_pending_by_token.sql",
+                hash_token(token)
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn confirm_in(
+        executor: impl PgExecutor<'_>,
+        id: Uuid,
+    ) -> Result<Option<EmailChange>> {
+        let res = timed!(
+            sqlx::query_file_as!(EmailChange, "queries/email_changes/confirm.sql", id)
+                .fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Deletes at most `limit` expired unconfirmed changes, returning how
+    /// many were removed.
+    pub async fn delete_expired(&self, limit: i64) -> Result<u64> {
+        let res = timed!(
+            sqlx::query_file!("queries/email_changes/delete_expired.sql", limit)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+// Tokens are random UUIDs, so like API keys a fast hash is enough
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: "mover".to_string(),
+                email: "old@example.com".to_string(),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    #[sqlx::test]
+    async fn test_latest_change_replaces_pending(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user_id = create_user(&pool).await?;
+        let storage = EmailChangesStorage::new(pool).await?;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        storage
+            .create(user_id, "First@example.com", "token-1", expires_at)
+            .await?;
+        let second = storage
+            .create(user_id, "second@example.com", "token-2", expires_at)
+            .await?;
+
+        assert!(storage.get_pending_by_token("token-1").await?.is_none());
+        let found = storage.get_pending_by_token("token-2").await?.unwrap();
+        assert_eq!(found.id, second.id);
+        assert_eq!(found.new_email, "second@example.com");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_confirm_once(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user_id = create_user(&pool).await?;
+        let storage = EmailChangesStorage::new(pool.clone()).await?;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        let change = storage
+            .create(user_id, "new@example.com", "token", expires_at)
+            .await?;
+        let confirmed = EmailChangesStorage::confirm_in(&pool, change.id).await?;
+        assert!(confirmed.is_some_and(|c| c.confirmed_at.is_some()));
+        assert!(
+            EmailChangesStorage::confirm_in(&pool, change.id)
+                .await?
+                .is_none()
+        );
+        assert!(storage.get_pending_by_token("token").await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_delete_expired(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user_id = create_user(&pool).await?;
+        let storage = EmailChangesStorage::new(pool.clone()).await?;
+        let past = Utc::now() - chrono::Duration::hours(1);
+
+        let confirmed = storage
+            .create(user_id, "kept@example.com", "token-1", past)
+            .await?;
+        EmailChangesStorage::confirm_in(&pool, confirmed.id).await?;
+        storage
+            .create(user_id, "expired@example.com", "token-2", past)
+            .await?;
+        // Creating a change drops the user's unconfirmed ones, so use another user
+        let other_id = UsersStorage::new(pool.clone())
+            .await?
+            .create(CreateUser {
+                username: "waiter".to_string(),
+                email: "waiter@example.com".to_string(),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?
+            .id;
+        storage
+            .create(
+                other_id,
+                "pending@example.com",
+                "token-3",
+                Utc::now() + chrono::Duration::hours(1),
+            )
+            .await?;
+
+        assert_eq!(storage.delete_expired(100).await?, 1);
+        assert_eq!(storage.delete_expired(100).await?, 0);
+        assert!(storage.get_pending_by_token("token-3").await?.is_some());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{Event, EventRecipient, EventSummary};
+
+/// Events and their RSVPs.
+#[derive(Clone, Debug)]
+pub struct EventsStorage {
+    pool: Pool<Postgres>,
+}
+
+/// What is stored about a new event.
+#[derive(Debug, Clone)]
+pub struct CreateEvent<'a> {
+    pub title: &'a str,
+    pub description: &'a str,
+    pub venue: &'a str,
+    pub starts_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub club_id: Option<Uuid>,
+    pub venue_id: Option<Uuid>,
+}
+
+impl EventsStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    pub async fn create(&self, event: CreateEvent<'_>) -> Result<Event> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                Event,
+                "queries/events/create.sql",
+                event.title,
+                event.description,
+                event.venue,
+                event.starts_at,
+                event.created_by,
+                event.club_id,
+                event.venue_id,
+            )
+            .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// `viewer_id` fills in [`EventSummary::viewer_status`].
+    pub async fn get(&self, id: Uuid, viewer_id: Option<Uuid>) -> Result<Option<EventSummary>> {
+        let res = timed!(
+            sqlx::query_file_as!(EventSummary, "queries/events/get_by_id.sql", viewer_id, id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_upcoming(
+        &self,
+        viewer_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<EventSummary>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                EventSummary,
+                "queries/events/list_upcoming.sql",
+                viewer_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_upcoming_by_club(
+        &self,
+        club_id: Uuid,
+        viewer_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<EventSummary>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                EventSummary,
+                "queries/events/list_upcoming_by_club.sql",
+                viewer_id,
+                club_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_upcoming_by_venue(
+        &self,
+        venue_id: Uuid,
+        viewer_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<EventSummary>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                EventSummary,
+                "queries/events/list_upcoming_by_venue.sql",
+                viewer_id,
+                venue_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Events starting within `within_secs` whose reminder has not gone out.
+    pub async fn list_due_reminders(&self, within_secs: f64, limit: i64) -> Result<Vec<Event>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                Event,
+                "queries/events/list_due_reminders.sql",
+                within_secs,
+                limit
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn mark_reminded(&self, id: Uuid) -> Result<()> {
+        timed!(sqlx::query_file!("queries/events/mark_reminded.sql", id).execute(&self.pool))
+            .await?;
+        Ok(())
+    }
+    /// Records the RSVP, replacing an earlier one.
+    pub async fn set_rsvp(&self, event_id: Uuid, user_id: Uuid, status: &str) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/event_rsvps/set.sql", event_id, user_id, status)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn remove_rsvp(&self, event_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let res = timed!(
+            sqlx::query_file!("queries/event_rsvps/remove.sql", event_id, user_id)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+    pub async fn list_recipients(&self, event_id: Uuid) -> Result<Vec<EventRecipient>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                EventRecipient,
+                "queries/event_rsvps/list_recipients.sql",
+                event_id
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::{ClubsStorage, CreateVenue, UsersStorage, VenuesStorage};
+    use chrono::Duration;
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    fn event(title: &str, starts_at: DateTime<Utc>, created_by: Uuid) -> CreateEvent<'_> {
+        CreateEvent {
+            title,
+            description: "",
+            venue: "",
+            starts_at,
+            created_by,
+            club_id: None,
+            venue_id: None,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_rsvps(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let host = create_user(&pool, "host").await?;
+        let guest = create_user(&pool, "guest").await?;
+        let storage = EventsStorage::new(pool.clone()).await?;
+        let event = storage
+            .create(CreateEvent {
+                venue: "Library",
+                ..event("Reading night", Utc::now() + Duration::days(3), host)
+            })
+            .await?;
+
+        storage.set_rsvp(event.id, host, "going").await?;
+        storage.set_rsvp(event.id, guest, "going").await?;
+        // A second answer replaces the first
+        storage.set_rsvp(event.id, guest, "interested").await?;
+
+        let summary = storage.get(event.id, Some(guest)).await?.expect("found");
+        assert_eq!(summary.going_count, 1);
+        assert_eq!(summary.interested_count, 1);
+        assert_eq!(summary.viewer_status.as_deref(), Some("interested"));
+        let anonymous = storage.get(event.id, None).await?.expect("found");
+        assert_eq!(anonymous.viewer_status, None);
+
+        assert_eq!(storage.list_recipients(event.id).await?.len(), 2);
+        assert!(storage.remove_rsvp(event.id, guest).await?);
+        assert!(!storage.remove_rsvp(event.id, guest).await?);
+        assert_eq!(storage.list_recipients(event.id).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_upcoming_and_reminders(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let host = create_user(&pool, "host").await?;
+        let clubs = ClubsStorage::new(pool.clone()).await?;
+        let club = clubs
+            .create("readers", "Readers", "", host)
+            .await?
+            .expect("created");
+        let venue = VenuesStorage::new(pool.clone())
+            .await?
+            .create(CreateVenue {
+                slug: "library",
+                name: "Library",
+                kind: "library",
+                address: "",
+                latitude: 55.75,
+                longitude: 37.61,
+                created_by: host,
+            })
+            .await?
+            .expect("created");
+        let storage = EventsStorage::new(pool.clone()).await?;
+        let now = Utc::now();
+        let past = storage
+            .create(event("Past", now - Duration::days(1), host))
+            .await?;
+        let soon = storage
+            .create(CreateEvent {
+                club_id: Some(club.id),
+                ..event("Soon", now + Duration::hours(2), host)
+            })
+            .await?;
+        let later = storage
+            .create(CreateEvent {
+                venue_id: Some(venue.id),
+                ..event("Later", now + Duration::days(7), host)
+            })
+            .await?;
+
+        let upcoming = storage.list_upcoming(None, 10).await?;
+        let ids: Vec<_> = upcoming.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![soon.id, later.id]);
+        assert_eq!(upcoming[0].club_slug.as_deref(), Some("readers"));
+
+        let by_club = storage.list_upcoming_by_club(club.id, None, 10).await?;
+        assert_eq!(by_club.len(), 1);
+        let by_venue = storage.list_upcoming_by_venue(venue.id, None, 10).await?;
+        assert_eq!(by_venue.len(), 1);
+        assert_eq!(by_venue[0].venue_slug.as_deref(), Some("library"));
+
+        let due = storage.list_due_reminders(86_400.0, 10).await?;
+        assert_eq!(due.iter().map(|e| e.id).collect::<Vec<_>>(), vec![soon.id]);
+        storage.mark_reminded(soon.id).await?;
+        assert!(storage.list_due_reminders(86_400.0, 10).await?.is_empty());
+        assert!(!ids.contains(&past.id));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::Genre;
+
+/// The genre taxonomy and the genres users picked as interests.
+#[derive(Clone, Debug)]
+pub struct GenresStorage {
+    pool: Pool<Postgres>,
+}
+
+impl GenresStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// `None` when the slug is taken.
+    pub async fn create(
+        &self,
+        slug: &str,
+        name: &str,
+        parent_id: Option<Uuid>,
+    ) -> Result<Option<Genre>> {
+        let res = timed!(
+            sqlx::query_file_as!(Genre, "queries/genres/create.sql", slug, name, parent_id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Genre>> {
+        let res = timed!(
+            sqlx::query_file_as!(Genre, "queries/genres/get_by_id.sql", id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list(&self) -> Result<Vec<Genre>> {
+        let res =
+            timed!(sqlx::query_file_as!(Genre, "queries/genres/list.sql").fetch_all(&self.pool))
+                .await?;
+        Ok(res)
+    }
+    /// `None` when the genre does not exist or `parent_id` is the genre
+    /// itself or one of its subgenres.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        slug: &str,
+        name: &str,
+        parent_id: Option<Uuid>,
+    ) -> Result<Option<Genre>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                Genre,
+                "queries/genres/update.sql",
+                id,
+                slug,
+                name,
+                parent_id
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Fails with a foreign key violation while the genre has subgenres.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let res =
+            timed!(sqlx::query_file!("queries/genres/delete.sql", id).execute(&self.pool)).await?;
+        Ok(res.rows_affected() > 0)
+    }
+    pub async fn add_interest(&self, user_id: Uuid, genre_id: Uuid) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/user_interests/add.sql", user_id, genre_id)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn remove_interest(&self, user_id: Uuid, genre_id: Uuid) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/user_interests/remove.sql", user_id, genre_id)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    /// Ids of the genres `user_id` is interested in.
+    pub async fn interest_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/user_interests/list_genre_ids.sql", user_id)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    #[sqlx::test]
+    async fn test_genre_hierarchy(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = GenresStorage::new(pool.clone()).await?;
+
+        let fiction = storage
+            .create("fiction", "Fiction", None)
+            .await?
+            .expect("created");
+        let scifi = storage
+            .create("science-fiction", "Science fiction", Some(fiction.id))
+            .await?
+            .expect("created");
+        // Slugs are unique regardless of case
+        assert!(storage.create("Fiction", "Other", None).await?.is_none());
+
+        // A genre cannot move under itself or its subgenres
+        assert!(
+            storage
+                .update(fiction.id, "fiction", "Fiction", Some(scifi.id))
+                .await?
+                .is_none()
+        );
+        assert!(
+            storage
+                .update(fiction.id, "fiction", "Fiction", Some(fiction.id))
+                .await?
+                .is_none()
+        );
+
+        let err = storage.delete(fiction.id).await.unwrap_err();
+        assert!(matches!(err, sqlx::Error::Database(e) if e.is_foreign_key_violation()));
+        let moved = storage
+            .update(scifi.id, "sf", "SF", None)
+            .await?
+            .expect("updated");
+        assert_eq!((moved.slug.as_str(), moved.parent_id), ("sf", None));
+        assert!(storage.delete(fiction.id).await?);
+        assert!(!storage.delete(fiction.id).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_interests(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let user = create_user(&pool, "reader").await?;
+        let storage = GenresStorage::new(pool.clone()).await?;
+        let poetry = storage
+            .create("poetry", "Poetry", None)
+            .await?
+            .expect("created");
+
+        storage.add_interest(user, poetry.id).await?;
+        storage.add_interest(user, poetry.id).await?;
+        assert_eq!(storage.interest_ids(user).await?, vec![poetry.id]);
+        storage.remove_interest(user, poetry.id).await?;
+        assert!(storage.interest_ids(user).await?.is_empty());
+
+        // Deleting a genre drops it from interests
+        storage.add_interest(user, poetry.id).await?;
+        storage.delete(poetry.id).await?;
+        assert!(storage.interest_ids(user).await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use sqlx::{Pool, Postgres, Result};
+
+use crate::models::domain::IdempotentResponse;
+
+/// Responses stored under `Idempotency-Key`s. Keys are stored already
+/// hashed together with the client's credentials.
+#[derive(Clone, Debug)]
+pub struct IdempotencyKeysStorage {
+    pool: Pool<Postgres>,
+}
+
+impl IdempotencyKeysStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// Takes `key` for a new request unless it is held by one younger than
+    /// `ttl_hours`; `true` when the caller should run the request.
+    pub async fn claim(&self, key: &str, request_hash: &str, ttl_hours: i32) -> Result<bool> {
+        let res = timed!(
+            sqlx::query_file_scalar!(
+                "queries/idempotency_keys/claim.sql",
+                key,
+                request_hash,
+                ttl_hours
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res.is_some())
+    }
+    pub async fn get(&self, key: &str) -> Result<Option<IdempotentResponse>> {
+        let res = timed!(
+            sqlx::query_file_as!(IdempotentResponse, "queries/idempotency_keys/get.sql", key)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn complete(
+        &self,
+        key: &str,
+        status: i16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<()> {
+        timed!(
+            sqlx::query_file!(
+                "queries/idempotency_keys/complete.sql",
+                key,
+                status,
+                content_type,
+                body
+            )
+            .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn release(&self, key: &str) -> Result<()> {
+        timed!(sqlx::query_file!("queries/idempotency_keys/release.sql", key).execute(&self.pool))
+            .await?;
+        Ok(())
+    }
+    /// Deletes at most `limit` keys older than `ttl_hours`, returning how
+    /// many were removed.
+    pub async fn delete_expired(&self, ttl_hours: i32, limit: i64) -> Result<u64> {
+        let res = timed!(
+            sqlx::query_file!(
+                "queries/idempotency_keys/delete_expired.sql",
+                ttl_hours,
+                limit
+            )
+            .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_claim_and_replay(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = IdempotencyKeysStorage::new(pool.clone()).await?;
+
+        assert!(storage.claim("key", "hash", 24).await?);
+        // Held by the running request
+        assert!(!storage.claim("key", "hash", 24).await?);
+        let running = storage.get("key").await?.unwrap();
+        assert_eq!(running.status, None);
+
+        storage
+            .complete("key", 201, Some("application/json"), b"{}")
+            .await?;
+        let done = storage.get("key").await?.unwrap();
+        assert_eq!(done.request_hash, "hash");
+        assert_eq!(done.status, Some(201));
+        assert_eq!(done.body.as_deref(), Some(&b"{}"[..]));
+
+        // A failed request frees the key for a retry
+        storage.release("key").await?;
+        assert!(storage.claim("key", "other", 24).await?);
+
+        // Expired keys are claimed again and purged
+        sqlx::query("UPDATE idempotency_keys SET created_at = NOW() - INTERVAL '2 days'")
+            .execute(&pool)
+            .await?;
+        assert!(storage.claim("key", "third", 24).await?);
+        assert_eq!(storage.get("key").await?.unwrap().request_hash, "third");
+        assert_eq!(storage.delete_expired(24, 100).await?, 0);
+        sqlx::query("UPDATE idempotency_keys SET created_at = NOW() - INTERVAL '2 days'")
+            .execute(&pool)
+            .await?;
+        assert_eq!(storage.delete_expired(24, 100).await?, 1);
+
+        Ok(())
+    }
+}
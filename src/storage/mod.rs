@@ -1,15 +1,183 @@
+// First, so the storages below can use its `timed!`
+#[macro_use]
+pub mod query_stats;
+mod api_keys_storage;
+mod clubs_storage;
+mod curator_applications_storage;
+mod data_exports_storage;
+mod email_changes_storage;
+mod events_storage;
+mod genres_storage;
+mod idempotency_keys_storage;
+mod polls_storage;
+mod reports_storage;
+mod system_storage;
+mod user_blocks_storage;
+mod users_repository;
 mod users_storage;
+mod venues_storage;
 use anyhow::Result;
-use config::Config;
-use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+pub use api_keys_storage::ApiKeysStorage;
+pub use clubs_storage::ClubsStorage;
+pub use curator_applications_storage::CuratorApplicationsStorage;
+pub use data_exports_storage::DataExportsStorage;
+pub use email_changes_storage::EmailChangesStorage;
+pub use events_storage::{CreateEvent, EventsStorage};
+pub use genres_storage::GenresStorage;
+pub use idempotency_keys_storage::IdempotencyKeysStorage;
+pub use polls_storage::{CreatePoll, PollsStorage};
+pub use reports_storage::ReportsStorage;
+use sqlx::{
+    Pool, Postgres,
+    postgres::{PgConnectOptions, PgPoolOptions},
+};
+use std::{pin::Pin, str::FromStr, time::Duration};
+
+use crate::configuration::{Argon2Settings, DatabaseSettings};
+pub use system_storage::SystemStorage;
+pub use user_blocks_storage::UserBlocksStorage;
+pub use users_repository::UsersRepository;
 pub use users_storage::UsersStorage;
+pub use venues_storage::{CreateVenue, VenuesStorage};
+
+/// Table the session store keeps sessions in; created by `axum_session`,
+/// not by our migrations.
+pub const SESSIONS_TABLE: &str = "sessions_table";
 
-pub async fn get_pool(config: &Config) -> Result<Pool<Postgres>> {
-    let db_url = config.get_string("database.url")?;
-    let pool = PgPoolOptions::new()
-        .max_connections(8)
-        .connect(&db_url)
-        .await?;
+/// Future returned by the closures passed to [`UsersStorage::with_tx`].
+pub type TxFuture<'c, T> = Pin<Box<dyn Future<Output = sqlx::Result<T>> + Send + 'c>>;
+
+pub async fn get_pool(settings: &DatabaseSettings) -> Result<Pool<Postgres>> {
+    let (pool_options, connect_options) = pool_options(settings, &settings.url)?;
+    let pool = pool_options.connect_with(connect_options).await?;
     sqlx::migrate!().run(&pool).await?;
     Ok(pool)
 }
+
+/// Connects to the read replica at `database.read_url`, if one is configured.
+/// Migrations only ever run against the primary.
+pub async fn get_read_pool(settings: &DatabaseSettings) -> Result<Option<Pool<Postgres>>> {
+    if settings.read_url.is_empty() {
+        return Ok(None);
+    }
+    let (pool_options, connect_options) = pool_options(settings, &settings.read_url)?;
+    let pool = pool_options.connect_with(connect_options).await?;
+    Ok(Some(pool))
+}
+
+/// Password hashing costs from `argon2.*`. Existing hashes are upgraded as
+/// their owners sign in.
+pub fn argon2_params(settings: &Argon2Settings) -> Result<argon2::Params> {
+    let params = argon2::Params::new(
+        settings.memory_kib,
+        settings.iterations,
+        settings.parallelism,
+        None,
+    )
+    .map_err(|e| anyhow::anyhow!("invalid argon2 params: {e}"))?;
+    Ok(params)
+}
+
+pub(crate) fn pool_options(
+    settings: &DatabaseSettings,
+    db_url: &str,
+) -> Result<(PgPoolOptions, PgConnectOptions)> {
+    let pool_options = PgPoolOptions::new()
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .acquire_timeout(Duration::from_secs(settings.acquire_timeout_secs));
+    let mut connect_options =
+        PgConnectOptions::from_str(db_url)?.application_name(&settings.application_name);
+    if !settings.password.is_empty() {
+        connect_options = connect_options.password(&settings.password);
+    }
+    // 0 keeps the server default (no timeout)
+    if settings.statement_timeout_ms > 0 {
+        connect_options = connect_options.options([(
+            "statement_timeout",
+            settings.statement_timeout_ms.to_string(),
+        )]);
+    }
+    Ok((pool_options, connect_options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::Config;
+
+    #[test]
+    fn test_pool_options_defaults() {
+        let config = Config::builder()
+            .set_override(
+                "database.url",
+                "postgresql://postgres@localhost/culturelist",
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let settings = config.get::<DatabaseSettings>("database").unwrap();
+        let (pool, connect) = pool_options(&settings, &settings.url).unwrap();
+        assert_eq!(pool.get_max_connections(), 8);
+        assert_eq!(pool.get_min_connections(), 0);
+        assert_eq!(pool.get_acquire_timeout(), Duration::from_secs(30));
+        assert_eq!(connect.get_application_name(), Some("culturelist"));
+        assert_eq!(connect.get_options(), None);
+    }
+
+    #[test]
+    fn test_pool_options_from_config() {
+        let config = Config::builder()
+            .set_override(
+                "database.url",
+                "postgresql://postgres@localhost/culturelist",
+            )
+            .unwrap()
+            .set_override("database.max_connections", 20)
+            .unwrap()
+            .set_override("database.min_connections", 2)
+            .unwrap()
+            .set_override("database.acquire_timeout_secs", 5)
+            .unwrap()
+            .set_override("database.statement_timeout_ms", 3000)
+            .unwrap()
+            .set_override("database.application_name", "culturelist-worker")
+            .unwrap()
+            .build()
+            .unwrap();
+        let settings = config.get::<DatabaseSettings>("database").unwrap();
+        let (pool, connect) = pool_options(&settings, &settings.url).unwrap();
+        assert_eq!(pool.get_max_connections(), 20);
+        assert_eq!(pool.get_min_connections(), 2);
+        assert_eq!(pool.get_acquire_timeout(), Duration::from_secs(5));
+        assert_eq!(connect.get_application_name(), Some("culturelist-worker"));
+        assert_eq!(connect.get_options(), Some("-c statement_timeout=3000"));
+    }
+
+    #[test]
+    fn test_argon2_params() {
+        let defaults = argon2_params(&Argon2Settings::default()).unwrap();
+        assert_eq!(defaults, argon2::Params::default());
+
+        let config = Config::builder()
+            .set_override("argon2.memory_kib", 65536)
+            .unwrap()
+            .set_override("argon2.iterations", 3)
+            .unwrap()
+            .set_override("argon2.parallelism", 2)
+            .unwrap()
+            .build()
+            .unwrap();
+        let params = argon2_params(&config.get::<Argon2Settings>("argon2").unwrap()).unwrap();
+        assert_eq!(params.m_cost(), 65536);
+        assert_eq!(params.t_cost(), 3);
+        assert_eq!(params.p_cost(), 2);
+
+        let config = Config::builder()
+            .set_override("argon2.iterations", 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(argon2_params(&config.get::<Argon2Settings>("argon2").unwrap()).is_err());
+    }
+}
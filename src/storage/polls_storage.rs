@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{Poll, PollOption};
+
+/// Club polls, their options and votes.
+#[derive(Clone, Debug)]
+pub struct PollsStorage {
+    pool: Pool<Postgres>,
+}
+
+/// What is stored about a new poll.
+#[derive(Debug, Clone)]
+pub struct CreatePoll<'a> {
+    pub club_id: Uuid,
+    pub question: &'a str,
+    /// In display order
+    pub options: &'a [String],
+    pub multiple: bool,
+    pub results: &'a str,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub created_by: Uuid,
+}
+
+impl PollsStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// Creates the poll with its options.
+    pub async fn create(&self, poll: CreatePoll<'_>) -> Result<Poll> {
+        let mut tx = self.pool.begin().await?;
+        let res = timed!(
+            sqlx::query_file_as!(
+                Poll,
+                "queries/polls/create.sql",
+                poll.club_id,
+                poll.question,
+                poll.multiple,
+                poll.results,
+                poll.closes_at,
+                poll.created_by,
+            )
+            .fetch_one(&mut *tx)
+        )
+        .await?;
+        timed!(
+            sqlx::query_file!("queries/poll_options/create.sql", res.id, poll.options)
+                .execute(&mut *tx)
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(res)
+    }
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Poll>> {
+        let res = timed!(
+            sqlx::query_file_as!(Poll, "queries/polls/get_by_id.sql", id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// The newest polls first.
+    pub async fn list_by_club(&self, club_id: Uuid, limit: i64) -> Result<Vec<Poll>> {
+        let res = timed!(
+            sqlx::query_file_as!(Poll, "queries/polls/list_by_club.sql", club_id, limit)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Options in display order with their vote counts.
+    pub async fn list_options(&self, poll_id: Uuid) -> Result<Vec<PollOption>> {
+        let res = timed!(
+            sqlx::query_file_as!(PollOption, "queries/poll_options/list.sql", poll_id)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn count_voters(&self, poll_id: Uuid) -> Result<i64> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/poll_votes/count_voters.sql", poll_id)
+                .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Ids of the options `user_id` voted for.
+    pub async fn list_choices(&self, poll_id: Uuid, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/poll_votes/list_choices.sql", poll_id, user_id)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Replaces the user's votes with the options `choose` picks given the
+    /// current ones; ids of other polls' options are ignored. The poll stays
+    /// locked meanwhile, so concurrent changes never work from stale choices.
+    pub async fn set_choices(
+        &self,
+        poll_id: Uuid,
+        user_id: Uuid,
+        choose: impl FnOnce(&[Uuid]) -> Vec<Uuid>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        timed!(sqlx::query_file!("queries/polls/lock.sql", poll_id).fetch_optional(&mut *tx))
+            .await?;
+        let current = timed!(
+            sqlx::query_file_scalar!("queries/poll_votes/list_choices.sql", poll_id, user_id)
+                .fetch_all(&mut *tx)
+        )
+        .await?;
+        let option_ids = choose(&current);
+        timed!(
+            sqlx::query_file!("queries/poll_votes/clear.sql", poll_id, user_id).execute(&mut *tx)
+        )
+        .await?;
+        timed!(
+            sqlx::query_file!("queries/poll_votes/add.sql", poll_id, &option_ids, user_id)
+                .execute(&mut *tx)
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::{CreateUser, toggle_choice};
+    use crate::storage::{ClubsStorage, UsersStorage};
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    fn poll<'a>(
+        club_id: Uuid,
+        question: &'a str,
+        options: &'a [String],
+        created_by: Uuid,
+    ) -> CreatePoll<'a> {
+        CreatePoll {
+            club_id,
+            question,
+            options,
+            multiple: false,
+            results: "always",
+            closes_at: None,
+            created_by,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_votes(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let host = create_user(&pool, "host").await?;
+        let reader = create_user(&pool, "reader").await?;
+        let club = ClubsStorage::new(pool.clone())
+            .await?
+            .create("readers", "Readers", "", host)
+            .await?
+            .expect("created");
+        let storage = PollsStorage::new(pool.clone()).await?;
+        let options = ["Dune".to_string(), "Solaris".to_string()];
+        let book = storage
+            .create(CreatePoll {
+                multiple: true,
+                ..poll(club.id, "Next book?", &options, host)
+            })
+            .await?;
+        let day = storage
+            .create(poll(club.id, "Meeting day?", &options, host))
+            .await?;
+
+        let listed = storage.list_options(book.id).await?;
+        let texts: Vec<_> = listed
+            .iter()
+            .map(|o| (o.position, o.text.as_str()))
+            .collect();
+        assert_eq!(texts, vec![(1, "Dune"), (2, "Solaris")]);
+        let (dune, solaris) = (listed[0].id, listed[1].id);
+        let foreign = storage.list_options(day.id).await?[0].id;
+
+        storage
+            .set_choices(book.id, host, |_| vec![dune, solaris])
+            .await?;
+        // Options of another poll are not counted
+        storage
+            .set_choices(book.id, reader, |_| vec![dune, foreign])
+            .await?;
+        assert_eq!(storage.list_choices(book.id, reader).await?, vec![dune]);
+        assert_eq!(storage.count_voters(book.id).await?, 2);
+        let votes: Vec<_> = storage
+            .list_options(book.id)
+            .await?
+            .iter()
+            .map(|o| o.votes)
+            .collect();
+        assert_eq!(votes, vec![2, 1]);
+
+        // New choices replace the old ones, none withdraws the vote
+        storage
+            .set_choices(book.id, reader, |_| vec![solaris])
+            .await?;
+        assert_eq!(storage.list_choices(book.id, reader).await?, vec![solaris]);
+        storage.set_choices(book.id, reader, |_| vec![]).await?;
+        assert_eq!(storage.count_voters(book.id).await?, 1);
+
+        let polls = storage.list_by_club(club.id, 10).await?;
+        assert_eq!(polls.len(), 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_choices(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let host = create_user(&pool, "host").await?;
+        let club = ClubsStorage::new(pool.clone())
+            .await?
+            .create("readers", "Readers", "", host)
+            .await?
+            .expect("created");
+        let storage = PollsStorage::new(pool.clone()).await?;
+        let options = ["Dune".to_string(), "Solaris".to_string()];
+        let day = storage
+            .create(poll(club.id, "Meeting day?", &options, host))
+            .await?;
+        let listed = storage.list_options(day.id).await?;
+
+        // Each change sees the other's outcome, so one answer remains
+        let (first, second) = tokio::join!(
+            storage.set_choices(day.id, host, |c| toggle_choice(c, listed[0].id, false)),
+            storage.set_choices(day.id, host, |c| toggle_choice(c, listed[1].id, false)),
+        );
+        first?;
+        second?;
+        assert_eq!(storage.list_choices(day.id, host).await?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Timing of every storage query: a `db.query` span per query, counters per
+//! query file for the admin traffic page, per-request totals for the access
+//! log and a warning for queries slower than `database.slow_query_ms`.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tracing::{Instrument, field, info_span, warn};
+
+use crate::models::domain::QueryTraffic;
+
+/// Rows in the report.
+const REPORT_ROWS: usize = 20;
+
+/// Wraps a `sqlx::query_file*!(...).fetch_*(executor)` chain in [`observe`],
+/// named after its file. Await the result as the bare query.
+macro_rules! timed {
+    (sqlx::query_file_as!($out:ident, $file:literal $(, $arg:expr)* $(,)?).$fetch:ident($executor:expr)) => {
+        $crate::storage::query_stats::observe(
+            $file,
+            &[$(stringify!($arg)),*],
+            sqlx::query_file_as!($out, $file $(, $arg)*).$fetch($executor),
+        )
+    };
+    (sqlx::$query:ident!($file:literal $(, $arg:expr)* $(,)?).$fetch:ident($executor:expr)) => {
+        $crate::storage::query_stats::observe(
+            $file,
+            &[$(stringify!($arg)),*],
+            sqlx::$query!($file $(, $arg)*).$fetch($executor),
+        )
+    };
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counter {
+    calls: u64,
+    errors: u64,
+    slow: u64,
+    total: Duration,
+    max: Duration,
+}
+
+pub const DEFAULT_SLOW_QUERY_MS: u64 = 500;
+/// Counters since startup, per process like the traffic stats.
+static COUNTERS: LazyLock<Mutex<HashMap<&'static str, Counter>>> = LazyLock::new(Mutex::default);
+/// 0 disables the slow query warning.
+static SLOW_QUERY_MS: AtomicU64 = AtomicU64::new(DEFAULT_SLOW_QUERY_MS);
+
+tokio::task_local! {
+    static REQUEST: RequestCounter;
+}
+
+/// Queries run while handling one request, see [`in_request`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RequestQueries {
+    pub count: u64,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Default)]
+struct RequestCounter {
+    count: AtomicU64,
+    micros: AtomicU64,
+}
+
+impl RequestCounter {
+    fn add(&self, elapsed: Duration) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+    fn totals(&self) -> RequestQueries {
+        RequestQueries {
+            count: self.count.load(Ordering::Relaxed),
+            elapsed: Duration::from_micros(self.micros.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Sets the slow query threshold from `database.slow_query_ms`.
+pub fn init(slow_query_ms: u64) {
+    SLOW_QUERY_MS.store(slow_query_ms, Ordering::Relaxed);
+}
+
+/// Runs `fut` counting the queries it makes. Tasks it spawns are not counted.
+pub async fn in_request<F: Future>(fut: F) -> (F::Output, RequestQueries) {
+    REQUEST
+        .scope(RequestCounter::default(), async {
+            let output = fut.await;
+            (output, REQUEST.with(RequestCounter::totals))
+        })
+        .await
+}
+
+/// Awaits one query under a `db.query` span and records its duration.
+/// `query` is the query file, or `<storage>::<method>` for queries built in
+/// code. `params` are the bind expressions; their values are never logged.
+pub(crate) async fn observe<T>(
+    query: &'static str,
+    params: &'static [&'static str],
+    fut: impl Future<Output = sqlx::Result<T>>,
+) -> sqlx::Result<T> {
+    let span = info_span!("db.query", query, elapsed_ms = field::Empty);
+    let started = Instant::now();
+    let result = fut.instrument(span.clone()).await;
+    let elapsed = started.elapsed();
+    span.record("elapsed_ms", elapsed.as_millis() as u64);
+    let slow = is_slow(elapsed);
+    record(query, elapsed, result.is_err(), slow);
+    let _ = REQUEST.try_with(|queries| queries.add(elapsed));
+    if slow {
+        warn!(
+            parent: &span,
+            query,
+            params = %redacted(params),
+            elapsed_ms = elapsed.as_millis() as u64,
+            "slow query"
+        );
+    }
+    result
+}
+
+fn is_slow(elapsed: Duration) -> bool {
+    let threshold = SLOW_QUERY_MS.load(Ordering::Relaxed);
+    threshold > 0 && elapsed >= Duration::from_millis(threshold)
+}
+
+fn record(query: &'static str, elapsed: Duration, failed: bool, slow: bool) {
+    let mut counters = COUNTERS.lock().expect("query stats lock poisoned");
+    let counter = counters.entry(query).or_default();
+    counter.calls += 1;
+    counter.errors += u64::from(failed);
+    counter.slow += u64::from(slow);
+    counter.total += elapsed;
+    counter.max = counter.max.max(elapsed);
+}
+
+/// `$1 = <expr>, ...` with the values left out.
+fn redacted(params: &[&str]) -> String {
+    params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| format!("${} = {param}", i + 1))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Queries with the most total time since startup.
+pub fn report() -> Vec<QueryTraffic> {
+    let counters: Vec<(&'static str, Counter)> = {
+        let counters = COUNTERS.lock().expect("query stats lock poisoned");
+        counters.iter().map(|(query, c)| (*query, *c)).collect()
+    };
+    let mut queries: Vec<QueryTraffic> = counters
+        .into_iter()
+        .map(|(query, c)| QueryTraffic {
+            query,
+            calls: c.calls,
+            errors: c.errors,
+            slow: c.slow,
+            total_ms: c.total.as_secs_f64() * 1000.0,
+            mean_ms: c.total.as_secs_f64() * 1000.0 / c.calls.max(1) as f64,
+            max_ms: c.max.as_secs_f64() * 1000.0,
+        })
+        .collect();
+    queries.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    queries.truncate(REPORT_ROWS);
+    queries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted() {
+        assert_eq!(
+            redacted(&["user_id", "data.email.to_lowercase()"]),
+            "$1 = user_id, $2 = data.email.to_lowercase()"
+        );
+        assert_eq!(redacted(&[]), "");
+    }
+
+    #[tokio::test]
+    async fn test_observe_counts_per_query_and_request() {
+        let ((), request) = in_request(async {
+            observe("queries/test/ok.sql", &["id"], async { Ok(()) })
+                .await
+                .unwrap();
+            observe::<()>("queries/test/failed.sql", &[], async {
+                Err(sqlx::Error::RowNotFound)
+            })
+            .await
+            .unwrap_err();
+        })
+        .await;
+        assert_eq!(request.count, 2);
+
+        // Other tests add their queries too, pushing these out of the report
+        let counters = COUNTERS.lock().unwrap();
+        let ok = counters["queries/test/ok.sql"];
+        assert_eq!((ok.calls, ok.errors), (1, 0));
+        let failed = counters["queries/test/failed.sql"];
+        assert_eq!((failed.calls, failed.errors), (1, 1));
+    }
+}
@@ -0,0 +1,212 @@
+use sqlx::{PgExecutor, Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{ModerationQueueItem, Report};
+
+/// Reports on user content and the content hidden because of them.
+#[derive(Clone, Debug)]
+pub struct ReportsStorage {
+    pool: Pool<Postgres>,
+}
+
+impl ReportsStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// `None` when the reporter already has an open report on the target.
+    pub async fn create(
+        &self,
+        target_kind: &str,
+        target_id: Uuid,
+        reporter_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<Report>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                Report,
+                "queries/reports/create.sql",
+                target_kind,
+                target_id,
+                reporter_id,
+                reason,
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Number of distinct users with an open report on the target.
+    pub async fn count_open(&self, target_kind: &str, target_id: Uuid) -> Result<i64> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/reports/count_open.sql", target_kind, target_id)
+                .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_queue(&self) -> Result<Vec<ModerationQueueItem>> {
+        let res = timed!(
+            sqlx::query_file_as!(ModerationQueueItem, "queries/reports/list_queue.sql")
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Closes the open reports on the target, returning how many were open.
+    pub async fn resolve_target_in(
+        executor: impl PgExecutor<'_>,
+        target_kind: &str,
+        target_id: Uuid,
+        status: &str,
+        resolved_by: Uuid,
+    ) -> Result<u64> {
+        let res = timed!(
+            sqlx::query_file!(
+                "queries/reports/resolve_target.sql",
+                target_kind,
+                target_id,
+                status,
+                resolved_by,
+            )
+            .execute(executor)
+        )
+        .await?;
+        Ok(res.rows_affected())
+    }
+    /// `hidden_by` is `None` when hidden automatically.
+    pub async fn hide_in(
+        executor: impl PgExecutor<'_>,
+        target_kind: &str,
+        target_id: Uuid,
+        hidden_by: Option<Uuid>,
+    ) -> Result<()> {
+        timed!(
+            sqlx::query_file!(
+                "queries/hidden_content/hide.sql",
+                target_kind,
+                target_id,
+                hidden_by
+            )
+            .execute(executor)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn hide(&self, target_kind: &str, target_id: Uuid) -> Result<()> {
+        Self::hide_in(&self.pool, target_kind, target_id, None).await
+    }
+    pub async fn unhide_in(
+        executor: impl PgExecutor<'_>,
+        target_kind: &str,
+        target_id: Uuid,
+    ) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/hidden_content/unhide.sql", target_kind, target_id)
+                .execute(executor)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn is_hidden(&self, target_kind: &str, target_id: Uuid) -> Result<bool> {
+        let res = timed!(
+            sqlx::query_file_scalar!(
+                "queries/hidden_content/is_hidden.sql",
+                target_kind,
+                target_id
+            )
+            .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::{CreateUser, REPORT_DISMISSED};
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    #[sqlx::test]
+    async fn test_one_open_report_per_reporter(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let target = create_user(&pool, "target").await?;
+        let first = create_user(&pool, "first").await?;
+        let second = create_user(&pool, "second").await?;
+        let storage = ReportsStorage::new(pool.clone()).await?;
+
+        assert!(
+            storage
+                .create("profile", target, first, "spam")
+                .await?
+                .is_some()
+        );
+        assert!(
+            storage
+                .create("profile", target, first, "spam")
+                .await?
+                .is_none()
+        );
+        assert!(
+            storage
+                .create("profile", target, second, "rude")
+                .await?
+                .is_some()
+        );
+        assert_eq!(storage.count_open("profile", target).await?, 2);
+
+        let queue = storage.list_queue().await?;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].target_label.as_deref(), Some("target"));
+        assert_eq!(queue[0].reasons, vec!["spam", "rude"]);
+
+        let closed =
+            ReportsStorage::resolve_target_in(&pool, "profile", target, REPORT_DISMISSED, first)
+                .await?;
+        assert_eq!(closed, 2);
+        assert_eq!(storage.count_open("profile", target).await?, 0);
+        assert!(storage.list_queue().await?.is_empty());
+        // Closed reports no longer block reporting again
+        assert!(
+            storage
+                .create("profile", target, first, "spam")
+                .await?
+                .is_some()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_hide_and_unhide(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let target = create_user(&pool, "target").await?;
+        let storage = ReportsStorage::new(pool.clone()).await?;
+
+        assert!(!storage.is_hidden("profile", target).await?);
+        storage.hide("profile", target).await?;
+        storage.hide("profile", target).await?;
+        assert!(storage.is_hidden("profile", target).await?);
+        ReportsStorage::unhide_in(&pool, "profile", target).await?;
+        assert!(!storage.is_hidden("profile", target).await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use sqlx::{Pool, Postgres, Result};
+
+use crate::{
+    models::domain::{AppliedMigration, MigrationStatus, PoolStats},
+    storage::{SESSIONS_TABLE, query_stats},
+};
+
+#[derive(Clone, Debug)]
+pub struct SystemStorage {
+    pool: Pool<Postgres>,
+}
+
+impl SystemStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    pub async fn migrations(&self) -> Result<Vec<MigrationStatus>> {
+        let applied = timed!(
+            sqlx::query_file_as!(AppliedMigration, "queries/system/migrations.sql")
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        let result = sqlx::migrate!()
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| {
+                let installed = applied.iter().find(|a| a.version == m.version && a.success);
+                MigrationStatus {
+                    version: m.version,
+                    description: m.description.to_string(),
+                    installed_on: installed.map(|a| a.installed_on),
+                    applied: installed.is_some(),
+                }
+            })
+            .collect();
+        Ok(result)
+    }
+    /// Deletes at most `limit` sessions past their expiry, returning how
+    /// many were removed.
+    pub async fn delete_expired_sessions(&self, limit: i64) -> Result<u64> {
+        // Not a query file: the session store creates this table at runtime
+        let res = query_stats::observe(
+            "system_storage::delete_expired_sessions",
+            &["limit"],
+            sqlx::query(&format!(
+                "DELETE FROM {SESSIONS_TABLE} WHERE id IN \
+                (SELECT id FROM {SESSIONS_TABLE} WHERE expires < EXTRACT(EPOCH FROM NOW()) LIMIT $1)"
+            ))
+            .bind(limit)
+            .execute(&self.pool),
+        )
+        .await?;
+        Ok(res.rows_affected())
+    }
+    pub fn pool_stats(&self) -> PoolStats {
+        PoolStats {
+            size: self.pool.size(),
+            idle: self.pool.num_idle(),
+            min_connections: self.pool.options().get_min_connections(),
+            max_connections: self.pool.options().get_max_connections(),
+        }
+    }
+}
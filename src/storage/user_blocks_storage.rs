@@ -0,0 +1,127 @@
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::UserBlock;
+
+/// Blocks and mutes between users.
+#[derive(Clone, Debug)]
+pub struct UserBlocksStorage {
+    pool: Pool<Postgres>,
+}
+
+impl UserBlocksStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// Records the relation, replacing an earlier one between the pair.
+    pub async fn set(&self, user_id: Uuid, target_id: Uuid, kind: &str) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/user_blocks/set.sql", user_id, target_id, kind)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    pub async fn remove(&self, user_id: Uuid, target_id: Uuid) -> Result<bool> {
+        let res = timed!(
+            sqlx::query_file!("queries/user_blocks/remove.sql", user_id, target_id)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<UserBlock>> {
+        let res = timed!(
+            sqlx::query_file_as!(UserBlock, "queries/user_blocks/list.sql", user_id)
+                .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Whether `user_id` blocked `target_id`; mutes do not count.
+    pub async fn is_blocked(&self, user_id: Uuid, target_id: Uuid) -> Result<bool> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/user_blocks/is_blocked.sql", user_id, target_id)
+                .fetch_one(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    #[sqlx::test]
+    async fn test_blocks(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let alice = create_user(&pool, "alice").await?;
+        let bob = create_user(&pool, "bob").await?;
+        let storage = UserBlocksStorage::new(pool.clone()).await?;
+
+        storage.set(alice, bob, "mute").await?;
+        assert!(!storage.is_blocked(alice, bob).await?);
+        // A block replaces the mute
+        storage.set(alice, bob, "block").await?;
+        assert!(storage.is_blocked(alice, bob).await?);
+        assert!(!storage.is_blocked(bob, alice).await?);
+
+        let blocks = storage.list(alice).await?;
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].username, "bob");
+        assert_eq!(blocks[0].kind, "block");
+        assert!(storage.list(bob).await?.is_empty());
+
+        // Nobody blocks themselves
+        assert!(storage.set(alice, alice, "block").await.is_err());
+
+        assert!(storage.remove(alice, bob).await?);
+        assert!(!storage.remove(alice, bob).await?);
+        assert!(!storage.is_blocked(alice, bob).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_suggest_hides_blocks(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let alice = create_user(&pool, "alice").await?;
+        let bob = create_user(&pool, "bob").await?;
+        let carol = create_user(&pool, "carol").await?;
+        let storage = UserBlocksStorage::new(pool.clone()).await?;
+        let users = UsersStorage::new(pool.clone()).await?;
+
+        storage.set(alice, bob, "block").await?;
+        storage.set(alice, carol, "mute").await?;
+        // The blocked user no longer finds the blocker
+        assert!(users.suggest("alice", Some(bob), 10).await?.is_empty());
+        // A muted user still does
+        assert_eq!(users.suggest("alice", Some(carol), 10).await?.len(), 1);
+        // The blocker sees neither of them
+        assert!(users.suggest("bob", Some(alice), 10).await?.is_empty());
+        assert!(users.suggest("carol", Some(alice), 10).await?.is_empty());
+        assert_eq!(users.suggest("bob", None, 10).await?.len(), 1);
+
+        Ok(())
+    }
+}
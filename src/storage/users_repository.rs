@@ -0,0 +1,96 @@
+use std::fmt::Debug;
+
+use sqlx::Result;
+use uuid::Uuid;
+
+use crate::{
+    models::domain::{CreateUser, UpdateUser, User, UserData, UserList, UserSearch},
+    storage::UsersStorage,
+};
+
+/// User records as [`UsersService`](crate::services::UsersService) needs
+/// them. [`UsersStorage`] is the Postgres implementation; tests and other
+/// backends provide their own.
+#[async_trait::async_trait]
+pub trait UsersRepository: Debug + Send + Sync + 'static {
+    /// Stores the user with `password` hashed.
+    async fn create(&self, data: CreateUser) -> Result<User>;
+    /// Fails when no user has `email`.
+    async fn verify_user(&self, email: &str, password: &str) -> Result<bool>;
+    /// Spends the time of a password check for unknown accounts.
+    fn verify_dummy(&self, password: &str);
+    async fn get_by_email(&self, email: &str) -> Result<Option<User>>;
+    /// The account `email` duplicates once both are canonicalized.
+    async fn get_by_canonical_email(&self, email: &str) -> Result<Option<User>>;
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<User>>;
+    async fn get_by_username(&self, username: &str) -> Result<Option<User>>;
+    async fn list_users(&self, data: UserSearch) -> Result<UserList>;
+    async fn update(&self, id: Uuid, data: UpdateUser) -> Result<Option<User>>;
+    async fn delete(&self, id: Uuid) -> Result<Option<Uuid>>;
+    async fn set_preferences(
+        &self,
+        id: Uuid,
+        locale: Option<&str>,
+        timezone: &str,
+    ) -> Result<Option<User>>;
+    async fn set_theme(&self, id: Uuid, theme: Option<&str>) -> Result<Option<User>>;
+    async fn make_admin(&self, id: Uuid) -> Result<Option<User>>;
+    /// Scrubs personal data and hands content to the ghost user.
+    async fn anonymize(&self, id: Uuid) -> Result<Option<User>>;
+    /// Everything stored about the user besides the profile.
+    async fn export_data(&self, id: Uuid) -> Result<Option<UserData>>;
+}
+
+#[async_trait::async_trait]
+impl UsersRepository for UsersStorage {
+    async fn create(&self, data: CreateUser) -> Result<User> {
+        UsersStorage::create(self, data).await
+    }
+    async fn verify_user(&self, email: &str, password: &str) -> Result<bool> {
+        UsersStorage::verify_user(self, email, password).await
+    }
+    fn verify_dummy(&self, password: &str) {
+        UsersStorage::verify_dummy(self, password)
+    }
+    async fn get_by_email(&self, email: &str) -> Result<Option<User>> {
+        UsersStorage::get_by_email(self, email).await
+    }
+    async fn get_by_canonical_email(&self, email: &str) -> Result<Option<User>> {
+        UsersStorage::get_by_canonical_email(self, email).await
+    }
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<User>> {
+        UsersStorage::get_by_id(self, id).await
+    }
+    async fn get_by_username(&self, username: &str) -> Result<Option<User>> {
+        UsersStorage::get_by_username(self, username).await
+    }
+    async fn list_users(&self, data: UserSearch) -> Result<UserList> {
+        UsersStorage::list_users(self, data).await
+    }
+    async fn update(&self, id: Uuid, data: UpdateUser) -> Result<Option<User>> {
+        UsersStorage::update(self, id, data).await
+    }
+    async fn delete(&self, id: Uuid) -> Result<Option<Uuid>> {
+        UsersStorage::delete(self, id).await
+    }
+    async fn set_preferences(
+        &self,
+        id: Uuid,
+        locale: Option<&str>,
+        timezone: &str,
+    ) -> Result<Option<User>> {
+        UsersStorage::set_preferences(self, id, locale, timezone).await
+    }
+    async fn set_theme(&self, id: Uuid, theme: Option<&str>) -> Result<Option<User>> {
+        UsersStorage::set_theme(self, id, theme).await
+    }
+    async fn make_admin(&self, id: Uuid) -> Result<Option<User>> {
+        UsersStorage::make_admin(self, id).await
+    }
+    async fn anonymize(&self, id: Uuid) -> Result<Option<User>> {
+        UsersStorage::anonymize(self, id).await
+    }
+    async fn export_data(&self, id: Uuid) -> Result<Option<UserData>> {
+        UsersStorage::export_data(self, id).await
+    }
+}
@@ -1,73 +1,191 @@
-use sqlx::{Pool, Postgres, Result};
+use std::sync::{Arc, OnceLock};
 
-use crate::models::{CreateUser, UpdateUser, User, UserListResponse, UserSearch};
+use argon2::{Algorithm, Argon2, Params, Version, password_hash::PasswordHash};
+use sqlx::{PgConnection, PgExecutor, Pool, Postgres, QueryBuilder, Result};
+
+use crate::{
+    markdown,
+    models::domain::{
+        CreateUser, EmailCanonicalSettings, GHOST_USER_ID, SearchSettings, UpdateUser, User,
+        UserData, UserList, UserSearch, UserSuggestion, anonymized_email, anonymized_username,
+        canonical_email, escape_like,
+    },
+    storage::{TxFuture, query_stats},
+};
 
 #[derive(Clone, Debug)]
 pub struct UsersStorage {
     pool: Pool<Postgres>,
+    /// Replica for listings and lookups, the primary when none is configured
+    read_pool: Pool<Postgres>,
+    argon2_params: Params,
+    /// Hash checked by [`Self::verify_dummy`], made with `argon2_params`
+    dummy_hash: Arc<OnceLock<String>>,
+    search: SearchSettings,
+    email_canonical: EmailCanonicalSettings,
 }
 
 impl UsersStorage {
     pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
-        let storage = Self { pool };
+        let storage = Self {
+            read_pool: pool.clone(),
+            pool,
+            argon2_params: Params::default(),
+            dummy_hash: Arc::default(),
+            search: SearchSettings::default(),
+            email_canonical: EmailCanonicalSettings::default(),
+        };
         Ok(storage)
     }
+    pub fn with_read_pool(mut self, read_pool: Option<Pool<Postgres>>) -> Self {
+        if let Some(read_pool) = read_pool {
+            self.read_pool = read_pool;
+        }
+        self
+    }
+    pub fn with_argon2_params(mut self, params: Params) -> Self {
+        self.argon2_params = params;
+        self.dummy_hash = Arc::default();
+        self
+    }
+    pub fn with_search_settings(mut self, search: SearchSettings) -> Self {
+        self.search = search;
+        self
+    }
+    pub fn with_email_canonical_settings(
+        mut self,
+        email_canonical: EmailCanonicalSettings,
+    ) -> Self {
+        self.email_canonical = email_canonical;
+        self
+    }
+    /// `email` as compared for duplicate accounts, see [`canonical_email`].
+    pub fn canonical_email(&self, email: &str) -> String {
+        canonical_email(email, &self.email_canonical)
+    }
     pub async fn create(&self, data: CreateUser) -> Result<User> {
-        let password_hash =
-            hash_password(&data.password).map_err(|_| sqlx::Error::WorkerCrashed)?;
-        let result = sqlx::query_file_as!(
-            User,
-            "queries/users/create.sql",
-            data.username,
-            data.email.to_lowercase(),
-            password_hash,
-            data.first_name,
-            data.last_name,
-            data.bio,
-        )
-        .fetch_one(&self.pool)
+        let password_hash = self.hash_password(&data.password)?;
+        let bio_html = data.bio.as_deref().map(markdown::render);
+        let email_canonical = self.canonical_email(&data.email);
+        let result = timed!(
+            sqlx::query_file_as!(
+                User,
+                "queries/users/create.sql",
+                data.username,
+                data.email.to_lowercase(),
+                password_hash,
+                data.first_name,
+                data.last_name,
+                data.bio,
+                data.profile_extras,
+                bio_html,
+                email_canonical,
+            )
+            .fetch_one(&self.pool)
+        )
         .await?;
         Ok(result)
     }
+    /// Checks the password and, when the stored hash was made with other
+    /// Argon2 parameters, replaces it with one using the current ones.
     pub async fn verify_user(&self, email: &str, password: &str) -> Result<bool> {
-        let password_hash: Option<String> =
-            sqlx::query_scalar("SELECT password FROM users WHERE email = $1")
+        let row: Option<(uuid::Uuid, String)> = query_stats::observe(
+            "users_storage::verify_user",
+            &["email.to_lowercase()"],
+            sqlx::query_as("SELECT id, password FROM users WHERE email = $1")
                 .bind(email.to_lowercase())
-                .fetch_optional(&self.pool)
-                .await?;
-        let res = password_hash
-            .and_then(|hash| verify_password(&hash, password).ok())
-            .ok_or(sqlx::Error::WorkerCrashed)?;
-        Ok(res)
+                .fetch_optional(&self.pool),
+        )
+        .await?;
+        let (id, password_hash) = row.ok_or(sqlx::Error::WorkerCrashed)?;
+        if !verify_password(&password_hash, password).map_err(|_| sqlx::Error::WorkerCrashed)? {
+            return Ok(false);
+        }
+        if needs_rehash(&password_hash, &self.argon2_params) {
+            // The sign in itself already succeeded; a failed upgrade is retried next time
+            if let Err(e) = self.rehash(id, password).await {
+                tracing::warn!(user_id = %id, "failed to rehash password: {e}");
+            }
+        }
+        Ok(true)
+    }
+    async fn rehash(&self, id: uuid::Uuid, password: &str) -> Result<()> {
+        let password_hash = self.hash_password(password)?;
+        timed!(
+            sqlx::query_file!("queries/users/set_password.sql", id, password_hash)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(())
+    }
+    /// Runs a password check against a throwaway hash, so failed sign-ins
+    /// for unknown accounts take as long as those for existing ones.
+    pub fn verify_dummy(&self, password: &str) {
+        let dummy_hash = self.dummy_hash.get_or_init(|| {
+            self.hash_password("dummy-password-for-timing")
+                .unwrap_or_default()
+        });
+        let _ = verify_password(dummy_hash, password);
     }
+    fn hash_password(&self, password: &str) -> Result<String> {
+        hash_password(&self.argon2_params, password).map_err(|_| sqlx::Error::WorkerCrashed)
+    }
+    // Sign in and session loading read from the primary: they must see rows
+    // written moments ago by sign up.
     pub async fn get_by_email(&self, email: &str) -> Result<Option<User>> {
-        let res =
+        let res = timed!(
             sqlx::query_file_as!(User, "queries/users/get_by_email.sql", email.to_lowercase())
                 .fetch_optional(&self.pool)
-                .await?;
+        )
+        .await?;
         Ok(res)
     }
-    pub async fn get_by_id(&self, id: uuid::Uuid) -> Result<Option<User>> {
-        let res = sqlx::query_file_as!(User, "queries/users/get_by_id.sql", id,)
+    /// The account whose email folds to the same address as `email`, which
+    /// may be written differently. Reads the primary like [`Self::get_by_email`].
+    pub async fn get_by_canonical_email(&self, email: &str) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                User,
+                "queries/users/get_by_canonical_email.sql",
+                self.canonical_email(email)
+            )
             .fetch_optional(&self.pool)
-            .await?;
+        )
+        .await?;
         Ok(res)
     }
-    pub async fn list_users(&self, data: UserSearch) -> Result<UserListResponse> {
-        let total_count = sqlx::query_file_scalar!("queries/users/list_count.sql", data.search)
-            .fetch_one(&self.pool)
-            .await?
-            .unwrap_or_default();
+    pub async fn get_by_id(&self, id: uuid::Uuid) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/get_by_id.sql", id,)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list_users(&self, data: UserSearch) -> Result<UserList> {
+        // Binds depend on the filters, so they are not listed
+        let total_count = query_stats::observe(
+            "users_storage::count_users",
+            &[],
+            count_query(&data)
+                .build_query_scalar::<i64>()
+                .fetch_one(&self.read_pool),
+        )
+        .await?;
         // Empty results are valid, continue with empty user list
         let limit = data.limit.unwrap_or(20);
         let offset = data.offset.unwrap_or(0);
 
-        let users =
-            sqlx::query_file_as!(User, "queries/users/list.sql", data.search, limit, offset,)
-                .fetch_all(&self.pool)
-                .await?;
+        let users = query_stats::observe(
+            "users_storage::list_users",
+            &[],
+            list_query(&data, limit, offset)
+                .build_query_as::<User>()
+                .fetch_all(&self.read_pool),
+        )
+        .await?;
 
-        let result = UserListResponse {
+        let result = UserList {
             users,
             total_count,
             limit,
@@ -76,64 +194,377 @@ impl UsersStorage {
         Ok(result)
     }
     pub async fn update(&self, id: uuid::Uuid, data: UpdateUser) -> Result<Option<User>> {
-        let result = sqlx::query_file_as!(
-            User,
-            "queries/users/update.sql",
-            id,
-            data.username,
-            data.email.map(|e| e.to_lowercase()),
-            data.password,
-            data.first_name,
-            data.last_name,
-            data.bio,
-        )
-        .fetch_optional(&self.pool)
+        let password_hash = data
+            .password
+            .map(|password| self.hash_password(&password))
+            .transpose()?;
+        let bio_html = data.bio.as_deref().map(markdown::render);
+        let email_canonical = data.email.as_deref().map(|e| self.canonical_email(e));
+        let result = timed!(
+            sqlx::query_file_as!(
+                User,
+                "queries/users/update.sql",
+                id,
+                data.username,
+                data.email.map(|e| e.to_lowercase()),
+                password_hash,
+                data.first_name,
+                data.last_name,
+                data.bio,
+                bio_html,
+                email_canonical,
+            )
+            .fetch_optional(&self.pool)
+        )
         .await?;
         Ok(result)
     }
     pub async fn delete(&self, id: uuid::Uuid) -> Result<Option<uuid::Uuid>> {
-        let result = sqlx::query_file_scalar!("queries/users/delete.sql", id)
-            .fetch_optional(&self.pool)
-            .await?;
+        let result = timed!(
+            sqlx::query_file_scalar!("queries/users/delete.sql", id).fetch_optional(&self.pool)
+        )
+        .await?;
         Ok(result)
     }
+    /// Deletes up to `limit` accounts over a day old whose username starts
+    /// with `prefix` and whose email is at `domain`, for test accounts.
+    pub async fn delete_disposable(&self, prefix: &str, domain: &str, limit: i64) -> Result<u64> {
+        let res = timed!(
+            sqlx::query_file!("queries/users/delete_disposable.sql", prefix, domain, limit)
+                .execute(&self.pool)
+        )
+        .await?;
+        Ok(res.rows_affected())
+    }
     pub async fn get_by_username(&self, username: &str) -> Result<Option<User>> {
-        let res = sqlx::query_file_as!(User, "queries/users/get_by_username.sql", username)
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/get_by_username.sql", username)
+                .fetch_optional(&self.read_pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// `pattern` is matched with LIKE, see [`escape_like`](crate::models::domain::escape_like).
+    /// `viewer_id` leaves out users hidden from the viewer by blocks and mutes.
+    pub async fn suggest(
+        &self,
+        pattern: &str,
+        viewer_id: Option<uuid::Uuid>,
+        limit: i64,
+    ) -> Result<Vec<UserSuggestion>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                UserSuggestion,
+                "queries/users/suggest.sql",
+                pattern,
+                limit,
+                viewer_id,
+                self.search.username_weight,
+                self.search.name_weight,
+                self.search.bio_weight
+            )
+            .fetch_all(&self.read_pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Users whose username starts with the `LIKE` `pattern`, for mentions.
+    pub async fn suggest_usernames(
+        &self,
+        pattern: &str,
+        viewer_id: Option<uuid::Uuid>,
+        limit: i64,
+    ) -> Result<Vec<UserSuggestion>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                UserSuggestion,
+                "queries/users/suggest_usernames.sql",
+                pattern,
+                limit,
+                viewer_id
+            )
+            .fetch_all(&self.read_pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Typo tolerant [`Self::suggest`] by trigram similarity to `query`,
+    /// which is not a `LIKE` pattern.
+    pub async fn suggest_similar(
+        &self,
+        query: &str,
+        viewer_id: Option<uuid::Uuid>,
+        limit: i64,
+    ) -> Result<Vec<UserSuggestion>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                UserSuggestion,
+                "queries/users/suggest_similar.sql",
+                query,
+                limit,
+                viewer_id,
+                self.search.username_weight,
+                self.search.name_weight
+            )
+            .fetch_all(&self.read_pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn set_badge(&self, id: uuid::Uuid, badge: Option<&str>) -> Result<Option<User>> {
+        Self::set_badge_in(&self.pool, id, badge).await
+    }
+    pub async fn set_badge_in(
+        executor: impl PgExecutor<'_>,
+        id: uuid::Uuid,
+        badge: Option<&str>,
+    ) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/set_badge.sql", id, badge)
+                .fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Sets `badge` only if the user has none, keeping the one an admin
+    /// assigned.
+    pub async fn grant_badge_in(
+        executor: impl PgExecutor<'_>,
+        id: uuid::Uuid,
+        badge: &str,
+    ) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/grant_badge.sql", id, badge)
+                .fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn make_admin(&self, id: uuid::Uuid) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/make_admin.sql", id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn set_username_in(
+        executor: impl PgExecutor<'_>,
+        id: uuid::Uuid,
+        username: &str,
+    ) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/set_username.sql", id, username)
+                .fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// `email_canonical` comes from [`Self::canonical_email`].
+    pub async fn set_email_in(
+        executor: impl PgExecutor<'_>,
+        id: uuid::Uuid,
+        email: &str,
+        email_canonical: &str,
+    ) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                User,
+                "queries/users/set_email.sql",
+                id,
+                email.to_lowercase(),
+                email_canonical
+            )
+            .fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn set_preferences(
+        &self,
+        id: uuid::Uuid,
+        locale: Option<&str>,
+        timezone: &str,
+    ) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                User,
+                "queries/users/set_preferences.sql",
+                id,
+                locale,
+                timezone
+            )
             .fetch_optional(&self.pool)
-            .await?;
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn set_theme(&self, id: uuid::Uuid, theme: Option<&str>) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/set_theme.sql", id, theme)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn ban_in(executor: impl PgExecutor<'_>, id: uuid::Uuid) -> Result<Option<User>> {
+        let res = timed!(
+            sqlx::query_file_as!(User, "queries/users/ban.sql", id).fetch_optional(executor)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Scrubs the user's personal data and hands their content to the ghost
+    /// user, creating it on first use.
+    pub async fn anonymize(&self, id: uuid::Uuid) -> Result<Option<User>> {
+        let mut tx = self.pool.begin().await?;
+        timed!(
+            sqlx::query_file!("queries/users/ensure_ghost.sql", GHOST_USER_ID).execute(&mut *tx)
+        )
+        .await?;
+        let res = timed!(
+            sqlx::query_file_as!(
+                User,
+                "queries/users/anonymize.sql",
+                id,
+                GHOST_USER_ID,
+                anonymized_username(id),
+                anonymized_email(id)
+            )
+            .fetch_optional(&mut *tx)
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(res)
+    }
+    /// Everything stored about the user besides the profile.
+    pub async fn export_data(&self, id: uuid::Uuid) -> Result<Option<UserData>> {
+        let res = timed!(
+            sqlx::query_file_as!(UserData, "queries/users/export_data.sql", id)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn record_username_change_in(
+        executor: impl PgExecutor<'_>,
+        id: uuid::Uuid,
+        old_username: &str,
+    ) -> Result<()> {
+        timed!(
+            sqlx::query_file!("queries/username_history/create.sql", id, old_username)
+                .execute(executor)
+        )
+        .await?;
+        Ok(())
+    }
+    /// Id of the user who most recently renamed away from `username`.
+    pub async fn get_id_by_previous_username(&self, username: &str) -> Result<Option<uuid::Uuid>> {
+        let res = timed!(
+            sqlx::query_file_scalar!("queries/username_history/get_user_id.sql", username)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Runs `f` in a transaction on the primary, committing if it returns `Ok`
+    /// and rolling back otherwise. Storage `*_in` functions accept the connection.
+    pub async fn with_tx<T, F>(&self, f: F) -> Result<T>
+    where
+        F: for<'c> FnOnce(&'c mut PgConnection) -> TxFuture<'c, T>,
+    {
+        let mut tx = self.pool.begin().await?;
+        let res = f(&mut tx).await?;
+        tx.commit().await?;
         Ok(res)
     }
 }
 
-fn hash_password(password: &str) -> argon2::password_hash::Result<String> {
-    use argon2::{
-        Argon2,
-        password_hash::{PasswordHasher, SaltString, rand_core::OsRng},
-    };
-    let salt = SaltString::generate(&mut OsRng);
+const USER_COLUMNS: &str = "id, username, email, first_name, last_name, bio, bio_html, profile_extras, is_admin, badge, locale, timezone, theme, banned_at, created_at, updated_at";
+
+/// Appends the `WHERE` clause for the optional filters of `data`.
+fn push_filters(builder: &mut QueryBuilder<'_, Postgres>, data: &UserSearch) {
+    builder.push(" WHERE TRUE");
+    if let Some(search) = data.search.as_ref().filter(|s| !s.is_empty()) {
+        builder
+            .push(" AND CONCAT_WS(' ', username, first_name, last_name, bio) ILIKE ")
+            .push_bind(format!("%{}%", escape_like(search)));
+    }
+    if let Some(is_admin) = data.is_admin {
+        builder.push(" AND is_admin = ").push_bind(is_admin);
+    }
+    if let Some(created_since) = data.created_since {
+        builder.push(" AND created_at >= ").push_bind(created_since);
+    }
+    if let Some(updated_since) = data.updated_since {
+        builder.push(" AND updated_at >= ").push_bind(updated_since);
+    }
+}
+
+fn list_query(data: &UserSearch, limit: i64, offset: i64) -> QueryBuilder<'static, Postgres> {
+    let mut builder = QueryBuilder::new(format!("SELECT {USER_COLUMNS} FROM users"));
+    push_filters(&mut builder, data);
+    builder
+        .push(" ORDER BY created_at DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    builder
+}
+
+fn count_query(data: &UserSearch) -> QueryBuilder<'static, Postgres> {
+    let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM users");
+    push_filters(&mut builder, data);
+    builder
+}
+
+fn argon2(params: &Params) -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+}
 
-    // Argon2 with default params (Argon2id v19)
-    let argon2 = Argon2::default();
+fn hash_password(params: &Params, password: &str) -> argon2::password_hash::Result<String> {
+    use argon2::password_hash::{PasswordHasher, SaltString, rand_core::OsRng};
+    let salt = SaltString::generate(&mut OsRng);
 
-    // Hash password to PHC string ($argon2id$v=19$...)
-    let password_hash = argon2
+    // Hash password to PHC string ($argon2id$v=19$m=...,t=...,p=...$...)
+    let password_hash = argon2(params)
         .hash_password(password.as_bytes(), &salt)?
         .to_string();
     Ok(password_hash)
 }
 
 fn verify_password(password_hash: &str, password: &str) -> argon2::password_hash::Result<bool> {
-    use argon2::{
-        Argon2,
-        password_hash::{PasswordHash, PasswordVerifier},
-    };
+    use argon2::password_hash::PasswordVerifier;
     let parsed_hash = PasswordHash::new(password_hash)?;
+    // Algorithm and parameters come from the hash itself, so hashes made
+    // under older settings keep verifying
     let res = Argon2::default()
         .verify_password(password.as_bytes(), &parsed_hash)
         .is_ok();
     Ok(res)
 }
 
+/// Whether `password_hash` was made with anything other than Argon2id v19
+/// under `params`.
+fn needs_rehash(password_hash: &str, params: &Params) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+    match Params::try_from(&parsed_hash) {
+        Ok(current) => {
+            current.m_cost() != params.m_cost()
+                || current.t_cost() != params.t_cost()
+                || current.p_cost() != params.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,6 +582,7 @@ mod tests {
             first_name: Some(FirstName().fake()),
             last_name: Some(LastName().fake()),
             bio: Some(Sentence(1..5).fake()),
+            profile_extras: serde_json::json!({}),
         }
     }
 
@@ -179,6 +611,7 @@ mod tests {
         assert_eq!(created_user.last_name, user_data.last_name);
         assert_eq!(created_user.bio, user_data.bio);
         assert_ne!(created_user.id, Uuid::nil());
+        assert!(!created_user.is_admin);
         let now = chrono::Utc::now();
         assert!(
             now.signed_duration_since(created_user.created_at)
@@ -189,6 +622,21 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn test_create_user_with_profile_extras(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let mut user_data = create_fake_user();
+        user_data.profile_extras = serde_json::json!({"city": "Казань"});
+        let created_user = storage.create(user_data).await?;
+        let loaded = storage.get_by_id(created_user.id).await?.unwrap();
+
+        assert_eq!(loaded.profile_extras, serde_json::json!({"city": "Казань"}));
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn test_get_by_id_success(pool: sqlx::PgPool) -> anyhow::Result<()> {
         sqlx::migrate!().run(&pool).await?;
@@ -247,6 +695,31 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn test_canonical_email_is_unique(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let mut user_data = create_fake_user();
+        user_data.email = "Jane.Doe+books@gmail.com".to_string();
+        let created_user = storage.create(user_data).await?;
+        // The address is kept as typed
+        assert_eq!(created_user.email, "jane.doe+books@gmail.com");
+
+        let found_user = storage
+            .get_by_canonical_email("janedoe@googlemail.com")
+            .await?;
+        assert_eq!(found_user.map(|u| u.id), Some(created_user.id));
+        assert!(storage.get_by_email("janedoe@gmail.com").await?.is_none());
+
+        let mut duplicate = create_fake_user();
+        duplicate.email = "j.a.n.e.doe@gmail.com".to_string();
+        let err = storage.create(duplicate).await.unwrap_err();
+        assert!(matches!(err, sqlx::Error::Database(e) if e.is_unique_violation()));
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn test_verify_user_success(pool: sqlx::PgPool) -> anyhow::Result<()> {
         sqlx::migrate!().run(&pool).await?;
@@ -279,6 +752,37 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn test_verify_user_rehashes_outdated_hash(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let old_params = Params::new(8 * 1024, 1, 1, None).unwrap();
+        let storage = UsersStorage::new(pool.clone())
+            .await?
+            .with_argon2_params(old_params);
+        let created_user = storage.create(create_fake_user()).await?;
+
+        let new_params = Params::new(16 * 1024, 2, 1, None).unwrap();
+        let storage = storage.with_argon2_params(new_params.clone());
+        assert!(
+            storage
+                .verify_user(&created_user.email, "Password123!")
+                .await?
+        );
+
+        let stored: String = sqlx::query_scalar("SELECT password FROM users WHERE id = $1")
+            .bind(created_user.id)
+            .fetch_one(&pool)
+            .await?;
+        assert!(!needs_rehash(&stored, &new_params));
+        assert!(
+            storage
+                .verify_user(&created_user.email, "Password123!")
+                .await?
+        );
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn test_verify_user_not_found(pool: sqlx::PgPool) -> anyhow::Result<()> {
         sqlx::migrate!().run(&pool).await?;
@@ -357,6 +861,7 @@ mod tests {
             search: None,
             limit: Some(2),
             offset: Some(0),
+            ..Default::default()
         };
         let result1 = storage.list_users(search1).await?;
         assert_eq!(result1.users.len(), 2);
@@ -369,6 +874,7 @@ mod tests {
             search: None,
             limit: Some(2),
             offset: Some(2),
+            ..Default::default()
         };
         let result2 = storage.list_users(search2).await?;
         assert_eq!(result2.users.len(), 2);
@@ -397,6 +903,7 @@ mod tests {
             first_name: None,
             last_name: None,
             bio: None,
+            profile_extras: serde_json::json!({}),
         };
         let user2_data = CreateUser {
             username: "othertest456".to_string(),
@@ -405,6 +912,7 @@ mod tests {
             first_name: None,
             last_name: None,
             bio: None,
+            profile_extras: serde_json::json!({}),
         };
 
         storage.create(user1_data).await?;
@@ -415,6 +923,7 @@ mod tests {
             search: Some("test".to_string()),
             limit: Some(20),
             offset: Some(0),
+            ..Default::default()
         };
         let result = storage.list_users(search).await?;
         assert_eq!(result.users.len(), 2);
@@ -425,6 +934,7 @@ mod tests {
             search: Some("123".to_string()),
             limit: Some(20),
             offset: Some(0),
+            ..Default::default()
         };
         let result = storage.list_users(search).await?;
         assert_eq!(result.users.len(), 1);
@@ -435,40 +945,35 @@ mod tests {
     }
 
     #[sqlx::test]
-    async fn test_list_users_search_by_email(pool: sqlx::PgPool) -> anyhow::Result<()> {
+    async fn test_list_users_search_ignores_email(pool: sqlx::PgPool) -> anyhow::Result<()> {
         sqlx::migrate!().run(&pool).await?;
         let storage = UsersStorage::new(pool).await?;
 
-        let user1_data = CreateUser {
-            username: "user1".to_string(),
-            email: "john.doe@example.com".to_string(),
-            password: "Password123!".to_string(),
-            first_name: None,
-            last_name: None,
-            bio: None,
-        };
-        let user2_data = CreateUser {
-            username: "user2".to_string(),
-            email: "jane.smith@test.org".to_string(),
-            password: "Password123!".to_string(),
-            first_name: None,
-            last_name: None,
-            bio: None,
-        };
-
-        storage.create(user1_data).await?;
-        storage.create(user2_data).await?;
+        storage
+            .create(CreateUser {
+                username: "user1".to_string(),
+                email: "john.doe@example.com".to_string(),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
 
-        // Search for "example" should return the first user
-        let search = UserSearch {
-            search: Some("example".to_string()),
-            limit: Some(20),
-            offset: Some(0),
-        };
-        let result = storage.list_users(search).await?;
-        assert_eq!(result.users.len(), 1);
-        assert_eq!(result.total_count, 1);
-        assert!(result.users[0].email.contains("example"));
+        // Other users' emails must not be confirmable through search, and
+        // wildcards in the term match literally
+        for term in ["john.doe@example.com", "example", "%", "user_"] {
+            let search = UserSearch {
+                search: Some(term.to_string()),
+                limit: Some(20),
+                offset: Some(0),
+                ..Default::default()
+            };
+            let result = storage.list_users(search).await?;
+            assert!(result.users.is_empty(), "{term} matched");
+            assert_eq!(result.total_count, 0);
+        }
 
         Ok(())
     }
@@ -496,6 +1001,118 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn test_bio_is_rendered(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let created = storage
+            .create(CreateUser {
+                bio: Some("**hi** <script>x</script>".to_string()),
+                ..create_fake_user()
+            })
+            .await?;
+        let bio_html = created.bio_html.unwrap();
+        assert!(bio_html.contains("<strong>hi</strong>"));
+        assert!(!bio_html.contains("<script"));
+
+        let updated = storage
+            .update(
+                created.id,
+                UpdateUser {
+                    username: None,
+                    email: None,
+                    password: None,
+                    first_name: None,
+                    last_name: None,
+                    bio: Some("*new*".to_string()),
+                },
+            )
+            .await?
+            .unwrap();
+        assert_eq!(updated.bio.as_deref(), Some("*new*"));
+        assert_eq!(updated.bio_html.as_deref(), Some("<p><em>new</em></p>\n"));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_suggest(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        for username in ["annabel", "anna", "hanna", "an_other"] {
+            storage
+                .create(CreateUser {
+                    username: username.to_string(),
+                    first_name: None,
+                    last_name: None,
+                    ..create_fake_user()
+                })
+                .await?;
+        }
+
+        let found = storage.suggest("anna", None, 10).await?;
+        let names: Vec<_> = found.iter().map(|u| u.username.as_str()).collect();
+        assert_eq!(names, vec!["anna", "annabel"]);
+
+        let found = storage.suggest(r"an\_", None, 10).await?;
+        assert_eq!(found.len(), 1);
+
+        let banned = storage.get_by_username("anna").await?.unwrap();
+        UsersStorage::ban_in(&pool, banned.id).await?;
+        let found = storage.suggest("anna", None, 10).await?;
+        assert_eq!(found.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_suggest_ranking_and_typos(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        for (username, first_name, bio) in [
+            ("reader", None, Some("Loves Margarita by Bulgakov")),
+            ("margo", Some("Margarita"), None),
+            ("margarita", None, None),
+        ] {
+            storage
+                .create(CreateUser {
+                    username: username.to_string(),
+                    first_name: first_name.map(str::to_string),
+                    last_name: None,
+                    bio: bio.map(str::to_string),
+                    ..create_fake_user()
+                })
+                .await?;
+        }
+
+        // Username over name over bio
+        let found = storage.suggest("margarita", None, 10).await?;
+        let names: Vec<_> = found.iter().map(|u| u.username.as_str()).collect();
+        assert_eq!(names, vec!["margarita", "margo", "reader"]);
+        assert!(found[0].score > found[1].score && found[1].score > found[2].score);
+
+        assert!(storage.suggest("margarrita", None, 10).await?.is_empty());
+        let found = storage.suggest_similar("margarrita", None, 10).await?;
+        let mut names: Vec<_> = found.iter().map(|u| u.username.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["margarita", "margo"]);
+
+        let found = storage.suggest_usernames("marg", None, 10).await?;
+        let names: Vec<_> = found.iter().map(|u| u.username.as_str()).collect();
+        assert_eq!(names, vec!["margo", "margarita"]);
+
+        let storage = storage.with_search_settings(SearchSettings {
+            username_weight: 1.0,
+            name_weight: 5.0,
+            bio_weight: 0.0,
+        });
+        let found = storage.suggest("margarita", None, 10).await?;
+        assert_eq!(found[0].username, "margo");
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn test_update_user_not_found(pool: sqlx::PgPool) -> anyhow::Result<()> {
         sqlx::migrate!().run(&pool).await?;
@@ -510,6 +1127,376 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn test_delete_disposable(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        let mut ids = Vec::new();
+        for (username, email) in [
+            ("smoke_old", "smoke_old@example.com"),
+            ("smoke_mail", "smoke_mail@mail.com"),
+            ("reader", "reader@example.com"),
+        ] {
+            let user = storage
+                .create(CreateUser {
+                    username: username.to_string(),
+                    email: email.to_string(),
+                    ..create_fake_user()
+                })
+                .await?;
+            ids.push(user.id);
+        }
+        let fresh = storage
+            .create(CreateUser {
+                username: "smoke_fresh".to_string(),
+                email: "smoke_fresh@example.com".to_string(),
+                ..create_fake_user()
+            })
+            .await?;
+        sqlx::query("UPDATE users SET created_at = NOW() - INTERVAL '2 days' WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&pool)
+            .await?;
+
+        assert_eq!(storage.delete_disposable("smoke_", "example.com", 10).await?, 1);
+        assert!(storage.get_by_id(ids[0]).await?.is_none());
+        assert!(storage.get_by_id(ids[1]).await?.is_some());
+        assert!(storage.get_by_id(ids[2]).await?.is_some());
+        assert!(storage.get_by_id(fresh.id).await?.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_set_badge(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let created_user = storage.create(create_fake_user()).await?;
+        assert!(created_user.badge.is_none());
+
+        let updated = storage.set_badge(created_user.id, Some("curator")).await?;
+        assert_eq!(updated.unwrap().badge.as_deref(), Some("curator"));
+
+        let cleared = storage.set_badge(created_user.id, None).await?;
+        assert!(cleared.unwrap().badge.is_none());
+
+        storage.set_badge(created_user.id, Some("verified")).await?;
+        let kept = UsersStorage::grant_badge_in(&storage.pool, created_user.id, "curator").await?;
+        assert_eq!(kept.unwrap().badge.as_deref(), Some("verified"));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_updated_at_is_touched(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let created_user = storage.create(create_fake_user()).await?;
+        assert_eq!(created_user.updated_at, created_user.created_at);
+
+        let updated = storage
+            .set_badge(created_user.id, Some("curator"))
+            .await?
+            .unwrap();
+        assert!(updated.updated_at > created_user.updated_at);
+
+        let changed = storage
+            .list_users(UserSearch {
+                updated_since: Some(updated.updated_at),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(changed.users.len(), 1);
+        assert_eq!(changed.users[0].id, created_user.id);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_export_data(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        let user = storage.create(create_fake_user()).await?;
+        let other = storage.create(create_fake_user()).await?;
+        sqlx::query(
+            "WITH c AS (INSERT INTO clubs (slug, name, created_by) VALUES ('readers', 'Readers', $1) \
+             RETURNING id) INSERT INTO club_members (club_id, user_id, role) SELECT id, $1, 'owner' FROM c",
+        )
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+        sqlx::query("INSERT INTO user_blocks (user_id, target_id, kind) VALUES ($1, $2, 'mute')")
+            .bind(user.id)
+            .bind(other.id)
+            .execute(&pool)
+            .await?;
+        sqlx::query(
+            "INSERT INTO api_keys (user_id, name, prefix, key_hash) VALUES ($1, 'cli', 'abcd1234', 'secret')",
+        )
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+
+        let data = storage.export_data(user.id).await?.unwrap();
+        assert_eq!(data.club_memberships[0]["club"], "Readers");
+        assert_eq!(data.club_memberships[0]["role"], "owner");
+        assert_eq!(data.blocks[0]["username"], other.username.as_str());
+        assert_eq!(data.api_keys[0]["prefix"], "abcd1234");
+        assert!(!data.api_keys.to_string().contains("secret"));
+        assert_eq!(data.poll_votes, serde_json::json!([]));
+
+        let empty = storage.export_data(other.id).await?.unwrap();
+        assert_eq!(empty.club_memberships, serde_json::json!([]));
+        assert!(storage.export_data(Uuid::new_v4()).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_anonymize(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+
+        let user_data = create_fake_user();
+        let created_user = storage.create(user_data.clone()).await?;
+        let club_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO clubs (slug, name, created_by) VALUES ('readers', 'Readers', $1) RETURNING id",
+        )
+        .bind(created_user.id)
+        .fetch_one(&pool)
+        .await?;
+        let poll_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO polls (club_id, question, created_by) VALUES ($1, 'Next?', $2) RETURNING id",
+        )
+        .bind(club_id)
+        .bind(created_user.id)
+        .fetch_one(&pool)
+        .await?;
+        sqlx::query(
+            "WITH o AS (INSERT INTO poll_options (poll_id, position, text) VALUES ($1, 1, 'Dune') \
+             RETURNING id) INSERT INTO poll_votes (poll_id, option_id, user_id) SELECT $1, id, $2 FROM o",
+        )
+        .bind(poll_id)
+        .bind(created_user.id)
+        .execute(&pool)
+        .await?;
+
+        let anonymized = storage.anonymize(created_user.id).await?.unwrap();
+        assert_eq!(anonymized.username, anonymized_username(created_user.id));
+        assert!(anonymized.first_name.is_none() && anonymized.bio.is_none());
+        assert!(anonymized.is_banned());
+        assert!(storage.get_by_email(&user_data.email).await?.is_none());
+        assert!(
+            storage
+                .get_by_canonical_email(&user_data.email)
+                .await?
+                .is_none()
+        );
+
+        let owner: Option<Uuid> = sqlx::query_scalar("SELECT created_by FROM clubs WHERE id = $1")
+            .bind(club_id)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(owner, Some(GHOST_USER_ID));
+        let poll_owner: Option<Uuid> =
+            sqlx::query_scalar("SELECT created_by FROM polls WHERE id = $1")
+                .bind(poll_id)
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(poll_owner, Some(GHOST_USER_ID));
+        let votes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM poll_votes WHERE poll_id = $1")
+            .bind(poll_id)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(votes, 0);
+
+        assert!(storage.anonymize(Uuid::new_v4()).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_anonymize_sole_club_owner(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        let owner = storage.create(create_fake_user()).await?;
+        let admin = storage.create(create_fake_user()).await?;
+        let member = storage.create(create_fake_user()).await?;
+        let mut clubs = Vec::new();
+        for slug in ["shared", "empty"] {
+            let club_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO clubs (slug, name, created_by) VALUES ($1, $1, $2) RETURNING id",
+            )
+            .bind(slug)
+            .bind(owner.id)
+            .fetch_one(&pool)
+            .await?;
+            clubs.push(club_id);
+        }
+        let members = [
+            (clubs[0], member.id, "member"),
+            (clubs[0], admin.id, "admin"),
+            (clubs[0], owner.id, "owner"),
+            (clubs[1], owner.id, "owner"),
+        ];
+        for (club_id, user_id, role) in members {
+            sqlx::query("INSERT INTO club_members (club_id, user_id, role) VALUES ($1, $2, $3)")
+                .bind(club_id)
+                .bind(user_id)
+                .bind(role)
+                .execute(&pool)
+                .await?;
+        }
+
+        assert!(storage.anonymize(owner.id).await?.is_some());
+
+        // The admin takes over; a club with nobody left goes to the ghost
+        let owners: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            "SELECT club_id, user_id FROM club_members WHERE role = 'owner' AND club_id = ANY($1)",
+        )
+        .bind(&clubs)
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(owners.len(), 2);
+        assert!(owners.contains(&(clubs[0], admin.id)));
+        assert!(owners.contains(&(clubs[1], GHOST_USER_ID)));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_anonymize_reporters_of_same_target(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        let first = storage.create(create_fake_user()).await?;
+        let second = storage.create(create_fake_user()).await?;
+        let target = Uuid::new_v4();
+        let reports = [
+            (first.id, "dismissed"),
+            (first.id, "open"),
+            (second.id, "open"),
+        ];
+        for (reporter, status) in reports {
+            sqlx::query(
+                "INSERT INTO reports (target_kind, target_id, reporter_id, reason, status) \
+                 VALUES ('club', $1, $2, 'spam', $3)",
+            )
+            .bind(target)
+            .bind(reporter)
+            .bind(status)
+            .execute(&pool)
+            .await?;
+        }
+
+        // Both open reports would end up as the ghost's open report on one target
+        assert!(storage.anonymize(first.id).await?.is_some());
+        assert!(storage.anonymize(second.id).await?.is_some());
+
+        let reports: Vec<(Uuid, String)> =
+            sqlx::query_as("SELECT reporter_id, status FROM reports WHERE target_id = $1")
+                .bind(target)
+                .fetch_all(&pool)
+                .await?;
+        assert_eq!(reports, vec![(GHOST_USER_ID, "dismissed".to_string())]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_make_admin(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let created_user = storage.create(create_fake_user()).await?;
+        let admin = storage.make_admin(created_user.id).await?.unwrap();
+        assert!(admin.is_admin);
+        assert!(storage.make_admin(Uuid::new_v4()).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_username_history(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool.clone()).await?;
+        let created_user = storage.create(create_fake_user()).await?;
+        let id = created_user.id;
+        let old_username = created_user.username.clone();
+
+        let mut tx = pool.begin().await?;
+        UsersStorage::set_username_in(&mut *tx, id, "renamed_reader").await?;
+        UsersStorage::record_username_change_in(&mut *tx, id, &old_username).await?;
+        tx.commit().await?;
+
+        let found = storage.get_by_username("RENAMED_READER").await?.unwrap();
+        assert_eq!(found.id, id);
+        let previous = storage
+            .get_id_by_previous_username(&old_username.to_uppercase())
+            .await?;
+        assert_eq!(previous, Some(id));
+        assert!(storage.get_by_username(&old_username).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_with_tx_rolls_back_on_error(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+        let created_user = storage.create(create_fake_user()).await?;
+        let id = created_user.id;
+
+        let res: Result<()> = storage
+            .with_tx(|conn| {
+                Box::pin(async move {
+                    UsersStorage::set_badge_in(&mut *conn, id, Some("curator")).await?;
+                    Err(sqlx::Error::RowNotFound)
+                })
+            })
+            .await;
+        assert!(res.is_err());
+        let loaded = storage.get_by_id(id).await?.unwrap();
+        assert!(loaded.badge.is_none());
+
+        storage
+            .with_tx(|conn| {
+                Box::pin(async move {
+                    UsersStorage::set_badge_in(&mut *conn, id, Some("curator")).await?;
+                    Ok(())
+                })
+            })
+            .await?;
+        let loaded = storage.get_by_id(id).await?.unwrap();
+        assert_eq!(loaded.badge.as_deref(), Some("curator"));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_set_preferences(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let storage = UsersStorage::new(pool).await?;
+
+        let created = storage.create(create_fake_user()).await?;
+        assert_eq!(created.locale, None);
+        assert_eq!(created.timezone, "UTC");
+
+        storage
+            .set_preferences(created.id, Some("en"), "Europe/Moscow")
+            .await?;
+        let loaded = storage.get_by_id(created.id).await?.unwrap();
+        assert_eq!(loaded.locale.as_deref(), Some("en"));
+        assert_eq!(loaded.timezone, "Europe/Moscow");
+
+        let themed = storage.set_theme(created.id, Some("dark")).await?.unwrap();
+        assert_eq!(themed.theme.as_deref(), Some("dark"));
+        assert_eq!(themed.timezone, "Europe/Moscow");
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn test_delete_user_success(pool: sqlx::PgPool) -> anyhow::Result<()> {
         sqlx::migrate!().run(&pool).await?;
@@ -542,11 +1529,84 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_needs_rehash() {
+        let params = Params::new(8 * 1024, 1, 1, None).unwrap();
+        let hash = hash_password(&params, "Password123!").unwrap();
+        assert!(verify_password(&hash, "Password123!").unwrap());
+        assert!(!needs_rehash(&hash, &params));
+        assert!(needs_rehash(
+            &hash,
+            &Params::new(8 * 1024, 2, 1, None).unwrap()
+        ));
+
+        let argon2i = Argon2::new(Algorithm::Argon2i, Version::V0x13, params.clone());
+        let salt = argon2::password_hash::SaltString::generate(
+            &mut argon2::password_hash::rand_core::OsRng,
+        );
+        let hash =
+            argon2::password_hash::PasswordHasher::hash_password(&argon2i, b"Password123!", &salt)
+                .unwrap()
+                .to_string();
+        assert!(verify_password(&hash, "Password123!").unwrap());
+        assert!(needs_rehash(&hash, &params));
+    }
+
+    #[test]
+    fn test_list_query_without_filters() {
+        let search = UserSearch::default();
+        let query = list_query(&search, 20, 0);
+
+        assert_eq!(
+            query.sql(),
+            format!(
+                "SELECT {USER_COLUMNS} FROM users WHERE TRUE ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+            )
+        );
+    }
+
+    #[test]
+    fn test_list_query_with_all_filters() {
+        let search = UserSearch {
+            search: Some("test".to_string()),
+            is_admin: Some(true),
+            created_since: Some(chrono::Utc::now()),
+            updated_since: Some(chrono::Utc::now()),
+            ..Default::default()
+        };
+        let query = list_query(&search, 20, 0);
+
+        assert_eq!(
+            query.sql(),
+            format!(
+                "SELECT {USER_COLUMNS} FROM users WHERE TRUE \
+                AND CONCAT_WS(' ', username, first_name, last_name, bio) ILIKE $1 \
+                AND is_admin = $2 AND created_at >= $3 AND updated_at >= $4 \
+                ORDER BY created_at DESC LIMIT $5 OFFSET $6"
+            )
+        );
+    }
+
+    #[test]
+    fn test_count_query_ignores_empty_search() {
+        let search = UserSearch {
+            search: Some(String::new()),
+            is_admin: Some(false),
+            ..Default::default()
+        };
+        let query = count_query(&search);
+
+        assert_eq!(
+            query.sql(),
+            "SELECT COUNT(*) FROM users WHERE TRUE AND is_admin = $1"
+        );
+    }
+
     #[test]
     fn test_hash_password() {
         let password = "test_password_123!";
-        let hash1 = hash_password(password).unwrap();
-        let hash2 = hash_password(password).unwrap();
+        let hash1 = hash_password(&Params::default(), password).unwrap();
+        let hash2 = hash_password(&Params::default(), password).unwrap();
 
         // Hashes should be different due to random salt
         assert_ne!(hash1, hash2);
@@ -562,7 +1622,7 @@ mod tests {
     #[test]
     fn test_verify_password() {
         let password = "test_password_123!";
-        let hash = hash_password(password).unwrap();
+        let hash = hash_password(&Params::default(), password).unwrap();
 
         // Correct password should verify
         assert!(verify_password(&hash, password).unwrap());
@@ -0,0 +1,181 @@
+use sqlx::{Pool, Postgres, Result};
+use uuid::Uuid;
+
+use crate::models::domain::{NearbyVenue, Venue};
+
+/// Venues and their locations.
+#[derive(Clone, Debug)]
+pub struct VenuesStorage {
+    pool: Pool<Postgres>,
+}
+
+/// What is stored about a new venue.
+#[derive(Debug, Clone)]
+pub struct CreateVenue<'a> {
+    pub slug: &'a str,
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub address: &'a str,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub created_by: Uuid,
+}
+
+impl VenuesStorage {
+    pub async fn new(pool: Pool<Postgres>) -> Result<Self> {
+        let storage = Self { pool };
+        Ok(storage)
+    }
+    /// `None` when the slug is taken.
+    pub async fn create(&self, venue: CreateVenue<'_>) -> Result<Option<Venue>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                Venue,
+                "queries/venues/create.sql",
+                venue.slug,
+                venue.name,
+                venue.kind,
+                venue.address,
+                venue.latitude,
+                venue.longitude,
+                venue.created_by,
+            )
+            .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn get_by_slug(&self, slug: &str) -> Result<Option<Venue>> {
+        let res = timed!(
+            sqlx::query_file_as!(Venue, "queries/venues/get_by_slug.sql", slug)
+                .fetch_optional(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    pub async fn list(&self, limit: i64) -> Result<Vec<Venue>> {
+        let res = timed!(
+            sqlx::query_file_as!(Venue, "queries/venues/list.sql", limit).fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+    /// Venues within `radius_m` meters of the point, nearest first.
+    pub async fn list_near(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        radius_m: f64,
+        limit: i64,
+    ) -> Result<Vec<NearbyVenue>> {
+        let res = timed!(
+            sqlx::query_file_as!(
+                NearbyVenue,
+                "queries/venues/list_near.sql",
+                latitude,
+                longitude,
+                radius_m,
+                limit
+            )
+            .fetch_all(&self.pool)
+        )
+        .await?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::domain::CreateUser;
+    use crate::storage::UsersStorage;
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> anyhow::Result<Uuid> {
+        let users = UsersStorage::new(pool.clone()).await?;
+        let user = users
+            .create(CreateUser {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: "Password123!".to_string(),
+                first_name: None,
+                last_name: None,
+                bio: None,
+                profile_extras: serde_json::json!({}),
+            })
+            .await?;
+        Ok(user.id)
+    }
+
+    fn venue(slug: &str, latitude: f64, longitude: f64, created_by: Uuid) -> CreateVenue<'_> {
+        CreateVenue {
+            slug,
+            name: slug,
+            kind: "theater",
+            address: "",
+            latitude,
+            longitude,
+            created_by,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_create_venue(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let admin = create_user(&pool, "admin").await?;
+        let storage = VenuesStorage::new(pool.clone()).await?;
+
+        let bolshoi = storage
+            .create(venue("bolshoi", 55.7601, 37.6186, admin))
+            .await?
+            .expect("created");
+        // Slugs are unique regardless of case
+        assert!(
+            storage
+                .create(venue("Bolshoi", 0.0, 0.0, admin))
+                .await?
+                .is_none()
+        );
+        assert_eq!(
+            storage.get_by_slug("BOLSHOI").await?.map(|v| v.id),
+            Some(bolshoi.id)
+        );
+        // Coordinates outside Earth are rejected by the table
+        assert!(
+            storage
+                .create(venue("nowhere", 95.0, 0.0, admin))
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_list_near(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::migrate!().run(&pool).await?;
+        let admin = create_user(&pool, "admin").await?;
+        let storage = VenuesStorage::new(pool.clone()).await?;
+        // Around Moscow's Teatralnaya square, and one in Saint Petersburg
+        for (slug, lat, lon) in [
+            ("bolshoi", 55.7601, 37.6186),
+            ("pushkin-museum", 55.7473, 37.6051),
+            ("hermitage", 59.9398, 30.3146),
+        ] {
+            storage.create(venue(slug, lat, lon, admin)).await?;
+        }
+
+        let near = storage.list_near(55.7587, 37.6200, 5_000.0, 10).await?;
+        let slugs: Vec<_> = near.iter().map(|v| v.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["bolshoi", "pushkin-museum"]);
+        assert!(near[0].distance_m < 300.0);
+        assert!(near[0].distance_m < near[1].distance_m);
+
+        assert!(storage.list_near(0.0, 0.0, 5_000.0, 10).await?.is_empty());
+        assert_eq!(
+            storage.list_near(55.7587, 37.62, 5_000.0, 1).await?.len(),
+            1
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! `test_support` feature: the whole app on a random port against a fresh
+//! database, so integration tests and benchmarks can go through HTTP.
+//!
+//! ```ignore
+//! let app = app::test_support::spawn_test_app().await?;
+//! app.client.sign_up("reader", "reader@example.com", "Password-42!").await?;
+//! app.shutdown().await?;
+//! ```
+
+use anyhow::{Context, Result};
+use sqlx::{Connection, Executor, PgConnection};
+use tokio::{net::TcpListener, sync::oneshot, task::JoinHandle};
+
+use crate::{
+    configuration::{self, Settings},
+    listener::Listener,
+    smoke::SiteClient,
+    storage,
+};
+
+/// Prefix of the temporary databases, to find ones left by crashed runs.
+pub const TEST_DATABASE_PREFIX: &str = "culturelist_test_";
+
+/// A running app; call [`TestApp::shutdown`] to stop it and drop its database.
+pub struct TestApp {
+    /// `http://127.0.0.1:<port>`
+    pub address: String,
+    pub client: SiteClient,
+    /// Name of the temporary database
+    pub database: String,
+    settings: Settings,
+    shutdown: oneshot::Sender<()>,
+    server: JoinHandle<Result<()>>,
+}
+
+/// Creates a database named after [`TEST_DATABASE_PREFIX`] next to
+/// `database.url`, applies the migrations and serves the app on a random
+/// local port. The configuration is loaded as for `app serve`.
+pub async fn spawn_test_app() -> Result<TestApp> {
+    let settings = Settings::load(&configuration::init()?)?;
+    let database = format!("{TEST_DATABASE_PREFIX}{}", uuid::Uuid::new_v4().simple());
+    execute(&settings, &format!("CREATE DATABASE \"{database}\"")).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    let address = format!("http://127.0.0.1:{port}");
+    let config = configuration::init_with(&[
+        (
+            "database.url",
+            with_database(&settings.database.url, &database),
+        ),
+        // A replica would still point at the shared database
+        ("database.read_url", String::new()),
+        ("server.listen", format!("tcp://127.0.0.1:{port}")),
+        ("app.base_url", address.clone()),
+    ])?;
+    let app = crate::build(&config, &Settings::load(&config)?).await?;
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        app.serve(Listener::Tcp(listener), async {
+            let _ = stopped.await;
+        })
+        .await
+    });
+    Ok(TestApp {
+        client: SiteClient::new(&address)?,
+        address,
+        database,
+        settings,
+        shutdown,
+        server,
+    })
+}
+
+impl TestApp {
+    /// Stops the server and drops the temporary database.
+    pub async fn shutdown(self) -> Result<()> {
+        let _ = self.shutdown.send(());
+        self.server.await.context("test app panicked")??;
+        execute(
+            &self.settings,
+            &format!("DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)", self.database),
+        )
+        .await
+    }
+}
+
+/// Runs `sql` on the configured database, outside any transaction.
+async fn execute(settings: &Settings, sql: &str) -> Result<()> {
+    let (_, connect_options) = storage::pool_options(&settings.database, &settings.database.url)?;
+    let mut conn = PgConnection::connect_with(&connect_options).await?;
+    conn.execute(sql).await?;
+    conn.close().await?;
+    Ok(())
+}
+
+/// `url` with its database name replaced, keeping the query string.
+fn with_database(url: &str, database: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+    // The authority alone after the scheme means no database was given
+    let authority_end = base.find("://").map_or(0, |i| i + 3);
+    let base = match base[authority_end..].find('/') {
+        Some(slash) => &base[..authority_end + slash],
+        None => base,
+    };
+    match query {
+        Some(query) => format!("{base}/{database}?{query}"),
+        None => format!("{base}/{database}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_database() {
+        assert_eq!(
+            with_database("postgres://app@localhost:5432/culturelist", "other"),
+            "postgres://app@localhost:5432/other"
+        );
+        assert_eq!(
+            with_database("postgres://localhost/culturelist?sslmode=disable", "other"),
+            "postgres://localhost/other?sslmode=disable"
+        );
+        assert_eq!(
+            with_database("postgres://localhost", "other"),
+            "postgres://localhost/other"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Password rules shared by the API models and the HTML forms.
+
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use validator::ValidationError;
+
+use crate::i18n::Locale;
+
+pub const PASSWORD_SPECIAL_CHARS: &str = "!@#$%^&*()_+-=[]{}|;:,.<>?";
+
+static PASSWORD_POLICY: OnceLock<PasswordPolicy> = OnceLock::new();
+
+/// `[password]` configuration section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 64,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn check(&self, password: &str) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        let len = password.chars().count();
+        if len < self.min_length || len > self.max_length {
+            errors.push(format!(
+                "length between {} and {} required",
+                self.min_length, self.max_length
+            ));
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            errors.push("uppercase letter required".into());
+        }
+        if self.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            errors.push("lowercase letter required".into());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            errors.push("digit required".into());
+        }
+        if self.require_special && !password.chars().any(|c| PASSWORD_SPECIAL_CHARS.contains(c)) {
+            errors.push("special character required".into());
+        }
+
+        if !errors.is_empty() {
+            let error_message = errors.join(", ");
+            let mut error = ValidationError::new("password_requirements");
+            error.message = Some(format!("Password requirements not met: {error_message}").into());
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Requirements as shown next to password inputs.
+    pub fn describe(&self, locale: Locale) -> String {
+        let mut parts = Vec::new();
+        if self.require_uppercase {
+            parts.push(locale.t("password-rule-uppercase"));
+        }
+        if self.require_lowercase {
+            parts.push(locale.t("password-rule-lowercase"));
+        }
+        if self.require_digit {
+            parts.push(locale.t("password-rule-digit"));
+        }
+        if self.require_special {
+            parts.push(locale.t("password-rule-special"));
+        }
+        parts.push(locale.t_args(
+            "password-rule-length",
+            &[
+                ("min", self.min_length.to_string()),
+                ("max", self.max_length.to_string()),
+            ],
+        ));
+        locale.t_args("password-requirements", &[("rules", parts.join(", "))])
+    }
+}
+
+/// Installs the `[password]` policy. Later calls are ignored.
+pub fn init(policy: PasswordPolicy) {
+    let _ = PASSWORD_POLICY.set(policy);
+}
+
+pub fn password_policy() -> &'static PasswordPolicy {
+    PASSWORD_POLICY.get_or_init(PasswordPolicy::default)
+}
+
+/// `validator` custom function applying the configured policy.
+pub fn validate_password(password: &str) -> Result<(), ValidationError> {
+    password_policy().check(password)
+}
+
+/// zxcvbn score (0-4) with a label for the strength meter. Advisory only:
+/// acceptance is decided by [`validate_password`].
+pub fn password_strength(password: &str, user_inputs: &[&str], locale: Locale) -> (u8, String) {
+    if password.is_empty() {
+        return (0, String::new());
+    }
+    let score = u8::from(zxcvbn::zxcvbn(password, user_inputs).score());
+    (score, locale.t(&format!("strength-{score}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use config::Config;
+
+    use super::*;
+
+    #[test]
+    fn test_password_validation_success() {
+        // Valid password with all requirements
+        let valid_password = "Password123!";
+        assert!(validate_password(valid_password).is_ok());
+    }
+
+    #[test]
+    fn test_password_validation_missing_uppercase() {
+        let invalid_password = "password123!";
+        let result = validate_password(invalid_password);
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("uppercase letter required"));
+    }
+
+    #[test]
+    fn test_password_validation_missing_lowercase() {
+        let invalid_password = "PASSWORD123!";
+        let result = validate_password(invalid_password);
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("lowercase letter required"));
+    }
+
+    #[test]
+    fn test_password_validation_missing_digit() {
+        let invalid_password = "Password!";
+        let result = validate_password(invalid_password);
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("digit required"));
+    }
+
+    #[test]
+    fn test_password_validation_missing_special() {
+        let invalid_password = "Password123";
+        let result = validate_password(invalid_password);
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("special character required"));
+    }
+
+    #[test]
+    fn test_password_validation_multiple_errors() {
+        let invalid_password = "weak";
+        let result = validate_password(invalid_password);
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        let error_string = error.to_string();
+
+        // Should contain multiple error messages
+        assert!(error_string.contains("length between 8 and 64 required"));
+        assert!(error_string.contains("uppercase letter required"));
+        assert!(error_string.contains("digit required"));
+    }
+
+    #[test]
+    fn test_password_with_edge_cases() {
+        // Test password with special characters at boundaries
+        let edge_case_passwords = vec![
+            "A1!aaaaa",       // Minimum valid length
+            "Password123@#%", // Multiple special chars
+            "12345A!a",       // Special chars mixed with numbers
+            "!@#Aa1b2c3",     // Special chars at start
+        ];
+
+        for password in edge_case_passwords {
+            assert!(
+                validate_password(password).is_ok(),
+                "Password '{}' should be valid",
+                password
+            );
+        }
+    }
+
+    #[test]
+    fn test_password_special_characters() {
+        // Test all supported special characters
+        for special_char in PASSWORD_SPECIAL_CHARS.chars() {
+            let password = format!("Password123{}", special_char);
+            assert!(
+                validate_password(&password).is_ok(),
+                "Password with special character '{}' should be valid",
+                special_char
+            );
+        }
+    }
+
+    #[test]
+    fn test_password_length_bounds() {
+        assert!(validate_password("Aa1!aaa").is_err());
+        assert!(validate_password(&format!("Aa1!{}", "a".repeat(61))).is_err());
+        assert!(validate_password(&format!("Aa1!{}", "a".repeat(60))).is_ok());
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_special: false,
+            require_uppercase: false,
+            ..Default::default()
+        };
+        assert!(policy.check("longpassword1").is_ok());
+        assert!(policy.check("short1").is_err());
+        assert_eq!(
+            policy.describe(Locale::Ru),
+            "Требования к паролю: строчная буква, цифра, длина от 12 до 64 символов"
+        );
+        assert_eq!(
+            policy.describe(Locale::En),
+            "Password requirements: lowercase letter, digit, 12 to 64 characters"
+        );
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let config = Config::builder()
+            .set_override("password.min_length", 10)
+            .unwrap()
+            .set_override("password.require_special", false)
+            .unwrap()
+            .build()
+            .unwrap();
+        let policy = config.get::<PasswordPolicy>("password").unwrap();
+        assert_eq!(policy.min_length, 10);
+        assert_eq!(policy.max_length, 64);
+        assert!(!policy.require_special);
+        assert!(policy.require_digit);
+    }
+
+    #[test]
+    fn test_password_strength() {
+        assert_eq!(password_strength("", &[], Locale::Ru), (0, String::new()));
+        let (weak, _) = password_strength("password", &[], Locale::Ru);
+        let (strong, label) = password_strength("correct-horse-battery-staple-42", &[], Locale::Ru);
+        assert!(weak < strong);
+        assert_eq!(label, "Надёжный");
+        // Passwords built from the user's own details score lower
+        let (own, _) = password_strength("ivanpetrov1990", &["ivanpetrov1990"], Locale::Ru);
+        assert_eq!(own, 0);
+    }
+}
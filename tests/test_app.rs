@@ -0,0 +1,29 @@
+//! Boots the whole app through [`app::test_support`]; run with
+//! `cargo test --features test_support`.
+#![cfg(feature = "test_support")]
+
+use app::test_support::spawn_test_app;
+use reqwest::StatusCode;
+
+#[tokio::test]
+async fn test_sign_up_through_http() -> anyhow::Result<()> {
+    let app = spawn_test_app().await?;
+    app.client.home().await?;
+    app.client
+        .sign_up("reader", "reader@example.com", "Password-42!")
+        .await?;
+    app.client.sign_out().await?;
+    app.client.log_in("reader@example.com", "Password-42!").await?;
+
+    // The batch endpoint is mounted and wants an API key
+    let res = app
+        .client
+        .http()
+        .post(app.client.url("/api/v1/batch"))
+        .json(&serde_json::json!({ "operations": [] }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    app.shutdown().await
+}